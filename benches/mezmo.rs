@@ -0,0 +1,153 @@
+use std::net::SocketAddr;
+
+use criterion::{BatchSize, Criterion, SamplingMode, Throughput, criterion_group, criterion_main};
+use futures::TryFutureExt;
+use hyper::{
+    Body, Response, Server,
+    service::{make_service_fn, service_fn},
+};
+use tokio::runtime::Runtime;
+use vector::{
+    Error,
+    config::{self, BoxedSink},
+    event::{Event, LogEvent},
+    sinks::mezmo::{
+        MezmoConfig,
+        bench_support::{MezmoLegacyConfig, build_legacy_request, build_request},
+    },
+    sources,
+    test_util::{addr::next_addr, random_lines, runtime, send_lines, start_topology, wait_for_tcp},
+};
+
+fn benchmark_mezmo(c: &mut Criterion) {
+    let num_lines: usize = 1_000;
+    let line_size: usize = 100;
+
+    let (_guard_0, in_addr) = next_addr();
+    let (_guard_1, out_addr) = next_addr();
+
+    let _srv = serve(out_addr);
+
+    let mut group = c.benchmark_group("mezmo");
+    group.throughput(Throughput::Bytes((num_lines * line_size) as u64));
+    group.sampling_mode(SamplingMode::Flat);
+
+    // A single partition, and one partition per line, to cover both ends of the batching, with
+    // the stream-based sink and the `HttpSink` based sink it replaced.
+    for (partitioning, hostname) in [
+        ("single_partition", "vector"),
+        ("partitioned", "{{ message }}"),
+    ] {
+        for (implementation, legacy) in [("stream", false), ("legacy", true)] {
+            group.bench_function(format!("{partitioning}/{implementation}"), |b| {
+                b.iter_batched(
+                    || {
+                        let mut config = config::Config::builder();
+                        config.add_source(
+                            "in",
+                            sources::socket::SocketConfig::make_basic_tcp_config(in_addr),
+                        );
+
+                        let sink: MezmoConfig = toml::from_str(&format!(
+                            r#"
+                            api_key = "mylogtoken"
+                            endpoint = "http://{out_addr}"
+                            hostname = "{hostname}"
+                            batch.max_bytes = {}
+                            "#,
+                            num_lines * line_size
+                        ))
+                        .unwrap();
+                        let sink: BoxedSink = if legacy {
+                            MezmoLegacyConfig::from(sink).into()
+                        } else {
+                            sink.into()
+                        };
+                        config.add_sink("out", &["in"], sink);
+
+                        let rt = runtime();
+                        let topology = rt.block_on(async move {
+                            let (topology, _crash) =
+                                start_topology(config.build().unwrap(), false).await;
+                            wait_for_tcp(in_addr).await;
+                            topology
+                        });
+                        (rt, topology)
+                    },
+                    |(rt, topology)| {
+                        rt.block_on(async move {
+                            let lines = random_lines(line_size).take(num_lines);
+                            send_lines(in_addr, lines).await.unwrap();
+                            topology.stop().await;
+                        })
+                    },
+                    BatchSize::PerIteration,
+                )
+            });
+        }
+    }
+
+    group.finish();
+}
+
+fn benchmark_mezmo_request(c: &mut Criterion) {
+    let num_lines: usize = 1_000;
+    let line_size: usize = 100;
+
+    let config: MezmoConfig = toml::from_str(&format!(
+        r#"
+        api_key = "mylogtoken"
+        hostname = "vector"
+        batch.max_bytes = {}
+        "#,
+        num_lines * line_size * 2
+    ))
+    .unwrap();
+    let events: Vec<Event> = random_lines(line_size)
+        .take(num_lines)
+        .map(|line| Event::Log(LogEvent::from(line)))
+        .collect();
+
+    let mut group = c.benchmark_group("mezmo/request");
+    group.throughput(Throughput::Bytes((num_lines * line_size) as u64));
+
+    // The request built from a batch of events by the `HttpSink` based sink, next to the one
+    // built by the stream-based sink.
+    let rt = runtime();
+    group.bench_function("legacy", |b| {
+        b.iter_batched(
+            || events.clone(),
+            |events| rt.block_on(build_legacy_request(&config, events)),
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("stream", |b| {
+        b.iter_batched(
+            || events.clone(),
+            |events| build_request(&config, events),
+            BatchSize::SmallInput,
+        )
+    });
+
+    group.finish();
+}
+
+fn serve(addr: SocketAddr) -> Runtime {
+    let rt = runtime();
+    rt.spawn(async move {
+        let make_service = make_service_fn(|_| async {
+            Ok::<_, Error>(service_fn(|_req| async {
+                Ok::<_, Error>(Response::new(Body::empty()))
+            }))
+        });
+
+        Server::bind(&addr)
+            .serve(make_service)
+            .map_err(|e| panic!("{}", e))
+            .await
+    });
+    rt
+}
+
+criterion_group!(benches, benchmark_mezmo, benchmark_mezmo_request);
+criterion_main!(benches);
@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
 use vector_core::config::LogNamespace;
 
-use crate::decoding::{Decoder, DeserializerConfig, FramingConfig};
+use crate::{
+    decoding::{Decoder, DeserializerConfig, FramingConfig},
+    schema_registry::SchemaRegistryConfig,
+};
 
 /// Config used to build a `Decoder`.
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -12,6 +15,9 @@ pub struct DecodingConfig {
     decoding: DeserializerConfig,
     /// The namespace used when decoding.
     log_namespace: LogNamespace,
+    /// The schema registry the decoded messages refer to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    schema_registry: Option<SchemaRegistryConfig>,
 }
 
 impl DecodingConfig {
@@ -26,9 +32,17 @@ impl DecodingConfig {
             framing,
             decoding,
             log_namespace,
+            schema_registry: None,
         }
     }
 
+    /// Decodes messages in the Confluent wire format, using the schemas of the given schema
+    /// registry.
+    pub fn with_schema_registry(mut self, schema_registry: Option<SchemaRegistryConfig>) -> Self {
+        self.schema_registry = schema_registry;
+        self
+    }
+
     /// Get the decoding configuration.
     pub const fn config(&self) -> &DeserializerConfig {
         &self.decoding
@@ -45,7 +59,12 @@ impl DecodingConfig {
         let framer = self.framing.build();
 
         // Build the deserializer.
-        let deserializer = self.decoding.build()?;
+        let deserializer = match &self.schema_registry {
+            Some(schema_registry) => self
+                .decoding
+                .build_with_schema_registry(schema_registry.build()?)?,
+            None => self.decoding.build()?,
+        };
 
         Ok(Decoder::new(framer, deserializer).with_log_namespace(self.log_namespace))
     }
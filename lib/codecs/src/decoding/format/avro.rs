@@ -12,14 +12,17 @@ use vector_core::{
 use vrl::value::KeyString;
 
 use super::Deserializer;
-use crate::encoding::AvroSerializerOptions;
+use crate::{
+    encoding::AvroSerializerOptions,
+    schema_registry::{
+        SchemaRegistry,
+        wire_format::{MAGIC_BYTE, SCHEMA_ID_PREFIX_LEN, decode_schema_id},
+    },
+};
 
 type VrlValue = vrl::value::Value;
 type AvroValue = apache_avro::types::Value;
 
-const CONFLUENT_MAGIC_BYTE: u8 = 0;
-const CONFLUENT_SCHEMA_PREFIX_LEN: usize = 5;
-
 /// Config used to build a `AvroDeserializer`.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AvroDeserializerConfig {
@@ -40,15 +43,42 @@ impl AvroDeserializerConfig {
 
     /// Build the `AvroDeserializer` from this configuration.
     pub fn build(&self) -> vector_common::Result<AvroDeserializer> {
-        let schema = apache_avro::Schema::parse_str(&self.avro_options.schema)
-            .map_err(|error| format!("Failed building Avro serializer: {error}"))?;
+        if self.avro_options.schema.is_empty() {
+            return Err("An Avro schema is required when no schema registry is configured".into());
+        }
+
+        Ok(AvroDeserializer {
+            schema: Some(self.parse_schema()?),
+            strip_schema_id_prefix: self.avro_options.strip_schema_id_prefix,
+            schema_registry: None,
+        })
+    }
+
+    /// Build an `AvroDeserializer` decoding messages in the Confluent wire format, whose writer
+    /// schema is fetched from the given schema registry. The configured schema, if any, is used
+    /// as the reader schema.
+    pub fn build_with_schema_registry(
+        &self,
+        schema_registry: SchemaRegistry,
+    ) -> vector_common::Result<AvroDeserializer> {
+        let schema = if self.avro_options.schema.is_empty() {
+            None
+        } else {
+            Some(self.parse_schema()?)
+        };
 
         Ok(AvroDeserializer {
             schema,
             strip_schema_id_prefix: self.avro_options.strip_schema_id_prefix,
+            schema_registry: Some(schema_registry),
         })
     }
 
+    fn parse_schema(&self) -> vector_common::Result<apache_avro::Schema> {
+        apache_avro::Schema::parse_str(&self.avro_options.schema)
+            .map_err(|error| format!("Failed building Avro deserializer: {error}").into())
+    }
+
     /// The data type of events that are accepted by `AvroDeserializer`.
     pub fn output_type(&self) -> DataType {
         DataType::Log
@@ -87,7 +117,7 @@ impl From<&AvroDeserializerOptions> for AvroSerializerOptions {
 }
 /// Apache Avro serializer options.
 #[configurable_component]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct AvroDeserializerOptions {
     /// The Avro schema definition.
     /// **Note**: The following [`apache_avro::types::Value`] variants are *not* supported:
@@ -96,6 +126,9 @@ pub struct AvroDeserializerOptions {
     /// * `Duration`
     /// * `Fixed`
     /// * `TimeMillis`
+    ///
+    /// When a schema registry is configured, this is the reader schema the registered schemas are
+    /// resolved to.
     #[configurable(metadata(
         docs::examples = r#"{ "type": "record", "name": "log", "fields": [{ "name": "message", "type": "string" }] }"#,
         docs::additional_props_description = r#"Supports most avro data types, unsupported data types includes
@@ -111,17 +144,30 @@ pub struct AvroDeserializerOptions {
 /// Serializer that converts bytes to an `Event` using the Apache Avro format.
 #[derive(Debug, Clone)]
 pub struct AvroDeserializer {
-    schema: apache_avro::Schema,
+    /// The schema of the messages, or the reader schema when a schema registry is used.
+    schema: Option<apache_avro::Schema>,
     strip_schema_id_prefix: bool,
+    schema_registry: Option<SchemaRegistry>,
 }
 
 impl AvroDeserializer {
     /// Creates a new `AvroDeserializer`.
     pub const fn new(schema: apache_avro::Schema, strip_schema_id_prefix: bool) -> Self {
         Self {
-            schema,
+            schema: Some(schema),
             strip_schema_id_prefix,
+            schema_registry: None,
+        }
+    }
+
+    /// Fetches the writer schema of a message from the schema registry, so that the message can
+    /// then be parsed without waiting for it.
+    pub async fn fetch_schema(&self, mut message: &[u8]) -> vector_common::Result<()> {
+        if let Some(schema_registry) = &self.schema_registry {
+            let schema_id = decode_schema_id(&mut message)?;
+            schema_registry.avro_schema(schema_id).await?;
         }
+        Ok(())
     }
 }
 
@@ -136,20 +182,34 @@ impl Deserializer for AvroDeserializer {
             return Ok(smallvec![]);
         }
 
-        let bytes = if self.strip_schema_id_prefix {
-            if bytes.len() >= CONFLUENT_SCHEMA_PREFIX_LEN && bytes[0] == CONFLUENT_MAGIC_BYTE {
-                bytes.slice(CONFLUENT_SCHEMA_PREFIX_LEN..)
-            } else {
-                return Err(vector_common::Error::from(
-                    "Expected avro datum to be prefixed with schema id",
-                ));
+        let value = match (&self.schema_registry, &self.schema) {
+            (Some(schema_registry), reader_schema) => {
+                let mut bytes = bytes;
+                let schema_id = decode_schema_id(&mut bytes)?;
+                let writer_schema = schema_registry.fetched_avro_schema(schema_id)?;
+                apache_avro::from_avro_datum(
+                    &writer_schema,
+                    &mut bytes.reader(),
+                    reader_schema.as_ref(),
+                )?
             }
-        } else {
-            bytes
+            (None, Some(schema)) => {
+                let bytes = if self.strip_schema_id_prefix {
+                    if bytes.len() >= SCHEMA_ID_PREFIX_LEN && bytes[0] == MAGIC_BYTE {
+                        bytes.slice(SCHEMA_ID_PREFIX_LEN..)
+                    } else {
+                        return Err(vector_common::Error::from(
+                            "Expected avro datum to be prefixed with schema id",
+                        ));
+                    }
+                } else {
+                    bytes
+                };
+                apache_avro::from_avro_datum(schema, &mut bytes.reader(), None)?
+            }
+            (None, None) => unreachable!("an Avro deserializer has a schema or a schema registry"),
         };
 
-        let value = apache_avro::from_avro_datum(&self.schema, &mut bytes.reader(), None)?;
-
         let apache_avro::types::Value::Record(fields) = value else {
             return Err(vector_common::Error::from("Expected an avro Record"));
         };
@@ -246,15 +306,27 @@ pub fn try_from(value: AvroValue) -> vector_common::Result<VrlValue> {
 mod tests {
     use apache_avro::Schema;
     use bytes::BytesMut;
+    use serde_json::json;
     use uuid::Uuid;
 
     use super::*;
+    use crate::schema_registry::{
+        SchemaRegistryConfig,
+        tests::{AVRO_SCHEMA, mock_registry},
+        wire_format::encode_schema_id,
+    };
 
     #[derive(Debug, Clone, Serialize, Deserialize)]
     struct Log {
         message: String,
     }
 
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct LogWithCount {
+        message: String,
+        count: i64,
+    }
+
     fn get_schema() -> Schema {
         let schema = String::from(
             r#"{
@@ -348,4 +420,69 @@ mod tests {
             &VrlValue::from(uuid)
         );
     }
+
+    #[tokio::test]
+    async fn deserialize_avro_schema_registry() {
+        let server = mock_registry(&[(7, json!({"schema": AVRO_SCHEMA}))]).await;
+        let schema_registry = SchemaRegistryConfig::new(server.uri()).build().unwrap();
+
+        let writer_schema = Schema::parse_str(AVRO_SCHEMA).unwrap();
+        let record_value = apache_avro::to_value(LogWithCount {
+            message: "hello from the registry".to_owned(),
+            count: 3,
+        })
+        .unwrap();
+        let mut bytes = BytesMut::new();
+        encode_schema_id(7, &mut bytes);
+        bytes.extend(apache_avro::to_avro_datum(&writer_schema, record_value).unwrap());
+        let bytes = bytes.freeze();
+
+        let deserializer = AvroDeserializerConfig::new(String::new(), false)
+            .build_with_schema_registry(schema_registry.clone())
+            .unwrap();
+        // The schema must have been fetched before parsing.
+        assert!(
+            deserializer
+                .parse(bytes.clone(), LogNamespace::Vector)
+                .is_err()
+        );
+        deserializer.fetch_schema(&bytes).await.unwrap();
+        let events = deserializer
+            .parse(bytes.clone(), LogNamespace::Vector)
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        let log = events[0].as_log();
+        assert_eq!(
+            log.get("message").unwrap(),
+            &VrlValue::from("hello from the registry")
+        );
+        assert_eq!(log.get("count").unwrap(), &VrlValue::from(3_i64));
+
+        // The configured schema is the reader schema.
+        let deserializer = AvroDeserializerConfig::new(get_schema().canonical_form(), false)
+            .build_with_schema_registry(schema_registry)
+            .unwrap();
+        let events = deserializer.parse(bytes, LogNamespace::Vector).unwrap();
+        let log = events[0].as_log();
+        assert_eq!(
+            log.get("message").unwrap(),
+            &VrlValue::from("hello from the registry")
+        );
+        assert!(log.get("count").is_none());
+
+        assert!(
+            deserializer
+                .parse(Bytes::from_static(b"not framed"), LogNamespace::Vector)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn build_avro_requires_schema() {
+        assert!(
+            AvroDeserializerConfig::new(String::new(), false)
+                .build()
+                .is_err()
+        );
+    }
 }
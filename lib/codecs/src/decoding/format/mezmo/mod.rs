@@ -5,6 +5,7 @@ use vector_core::{
 };
 
 use crate::decoding::FramingConfig;
+use open_telemetry::OpenTelemetryPayloadFormat;
 
 pub mod open_telemetry;
 mod prometheus_remote_write;
@@ -31,16 +32,42 @@ pub enum MezmoDeserializer {
 impl MezmoDeserializer {
     /// Build the Deserializer
     pub fn build(&self) -> Box<dyn crate::decoding::format::Deserializer> {
+        self.build_with_format(OpenTelemetryPayloadFormat::default())
+    }
+
+    /// Build the Deserializer for payloads of the given `Content-Type`
+    ///
+    /// The Open Telemetry deserializers accept OTLP/JSON payloads sent as `application/json`,
+    /// any other or missing content type is decoded as OTLP/protobuf.
+    pub fn build_for_content_type(
+        &self,
+        content_type: Option<&str>,
+    ) -> Box<dyn crate::decoding::format::Deserializer> {
+        self.build_with_format(
+            content_type
+                .and_then(OpenTelemetryPayloadFormat::from_content_type)
+                .unwrap_or_default(),
+        )
+    }
+
+    fn build_with_format(
+        &self,
+        format: OpenTelemetryPayloadFormat,
+    ) -> Box<dyn crate::decoding::format::Deserializer> {
         use MezmoDeserializer::*;
         match self {
             PrometheusRemoteWrite => {
                 Box::<prometheus_remote_write::PrometheusRemoteWriteDeserializer>::default()
             }
             OpenTelemetryMetrics => {
-                Box::<open_telemetry::OpenTelemetryMetricDeserializer>::default()
+                Box::new(open_telemetry::OpenTelemetryMetricDeserializer::new(format))
+            }
+            OpenTelemetryLogs => {
+                Box::new(open_telemetry::OpenTelemetryLogDeserializer::new(format))
+            }
+            OpenTelemetryTraces => {
+                Box::new(open_telemetry::OpenTelemetryTraceDeserializer::new(format))
             }
-            OpenTelemetryLogs => Box::<open_telemetry::OpenTelemetryLogDeserializer>::default(),
-            OpenTelemetryTraces => Box::<open_telemetry::OpenTelemetryTraceDeserializer>::default(),
         }
     }
 
@@ -0,0 +1,223 @@
+//! Conversion between the OTLP/JSON and OTLP/protobuf encodings of the OpenTelemetry requests
+//! and responses.
+//!
+//! OTLP/JSON is the proto3 JSON mapping of the OTLP messages, with the exception of the trace and
+//! span ids which are hex encoded rather than base64 encoded, and of the enums which are encoded
+//! as integers.
+//! See https://opentelemetry.io/docs/specs/otlp/#json-protobuf-encoding
+
+use std::sync::LazyLock;
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use opentelemetry_proto::proto::DESCRIPTOR_BYTES;
+use prost_reflect::{
+    DescriptorPool, DeserializeOptions, DynamicMessage, MessageDescriptor, SerializeOptions,
+    prost::Message,
+};
+use serde_json::Value as JsonValue;
+
+static DESCRIPTOR_POOL: LazyLock<DescriptorPool> = LazyLock::new(|| {
+    DescriptorPool::decode(DESCRIPTOR_BYTES).expect("OpenTelemetry descriptors should be valid")
+});
+
+/// The fields holding trace and span ids, which OTLP/JSON encodes as hex strings. Receivers
+/// should accept both the lowerCamelCase and the original field names.
+const ID_FIELDS: [&str; 6] = [
+    "traceId",
+    "spanId",
+    "parentSpanId",
+    "trace_id",
+    "span_id",
+    "parent_span_id",
+];
+
+/// OTLP/JSON conversion error list
+#[derive(Debug, snafu::Snafu)]
+pub enum OtlpJsonError {
+    /// The JSON payload isn't a valid OTLP message
+    #[snafu(display("Invalid OTLP/JSON payload: {source}"))]
+    Json {
+        /// The original error
+        source: serde_json::Error,
+    },
+    /// The protobuf payload isn't a valid OTLP message
+    #[snafu(display("Invalid OTLP/protobuf payload: {source}"))]
+    Protobuf {
+        /// The original error
+        source: prost_reflect::prost::DecodeError,
+    },
+}
+
+fn message_descriptor(message_type: &str) -> MessageDescriptor {
+    DESCRIPTOR_POOL
+        .get_message_by_name(message_type)
+        .unwrap_or_else(|| panic!("{message_type} should be an OpenTelemetry message"))
+}
+
+/// Applies `convert` to the id fields of all the objects found in `value`. Ids which can't be
+/// converted are left as they are.
+fn convert_ids(value: &mut JsonValue, convert: &impl Fn(&str) -> Option<String>) {
+    match value {
+        JsonValue::Object(object) => {
+            for (key, value) in object.iter_mut() {
+                match value {
+                    JsonValue::String(id) if ID_FIELDS.contains(&key.as_str()) => {
+                        if let Some(converted) = convert(id) {
+                            *id = converted;
+                        }
+                    }
+                    value => convert_ids(value, convert),
+                }
+            }
+        }
+        JsonValue::Array(values) => {
+            for value in values {
+                convert_ids(value, convert);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn hex_to_base64(id: &str) -> Option<String> {
+    let mut bytes = vec![0; id.len() / 2];
+    faster_hex::hex_decode(id.as_bytes(), &mut bytes).ok()?;
+    Some(BASE64.encode(bytes))
+}
+
+fn base64_to_hex(id: &str) -> Option<String> {
+    BASE64
+        .decode(id)
+        .ok()
+        .map(|bytes| faster_hex::hex_string(&bytes))
+}
+
+/// Converts an OTLP/JSON encoded message of the given type to its protobuf encoding.
+///
+/// Unknown fields are ignored, as required by the spec.
+pub fn json_to_protobuf(json: &[u8], message_type: &str) -> Result<Vec<u8>, OtlpJsonError> {
+    let mut value: JsonValue =
+        serde_json::from_slice(json).map_err(|source| OtlpJsonError::Json { source })?;
+    convert_ids(&mut value, &hex_to_base64);
+
+    let options = DeserializeOptions::new().deny_unknown_fields(false);
+    let message =
+        DynamicMessage::deserialize_with_options(message_descriptor(message_type), value, &options)
+            .map_err(|source| OtlpJsonError::Json { source })?;
+
+    Ok(message.encode_to_vec())
+}
+
+/// Converts a protobuf encoded message of the given type to its OTLP/JSON encoding.
+pub fn protobuf_to_json(protobuf: &[u8], message_type: &str) -> Result<Vec<u8>, OtlpJsonError> {
+    let message = DynamicMessage::decode(message_descriptor(message_type), protobuf)
+        .map_err(|source| OtlpJsonError::Protobuf { source })?;
+
+    let options = SerializeOptions::new().use_enum_numbers(true);
+    let mut value = message
+        .serialize_with_options(serde_json::value::Serializer, &options)
+        .map_err(|source| OtlpJsonError::Json { source })?;
+    convert_ids(&mut value, &base64_to_hex);
+
+    serde_json::to_vec(&value).map_err(|source| OtlpJsonError::Json { source })
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry_proto::proto::{
+        TRACES_REQUEST_MESSAGE_TYPE,
+        collector::trace::v1::ExportTraceServiceRequest,
+        common::v1::{AnyValue, KeyValue, any_value},
+        trace::v1::{ResourceSpans, ScopeSpans, Span, span::SpanKind},
+    };
+    use prost::Message;
+    use serde_json::json;
+
+    use super::*;
+
+    fn request() -> ExportTraceServiceRequest {
+        ExportTraceServiceRequest {
+            resource_spans: vec![ResourceSpans {
+                scope_spans: vec![ScopeSpans {
+                    spans: vec![Span {
+                        trace_id: vec![
+                            0x5b, 0x8e, 0xff, 0xf7, 0x98, 0x03, 0x81, 0x03, 0xd2, 0x69, 0xb6, 0x33,
+                            0x81, 0x3f, 0xc6, 0x0c,
+                        ],
+                        span_id: vec![0xee, 0xe1, 0x9b, 0x7e, 0xc3, 0xc1, 0xb1, 0x74],
+                        name: "checkout".to_owned(),
+                        kind: SpanKind::Server as i32,
+                        start_time_unix_nano: 1_579_134_612_000_000_011,
+                        attributes: vec![KeyValue {
+                            key: "traceId".to_owned(),
+                            value: Some(AnyValue {
+                                value: Some(any_value::Value::IntValue(42)),
+                            }),
+                        }],
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        }
+    }
+
+    #[test]
+    fn otlp_json_to_protobuf() {
+        let json = json!({
+            "resourceSpans": [{
+                "scopeSpans": [{
+                    "spans": [{
+                        "traceId": "5b8efff798038103d269b633813fc60c",
+                        "span_id": "eee19b7ec3c1b174",
+                        "name": "checkout",
+                        "kind": 2,
+                        "startTimeUnixNano": "1579134612000000011",
+                        "attributes": [{"key": "traceId", "value": {"intValue": "42"}}],
+                        "someUnknownField": true
+                    }]
+                }]
+            }]
+        });
+
+        let protobuf =
+            json_to_protobuf(json.to_string().as_bytes(), TRACES_REQUEST_MESSAGE_TYPE).unwrap();
+
+        assert_eq!(
+            ExportTraceServiceRequest::decode(&protobuf[..]).unwrap(),
+            request()
+        );
+    }
+
+    #[test]
+    fn otlp_protobuf_to_json() {
+        let json =
+            protobuf_to_json(&request().encode_to_vec(), TRACES_REQUEST_MESSAGE_TYPE).unwrap();
+
+        assert_eq!(
+            serde_json::from_slice::<JsonValue>(&json).unwrap(),
+            json!({
+                "resourceSpans": [{
+                    "scopeSpans": [{
+                        "spans": [{
+                            "traceId": "5b8efff798038103d269b633813fc60c",
+                            "spanId": "eee19b7ec3c1b174",
+                            "name": "checkout",
+                            "kind": 2,
+                            "startTimeUnixNano": "1579134612000000011",
+                            "attributes": [{"key": "traceId", "value": {"intValue": "42"}}]
+                        }]
+                    }]
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn otlp_json_invalid_payload() {
+        assert!(json_to_protobuf(b"{", TRACES_REQUEST_MESSAGE_TYPE).is_err());
+        assert!(json_to_protobuf(br#"{"resourceSpans": 1}"#, TRACES_REQUEST_MESSAGE_TYPE).is_err());
+        assert!(protobuf_to_json(b"\xff", TRACES_REQUEST_MESSAGE_TYPE).is_err());
+    }
+}
@@ -613,18 +613,61 @@ impl<'a> MetricArbitraryAccessor<'a> for HistogramMetricMetadata<'_> {
 }
 
 #[derive(Debug, PartialEq)]
-pub struct ExponentialHistogramMetricValue<'a> {
-    pub exp_histogram_metric: ExponentialHistogramDataPoint<'a>,
+pub struct ExponentialHistogramMetricValue {
+    pub count: u64,
+    pub sum: f64,
+    pub buckets: Vec<HistogramBucketValue>,
 }
 
-impl<'a> ExponentialHistogramMetricValue<'a> {
+impl<'a> ExponentialHistogramMetricValue {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
     fn new(exp_histogram_metric: ExponentialHistogramDataPoint<'a>) -> Self {
+        // The exponential buckets are converted into explicit ones, so the data point can be
+        // handled as a regular histogram by any metric sink. The original buckets are kept
+        // as arbitrary fields. The bucket at index `i` covers the (base^i, base^(i+1)] range,
+        // mirrored for the negative buckets.
+        // https://opentelemetry.io/docs/specs/otel/metrics/data-model/#exponentialhistogram
+        // base = 2^(2^-scale)
+        let base = (-f64::from(exp_histogram_metric.scale)).exp2().exp2();
+        let mut buckets: Vec<HistogramBucketValue> = Vec::new();
+
+        if let Some(negative) = &exp_histogram_metric.negative {
+            buckets.extend(negative.bucket_counts.iter().enumerate().rev().map(
+                |(index, count)| HistogramBucketValue {
+                    upper_limit: -base.powi(negative.offset + index as i32),
+                    count: *count,
+                },
+            ));
+        }
+
+        if exp_histogram_metric.zero_count > 0 {
+            buckets.push(HistogramBucketValue {
+                upper_limit: exp_histogram_metric.zero_threshold,
+                count: exp_histogram_metric.zero_count,
+            });
+        }
+
+        if let Some(positive) = &exp_histogram_metric.positive {
+            buckets.extend(
+                positive
+                    .bucket_counts
+                    .iter()
+                    .enumerate()
+                    .map(|(index, count)| HistogramBucketValue {
+                        upper_limit: base.powi(positive.offset + index as i32 + 1),
+                        count: *count,
+                    }),
+            );
+        }
+
         // TODO LOG-19828 It's not clear how to handle aggregation_temporality.delta flag.
         // Based on documentation we have to convert a data point from delta
         // to cumulative.
 
         ExponentialHistogramMetricValue {
-            exp_histogram_metric,
+            count: exp_histogram_metric.count,
+            sum: exp_histogram_metric.sum,
+            buckets,
         }
     }
 
@@ -633,17 +676,22 @@ impl<'a> ExponentialHistogramMetricValue<'a> {
     }
 }
 
-impl<'a> MetricValueAccessor<'a> for ExponentialHistogramMetricValue<'_> {
+impl<'a> MetricValueAccessor<'a> for ExponentialHistogramMetricValue {
     type ArrIter = std::array::IntoIter<&'a dyn IntoValue, 0>;
-    type ObjIter = std::array::IntoIter<(&'a dyn ToString, &'a dyn IntoValue), 0>;
+    type ObjIter = std::array::IntoIter<(&'a dyn ToString, &'a dyn IntoValue), 3>;
 
     fn metric_type(&'a self) -> Option<Cow<'a, str>> {
-        Some(Cow::from("exponential_histogram"))
+        Some(Cow::from("histogram"))
     }
 
     fn value(&'a self) -> MetricValueSerializable<'a, Self::ArrIter, Self::ObjIter> {
         MetricValueSerializable::Object(MetricValuePairs {
-            elements: [].into_iter(),
+            elements: [
+                (&"count" as &dyn ToString, &self.count as &dyn IntoValue),
+                (&"sum" as &dyn ToString, &self.sum as &dyn IntoValue),
+                (&"buckets" as &dyn ToString, &self.buckets as &dyn IntoValue),
+            ]
+            .into_iter(),
         })
     }
 }
@@ -762,6 +810,8 @@ pub struct ExponentialHistogramMetricMetadata<'a> {
     pub resource: &'a ResourceMetricValue<'a>,
     pub scope: ScopeMetricValue<'a>,
     pub attributes: OpenTelemetryKeyValue<'a>,
+    original_type: Cow<'a, str>,
+    data_provider: Cow<'a, str>,
 }
 
 impl<'a> ExponentialHistogramMetricMetadata<'a> {
@@ -776,16 +826,26 @@ impl<'a> ExponentialHistogramMetricMetadata<'a> {
             attributes: OpenTelemetryKeyValue {
                 attributes: exp_histogram_metric.attributes,
             },
+            original_type: Cow::from("exponential_histogram"),
+            data_provider: Cow::from("otlp"),
         }
     }
 }
 
 impl<'a> MetricArbitraryAccessor<'a> for ExponentialHistogramMetricMetadata<'_> {
-    type ObjIter = std::array::IntoIter<(&'a dyn ToString, &'a dyn IntoValue), 3>;
+    type ObjIter = std::array::IntoIter<(&'a dyn ToString, &'a dyn IntoValue), 5>;
 
     fn value(&'a self) -> MetricValuePairs<Self::ObjIter> {
         MetricValuePairs {
             elements: [
+                (
+                    &"original_type" as &dyn ToString,
+                    &self.original_type as &dyn IntoValue,
+                ),
+                (
+                    &"data_provider" as &dyn ToString,
+                    &self.data_provider as &dyn IntoValue,
+                ),
                 (
                     &"resource" as &dyn ToString,
                     self.resource as &dyn IntoValue,
@@ -1559,20 +1619,20 @@ pub fn to_events(metric_request: ExportMetricsServiceRequest) -> SmallVec<[Event
                                 ),
                             };
 
-                            let _mezmo_metric = MezmoMetric {
-                                name: metric.name.clone(),
-                                namespace: None,
-                                kind: metric_value.kind(),
-                                tags: Some(&tags),
-                                user_metadata: Some(&metric_metadata),
-                                arbitrary_data: Some(&metric_arbitrary),
-                                value: &metric_value,
-                            };
-
-                            // TODO LOG-19820 Exponential histogram has to be converted to
-                            // a native histogram to be able to be handled by any metric sinks.
-                            // For now we just skip this exponential histogram metrics.
-                            // out.push(make_event({mezmo_metric}.to_log_event()));
+                            out.push(make_event(
+                                {
+                                    MezmoMetric {
+                                        name: metric.name.clone(),
+                                        namespace: None,
+                                        kind: metric_value.kind(),
+                                        tags: Some(&tags),
+                                        user_metadata: Some(&metric_metadata),
+                                        arbitrary_data: Some(&metric_arbitrary),
+                                        value: &metric_value,
+                                    }
+                                }
+                                .to_log_event(),
+                            ));
                         })
                     }
                     MetricOneOfdata::summary(summary) => {
@@ -2800,23 +2860,17 @@ mod tests {
                                 ],
                                 start_time_unix_nano: 1_579_134_612_000_000_011,
                                 time_unix_nano: 1_579_134_612_000_000_011,
-                                count: 10,
+                                count: 22,
                                 sum: 3.7_f64,
-                                scale: 10,
+                                scale: 0,
                                 zero_count: 12,
                                 positive: Some(ExponentialHistogramDataPointBuckets {
                                     offset: 1,
-                                    bucket_counts: vec![
-                                        1_579_134_612_000_000_011,
-                                        9_223_372_036_854_775_807,
-                                    ],
+                                    bucket_counts: vec![1, 2],
                                 }),
                                 negative: Some(ExponentialHistogramDataPointBuckets {
                                     offset: 1,
-                                    bucket_counts: vec![
-                                        1_579_134_612_000_000_011,
-                                        9_223_372_036_854_775_807,
-                                    ],
+                                    bucket_counts: vec![3, 4],
                                 }),
                                 flags: 1,
                                 exemplars: vec![Exemplar {
@@ -2831,7 +2885,7 @@ mod tests {
                                 }],
                                 min: 0.1_f64,
                                 max: 9.9_f64,
-                                zero_threshold: 3.3_f64,
+                                zero_threshold: 0.5_f64,
                             }],
                             aggregation_temporality:
                                 AggregationTemporality::AGGREGATION_TEMPORALITY_CUMULATIVE,
@@ -2844,7 +2898,91 @@ mod tests {
         };
 
         let metrics = to_events(metrics_data.clone());
-        assert!(metrics.is_empty());
+        assert_eq!(metrics.len(), 1);
+
+        let log = metrics[0].clone().into_log();
+        let message = log.value().get("message").unwrap();
+
+        assert_eq!(
+            *message.get("value.value").unwrap(),
+            Value::Object(BTreeMap::from([
+                ("count".into(), Value::Integer(22)),
+                ("sum".into(), from_f64_or_zero(3.7)),
+                (
+                    "buckets".into(),
+                    Value::Array(Vec::from([
+                        Value::Object(btreemap! {
+                            "upper_limit" => -4.0,
+                            "count" => 4,
+                        }),
+                        Value::Object(btreemap! {
+                            "upper_limit" => -2.0,
+                            "count" => 3,
+                        }),
+                        Value::Object(btreemap! {
+                            "upper_limit" => 0.5,
+                            "count" => 12,
+                        }),
+                        Value::Object(btreemap! {
+                            "upper_limit" => 4.0,
+                            "count" => 1,
+                        }),
+                        Value::Object(btreemap! {
+                            "upper_limit" => 8.0,
+                            "count" => 2,
+                        }),
+                    ]))
+                ),
+            ]))
+        );
+        assert_eq!(*message.get("value.type").unwrap(), "histogram".into());
+        assert_eq!(*message.get("value.scale").unwrap(), Value::Integer(0));
+        assert_eq!(
+            *message.get("value.positive").unwrap(),
+            Value::Object(btreemap! {
+                "offset" => 1,
+                "bucket_counts" => Value::Array(vec![Value::Integer(1), Value::Integer(2)]),
+            })
+        );
+
+        let metadata = log.value().get("metadata").unwrap();
+        assert_eq!(
+            *metadata.get("original_type").unwrap(),
+            "exponential_histogram".into()
+        );
+        assert_eq!(*metadata.get("data_provider").unwrap(), "otlp".into());
+
+        let metric = to_metric(&log).expect("Failed to convert log to metric");
+
+        assert_eq!(
+            metric.value(),
+            &MetricValue::AggregatedHistogram {
+                buckets: vec![
+                    Bucket {
+                        upper_limit: -4.0,
+                        count: 4
+                    },
+                    Bucket {
+                        upper_limit: -2.0,
+                        count: 3
+                    },
+                    Bucket {
+                        upper_limit: 0.5,
+                        count: 12
+                    },
+                    Bucket {
+                        upper_limit: 4.0,
+                        count: 1
+                    },
+                    Bucket {
+                        upper_limit: 8.0,
+                        count: 2
+                    },
+                ],
+                count: 22,
+                sum: 3.7,
+            }
+        );
     }
 
     #[test]
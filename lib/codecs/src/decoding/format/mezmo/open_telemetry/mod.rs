@@ -1,9 +1,15 @@
+#[cfg(feature = "opentelemetry")]
+mod json;
 mod log_parser;
 mod metric_parser;
 mod trace_parser;
 
 use bytes::Bytes;
 use chrono::{DateTime, Utc};
+#[cfg(feature = "opentelemetry")]
+use opentelemetry_proto::proto::{
+    LOGS_REQUEST_MESSAGE_TYPE, METRICS_REQUEST_MESSAGE_TYPE, TRACES_REQUEST_MESSAGE_TYPE,
+};
 use opentelemetry_rs::opentelemetry::common::{AnyValue, AnyValueOneOfvalue, KeyValue};
 use std::borrow::Cow;
 use std::cell::Cell;
@@ -29,6 +35,9 @@ use vrl::value::kind::Collection;
 
 use opentelemetry_rs::Error as OpenTelemetryError;
 
+#[cfg(feature = "opentelemetry")]
+pub use json::{OtlpJsonError, json_to_protobuf, protobuf_to_json};
+
 thread_local! {
     static OTLP_REQUEST_COUNTER: Cell<u64> = const { Cell::new(0) };
 }
@@ -71,13 +80,94 @@ pub enum DeserializerError {
         /// The original error
         source: OpenTelemetryError,
     },
+    /// OTLP/JSON parser error
+    #[cfg(feature = "opentelemetry")]
+    JsonParseError {
+        /// The original error
+        source: OtlpJsonError,
+    },
+}
+
+/// The encoding of an OTLP payload
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OpenTelemetryPayloadFormat {
+    /// OTLP/protobuf, the binary protobuf encoding
+    #[default]
+    Protobuf,
+
+    /// OTLP/JSON, the JSON protobuf encoding
+    #[cfg(feature = "opentelemetry")]
+    Json,
+}
+
+impl OpenTelemetryPayloadFormat {
+    /// The payload format of the given `Content-Type`, if it's one of the OTLP/HTTP ones. OTLP/JSON
+    /// is only supported with the `opentelemetry` feature.
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let media_type = content_type.split(';').next().unwrap_or_default().trim();
+        #[cfg(feature = "opentelemetry")]
+        if media_type.eq_ignore_ascii_case("application/json") {
+            return Some(Self::Json);
+        }
+
+        if media_type.eq_ignore_ascii_case("application/x-protobuf")
+            || media_type.eq_ignore_ascii_case("application/protobuf")
+        {
+            Some(Self::Protobuf)
+        } else {
+            None
+        }
+    }
+
+    /// The `Content-Type` of the payloads in this format
+    pub const fn content_type(self) -> &'static str {
+        match self {
+            Self::Protobuf => "application/x-protobuf",
+            #[cfg(feature = "opentelemetry")]
+            Self::Json => "application/json",
+        }
+    }
+
+    /// Converts a payload in this format holding a message of the given type to its protobuf
+    /// encoding
+    #[cfg(feature = "opentelemetry")]
+    pub fn payload_to_protobuf(
+        self,
+        payload: Bytes,
+        message_type: &str,
+    ) -> Result<Bytes, OtlpJsonError> {
+        match self {
+            Self::Protobuf => Ok(payload),
+            Self::Json => json_to_protobuf(&payload, message_type).map(Bytes::from),
+        }
+    }
+
+    /// Converts a protobuf encoded message of the given type to a payload in this format
+    #[cfg(feature = "opentelemetry")]
+    pub fn protobuf_to_payload(
+        self,
+        protobuf: Vec<u8>,
+        message_type: &str,
+    ) -> Result<Vec<u8>, OtlpJsonError> {
+        match self {
+            Self::Protobuf => Ok(protobuf),
+            Self::Json => protobuf_to_json(&protobuf, message_type),
+        }
+    }
 }
 
 /// The OpenTelemetry metrics deserializer
 #[derive(Clone, Debug, Default)]
-pub struct OpenTelemetryMetricDeserializer;
+pub struct OpenTelemetryMetricDeserializer {
+    format: OpenTelemetryPayloadFormat,
+}
 
 impl OpenTelemetryMetricDeserializer {
+    /// Creates a deserializer for payloads in the given format
+    pub const fn new(format: OpenTelemetryPayloadFormat) -> Self {
+        Self { format }
+    }
+
     /// Output type of the Deserializer
     ///
     /// OpenTelemetryMetricDeserializer returns vector Log types encoding
@@ -117,15 +207,30 @@ impl Deserializer for OpenTelemetryMetricDeserializer {
         // See lib/vector-core/src/event/metric/mezmo.rs from_metric for an
         // example of converting the internal vector metric format
 
+        let bytes = match self.format {
+            OpenTelemetryPayloadFormat::Protobuf => bytes,
+            #[cfg(feature = "opentelemetry")]
+            format @ OpenTelemetryPayloadFormat::Json => format
+                .payload_to_protobuf(bytes, METRICS_REQUEST_MESSAGE_TYPE)
+                .map_err(|source| DeserializerError::JsonParseError { source })?,
+        };
+
         metric_parser::parse_metrics_request(&bytes[..])
     }
 }
 
 /// The OpenTelemetry logs deserializer
 #[derive(Clone, Debug, Default)]
-pub struct OpenTelemetryLogDeserializer;
+pub struct OpenTelemetryLogDeserializer {
+    format: OpenTelemetryPayloadFormat,
+}
 
 impl OpenTelemetryLogDeserializer {
+    /// Creates a deserializer for payloads in the given format
+    pub const fn new(format: OpenTelemetryPayloadFormat) -> Self {
+        Self { format }
+    }
+
     /// Output type of the Deserializer
     ///
     /// OpenTelemetryLogDeserializer returns vector Log types encoding
@@ -162,15 +267,30 @@ impl Deserializer for OpenTelemetryLogDeserializer {
     ) -> vector_common::Result<SmallVec<[Event; 1]>> {
         // Convert Open Telemetry write request logs into vector_core::event::LogEvent
 
+        let bytes = match self.format {
+            OpenTelemetryPayloadFormat::Protobuf => bytes,
+            #[cfg(feature = "opentelemetry")]
+            format @ OpenTelemetryPayloadFormat::Json => format
+                .payload_to_protobuf(bytes, LOGS_REQUEST_MESSAGE_TYPE)
+                .map_err(|source| DeserializerError::JsonParseError { source })?,
+        };
+
         log_parser::parse_logs_request(&bytes[..])
     }
 }
 
 /// The OpenTelemetry traces deserializer
 #[derive(Clone, Debug, Default)]
-pub struct OpenTelemetryTraceDeserializer;
+pub struct OpenTelemetryTraceDeserializer {
+    format: OpenTelemetryPayloadFormat,
+}
 
 impl OpenTelemetryTraceDeserializer {
+    /// Creates a deserializer for payloads in the given format
+    pub const fn new(format: OpenTelemetryPayloadFormat) -> Self {
+        Self { format }
+    }
+
     /// Output type of the Deserializer
     ///
     /// OpenTelemetryTraceDeserializer returns vector Trace types encoding
@@ -207,6 +327,14 @@ impl Deserializer for OpenTelemetryTraceDeserializer {
     ) -> vector_common::Result<SmallVec<[Event; 1]>> {
         // Convert Open Telemetry write request traces into vector_core::event::LogEvent
 
+        let bytes = match self.format {
+            OpenTelemetryPayloadFormat::Protobuf => bytes,
+            #[cfg(feature = "opentelemetry")]
+            format @ OpenTelemetryPayloadFormat::Json => format
+                .payload_to_protobuf(bytes, TRACES_REQUEST_MESSAGE_TYPE)
+                .map_err(|source| DeserializerError::JsonParseError { source })?,
+        };
+
         trace_parser::parse_traces_request(&bytes[..])
     }
 }
@@ -279,3 +407,168 @@ impl IntoValue for OpenTelemetryAnyValue<'_> {
         }
     }
 }
+
+#[cfg(all(test, feature = "opentelemetry"))]
+mod tests {
+    use super::*;
+    use crate::decoding::MezmoDeserializer;
+
+    fn parse_json(deserializer: MezmoDeserializer, json: &str) -> SmallVec<[Event; 1]> {
+        deserializer
+            .build_for_content_type(Some("application/json; charset=utf-8"))
+            .parse(
+                Bytes::copy_from_slice(json.as_bytes()),
+                LogNamespace::Legacy,
+            )
+            .expect("Failed to parse")
+    }
+
+    #[test]
+    fn otlp_payload_format_from_content_type() {
+        assert_eq!(
+            OpenTelemetryPayloadFormat::from_content_type("application/json"),
+            Some(OpenTelemetryPayloadFormat::Json)
+        );
+        assert_eq!(
+            OpenTelemetryPayloadFormat::from_content_type("Application/JSON; charset=utf-8"),
+            Some(OpenTelemetryPayloadFormat::Json)
+        );
+        assert_eq!(
+            OpenTelemetryPayloadFormat::from_content_type("application/x-protobuf"),
+            Some(OpenTelemetryPayloadFormat::Protobuf)
+        );
+        assert_eq!(
+            OpenTelemetryPayloadFormat::from_content_type("text/plain"),
+            None
+        );
+    }
+
+    #[test]
+    fn otlp_json_logs_deserialize() {
+        let logs = parse_json(
+            MezmoDeserializer::OpenTelemetryLogs,
+            r#"{
+                "resourceLogs": [{
+                    "resource": {
+                        "attributes": [{"key": "service.name", "value": {"stringValue": "web"}}]
+                    },
+                    "scopeLogs": [{
+                        "logRecords": [{
+                            "timeUnixNano": "1579134612000000011",
+                            "severityNumber": 9,
+                            "severityText": "INFO",
+                            "traceId": "5b8efff798038103d269b633813fc60c",
+                            "spanId": "eee19b7ec3c1b174",
+                            "body": {"stringValue": "hello"}
+                        }]
+                    }]
+                }]
+            }"#,
+        );
+
+        assert_eq!(logs.len(), 1);
+        let log = logs[0].clone().into_log();
+        assert_eq!(*log.get("message").unwrap(), Value::from("hello"));
+        assert_eq!(
+            *log.get("metadata.trace_id").unwrap(),
+            Value::from("5b8efff798038103d269b633813fc60c")
+        );
+        assert_eq!(
+            *log.get("metadata.span_id").unwrap(),
+            Value::from("eee19b7ec3c1b174")
+        );
+        assert_eq!(
+            *log.get("metadata.severity_number").unwrap(),
+            Value::from(9)
+        );
+        assert_eq!(
+            *log.get("metadata.resource.attributes.\"service.name\"")
+                .unwrap(),
+            Value::from("web")
+        );
+    }
+
+    #[test]
+    fn otlp_json_metrics_deserialize() {
+        let metrics = parse_json(
+            MezmoDeserializer::OpenTelemetryMetrics,
+            r#"{
+                "resourceMetrics": [{
+                    "scopeMetrics": [{
+                        "metrics": [{
+                            "name": "queue",
+                            "gauge": {
+                                "dataPoints": [{
+                                    "timeUnixNano": "1579134612000000011",
+                                    "asDouble": 3.5
+                                }]
+                            }
+                        }]
+                    }]
+                }]
+            }"#,
+        );
+
+        assert_eq!(metrics.len(), 1);
+        let log = metrics[0].clone().into_log();
+        assert_eq!(
+            *log.get("message.value.type").unwrap(),
+            Value::from("gauge")
+        );
+        assert_eq!(*log.get("message.value.value").unwrap(), Value::from(3.5));
+    }
+
+    #[test]
+    fn otlp_json_traces_deserialize() {
+        let traces = parse_json(
+            MezmoDeserializer::OpenTelemetryTraces,
+            r#"{
+                "resourceSpans": [{
+                    "scopeSpans": [{
+                        "spans": [{
+                            "traceId": "5b8efff798038103d269b633813fc60c",
+                            "spanId": "eee19b7ec3c1b174",
+                            "parentSpanId": "eee19b7ec3c1b173",
+                            "name": "checkout",
+                            "kind": 2,
+                            "startTimeUnixNano": "1579134612000000011",
+                            "endTimeUnixNano": "1579134612000000012"
+                        }]
+                    }]
+                }]
+            }"#,
+        );
+
+        assert_eq!(traces.len(), 1);
+        let trace = traces[0].clone().into_log();
+        assert_eq!(*trace.get("message.name").unwrap(), Value::from("checkout"));
+        assert_eq!(
+            *trace.get("message.trace_id").unwrap(),
+            Value::from("5b8efff798038103d269b633813fc60c")
+        );
+        assert_eq!(
+            *trace.get("message.parent_span_id").unwrap(),
+            Value::from("eee19b7ec3c1b173")
+        );
+        assert_eq!(*trace.get("message.kind").unwrap(), Value::from(2));
+    }
+
+    #[test]
+    fn otlp_content_type_selects_format() {
+        // An empty payload is an empty protobuf request, but isn't valid JSON
+        for content_type in [None, Some("text/plain"), Some("application/x-protobuf")] {
+            let events = MezmoDeserializer::OpenTelemetryLogs
+                .build_for_content_type(content_type)
+                .parse(Bytes::new(), LogNamespace::Legacy)
+                .unwrap();
+            assert!(events.is_empty());
+        }
+
+        assert!(
+            MezmoDeserializer::OpenTelemetryLogs
+                .build_for_content_type(Some("application/json"))
+                .parse(Bytes::new(), LogNamespace::Legacy)
+                .is_err()
+        );
+    }
+}
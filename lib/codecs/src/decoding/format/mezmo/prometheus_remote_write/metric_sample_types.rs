@@ -3,7 +3,8 @@ use std::borrow::Cow;
 use vrl::value::Value;
 
 use vector_core::event::metric::mezmo::{
-    IntoValue, MetricValueAccessor, MetricValuePairs, MetricValueSerializable, from_f64_or_zero,
+    IntoValue, MetricArbitraryAccessor, MetricValueAccessor, MetricValuePairs,
+    MetricValueSerializable, from_f64_or_zero,
 };
 
 #[derive(Debug, Default, PartialEq)]
@@ -101,6 +102,25 @@ impl<'a> MetricValueAccessor<'a> for HistogramMetricValue {
     }
 }
 
+/// Named values added to the metric value, or kept as the user metadata of its event.
+#[derive(Debug, Default, PartialEq)]
+pub struct NamedValues(pub Vec<(&'static str, Value)>);
+
+impl<'a> MetricArbitraryAccessor<'a> for NamedValues {
+    type ObjIter = std::iter::Map<
+        std::slice::Iter<'a, (&'static str, Value)>,
+        fn(&'a (&'static str, Value)) -> (&'a dyn ToString, &'a dyn IntoValue),
+    >;
+
+    fn value(&'a self) -> MetricValuePairs<Self::ObjIter> {
+        MetricValuePairs {
+            elements: self.0.iter().map(|pair: &'a (&'static str, Value)| {
+                (&pair.0 as &'a dyn ToString, &pair.1 as &'a dyn IntoValue)
+            }),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Counter;
 #[derive(Debug)]
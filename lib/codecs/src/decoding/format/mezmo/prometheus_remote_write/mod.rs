@@ -1,5 +1,7 @@
 mod metric_sample_types;
+mod native_histogram;
 mod parser;
+mod v2;
 
 use bytes::Bytes;
 
@@ -28,6 +30,10 @@ pub enum DeserializerError {
         #[snafu(source)]
         source: prometheus_remote_write::Error,
     },
+    RemoteWriteV2 {
+        #[snafu(source)]
+        source: quick_protobuf::Error,
+    },
 }
 
 #[derive(Clone, Debug, Default)]
@@ -61,6 +67,36 @@ impl PrometheusRemoteWriteDeserializer {
     pub const fn content_type(_framer: &FramingConfig) -> &'static str {
         "text/plain"
     }
+
+    /// Parses a Remote-Write 2.0 request, whose series carry their own metadata.
+    fn parse_v2(&self, bytes: &[u8]) -> vector_common::Result<SmallVec<[Event; 1]>> {
+        let request = v2::Request::try_from(bytes)
+            .map_err(|source| DeserializerError::RemoteWriteV2 { source })?;
+
+        let (mut timeseries, families) = request
+            .into_timeseries()
+            .map_err(|source| DeserializerError::Parse { source })?;
+
+        if !families.is_empty() {
+            let mut metadata_cache = self
+                .metadata_cache
+                .write()
+                .unwrap_or_else(|e| e.into_inner());
+            for (metric_family_name, type_pb) in families {
+                metadata_cache.insert(metric_family_name.into(), type_pb);
+            }
+        }
+
+        let metric_types_lookup = self
+            .metadata_cache
+            .read()
+            .unwrap_or_else(|e| e.into_inner());
+
+        Ok(
+            parser::parse_write_req(&mut timeseries, &metric_types_lookup)
+                .map_err(|source| DeserializerError::Parse { source })?,
+        )
+    }
 }
 // import prometheus remote write types
 impl Deserializer for PrometheusRemoteWriteDeserializer {
@@ -78,6 +114,10 @@ impl Deserializer for PrometheusRemoteWriteDeserializer {
 
         let bytes = Decoder::new().decompress_vec(&bytes[..])?;
 
+        if v2::is_v2_request(&bytes) {
+            return self.parse_v2(&bytes);
+        }
+
         let mut write_req = WriteRequest::try_from(&bytes[..])
             .map_err(|source| DeserializerError::Protobuf { source })?;
 
@@ -121,6 +161,173 @@ mod test {
 
     use std::borrow::Cow;
 
+    use smallvec::SmallVec;
+    use vector_core::event::Event;
+    use vrl::value;
+
+    /// Snappy compresses an uncompressed request, as sent by Prometheus.
+    fn parse_request(
+        deser: &PrometheusRemoteWriteDeserializer,
+        request: &[u8],
+    ) -> SmallVec<[Event; 1]> {
+        let out = snap::raw::Encoder::new()
+            .compress_vec(request)
+            .expect("Failed to compress");
+        deser
+            .parse(out.into(), vector_core::config::LogNamespace::Legacy)
+            .expect("Failed to parse")
+    }
+
+    fn assert_native_histogram(event: &Event) {
+        let log = event.as_log();
+        assert_eq!(
+            log.get(".message.name").unwrap(),
+            &value!("http_request_duration_seconds")
+        );
+        assert_eq!(log.get(".message.tags").unwrap(), &value!({"job": "api"}));
+        assert_eq!(
+            log.get(".message.value.type").unwrap(),
+            &value!("histogram")
+        );
+        assert_eq!(log.get(".message.value.value.count").unwrap(), &value!(6));
+        assert_eq!(log.get(".message.value.value.sum").unwrap(), &value!(11.5));
+
+        let buckets = log
+            .get(".message.value.value.bucket")
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|bucket| {
+                let bucket = bucket.as_object().unwrap();
+                (
+                    bucket["upper_limit"].as_float().unwrap().into_inner(),
+                    bucket["count"].as_integer().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            buckets,
+            vec![
+                (1e-128, 1),
+                (1.0, 3),
+                (2.0, 4),
+                (4.0, 4),
+                (8.0, 6),
+                (f64::INFINITY, 6),
+            ]
+        );
+
+        assert_eq!(log.get(".message.value.scale").unwrap(), &value!(0));
+        assert_eq!(log.get(".message.value.zero_count").unwrap(), &value!(1));
+        assert_eq!(
+            log.get(".message.value.positive").unwrap(),
+            &value!({"offset": -1, "bucket_counts": [2, 1, 0, 2]})
+        );
+        assert_eq!(
+            log.get(".message.value.negative").unwrap(),
+            &value!({"offset": 0, "bucket_counts": []})
+        );
+    }
+
+    #[test]
+    fn test_native_histogram_fixture() {
+        let deser = PrometheusRemoteWriteDeserializer::default();
+        let ret = parse_request(
+            &deser,
+            include_bytes!(
+                "../../../../../tests/data/mezmo/prometheus_remote_write/native_histogram_v1.pb"
+            ),
+        );
+
+        assert_eq!(ret.len(), 1);
+        assert_native_histogram(&ret[0]);
+
+        let log = ret[0].as_log();
+        assert_eq!(
+            log.get(".timestamp")
+                .unwrap()
+                .as_timestamp()
+                .unwrap()
+                .timestamp_millis(),
+            1_700_000_000_000
+        );
+        assert_eq!(
+            log.get(".metadata.exemplars[0].labels").unwrap(),
+            &value!({"trace_id": "abc123"})
+        );
+        assert_eq!(
+            log.get(".metadata.exemplars[0].value").unwrap(),
+            &value!(0.7)
+        );
+        assert_eq!(
+            log.get(".metadata.exemplars[0].timestamp").unwrap(),
+            log.get(".timestamp").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_remote_write_v2_fixture() {
+        let deser = PrometheusRemoteWriteDeserializer::default();
+        let ret = parse_request(
+            &deser,
+            include_bytes!("../../../../../tests/data/mezmo/prometheus_remote_write/request_v2.pb"),
+        );
+
+        // Native histograms come first, followed by the grouped samples by name
+        assert_eq!(ret.len(), 3);
+        assert_native_histogram(&ret[0]);
+
+        let counter = ret[1].as_log();
+        assert_eq!(
+            counter.get(".message.name").unwrap(),
+            &value!("http_requests_total")
+        );
+        assert_eq!(
+            counter.get(".message.tags").unwrap(),
+            &value!({"job": "api"})
+        );
+        assert_eq!(
+            counter.get(".message.value").unwrap(),
+            &value!({"type": "count", "value": 42.0})
+        );
+        assert_eq!(
+            counter.get(".metadata.exemplars[0].labels").unwrap(),
+            &value!({"trace_id": "abc123"})
+        );
+        assert_eq!(
+            counter.get(".metadata.exemplars[0].value").unwrap(),
+            &value!(1.0)
+        );
+
+        // The classic histogram series are grouped by their family from the series metadata
+        let histogram = ret[2].as_log();
+        assert_eq!(
+            histogram.get(".message.name").unwrap(),
+            &value!("rpc_duration_seconds")
+        );
+        assert_eq!(
+            histogram.get(".message.tags").unwrap(),
+            &value!({"job": "api"})
+        );
+        assert_eq!(
+            histogram.get(".message.value.type").unwrap(),
+            &value!("histogram")
+        );
+        assert_eq!(
+            histogram.get(".message.value.value.count").unwrap(),
+            &value!(5)
+        );
+        assert_eq!(
+            histogram.get(".message.value.value.sum").unwrap(),
+            &value!(2.5)
+        );
+        assert_eq!(
+            histogram.get(".message.value.value.bucket[0]").unwrap(),
+            &value!({"upper_limit": 0.5, "count": 3})
+        );
+    }
+
     #[test]
     fn test_count() {
         let test_label = Label {
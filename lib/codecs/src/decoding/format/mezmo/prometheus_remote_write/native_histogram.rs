@@ -0,0 +1,288 @@
+use prometheus_remote_write::prometheus::{
+    BucketSpan, Histogram, HistogramOneOfcount, HistogramOneOfzero_count,
+};
+use vrl::value::Value;
+
+use vector_core::event::metric::mezmo::from_f64_or_zero;
+
+use super::metric_sample_types::{HistogramBucketValue, HistogramMetricValue, NamedValues};
+use super::parser::{ParseError, try_f64_to_u64};
+
+/// The range of exponential schemas, from a bucket growth factor of 65536 down to about 1.0027.
+const MIN_SCHEMA: i32 = -4;
+const MAX_SCHEMA: i32 = 8;
+
+/// The number of buckets needed to cover all of the finite float values at the highest resolution
+/// schema, beyond which the spans of a histogram can't be valid.
+const MAX_BUCKET_SPAN: u64 = 2 * (1024 << MAX_SCHEMA);
+
+/// The buckets of one sign of a native histogram, with the gaps between their spans filled in.
+#[derive(Debug, Default, PartialEq)]
+struct DenseBuckets {
+    /// The Prometheus index of the first bucket, which covers `(base^(index-1), base^index]`.
+    first_index: i32,
+    counts: Vec<f64>,
+}
+
+impl DenseBuckets {
+    /// Expands the spans of a native histogram. Integer histograms delta encode their bucket
+    /// counts across all the spans, while float histograms send absolute counts.
+    fn new(spans: &[BucketSpan], deltas: &[i64], counts: &[f64]) -> Result<Self, ParseError> {
+        let absolute: Vec<f64> = if deltas.is_empty() {
+            counts.to_vec()
+        } else {
+            deltas
+                .iter()
+                .scan(0i64, |count, delta| {
+                    *count = count.saturating_add(*delta);
+                    Some(*count as f64)
+                })
+                .collect()
+        };
+
+        let expected: u64 = spans.iter().map(|span| u64::from(span.length)).sum();
+        if absolute.len() as u64 != expected {
+            return Err(ParseError::InvalidHistogramBuckets);
+        }
+
+        // The first span is offset from zero, the following ones from the end of the previous
+        // span. The gaps are filled in, so their total is bounded before allocating them.
+        let mut gaps = Vec::with_capacity(spans.len());
+        for span in spans.iter().skip(1) {
+            gaps.push(u64::try_from(span.offset).map_err(|_| ParseError::InvalidHistogramBuckets)?);
+        }
+        let span = gaps
+            .iter()
+            .fold(expected, |total, gap| total.saturating_add(*gap));
+        if span > MAX_BUCKET_SPAN {
+            return Err(ParseError::HistogramBucketSpanTooLarge { span });
+        }
+
+        let mut buckets = Self {
+            first_index: spans.first().map_or(0, |span| span.offset),
+            counts: Vec::with_capacity(span as usize),
+        };
+        let mut absolute = absolute.into_iter();
+        for (n, span) in spans.iter().enumerate() {
+            if n > 0 {
+                buckets
+                    .counts
+                    .extend(std::iter::repeat_n(0.0, gaps[n - 1] as usize));
+            }
+            buckets
+                .counts
+                .extend(absolute.by_ref().take(span.length as usize));
+        }
+        Ok(buckets)
+    }
+
+    /// The buckets with their Prometheus index, from the lowest index.
+    fn indexed(&self) -> impl DoubleEndedIterator<Item = (i32, f64)> + '_ {
+        self.counts
+            .iter()
+            .enumerate()
+            .map(|(n, count)| (self.first_index.saturating_add(n as i32), *count))
+    }
+
+    /// The buckets in the layout of OpenTelemetry exponential histograms, whose bucket index `i`
+    /// covers `(base^i, base^(i+1)]`, one below the Prometheus index of the same bucket.
+    fn to_value(&self, is_float: bool) -> Value {
+        let offset = if self.counts.is_empty() {
+            0
+        } else {
+            self.first_index.saturating_sub(1)
+        };
+        let bucket_counts = self
+            .counts
+            .iter()
+            .map(|count| count_value(*count, is_float))
+            .collect();
+
+        Value::Object(
+            [
+                ("offset".into(), Value::from(i64::from(offset))),
+                ("bucket_counts".into(), Value::Array(bucket_counts)),
+            ]
+            .into_iter()
+            .collect(),
+        )
+    }
+}
+
+fn count_value(count: f64, is_float: bool) -> Value {
+    if is_float {
+        from_f64_or_zero(count)
+    } else {
+        Value::from(count as u64)
+    }
+}
+
+/// A Prometheus native (sparse) histogram, converted to a bucketed histogram with its exponential
+/// buckets kept alongside.
+#[derive(Debug, PartialEq)]
+pub(super) struct NativeHistogram {
+    pub timestamp: i64,
+    pub value: HistogramMetricValue,
+    pub exponential: NamedValues,
+}
+
+impl NativeHistogram {
+    pub(super) fn new(histogram: &Histogram) -> Result<Self, ParseError> {
+        let schema = histogram.schema;
+        if !(MIN_SCHEMA..=MAX_SCHEMA).contains(&schema) {
+            return Err(ParseError::UnsupportedHistogramSchema { schema });
+        }
+
+        let (count, is_float) = match &histogram.count {
+            HistogramOneOfcount::count_int(count) => (*count as f64, false),
+            HistogramOneOfcount::count_float(count) => (*count, true),
+            HistogramOneOfcount::None => (0.0, false),
+        };
+        let zero_count = match &histogram.zero_count {
+            HistogramOneOfzero_count::zero_count_int(count) => *count as f64,
+            HistogramOneOfzero_count::zero_count_float(count) => *count,
+            HistogramOneOfzero_count::None => 0.0,
+        };
+
+        let positive = DenseBuckets::new(
+            &histogram.positive_spans[..],
+            &histogram.positive_deltas[..],
+            &histogram.positive_counts[..],
+        )?;
+        let negative = DenseBuckets::new(
+            &histogram.negative_spans[..],
+            &histogram.negative_deltas[..],
+            &histogram.negative_counts[..],
+        )?;
+
+        // The upper bound of the bucket with Prometheus index `i` is `2^(i * 2^-schema)`
+        let bucket_width = 2f64.powi(-schema);
+        let upper_bound = |index: i32| (f64::from(index) * bucket_width).exp2();
+
+        // Cumulative buckets from the most negative bound, as with classic histograms
+        let mut buckets = Vec::with_capacity(negative.counts.len() + positive.counts.len() + 2);
+        let mut cumulative = 0.0;
+        for (index, bucket_count) in negative.indexed().rev() {
+            cumulative += bucket_count;
+            buckets.push(HistogramBucketValue {
+                upper_limit: -upper_bound(index - 1),
+                count: try_f64_to_u64(cumulative)?,
+            });
+        }
+        cumulative += zero_count;
+        buckets.push(HistogramBucketValue {
+            upper_limit: histogram.zero_threshold,
+            count: try_f64_to_u64(cumulative)?,
+        });
+        for (index, bucket_count) in positive.indexed() {
+            cumulative += bucket_count;
+            buckets.push(HistogramBucketValue {
+                upper_limit: upper_bound(index),
+                count: try_f64_to_u64(cumulative)?,
+            });
+        }
+        buckets.push(HistogramBucketValue {
+            upper_limit: f64::INFINITY,
+            count: try_f64_to_u64(count)?,
+        });
+
+        Ok(Self {
+            timestamp: histogram.timestamp,
+            value: HistogramMetricValue {
+                buckets,
+                sum: histogram.sum,
+                count: try_f64_to_u64(count)?,
+            },
+            exponential: NamedValues(vec![
+                ("scale", Value::from(i64::from(schema))),
+                ("zero_count", count_value(zero_count, is_float)),
+                ("zero_threshold", from_f64_or_zero(histogram.zero_threshold)),
+                ("positive", positive.to_value(is_float)),
+                ("negative", negative.to_value(is_float)),
+            ]),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(offset: i32, length: u32) -> BucketSpan {
+        BucketSpan { offset, length }
+    }
+
+    #[test]
+    fn expands_integer_buckets() {
+        let buckets =
+            DenseBuckets::new(&[span(-1, 2), span(2, 1)], &[3, -2, 4], &[]).expect("valid spans");
+        assert_eq!(
+            buckets,
+            DenseBuckets {
+                first_index: -1,
+                counts: vec![3.0, 1.0, 0.0, 0.0, 5.0],
+            }
+        );
+    }
+
+    #[test]
+    fn expands_float_buckets() {
+        let buckets =
+            DenseBuckets::new(&[span(0, 1), span(1, 1)], &[], &[1.5, 2.5]).expect("valid spans");
+        assert_eq!(
+            buckets,
+            DenseBuckets {
+                first_index: 0,
+                counts: vec![1.5, 0.0, 2.5],
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_mismatched_spans() {
+        assert!(matches!(
+            DenseBuckets::new(&[span(0, 3)], &[1, 1], &[]),
+            Err(ParseError::InvalidHistogramBuckets)
+        ));
+        assert!(matches!(
+            DenseBuckets::new(&[span(0, 1), span(-1, 1)], &[1, 1], &[]),
+            Err(ParseError::InvalidHistogramBuckets)
+        ));
+    }
+
+    #[test]
+    fn rejects_oversized_bucket_span() {
+        assert!(matches!(
+            DenseBuckets::new(&[span(0, 1), span(i32::MAX, 1)], &[1, 1], &[]),
+            Err(ParseError::HistogramBucketSpanTooLarge { span }) if span == i32::MAX as u64 + 2
+        ));
+        assert!(matches!(
+            DenseBuckets::new(
+                &[span(0, 1), span(i32::MAX, 1), span(i32::MAX, 1)],
+                &[1, 1, 1],
+                &[]
+            ),
+            Err(ParseError::HistogramBucketSpanTooLarge { .. })
+        ));
+        assert!(
+            DenseBuckets::new(
+                &[span(0, 1), span((MAX_BUCKET_SPAN - 2) as i32, 1)],
+                &[1, 1],
+                &[]
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn rejects_custom_bucket_schema() {
+        let histogram = Histogram {
+            schema: -53,
+            ..Default::default()
+        };
+        assert!(matches!(
+            NativeHistogram::new(&histogram),
+            Err(ParseError::UnsupportedHistogramSchema { schema: -53 })
+        ));
+    }
+}
@@ -1,5 +1,7 @@
 use chrono::DateTime;
-use prometheus_remote_write::prometheus::{Label, MetricMetadata, MetricType, Sample, TimeSeries};
+use prometheus_remote_write::prometheus::{
+    Exemplar, Histogram, Label, MetricMetadata, MetricType, Sample, TimeSeries,
+};
 use std::borrow::Cow;
 use std::collections::BTreeMap;
 
@@ -8,19 +10,20 @@ use smallvec::SmallVec;
 use vector_core::{
     config::log_schema,
     event::{
-        Event, MetricKind,
+        Event, KeyString, LogEvent, MetricKind, Value,
         metric::mezmo::{
             IntoTagValue, IntoValue, MetricArbitraryAccessor, MetricTags, MetricTagsAccessor,
             MetricToLogEvent, MetricValueAccessor, MetricValuePairs, MetricValueSerializable,
-            MezmoMetric,
+            MezmoMetric, from_f64_or_zero,
         },
     },
 };
 
 use super::metric_sample_types::{
-    BasicMetricValue, Counter, Gauge, HistogramBucketValue, HistogramMetricValue,
+    BasicMetricValue, Counter, Gauge, HistogramBucketValue, HistogramMetricValue, NamedValues,
     SummaryMetricValue, SummaryQuantileValue, Untyped,
 };
+use super::native_histogram::NativeHistogram;
 
 #[derive(Debug, snafu::Snafu)]
 pub enum ParseError {
@@ -38,9 +41,19 @@ pub enum ParseError {
     },
     #[snafu(display("Value {value} out of range to be converted to a u64"))]
     F64toU64ValueOutOfRange { value: f64 },
+    #[snafu(display("Unsupported native histogram schema {schema}"))]
+    UnsupportedHistogramSchema { schema: i32 },
+    #[snafu(display("Native histogram spans don't match its bucket counts"))]
+    InvalidHistogramBuckets,
+    #[snafu(display("Native histogram spans cover {span} buckets, over the limit"))]
+    HistogramBucketSpanTooLarge { span: u64 },
+    #[snafu(display("Symbol reference {index} out of range"))]
+    InvalidSymbolReference { index: u32 },
+    #[snafu(display("Odd number of label symbol references"))]
+    InvalidLabelReferences,
 }
 
-fn try_f64_to_u64(f: f64) -> Result<u64, ParseError> {
+pub(super) fn try_f64_to_u64(f: f64) -> Result<u64, ParseError> {
     if 0.0 <= f && f <= u64::MAX as f64 {
         Ok(f as u64)
     } else {
@@ -102,6 +115,10 @@ struct SampleGroupKey<'a> {
 
 type SampleGroupMap<'a, T> = BTreeMap<SampleGroupKey<'a>, T>;
 
+/// The exemplars of the series of each metric group and labels, kept as the user metadata of
+/// their events.
+type ExemplarGroups<'a> = BTreeMap<(Cow<'a, str>, &'a [Label<'a>]), Vec<Value>>;
+
 #[derive(Debug)]
 enum TypedSampleGroupMap<'a> {
     Summary(SampleGroupMap<'a, SummaryMetricValue>),
@@ -190,6 +207,28 @@ where
     }
 }
 
+struct MetricTagsWrapper<'a> {
+    tags: &'a [Label<'a>],
+}
+
+impl<'a> MetricTagsAccessor<'a> for MetricTagsWrapper<'a> {
+    type Iter = std::iter::Map<
+        std::slice::Iter<'a, Label<'a>>,
+        fn(&'a Label<'a>) -> (&'a dyn ToString, &'a dyn IntoTagValue),
+    >;
+
+    fn tags(&'a self) -> MetricTags<'a, Self::Iter> {
+        MetricTags {
+            tags: self.tags.iter().map(|label: &'a Label| {
+                (
+                    &label.name as &'a dyn ToString,
+                    &label.value as &'a dyn IntoTagValue,
+                )
+            }),
+        }
+    }
+}
+
 // Blank Arbitrary Accessor
 struct BlankArbitraryAccessor {}
 
@@ -267,30 +306,12 @@ impl<'a> TypedSampleGroupMap<'a> {
         Ok(())
     }
 
-    fn to_events(&self, metric_group_name: Cow<'a, str>, out: &mut SmallVec<[Event; 1]>) {
-        // Helper struct
-        struct MetricTagsWrapper<'a> {
-            tags: &'a [Label<'a>],
-        }
-
-        impl<'a> MetricTagsAccessor<'a> for MetricTagsWrapper<'a> {
-            type Iter = std::iter::Map<
-                std::slice::Iter<'a, Label<'a>>,
-                fn(&'a Label<'a>) -> (&'a dyn ToString, &'a dyn IntoTagValue),
-            >;
-
-            fn tags(&'a self) -> MetricTags<'a, Self::Iter> {
-                MetricTags {
-                    tags: self.tags.iter().map(|label: &'a Label| {
-                        (
-                            &label.name as &'a dyn ToString,
-                            &label.value as &'a dyn IntoTagValue,
-                        )
-                    }),
-                }
-            }
-        }
-
+    fn to_events(
+        &self,
+        metric_group_name: Cow<'a, str>,
+        exemplar_groups: &ExemplarGroups<'a>,
+        out: &mut SmallVec<[Event; 1]>,
+    ) {
         use TypedSampleGroupMap::*;
         let mut iter = match self {
             Counter(metrics) => TypedSampleGroupMapIter::Counter(
@@ -320,30 +341,98 @@ impl<'a> TypedSampleGroupMap<'a> {
             }
         }) {
             let tags = MetricTagsWrapper { tags: key.labels };
+            let user_metadata = exemplar_groups
+                .get(&(metric_group_name.clone(), key.labels))
+                .map(|exemplars| exemplars_metadata(exemplars.clone()));
             let mut log_event = {
                 MezmoMetric {
                     name: metric_group_name.clone(),
                     namespace: None,             // TODO
                     kind: &MetricKind::Absolute, // All prom metrics are Absolute?
                     tags: Some(&tags),
-                    user_metadata: None::<&BlankArbitraryAccessor>,
+                    user_metadata: user_metadata.as_ref(),
                     arbitrary_data: None::<&BlankArbitraryAccessor>,
                     value: &value,
                 }
                 .to_log_event()
             };
-            if let (Some(timestamp_key), Some(timestamp)) =
-                (log_schema().timestamp_key(), key.timestamp)
-            {
-                let ts = DateTime::from_timestamp_millis(timestamp)
-                    .expect("timestamp should be a valid timestamp");
-                log_event.insert((lookup::PathPrefix::Event, timestamp_key), ts);
-            }
+            insert_timestamp(&mut log_event, key.timestamp);
             out.push(log_event.into());
         }
     }
 }
 
+fn insert_timestamp(log_event: &mut LogEvent, timestamp: Option<i64>) {
+    if let (Some(timestamp_key), Some(timestamp)) = (log_schema().timestamp_key(), timestamp) {
+        let ts = DateTime::from_timestamp_millis(timestamp)
+            .expect("timestamp should be a valid timestamp");
+        log_event.insert((lookup::PathPrefix::Event, timestamp_key), ts);
+    }
+}
+
+fn exemplar_value(exemplar: &Exemplar) -> Value {
+    let labels = exemplar
+        .labels
+        .iter()
+        .map(|label| {
+            (
+                KeyString::from(label.name.as_ref()),
+                Value::from(label.value.as_ref()),
+            )
+        })
+        .collect::<BTreeMap<_, _>>();
+    // Exemplars without a timestamp are sent with a zero timestamp
+    let timestamp = (exemplar.timestamp != 0)
+        .then(|| DateTime::from_timestamp_millis(exemplar.timestamp))
+        .flatten()
+        .map_or(Value::Null, Value::from);
+
+    Value::Object(
+        [
+            ("labels".into(), Value::Object(labels)),
+            ("value".into(), from_f64_or_zero(exemplar.value)),
+            ("timestamp".into(), timestamp),
+        ]
+        .into_iter()
+        .collect(),
+    )
+}
+
+fn exemplars_metadata(exemplars: Vec<Value>) -> NamedValues {
+    NamedValues(vec![("exemplars", Value::Array(exemplars))])
+}
+
+/// Converts the native histograms of a series to events. Unlike classic histograms, they're sent
+/// in a single series named after the metric family, without the `le` label.
+fn process_native_histograms(
+    name: &str,
+    labels: &[Label],
+    histograms: &[Histogram],
+    exemplars: &[Exemplar],
+    out: &mut SmallVec<[Event; 1]>,
+) -> Result<(), ParseError> {
+    let tags = MetricTagsWrapper { tags: labels };
+    let user_metadata = (!exemplars.is_empty())
+        .then(|| exemplars_metadata(exemplars.iter().map(exemplar_value).collect()));
+
+    for histogram in histograms {
+        let native = NativeHistogram::new(histogram)?;
+        let mut log_event = MezmoMetric {
+            name: Cow::from(name),
+            namespace: None,
+            kind: &MetricKind::Absolute,
+            tags: Some(&tags),
+            user_metadata: user_metadata.as_ref(),
+            arbitrary_data: Some(&native.exponential),
+            value: &native.value,
+        }
+        .to_log_event();
+        insert_timestamp(&mut log_event, Some(native.timestamp));
+        out.push(log_event.into());
+    }
+    Ok(())
+}
+
 fn process_samples<'a, 's>(
     sample_groups: &'s mut BTreeMap<Cow<'a, str>, TypedSampleGroupMap<'a>>,
     metric_base_name: Cow<'a, str>,
@@ -379,7 +468,7 @@ impl<'a> std::iter::FromIterator<&'a MetricMetadata<'a>> for MetricMetadataGroup
 }
 
 impl<'a> MetricMetadataGroups {
-    fn insert(&mut self, metric_family_name: Cow<'a, str>, type_pb: MetricType) {
+    pub(super) fn insert(&mut self, metric_family_name: Cow<'a, str>, type_pb: MetricType) {
         self.0.insert(metric_family_name.into(), type_pb);
     }
 
@@ -481,29 +570,51 @@ pub(crate) fn parse_write_req<'a>(
     metric_types_lookup: &'a MetricMetadataGroups,
 ) -> Result<SmallVec<[Event; 1]>, ParseError> {
     const METRIC_NAME_LABEL: &str = "__name__";
-    // Group the samples
-    let grouped_samples = timeseries.iter_mut().try_fold(
-        BTreeMap::new(),
-        |mut acc,
-         TimeSeries {
-             labels, samples, ..
-         }| {
-            let name = extract_label(METRIC_NAME_LABEL, labels)
-                .map(|label| label.value.to_string())
-                .ok_or_else(|| ParseError::MissingLabel {
-                    value: METRIC_NAME_LABEL.to_string(),
-                })?;
-            let (base_name, grouping_strategy) =
-                metric_types_lookup.get_grouping_strategy(labels, name)?;
-            process_samples(&mut acc, base_name, labels, &grouping_strategy, samples)?;
-            Ok::<_, ParseError>(acc)
-        },
-    )?;
 
     let mut res = smallvec::smallvec!();
-    let grouped_samples_iter = grouped_samples.into_iter();
-    for (metric_group_name, metric_group) in grouped_samples_iter {
-        metric_group.to_events(metric_group_name, &mut res);
+    let mut grouped_samples = BTreeMap::new();
+    let mut exemplar_groups = ExemplarGroups::new();
+    for TimeSeries {
+        labels,
+        samples,
+        histograms,
+        exemplars,
+    } in timeseries.iter_mut()
+    {
+        let name = extract_label(METRIC_NAME_LABEL, labels)
+            .map(|label| label.value.to_string())
+            .ok_or_else(|| ParseError::MissingLabel {
+                value: METRIC_NAME_LABEL.to_string(),
+            })?;
+
+        if !histograms.is_empty() {
+            process_native_histograms(&name, labels, histograms, exemplars, &mut res)?;
+            if samples.is_empty() {
+                continue;
+            }
+        }
+
+        // Group the samples
+        let (base_name, grouping_strategy) =
+            metric_types_lookup.get_grouping_strategy(labels, name)?;
+        let labels: &[Label] = labels;
+        if !exemplars.is_empty() {
+            exemplar_groups
+                .entry((base_name.clone(), labels))
+                .or_default()
+                .extend(exemplars.iter().map(exemplar_value));
+        }
+        process_samples(
+            &mut grouped_samples,
+            base_name,
+            labels,
+            &grouping_strategy,
+            samples,
+        )?;
+    }
+
+    for (metric_group_name, metric_group) in grouped_samples {
+        metric_group.to_events(metric_group_name, &exemplar_groups, &mut res);
     }
     Ok(res)
 }
@@ -0,0 +1,280 @@
+//! Remote-Write 2.0 (`io.prometheus.write.v2.Request`) messages.
+//!
+//! The series of a 2.0 request reference their label names and values, as well as their metadata,
+//! through a symbol table shared by the whole request. They're resolved into the 1.0 series types
+//! so both versions are grouped and converted the same way.
+
+use std::borrow::Cow;
+
+use prometheus_remote_write::prometheus::{self, Histogram, Label, MetricType, Sample};
+use quick_protobuf::{BytesReader, MessageRead};
+
+use super::parser::ParseError;
+
+const METRIC_NAME_LABEL: &str = "__name__";
+
+/// Whether a (decompressed) write request is a Remote-Write 2.0 message. 1.0 requests only use
+/// the fields 1 and 3, while 2.0 requests start with their symbols (4) or series (5).
+pub(super) fn is_v2_request(bytes: &[u8]) -> bool {
+    let mut reader = BytesReader::from_bytes(bytes);
+    matches!(reader.next_tag(bytes), Ok(tag) if matches!(tag >> 3, 4 | 5))
+}
+
+fn read_refs(
+    r: &mut BytesReader,
+    bytes: &[u8],
+    tag: u32,
+    refs: &mut Vec<u32>,
+) -> quick_protobuf::Result<()> {
+    // Repeated scalars are packed by default, but parsers must accept both encodings
+    if tag & 0x7 == 2 {
+        refs.extend(r.read_packed(bytes, |r, bytes| r.read_uint32(bytes))?);
+    } else {
+        refs.push(r.read_uint32(bytes)?);
+    }
+    Ok(())
+}
+
+#[derive(Debug, Default)]
+pub(super) struct Request<'a> {
+    symbols: Vec<&'a str>,
+    timeseries: Vec<TimeSeries<'a>>,
+}
+
+impl<'a> MessageRead<'a> for Request<'a> {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> quick_protobuf::Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(34) => msg.symbols.push(r.read_string(bytes)?),
+                Ok(42) => msg.timeseries.push(r.read_message::<TimeSeries>(bytes)?),
+                Ok(t) => {
+                    r.read_unknown(bytes, t)?;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for Request<'a> {
+    type Error = quick_protobuf::Error;
+
+    fn try_from(bytes: &'a [u8]) -> quick_protobuf::Result<Self> {
+        let mut reader = BytesReader::from_bytes(bytes);
+        Self::from_reader(&mut reader, bytes)
+    }
+}
+
+/// The metric type of a metric family, from the metadata of its series.
+pub(super) type FamilyType = (String, MetricType);
+
+impl<'a> Request<'a> {
+    fn symbol(&self, index: u32) -> Result<&'a str, ParseError> {
+        self.symbols
+            .get(index as usize)
+            .copied()
+            .ok_or(ParseError::InvalidSymbolReference { index })
+    }
+
+    fn labels(&self, refs: &[u32]) -> Result<Vec<Label<'a>>, ParseError> {
+        if refs.len() % 2 != 0 {
+            return Err(ParseError::InvalidLabelReferences);
+        }
+        refs.chunks_exact(2)
+            .map(|pair| {
+                Ok(Label {
+                    name: Cow::Borrowed(self.symbol(pair[0])?),
+                    value: Cow::Borrowed(self.symbol(pair[1])?),
+                })
+            })
+            .collect()
+    }
+
+    /// Resolves the symbol references of the series, returning them along with the metric type
+    /// of the families whose series have metadata.
+    pub(super) fn into_timeseries(
+        mut self,
+    ) -> Result<(Vec<prometheus::TimeSeries<'a>>, Vec<FamilyType>), ParseError> {
+        let timeseries = std::mem::take(&mut self.timeseries);
+        let mut families = Vec::new();
+        let mut series = Vec::with_capacity(timeseries.len());
+        for ts in timeseries {
+            let labels = self.labels(&ts.labels_refs)?;
+            let exemplars = ts
+                .exemplars
+                .iter()
+                .map(|exemplar| {
+                    Ok(prometheus::Exemplar {
+                        labels: self.labels(&exemplar.labels_refs)?,
+                        value: exemplar.value,
+                        timestamp: exemplar.timestamp,
+                    })
+                })
+                .collect::<Result<_, ParseError>>()?;
+
+            if let Some(type_pb) = ts.metadata.map(|metadata| metadata.type_pb)
+                && type_pb != MetricType::UNKNOWN
+                && let Some(name) = labels.iter().find(|label| label.name == METRIC_NAME_LABEL)
+            {
+                let name = family_name(&name.value, type_pb, ts.histograms.is_empty());
+                families.push((name.to_owned(), type_pb));
+            }
+
+            series.push(prometheus::TimeSeries {
+                labels,
+                samples: ts.samples,
+                histograms: ts.histograms,
+                exemplars,
+            });
+        }
+        Ok((series, families))
+    }
+}
+
+/// The family name of a series. The series of classic histograms and summaries are named after
+/// their family with a `_bucket`, `_sum` or `_count` suffix, while native histograms aren't.
+fn family_name(name: &str, type_pb: MetricType, is_classic: bool) -> &str {
+    if is_classic
+        && matches!(
+            type_pb,
+            MetricType::HISTOGRAM | MetricType::GAUGEHISTOGRAM | MetricType::SUMMARY
+        )
+    {
+        ["_bucket", "_sum", "_count"]
+            .iter()
+            .find_map(|suffix| name.strip_suffix(suffix))
+            .unwrap_or(name)
+    } else {
+        name
+    }
+}
+
+#[derive(Debug, Default)]
+struct TimeSeries<'a> {
+    labels_refs: Vec<u32>,
+    samples: Vec<Sample>,
+    histograms: Vec<Histogram<'a>>,
+    exemplars: Vec<Exemplar>,
+    metadata: Option<Metadata>,
+}
+
+impl<'a> MessageRead<'a> for TimeSeries<'a> {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> quick_protobuf::Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(t @ (8 | 10)) => read_refs(r, bytes, t, &mut msg.labels_refs)?,
+                Ok(18) => msg.samples.push(r.read_message::<Sample>(bytes)?),
+                // Native histograms are unchanged from 1.0, other than the custom bucket schema
+                Ok(26) => msg.histograms.push(r.read_message::<Histogram>(bytes)?),
+                Ok(34) => msg.exemplars.push(r.read_message::<Exemplar>(bytes)?),
+                Ok(42) => msg.metadata = Some(r.read_message::<Metadata>(bytes)?),
+                Ok(t) => {
+                    r.read_unknown(bytes, t)?;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Exemplar {
+    labels_refs: Vec<u32>,
+    value: f64,
+    timestamp: i64,
+}
+
+impl<'a> MessageRead<'a> for Exemplar {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> quick_protobuf::Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(t @ (8 | 10)) => read_refs(r, bytes, t, &mut msg.labels_refs)?,
+                Ok(17) => msg.value = r.read_double(bytes)?,
+                Ok(24) => msg.timestamp = r.read_int64(bytes)?,
+                Ok(t) => {
+                    r.read_unknown(bytes, t)?;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+/// The metadata of a series. Its help and unit aren't kept, as with 1.0 metadata.
+#[derive(Debug, Default)]
+struct Metadata {
+    // The metric types share their values with 1.0
+    type_pb: MetricType,
+}
+
+impl<'a> MessageRead<'a> for Metadata {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> quick_protobuf::Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.type_pb = r.read_enum(bytes)?,
+                Ok(t) => {
+                    r.read_unknown(bytes, t)?;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_v2_requests() {
+        // symbols = ["a"]
+        assert!(is_v2_request(&[0x22, 0x01, b'a']));
+        // 1.0 timeseries = [{}]
+        assert!(!is_v2_request(&[0x0a, 0x00]));
+        assert!(!is_v2_request(&[]));
+    }
+
+    #[test]
+    fn strips_classic_histogram_suffixes() {
+        assert_eq!(
+            family_name("latency_bucket", MetricType::HISTOGRAM, true),
+            "latency"
+        );
+        assert_eq!(
+            family_name("latency_count", MetricType::SUMMARY, true),
+            "latency"
+        );
+        assert_eq!(
+            family_name("latency", MetricType::HISTOGRAM, false),
+            "latency"
+        );
+        assert_eq!(
+            family_name("requests_count", MetricType::COUNTER, true),
+            "requests_count"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_label_references() {
+        let request = Request {
+            symbols: vec!["", "__name__", "up"],
+            timeseries: vec![],
+        };
+        assert!(matches!(
+            request.labels(&[1, 2, 1]),
+            Err(ParseError::InvalidLabelReferences)
+        ));
+        assert!(matches!(
+            request.labels(&[1, 3]),
+            Err(ParseError::InvalidSymbolReference { index: 3 })
+        ));
+    }
+}
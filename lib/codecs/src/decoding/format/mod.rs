@@ -25,9 +25,13 @@ use dyn_clone::DynClone;
 pub use gelf::{GelfDeserializer, GelfDeserializerConfig, GelfDeserializerOptions};
 pub use influxdb::{InfluxdbDeserializer, InfluxdbDeserializerConfig};
 pub use json::{JsonDeserializer, JsonDeserializerConfig, JsonDeserializerOptions};
+#[cfg(feature = "opentelemetry")]
+pub use mezmo::open_telemetry::OtlpJsonError;
 pub use mezmo::{
     MezmoDeserializer,
-    open_telemetry::{DeserializerError, OpenTelemetryMetricDeserializer},
+    open_telemetry::{
+        DeserializerError, OpenTelemetryMetricDeserializer, OpenTelemetryPayloadFormat,
+    },
 };
 pub use native::{NativeDeserializer, NativeDeserializerConfig};
 pub use native_json::{
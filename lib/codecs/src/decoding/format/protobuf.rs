@@ -20,6 +20,10 @@ use vrl::{
 };
 
 use super::Deserializer;
+use crate::schema_registry::{
+    SchemaRegistry,
+    wire_format::{decode_message_indexes, decode_schema_id},
+};
 
 /// Config used to build a `ProtobufDeserializer`.
 #[configurable_component]
@@ -36,6 +40,20 @@ impl ProtobufDeserializerConfig {
         ProtobufDeserializer::try_from(self)
     }
 
+    /// Build a `ProtobufDeserializer` decoding messages in the Confluent wire format, whose
+    /// message type is fetched from the given schema registry instead of the descriptor file.
+    pub fn build_with_schema_registry(
+        &self,
+        schema_registry: SchemaRegistry,
+    ) -> ProtobufDeserializer {
+        ProtobufDeserializer {
+            message_source: MessageSource::SchemaRegistry(schema_registry),
+            options: Options {
+                use_json_names: self.protobuf.use_json_names,
+            },
+        }
+    }
+
     /// Return the type of event build by this deserializer.
     pub fn output_type(&self) -> DataType {
         DataType::Log
@@ -93,10 +111,20 @@ pub struct ProtobufDeserializerOptions {
     pub use_json_names: bool,
 }
 
+/// Where the message type of the decoded messages comes from.
+#[derive(Debug, Clone)]
+enum MessageSource {
+    /// All the messages have the same type.
+    Descriptor(MessageDescriptor),
+    /// The messages are in the Confluent wire format and their type is looked up in the schema
+    /// registry.
+    SchemaRegistry(SchemaRegistry),
+}
+
 /// Deserializer that builds `Event`s from a byte frame containing protobuf.
 #[derive(Debug, Clone)]
 pub struct ProtobufDeserializer {
-    message_descriptor: MessageDescriptor,
+    message_source: MessageSource,
     options: Options,
 }
 
@@ -104,7 +132,7 @@ impl ProtobufDeserializer {
     /// Creates a new `ProtobufDeserializer`.
     pub fn new(message_descriptor: MessageDescriptor) -> Self {
         Self {
-            message_descriptor,
+            message_source: MessageSource::Descriptor(message_descriptor),
             options: Default::default(),
         }
     }
@@ -117,10 +145,20 @@ impl ProtobufDeserializer {
     ) -> vector_common::Result<Self> {
         let message_descriptor = get_message_descriptor_from_bytes(desc_bytes, message_type)?;
         Ok(Self {
-            message_descriptor,
+            message_source: MessageSource::Descriptor(message_descriptor),
             options,
         })
     }
+
+    /// Fetches the schema of a message from the schema registry, so that the message can then be
+    /// parsed without waiting for it.
+    pub async fn fetch_schema(&self, mut message: &[u8]) -> vector_common::Result<()> {
+        if let MessageSource::SchemaRegistry(schema_registry) = &self.message_source {
+            let schema_id = decode_schema_id(&mut message)?;
+            schema_registry.protobuf_schema(schema_id).await?;
+        }
+        Ok(())
+    }
 }
 
 fn extract_vrl_value(
@@ -144,7 +182,19 @@ impl Deserializer for ProtobufDeserializer {
         bytes: Bytes,
         log_namespace: LogNamespace,
     ) -> vector_common::Result<SmallVec<[Event; 1]>> {
-        let vrl_value = extract_vrl_value(bytes, &self.message_descriptor, &self.options)?;
+        let vrl_value = match &self.message_source {
+            MessageSource::Descriptor(message_descriptor) => {
+                extract_vrl_value(bytes, message_descriptor, &self.options)?
+            }
+            MessageSource::SchemaRegistry(schema_registry) => {
+                let mut bytes = bytes;
+                let schema_id = decode_schema_id(&mut bytes)?;
+                let indexes = decode_message_indexes(&mut bytes)?;
+                let message_descriptor =
+                    schema_registry.fetched_protobuf_message(schema_id, &indexes)?;
+                extract_vrl_value(bytes, &message_descriptor, &self.options)?
+            }
+        };
         let mut event = Event::Log(LogEvent::from(vrl_value));
 
         let event = match log_namespace {
@@ -171,7 +221,7 @@ impl TryFrom<&ProtobufDeserializerConfig> for ProtobufDeserializer {
         let message_descriptor =
             get_message_descriptor(&config.protobuf.desc_file, &config.protobuf.message_type)?;
         Ok(Self {
-            message_descriptor,
+            message_source: MessageSource::Descriptor(message_descriptor),
             options: Options {
                 use_json_names: config.protobuf.use_json_names,
             },
@@ -185,9 +235,16 @@ mod tests {
 
     use std::{env, fs, path::PathBuf};
 
+    use bytes::{BufMut, BytesMut};
+    use serde_json::json;
     use vector_core::config::log_schema;
 
     use super::*;
+    use crate::schema_registry::{
+        SchemaRegistryConfig,
+        tests::{mock_registry, protobuf_schema},
+        wire_format::{encode_message_indexes, encode_schema_id},
+    };
 
     fn test_data_dir() -> PathBuf {
         PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap()).join("tests/data/protobuf")
@@ -307,4 +364,49 @@ mod tests {
             assert!(deserializer.parse(input.clone(), namespace).is_err());
         }
     }
+
+    #[tokio::test]
+    async fn deserialize_protobuf_schema_registry() {
+        let server = mock_registry(&[(
+            2,
+            json!({"schemaType": "PROTOBUF", "schema": protobuf_schema()}),
+        )])
+        .await;
+        let schema_registry = SchemaRegistryConfig::new(server.uri()).build().unwrap();
+        let deserializer = ProtobufDeserializerConfig {
+            protobuf: ProtobufDeserializerOptions {
+                use_json_names: true,
+                ..Default::default()
+            },
+        }
+        .build_with_schema_registry(schema_registry);
+
+        let mut bytes = BytesMut::new();
+        encode_schema_id(2, &mut bytes);
+        encode_message_indexes(&[0], &mut bytes);
+        bytes.put_slice(&fs::read(test_data_dir().join("pbs/person_someone3.pb")).unwrap());
+        let bytes = bytes.freeze();
+
+        // The schema must have been fetched before parsing.
+        assert!(
+            deserializer
+                .parse(bytes.clone(), LogNamespace::Vector)
+                .is_err()
+        );
+        deserializer.fetch_schema(&bytes).await.unwrap();
+        let events = deserializer.parse(bytes, LogNamespace::Vector).unwrap();
+        assert_eq!(events.len(), 1);
+        let log = events[0].as_log();
+        assert_eq!(log["name"], "someone".into());
+        assert_eq!(log["jobDescription"], "Software Engineer".into());
+
+        let mut bytes = BytesMut::new();
+        encode_schema_id(2, &mut bytes);
+        encode_message_indexes(&[5], &mut bytes);
+        assert!(
+            deserializer
+                .parse(bytes.freeze(), LogNamespace::Vector)
+                .is_err()
+        );
+    }
 }
@@ -18,11 +18,11 @@ pub use format::{
     GelfDeserializerConfig, GelfDeserializerOptions, InfluxdbDeserializer,
     InfluxdbDeserializerConfig, JsonDeserializer, JsonDeserializerConfig, JsonDeserializerOptions,
     MezmoDeserializer, NativeDeserializer, NativeDeserializerConfig, NativeJsonDeserializer,
-    NativeJsonDeserializerConfig, NativeJsonDeserializerOptions, ProtobufDeserializer,
-    ProtobufDeserializerConfig, ProtobufDeserializerOptions,
+    NativeJsonDeserializerConfig, NativeJsonDeserializerOptions, OpenTelemetryPayloadFormat,
+    ProtobufDeserializer, ProtobufDeserializerConfig, ProtobufDeserializerOptions,
 };
 #[cfg(feature = "opentelemetry")]
-pub use format::{OtlpDeserializer, OtlpDeserializerConfig, OtlpSignalType};
+pub use format::{OtlpDeserializer, OtlpDeserializerConfig, OtlpJsonError, OtlpSignalType};
 #[cfg(feature = "syslog")]
 pub use format::{SyslogDeserializer, SyslogDeserializerConfig, SyslogDeserializerOptions};
 pub use framing::{
@@ -42,7 +42,10 @@ use vector_core::{
 };
 
 use self::format::{AvroDeserializer, AvroDeserializerConfig, AvroDeserializerOptions};
-use crate::decoding::format::{VrlDeserializer, VrlDeserializerConfig};
+use crate::{
+    decoding::format::{VrlDeserializer, VrlDeserializerConfig},
+    schema_registry::SchemaRegistry,
+};
 
 /// An error that occurred while decoding structured events from a byte stream /
 /// byte messages.
@@ -322,6 +325,7 @@ pub enum DeserializerConfig {
     /// [apache_avro]: https://avro.apache.org/
     Avro {
         /// Apache Avro-specific encoder options.
+        #[serde(default)]
         avro: AvroDeserializerOptions,
     },
 
@@ -402,6 +406,30 @@ impl DeserializerConfig {
         }
     }
 
+    /// Build the `Deserializer` from this configuration, decoding messages in the Confluent wire
+    /// format using the schemas of the given schema registry.
+    ///
+    /// Only the `avro` and `protobuf` codecs support a schema registry.
+    pub fn build_with_schema_registry(
+        &self,
+        schema_registry: SchemaRegistry,
+    ) -> vector_common::Result<Deserializer> {
+        match self {
+            DeserializerConfig::Avro { avro } => Ok(Deserializer::Avro(
+                AvroDeserializerConfig {
+                    avro_options: avro.clone(),
+                }
+                .build_with_schema_registry(schema_registry)?,
+            )),
+            DeserializerConfig::Protobuf(config) => Ok(Deserializer::Protobuf(
+                config.build_with_schema_registry(schema_registry),
+            )),
+            _ => Err(
+                "A schema registry can only be used with the `avro` and `protobuf` codecs".into(),
+            ),
+        }
+    }
+
     /// Return an appropriate default framer for the given deserializer
     pub fn default_stream_framing(&self) -> FramingConfig {
         match self {
@@ -578,6 +606,19 @@ impl format::Deserializer for Deserializer {
     }
 }
 
+impl Deserializer {
+    /// Fetches the schema of a message from the schema registry of the `avro` and `protobuf`
+    /// deserializers, so that parsing the message doesn't have to wait for it. The other
+    /// deserializers don't need any schema.
+    pub async fn fetch_schema(&self, message: &[u8]) -> vector_common::Result<()> {
+        match self {
+            Deserializer::Avro(deserializer) => deserializer.fetch_schema(message).await,
+            Deserializer::Protobuf(deserializer) => deserializer.fetch_schema(message).await,
+            _ => Ok(()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
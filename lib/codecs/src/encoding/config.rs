@@ -2,13 +2,10 @@ use vector_config::configurable_component;
 
 use super::{Encoder, EncoderKind, Transformer};
 use crate::encoding::{
-    CharacterDelimitedEncoder, Framer, FramingConfig, LengthDelimitedEncoder,
+    BytesEncoder, CharacterDelimitedEncoder, Framer, FramingConfig, LengthDelimitedEncoder,
     NewlineDelimitedEncoder, Serializer, SerializerConfig,
 };
 
-#[cfg(feature = "opentelemetry")]
-use crate::encoding::BytesEncoder;
-
 /// Encoding configuration.
 #[configurable_component]
 #[derive(Clone, Debug)]
@@ -135,6 +132,7 @@ impl EncodingConfigWithFraming {
             (None, Serializer::Syslog(_)) => NewlineDelimitedEncoder::default().into(),
             #[cfg(feature = "opentelemetry")]
             (None, Serializer::Otlp(_)) => BytesEncoder.into(),
+            (None, Serializer::Mezmo(_)) => BytesEncoder.into(),
         };
 
         Ok((framer, serializer))
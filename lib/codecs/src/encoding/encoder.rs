@@ -194,6 +194,7 @@ impl Encoder<Framer> {
                 }),
             ) => "application/json",
             (Serializer::Native(_), _) | (Serializer::Protobuf(_), _) => "application/octet-stream",
+            (Serializer::Mezmo(serializer), _) => serializer.content_type(),
             (
                 Serializer::Avro(_)
                 | Serializer::Cef(_)
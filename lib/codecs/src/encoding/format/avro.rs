@@ -4,7 +4,10 @@ use tokio_util::codec::Encoder;
 use vector_config::configurable_component;
 use vector_core::{config::DataType, event::Event, schema};
 
-use crate::encoding::BuildError;
+use crate::{
+    encoding::BuildError,
+    schema_registry::{SchemaRegistry, wire_format::encode_schema_id},
+};
 
 /// Config used to build a `AvroSerializer`.
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -23,9 +26,30 @@ impl AvroSerializerConfig {
 
     /// Build the `AvroSerializer` from this configuration.
     pub fn build(&self) -> Result<AvroSerializer, BuildError> {
+        if self.avro.schema.is_empty() {
+            return Err("An Avro schema is required when no schema registry is configured".into());
+        }
         let schema = apache_avro::Schema::parse_str(&self.avro.schema)
             .map_err(|error| format!("Failed building Avro serializer: {error}"))?;
-        Ok(AvroSerializer { schema })
+        Ok(AvroSerializer {
+            schema,
+            schema_id: None,
+        })
+    }
+
+    /// Build an `AvroSerializer` encoding events in the Confluent wire format with the schema
+    /// registered under the given id, which replaces the configured schema. The schema is fetched
+    /// from the registry unless it's already in memory.
+    pub async fn build_with_schema_registry(
+        &self,
+        schema_registry: &SchemaRegistry,
+        schema_id: u32,
+    ) -> Result<AvroSerializer, BuildError> {
+        let schema = schema_registry.avro_schema(schema_id).await?;
+        Ok(AvroSerializer {
+            schema: schema.as_ref().clone(),
+            schema_id: Some(schema_id),
+        })
     }
 
     /// The data type of events that are accepted by `AvroSerializer`.
@@ -42,7 +66,7 @@ impl AvroSerializerConfig {
 
 /// Apache Avro serializer options.
 #[configurable_component]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct AvroSerializerOptions {
     /// The Avro schema.
     #[configurable(metadata(
@@ -56,12 +80,17 @@ pub struct AvroSerializerOptions {
 #[derive(Debug, Clone)]
 pub struct AvroSerializer {
     schema: apache_avro::Schema,
+    /// The id prefixing the messages in the Confluent wire format.
+    schema_id: Option<u32>,
 }
 
 impl AvroSerializer {
     /// Creates a new `AvroSerializer`.
     pub const fn new(schema: apache_avro::Schema) -> Self {
-        Self { schema }
+        Self {
+            schema,
+            schema_id: None,
+        }
     }
 }
 
@@ -73,6 +102,9 @@ impl Encoder<Event> for AvroSerializer {
         let value = apache_avro::to_value(log)?;
         let value = value.resolve(&self.schema)?;
         let bytes = apache_avro::to_avro_datum(&self.schema, value)?;
+        if let Some(schema_id) = self.schema_id {
+            encode_schema_id(schema_id, buffer);
+        }
         buffer.put_slice(&bytes);
         Ok(())
     }
@@ -82,10 +114,21 @@ impl Encoder<Event> for AvroSerializer {
 mod tests {
     use bytes::BytesMut;
     use indoc::indoc;
-    use vector_core::event::{LogEvent, Value};
+    use serde_json::json;
+    use vector_core::{
+        config::LogNamespace,
+        event::{LogEvent, Value},
+    };
     use vrl::btreemap;
 
     use super::*;
+    use crate::{
+        decoding::format::{AvroDeserializerConfig, Deserializer},
+        schema_registry::{
+            SchemaRegistryConfig,
+            tests::{AVRO_SCHEMA, mock_registry},
+        },
+    };
 
     #[test]
     fn serialize_avro() {
@@ -113,4 +156,31 @@ mod tests {
 
         assert_eq!(bytes.freeze(), b"\0\x06bar".as_slice());
     }
+
+    #[tokio::test]
+    async fn serialize_avro_schema_registry() {
+        let server = mock_registry(&[(7, json!({"schema": AVRO_SCHEMA}))]).await;
+        let schema_registry = SchemaRegistryConfig::new(server.uri()).build().unwrap();
+        let event = Event::Log(LogEvent::from(btreemap! {
+            "message" => Value::from("hello"),
+            "count" => Value::from(3),
+        }));
+
+        let mut serializer = AvroSerializerConfig::new(String::new())
+            .build_with_schema_registry(&schema_registry, 7)
+            .await
+            .unwrap();
+        let mut bytes = BytesMut::new();
+        serializer.encode(event.clone(), &mut bytes).unwrap();
+        assert_eq!(bytes[..5], [0, 0, 0, 0, 7]);
+
+        let deserializer = AvroDeserializerConfig::new(String::new(), false)
+            .build_with_schema_registry(schema_registry)
+            .unwrap();
+        // The schema was fetched when building the serializer.
+        let events = deserializer
+            .parse(bytes.freeze(), LogNamespace::Vector)
+            .unwrap();
+        assert_eq!(events[0].as_log().value(), event.as_log().value());
+    }
 }
@@ -0,0 +1,74 @@
+use bytes::BytesMut;
+use tokio_util::codec::Encoder;
+use vector_config::configurable_component;
+use vector_core::{config::DataType, event::Event, schema};
+
+mod prometheus_remote_write;
+
+pub use prometheus_remote_write::{
+    PrometheusRemoteWriteSerializer, PrometheusRemoteWriteSerializerError,
+};
+
+/// Mezmo Serializers
+#[configurable_component]
+#[derive(Debug, Clone)]
+#[serde(tag = "encoding", rename_all = "snake_case")]
+#[configurable(metadata(docs::enum_tag_description = "Mezmo Serializer variants"))]
+pub enum MezmoSerializerConfig {
+    /// Prometheus Remote Write config
+    ///
+    /// Encodes each Mezmo metric log as an uncompressed remote write request. The requests of a
+    /// batch are written back to back, which decodes as a single request, so the sink must
+    /// compress its batches with `snappy`.
+    PrometheusRemoteWrite,
+}
+
+impl MezmoSerializerConfig {
+    /// Build the Serializer
+    pub fn build(&self) -> MezmoSerializer {
+        match self {
+            MezmoSerializerConfig::PrometheusRemoteWrite => {
+                MezmoSerializer::PrometheusRemoteWrite(PrometheusRemoteWriteSerializer)
+            }
+        }
+    }
+
+    /// Input type of the Serializer
+    ///
+    /// The Mezmo serializers accept vector Log types encoding Metrics in the standard Mezmo
+    /// format
+    pub fn input_type(&self) -> DataType {
+        DataType::Log
+    }
+
+    /// The schema required by the Serializer
+    pub fn schema_requirement(&self) -> schema::Requirement {
+        schema::Requirement::empty()
+    }
+}
+
+/// Mezmo Serializer implementations
+#[derive(Debug, Clone)]
+pub enum MezmoSerializer {
+    /// Uses a `PrometheusRemoteWriteSerializer` for serialization.
+    PrometheusRemoteWrite(PrometheusRemoteWriteSerializer),
+}
+
+impl MezmoSerializer {
+    /// Content Type produced by the Serializer
+    pub const fn content_type(&self) -> &'static str {
+        match self {
+            MezmoSerializer::PrometheusRemoteWrite(_) => "application/x-protobuf",
+        }
+    }
+}
+
+impl Encoder<Event> for MezmoSerializer {
+    type Error = vector_common::Error;
+
+    fn encode(&mut self, event: Event, buffer: &mut BytesMut) -> Result<(), Self::Error> {
+        match self {
+            MezmoSerializer::PrometheusRemoteWrite(serializer) => serializer.encode(event, buffer),
+        }
+    }
+}
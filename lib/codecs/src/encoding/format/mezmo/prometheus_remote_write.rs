@@ -0,0 +1,721 @@
+use std::borrow::Cow;
+
+use bytes::{BufMut, BytesMut};
+use chrono::Utc;
+use lookup::PathPrefix;
+use prometheus_remote_write::prometheus::{
+    Exemplar, Label, MetricMetadata, MetricType, Sample, TimeSeries, WriteRequest,
+};
+use quick_protobuf::{MessageWrite, Writer};
+use tokio_util::codec::Encoder;
+use vector_core::{
+    config::log_schema,
+    event::{Event, LogEvent, ObjectMap, Value},
+};
+
+const METRIC_NAME_LABEL: &str = "__name__";
+const LE_LABEL: &str = "le";
+const QUANTILE_LABEL: &str = "quantile";
+
+/// Errors encoding a Mezmo metric log as a remote write request.
+#[derive(Debug, snafu::Snafu)]
+pub enum PrometheusRemoteWriteSerializerError {
+    /// The event isn't a log.
+    #[snafu(display("Only Mezmo metric logs can be encoded as remote write requests"))]
+    UnsupportedEvent,
+    /// A required field of the metric is missing or null.
+    #[snafu(display("Required field '{field}' not found in the metric"))]
+    FieldNotFound {
+        /// Path of the field in the metric.
+        field: &'static str,
+    },
+    /// A field of the metric has an unexpected type.
+    #[snafu(display("Field '{field}' type is not valid"))]
+    FieldInvalidType {
+        /// Path of the field in the metric.
+        field: &'static str,
+    },
+    /// The metric type has no remote write equivalent.
+    #[snafu(display("Metric type '{type_name}' is not supported"))]
+    UnsupportedMetricType {
+        /// The Mezmo metric type.
+        type_name: String,
+    },
+    /// The request couldn't be written.
+    #[snafu(display("Failed to write the remote write request: {source}"))]
+    Protobuf {
+        /// The source of the error.
+        #[snafu(source)]
+        source: quick_protobuf::Error,
+    },
+}
+
+use PrometheusRemoteWriteSerializerError as Error;
+
+/// Serializer that converts a Mezmo metric log to an uncompressed remote write request.
+///
+/// This is the inverse of the Mezmo Prometheus remote write deserializer: histograms are written
+/// as their cumulative `_bucket` series along with `_sum` and `_count`, and summaries as their
+/// quantile series along with `_sum` and `_count`. Each request carries the metadata of its metric
+/// family, and the exemplars kept in the user metadata of the log.
+///
+/// The requests of a batch are written back to back, which protobuf decodes as a single request
+/// with all of their series and metadata, so a batch is sent as one request once the sink
+/// compresses it with snappy.
+#[derive(Debug, Clone, Default)]
+pub struct PrometheusRemoteWriteSerializer;
+
+impl Encoder<Event> for PrometheusRemoteWriteSerializer {
+    type Error = vector_common::Error;
+
+    fn encode(&mut self, event: Event, buffer: &mut BytesMut) -> Result<(), Self::Error> {
+        let Event::Log(log) = event else {
+            return Err(Error::UnsupportedEvent.into());
+        };
+
+        let request = write_request(&log)?;
+        let mut bytes = Vec::with_capacity(request.get_size());
+        request
+            .write_message(&mut Writer::new(&mut bytes))
+            .map_err(|source| Error::Protobuf { source })?;
+
+        buffer.put_slice(&bytes);
+        Ok(())
+    }
+}
+
+fn field<'a>(
+    object: &'a ObjectMap,
+    name: &str,
+    field_path: &'static str,
+) -> Result<&'a Value, Error> {
+    match object.get(name) {
+        None | Some(Value::Null) => Err(Error::FieldNotFound { field: field_path }),
+        Some(value) => Ok(value),
+    }
+}
+
+fn object_field<'a>(
+    object: &'a ObjectMap,
+    name: &str,
+    field_path: &'static str,
+) -> Result<&'a ObjectMap, Error> {
+    field(object, name, field_path)?
+        .as_object()
+        .ok_or(Error::FieldInvalidType { field: field_path })
+}
+
+fn array_field<'a>(
+    object: &'a ObjectMap,
+    name: &str,
+    field_path: &'static str,
+) -> Result<&'a [Value], Error> {
+    field(object, name, field_path)?
+        .as_array()
+        .map(Vec::as_slice)
+        .ok_or(Error::FieldInvalidType { field: field_path })
+}
+
+fn number(value: &Value, field: &'static str) -> Result<f64, Error> {
+    match value {
+        Value::Float(value) => Ok(value.into_inner()),
+        Value::Integer(value) => Ok(*value as f64),
+        _ => Err(Error::FieldInvalidType { field }),
+    }
+}
+
+fn number_field(object: &ObjectMap, name: &str, field_path: &'static str) -> Result<f64, Error> {
+    number(field(object, name, field_path)?, field_path)
+}
+
+/// Formats a bucket bound or quantile the way Prometheus clients do.
+fn format_float(value: f64) -> String {
+    if value == f64::INFINITY {
+        "+Inf".to_owned()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_owned()
+    } else if value == 0.0 || (1e-4..1e21).contains(&value.abs()) {
+        value.to_string()
+    } else {
+        format!("{value:e}")
+    }
+}
+
+fn timestamp_millis(value: Option<&Value>) -> Option<i64> {
+    value
+        .and_then(Value::as_timestamp)
+        .map(|timestamp| timestamp.timestamp_millis())
+}
+
+fn labels_of(tags: Option<&ObjectMap>) -> Vec<Label<'static>> {
+    tags.into_iter()
+        .flatten()
+        .filter(|(name, value)| name.as_str() != METRIC_NAME_LABEL && !value.is_null())
+        .map(|(name, value)| Label {
+            name: Cow::Owned(name.to_string()),
+            value: Cow::Owned(value.to_string_lossy().into_owned()),
+        })
+        .collect()
+}
+
+fn exemplars_of(log: &LogEvent) -> Result<Vec<(f64, Exemplar<'static>)>, Error> {
+    let Some(exemplars) = log
+        .get((PathPrefix::Event, log_schema().user_metadata_key()))
+        .and_then(Value::as_object)
+        .and_then(|metadata| metadata.get("exemplars"))
+        .and_then(Value::as_array)
+    else {
+        return Ok(Vec::new());
+    };
+
+    exemplars
+        .iter()
+        .map(|exemplar| {
+            let exemplar = exemplar
+                .as_object()
+                .ok_or(Error::FieldInvalidType { field: "exemplars" })?;
+            let value = number_field(exemplar, "value", "exemplars.value")?;
+            let mut labels = labels_of(exemplar.get("labels").and_then(Value::as_object));
+            labels.sort_by(|a, b| a.name.cmp(&b.name));
+            Ok((
+                value,
+                Exemplar {
+                    labels,
+                    value,
+                    // Exemplars without a timestamp are sent with a zero timestamp
+                    timestamp: timestamp_millis(exemplar.get("timestamp")).unwrap_or_default(),
+                },
+            ))
+        })
+        .collect()
+}
+
+/// The series of a single metric, sharing its labels and timestamp.
+struct SeriesBuilder {
+    labels: Vec<Label<'static>>,
+    timestamp: i64,
+    timeseries: Vec<TimeSeries<'static>>,
+}
+
+impl SeriesBuilder {
+    fn push(
+        &mut self,
+        name: String,
+        label: Option<(&'static str, String)>,
+        value: f64,
+    ) -> &mut TimeSeries<'static> {
+        let mut labels = self.labels.clone();
+        labels.push(Label {
+            name: Cow::Borrowed(METRIC_NAME_LABEL),
+            value: Cow::Owned(name),
+        });
+        if let Some((name, value)) = label {
+            labels.retain(|label| label.name != name);
+            labels.push(Label {
+                name: Cow::Borrowed(name),
+                value: Cow::Owned(value),
+            });
+        }
+        // Remote write receivers expect the labels sorted by name
+        labels.sort_by(|a, b| a.name.cmp(&b.name));
+
+        self.timeseries.push(TimeSeries {
+            labels,
+            samples: vec![Sample {
+                value,
+                timestamp: self.timestamp,
+            }],
+            exemplars: vec![],
+            histograms: vec![],
+        });
+        self.timeseries.last_mut().expect("series was just pushed")
+    }
+}
+
+fn write_request(log: &LogEvent) -> Result<WriteRequest<'static>, Error> {
+    let message = log_schema()
+        .message_key_target_path()
+        .and_then(|path| log.get(path))
+        .ok_or(Error::FieldNotFound { field: "message" })?
+        .as_object()
+        .ok_or(Error::FieldInvalidType { field: "message" })?;
+
+    let name = field(message, "name", "name")?
+        .as_str()
+        .ok_or(Error::FieldInvalidType { field: "name" })?;
+    let name = match message.get("namespace").and_then(Value::as_str) {
+        Some(namespace) => format!("{namespace}_{name}"),
+        None => name.into_owned(),
+    };
+
+    let mut series = SeriesBuilder {
+        labels: labels_of(message.get("tags").and_then(Value::as_object)),
+        timestamp: timestamp_millis(
+            log_schema()
+                .timestamp_key_target_path()
+                .and_then(|path| log.get(path)),
+        )
+        .unwrap_or_else(|| Utc::now().timestamp_millis()),
+        timeseries: Vec::new(),
+    };
+    let mut exemplars = exemplars_of(log)?;
+
+    let value_object = object_field(message, "value", "value")?;
+    let type_name = value_object.get("type").and_then(Value::as_str);
+    let type_pb = match type_name.as_deref() {
+        // Counters are decoded from remote write as `count`
+        Some("counter" | "count") => MetricType::COUNTER,
+        Some("gauge") => MetricType::GAUGE,
+        // Untyped samples are sent without metadata
+        None => MetricType::UNKNOWN,
+        Some("histogram") => {
+            let value = object_field(value_object, "value", "value.value")?;
+            // Mezmo metrics count the values of each bucket, while histograms decoded from remote
+            // write keep their cumulative buckets as `bucket`
+            let (buckets, cumulative) = match array_field(value, "buckets", "value.buckets") {
+                Ok(buckets) => (buckets, false),
+                Err(_) => (array_field(value, "bucket", "value.buckets")?, true),
+            };
+            let mut buckets = buckets
+                .iter()
+                .map(|bucket| {
+                    let bucket = bucket.as_object().ok_or(Error::FieldInvalidType {
+                        field: "value.buckets",
+                    })?;
+                    Ok((
+                        number_field(bucket, "upper_limit", "value.buckets.upper_limit")?,
+                        number_field(bucket, "count", "value.buckets.count")?,
+                    ))
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            buckets.sort_by(|a, b| a.0.total_cmp(&b.0));
+            if !cumulative {
+                let mut total = 0.0;
+                for (_, count) in &mut buckets {
+                    total += *count;
+                    *count = total;
+                }
+            }
+
+            let count = number_field(value, "count", "value.count")?;
+            if buckets
+                .last()
+                .is_none_or(|(upper_limit, _)| *upper_limit != f64::INFINITY)
+            {
+                buckets.push((f64::INFINITY, count));
+            }
+
+            // Each exemplar goes with the first bucket it falls into
+            exemplars.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let mut exemplars = exemplars.drain(..).peekable();
+            for (upper_limit, bucket_count) in buckets {
+                let bucket = series.push(
+                    format!("{name}_bucket"),
+                    Some((LE_LABEL, format_float(upper_limit))),
+                    bucket_count,
+                );
+                while let Some((_, exemplar)) =
+                    exemplars.next_if(|(value, _)| *value <= upper_limit)
+                {
+                    bucket.exemplars.push(exemplar);
+                }
+            }
+            series.push(
+                format!("{name}_sum"),
+                None,
+                number_field(value, "sum", "value.sum")?,
+            );
+            series.push(format!("{name}_count"), None, count);
+            MetricType::HISTOGRAM
+        }
+        Some("summary") => {
+            let value = object_field(value_object, "value", "value.value")?;
+            for quantile in array_field(value, "quantiles", "value.quantiles")? {
+                let quantile = quantile.as_object().ok_or(Error::FieldInvalidType {
+                    field: "value.quantiles",
+                })?;
+                series.push(
+                    name.clone(),
+                    Some((
+                        QUANTILE_LABEL,
+                        format_float(number_field(
+                            quantile,
+                            "quantile",
+                            "value.quantiles.quantile",
+                        )?),
+                    )),
+                    number_field(quantile, "value", "value.quantiles.value")?,
+                );
+            }
+            series.push(
+                format!("{name}_sum"),
+                None,
+                number_field(value, "sum", "value.sum")?,
+            );
+            series.push(
+                format!("{name}_count"),
+                None,
+                number_field(value, "count", "value.count")?,
+            );
+            // Summaries don't have exemplars
+            exemplars.clear();
+            MetricType::SUMMARY
+        }
+        Some(other) => {
+            return Err(Error::UnsupportedMetricType {
+                type_name: other.to_string(),
+            });
+        }
+    };
+
+    if series.timeseries.is_empty() {
+        let value = number_field(value_object, "value", "value.value")?;
+        let sample = series.push(name.clone(), None, value);
+        sample.exemplars = exemplars
+            .into_iter()
+            .map(|(_, exemplar)| exemplar)
+            .collect();
+    }
+
+    let metadata = if type_pb == MetricType::UNKNOWN {
+        vec![]
+    } else {
+        vec![MetricMetadata {
+            type_pb,
+            metric_family_name: Cow::Owned(name),
+            help: Cow::Borrowed(""),
+            unit: Cow::Borrowed(""),
+        }]
+    };
+
+    Ok(WriteRequest {
+        timeseries: series.timeseries,
+        metadata,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_core::config::LogNamespace;
+    use vrl::value;
+
+    use super::*;
+    use crate::decoding::{MezmoDeserializer, format::Deserializer};
+
+    fn encode(log: Value) -> Result<Vec<u8>, vector_common::Error> {
+        let mut buffer = BytesMut::new();
+        PrometheusRemoteWriteSerializer.encode(Event::Log(LogEvent::from(log)), &mut buffer)?;
+        Ok(buffer.to_vec())
+    }
+
+    fn labels(series: &TimeSeries) -> Vec<(String, String)> {
+        series
+            .labels
+            .iter()
+            .map(|label| (label.name.to_string(), label.value.to_string()))
+            .collect()
+    }
+
+    fn pairs(labels: &[(&str, &str)]) -> Vec<(String, String)> {
+        labels
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn encodes_counter() {
+        let bytes = encode(value!({
+            "message": {
+                "name": "requests_total",
+                "namespace": "http",
+                "kind": "absolute",
+                "tags": {"job": "api", "code": "200"},
+                "value": {"type": "count", "value": 42.0},
+            },
+            "timestamp": (chrono::DateTime::from_timestamp_millis(1_700_000_000_000).unwrap()),
+        }))
+        .unwrap();
+        let request = WriteRequest::try_from(&bytes[..]).unwrap();
+
+        assert_eq!(request.timeseries.len(), 1);
+        let series = &request.timeseries[0];
+        assert_eq!(
+            labels(series),
+            pairs(&[
+                ("__name__", "http_requests_total"),
+                ("code", "200"),
+                ("job", "api"),
+            ])
+        );
+        assert_eq!(series.samples.len(), 1);
+        assert_eq!(series.samples[0].value, 42.0);
+        assert_eq!(series.samples[0].timestamp, 1_700_000_000_000);
+
+        assert_eq!(request.metadata.len(), 1);
+        assert_eq!(request.metadata[0].type_pb, MetricType::COUNTER);
+        assert_eq!(
+            request.metadata[0].metric_family_name,
+            "http_requests_total"
+        );
+    }
+
+    #[test]
+    fn encodes_histogram() {
+        let bytes = encode(value!({
+            "message": {
+                "name": "latency",
+                "kind": "absolute",
+                "tags": {"job": "api"},
+                "value": {
+                    "type": "histogram",
+                    "value": {
+                        "buckets": [
+                            {"upper_limit": 1.0, "count": 3},
+                            {"upper_limit": 0.5, "count": 1},
+                        ],
+                        "sum": 2.5,
+                        "count": 4,
+                    },
+                },
+            },
+            "metadata": {
+                "exemplars": [
+                    {"labels": {"trace_id": "abc"}, "value": 0.7, "timestamp": null},
+                ],
+            },
+        }))
+        .unwrap();
+        let request = WriteRequest::try_from(&bytes[..]).unwrap();
+
+        let series = request
+            .timeseries
+            .iter()
+            .map(|series| (labels(series), series.samples[0].value))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            series,
+            vec![
+                (
+                    pairs(&[
+                        ("__name__", "latency_bucket"),
+                        ("job", "api"),
+                        ("le", "0.5")
+                    ]),
+                    1.0
+                ),
+                (
+                    pairs(&[("__name__", "latency_bucket"), ("job", "api"), ("le", "1")]),
+                    4.0
+                ),
+                (
+                    pairs(&[
+                        ("__name__", "latency_bucket"),
+                        ("job", "api"),
+                        ("le", "+Inf")
+                    ]),
+                    4.0
+                ),
+                (pairs(&[("__name__", "latency_sum"), ("job", "api")]), 2.5),
+                (pairs(&[("__name__", "latency_count"), ("job", "api")]), 4.0),
+            ]
+        );
+
+        // The exemplar goes with the `le="1"` bucket
+        assert_eq!(request.timeseries[1].exemplars.len(), 1);
+        let exemplar = &request.timeseries[1].exemplars[0];
+        assert_eq!(exemplar.value, 0.7);
+        assert_eq!(exemplar.timestamp, 0);
+        assert_eq!(exemplar.labels[0].value, "abc");
+
+        assert_eq!(request.metadata[0].type_pb, MetricType::HISTOGRAM);
+        assert_eq!(request.metadata[0].metric_family_name, "latency");
+    }
+
+    #[test]
+    fn encodes_cumulative_histogram_buckets() {
+        let bytes = encode(value!({
+            "message": {
+                "name": "latency",
+                "kind": "absolute",
+                "value": {
+                    "type": "histogram",
+                    "value": {
+                        "buckets": [
+                            {"upper_limit": 0.1, "count": 2},
+                            {"upper_limit": 0.5, "count": 3},
+                            {"upper_limit": 1.0, "count": 0},
+                            {"upper_limit": (f64::INFINITY), "count": 1},
+                        ],
+                        "sum": 3.2,
+                        "count": 6,
+                    },
+                },
+            },
+        }))
+        .unwrap();
+        let request = WriteRequest::try_from(&bytes[..]).unwrap();
+
+        let buckets = request
+            .timeseries
+            .iter()
+            .filter(|series| series.labels.iter().any(|label| label.name == LE_LABEL))
+            .map(|series| {
+                let le = series
+                    .labels
+                    .iter()
+                    .find(|label| label.name == LE_LABEL)
+                    .unwrap();
+                (le.value.to_string(), series.samples[0].value)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            buckets,
+            vec![
+                ("0.1".to_string(), 2.0),
+                ("0.5".to_string(), 5.0),
+                ("1".to_string(), 5.0),
+                ("+Inf".to_string(), 6.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn encodes_batch_as_single_request() {
+        let mut buffer = BytesMut::new();
+        for name in ["first", "second"] {
+            PrometheusRemoteWriteSerializer
+                .encode(
+                    Event::Log(LogEvent::from(value!({
+                        "message": {
+                            "name": name,
+                            "kind": "absolute",
+                            "value": {"type": "gauge", "value": 1.0},
+                        },
+                    }))),
+                    &mut buffer,
+                )
+                .unwrap();
+        }
+        let request = WriteRequest::try_from(&buffer[..]).unwrap();
+
+        let names = request
+            .timeseries
+            .iter()
+            .map(|series| series.labels[0].value.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["first", "second"]);
+        let families = request
+            .metadata
+            .iter()
+            .map(|metadata| metadata.metric_family_name.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(families, vec!["first", "second"]);
+    }
+
+    #[test]
+    fn encodes_summary() {
+        let bytes = encode(value!({
+            "message": {
+                "name": "rpc_seconds",
+                "kind": "absolute",
+                "value": {
+                    "type": "summary",
+                    "value": {
+                        "quantiles": [
+                            {"quantile": 0.5, "value": 0.1},
+                            {"quantile": 0.99, "value": 0.4},
+                        ],
+                        "sum": 12.0,
+                        "count": 60,
+                    },
+                },
+            },
+        }))
+        .unwrap();
+        let request = WriteRequest::try_from(&bytes[..]).unwrap();
+
+        let series = request
+            .timeseries
+            .iter()
+            .map(|series| (labels(series), series.samples[0].value))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            series,
+            vec![
+                (
+                    pairs(&[("__name__", "rpc_seconds"), ("quantile", "0.5")]),
+                    0.1
+                ),
+                (
+                    pairs(&[("__name__", "rpc_seconds"), ("quantile", "0.99")]),
+                    0.4
+                ),
+                (pairs(&[("__name__", "rpc_seconds_sum")]), 12.0),
+                (pairs(&[("__name__", "rpc_seconds_count")]), 60.0),
+            ]
+        );
+        assert_eq!(request.metadata[0].type_pb, MetricType::SUMMARY);
+    }
+
+    #[test]
+    fn rejects_unsupported_metrics() {
+        let error = encode(value!({
+            "message": {
+                "name": "users",
+                "kind": "absolute",
+                "value": {"type": "set", "value": {"values": ["a"]}},
+            },
+        }))
+        .unwrap_err();
+        assert_eq!(error.to_string(), "Metric type 'set' is not supported");
+
+        let error = encode(value!({"message": "not a metric"})).unwrap_err();
+        assert_eq!(error.to_string(), "Field 'message' type is not valid");
+    }
+
+    #[test]
+    fn formats_floats_like_prometheus() {
+        assert_eq!(format_float(1.0), "1");
+        assert_eq!(format_float(0.25), "0.25");
+        assert_eq!(format_float(1e-128), "1e-128");
+        assert_eq!(format_float(f64::INFINITY), "+Inf");
+    }
+
+    #[test]
+    fn round_trips_through_the_deserializer() {
+        let message = value!({
+            "name": "latency",
+            "kind": "absolute",
+            "tags": {"job": "api"},
+            "value": {
+                "type": "histogram",
+                "value": {
+                    "bucket": [
+                        {"upper_limit": 0.5, "count": 1},
+                        {"upper_limit": 1.0, "count": 3},
+                        {"upper_limit": (f64::INFINITY), "count": 4},
+                    ],
+                    "sum": 2.5,
+                    "count": 4,
+                },
+            },
+        });
+
+        let mut buffer = BytesMut::new();
+        PrometheusRemoteWriteSerializer
+            .encode(
+                Event::Log(LogEvent::from(value!({"message": (message.clone())}))),
+                &mut buffer,
+            )
+            .unwrap();
+
+        let compressed = snap::raw::Encoder::new().compress_vec(&buffer).unwrap();
+        let events = MezmoDeserializer::PrometheusRemoteWrite
+            .build()
+            .parse(compressed.into(), LogNamespace::Legacy)
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].as_log().get(".message").unwrap(), &message);
+    }
+}
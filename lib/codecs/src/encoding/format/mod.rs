@@ -12,6 +12,7 @@ mod csv;
 mod gelf;
 mod json;
 mod logfmt;
+mod mezmo;
 mod native;
 mod native_json;
 #[cfg(feature = "opentelemetry")]
@@ -35,6 +36,10 @@ use dyn_clone::DynClone;
 pub use gelf::{GelfSerializer, GelfSerializerConfig};
 pub use json::{JsonSerializer, JsonSerializerConfig, JsonSerializerOptions};
 pub use logfmt::{LogfmtSerializer, LogfmtSerializerConfig};
+pub use mezmo::{
+    MezmoSerializer, MezmoSerializerConfig, PrometheusRemoteWriteSerializer,
+    PrometheusRemoteWriteSerializerError,
+};
 pub use native::{NativeSerializer, NativeSerializerConfig};
 pub use native_json::{NativeJsonSerializer, NativeJsonSerializerConfig};
 #[cfg(feature = "opentelemetry")]
@@ -1,7 +1,13 @@
 use std::path::PathBuf;
 
-use crate::encoding::BuildError;
-use bytes::BytesMut;
+use crate::{
+    encoding::BuildError,
+    schema_registry::{
+        SchemaRegistry,
+        wire_format::{encode_message_indexes, encode_schema_id},
+    },
+};
+use bytes::{BufMut, BytesMut};
 use prost_reflect::{MessageDescriptor, prost::Message as _};
 use tokio_util::codec::Encoder;
 use vector_config_macros::configurable_component;
@@ -17,15 +23,22 @@ use vrl::protobuf::{
 
 /// Config used to build a `ProtobufSerializer`.
 #[configurable_component]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ProtobufSerializerConfig {
     /// Options for the Protobuf serializer.
+    #[serde(default)]
     pub protobuf: ProtobufSerializerOptions,
 }
 
 impl ProtobufSerializerConfig {
     /// Build the `ProtobufSerializer` from this configuration.
     pub fn build(&self) -> Result<ProtobufSerializer, BuildError> {
+        if self.protobuf.desc_file.as_os_str().is_empty() {
+            return Err(
+                "A protobuf descriptor file is required when no schema registry is configured"
+                    .into(),
+            );
+        }
         let message_descriptor =
             get_message_descriptor(&self.protobuf.desc_file, &self.protobuf.message_type)?;
         Ok(ProtobufSerializer {
@@ -33,6 +46,34 @@ impl ProtobufSerializerConfig {
             options: Options {
                 use_json_names: self.protobuf.use_json_names,
             },
+            wire_format_prefix: None,
+        })
+    }
+
+    /// Build a `ProtobufSerializer` encoding events in the Confluent wire format with the schema
+    /// registered under the given id, which replaces the descriptor file and message type. The
+    /// given message type is looked up in that schema, an empty one selecting its first message
+    /// type. The schema is fetched from the registry unless it's already in memory.
+    pub async fn build_with_schema_registry(
+        &self,
+        schema_registry: &SchemaRegistry,
+        schema_id: u32,
+        message_type: &str,
+    ) -> Result<ProtobufSerializer, BuildError> {
+        let (message_descriptor, indexes) = schema_registry
+            .protobuf_message_by_name(schema_id, message_type)
+            .await?;
+
+        let mut wire_format_prefix = Vec::new();
+        encode_schema_id(schema_id, &mut wire_format_prefix);
+        encode_message_indexes(&indexes, &mut wire_format_prefix);
+
+        Ok(ProtobufSerializer {
+            message_descriptor,
+            options: Options {
+                use_json_names: self.protobuf.use_json_names,
+            },
+            wire_format_prefix: Some(wire_format_prefix),
         })
     }
 
@@ -51,7 +92,7 @@ impl ProtobufSerializerConfig {
 
 /// Protobuf serializer options.
 #[configurable_component]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ProtobufSerializerOptions {
     /// The path to the protobuf descriptor set file.
     ///
@@ -82,6 +123,8 @@ pub struct ProtobufSerializer {
     /// The protobuf message definition to use for serialization.
     message_descriptor: MessageDescriptor,
     options: Options,
+    /// The schema id and message indexes prefixing the messages in the Confluent wire format.
+    wire_format_prefix: Option<Vec<u8>>,
 }
 
 impl ProtobufSerializer {
@@ -90,6 +133,7 @@ impl ProtobufSerializer {
         Self {
             message_descriptor,
             options: Options::default(),
+            wire_format_prefix: None,
         }
     }
 
@@ -103,6 +147,7 @@ impl ProtobufSerializer {
         Ok(Self {
             message_descriptor,
             options: options.clone(),
+            wire_format_prefix: None,
         })
     }
 
@@ -127,6 +172,61 @@ impl Encoder<Event> for ProtobufSerializer {
                 &self.options,
             ),
         }?;
+        if let Some(prefix) = &self.wire_format_prefix {
+            buffer.put_slice(prefix);
+        }
         message.encode(buffer).map_err(Into::into)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use serde_json::json;
+    use vector_core::{config::LogNamespace, event::LogEvent};
+    use vrl::btreemap;
+
+    use super::*;
+    use crate::{
+        decoding::format::{Deserializer, ProtobufDeserializerConfig},
+        schema_registry::{
+            SchemaRegistryConfig,
+            tests::{mock_registry, protobuf_schema},
+        },
+    };
+
+    #[tokio::test]
+    async fn serialize_protobuf_schema_registry() {
+        let server = mock_registry(&[(
+            2,
+            json!({"schemaType": "PROTOBUF", "schema": protobuf_schema()}),
+        )])
+        .await;
+        let schema_registry = SchemaRegistryConfig::new(server.uri()).build().unwrap();
+        let event = Event::Log(LogEvent::from(btreemap! {
+            "number" => Value::from("1234"),
+        }));
+
+        let mut serializer = ProtobufSerializerConfig::default()
+            .build_with_schema_registry(&schema_registry, 2, "test_protobuf3.Person.PhoneNumber")
+            .await
+            .unwrap();
+        let mut bytes = BytesMut::new();
+        serializer.encode(event.clone(), &mut bytes).unwrap();
+        // The schema id, then the message indexes `[0, 0]`.
+        assert_eq!(bytes[..8], [0, 0, 0, 0, 2, 4, 0, 0]);
+
+        let deserializer =
+            ProtobufDeserializerConfig::default().build_with_schema_registry(schema_registry);
+        // The schema was fetched when building the serializer.
+        let events = deserializer
+            .parse(Bytes::from(bytes), LogNamespace::Vector)
+            .unwrap();
+        assert_eq!(events[0].as_log().value(), event.as_log().value());
+    }
+
+    #[test]
+    fn build_protobuf_requires_desc_file() {
+        assert!(ProtobufSerializerConfig::default().build().is_err());
+    }
+}
@@ -16,15 +16,17 @@ use super::{
         AvroSerializer, AvroSerializerConfig, AvroSerializerOptions, CefSerializer,
         CefSerializerConfig, CsvSerializer, CsvSerializerConfig, GelfSerializer,
         GelfSerializerConfig, JsonSerializer, JsonSerializerConfig, LogfmtSerializer,
-        LogfmtSerializerConfig, NativeJsonSerializer, NativeJsonSerializerConfig, NativeSerializer,
-        NativeSerializerConfig, ProtobufSerializer, ProtobufSerializerConfig, RawMessageSerializer,
-        RawMessageSerializerConfig, TextSerializer, TextSerializerConfig,
+        LogfmtSerializerConfig, MezmoSerializer, MezmoSerializerConfig, NativeJsonSerializer,
+        NativeJsonSerializerConfig, NativeSerializer, NativeSerializerConfig, ProtobufSerializer,
+        ProtobufSerializerConfig, RawMessageSerializer, RawMessageSerializerConfig, TextSerializer,
+        TextSerializerConfig,
     },
     framing::{
         CharacterDelimitedEncoderConfig, FramingConfig, LengthDelimitedEncoderConfig,
         VarintLengthDelimitedEncoderConfig,
     },
 };
+use crate::schema_registry::SchemaRegistry;
 
 /// Serializer configuration.
 #[configurable_component]
@@ -37,6 +39,7 @@ pub enum SerializerConfig {
     /// [apache_avro]: https://avro.apache.org/
     Avro {
         /// Apache Avro-specific encoder options.
+        #[serde(default)]
         avro: AvroSerializerOptions,
     },
 
@@ -81,6 +84,12 @@ pub enum SerializerConfig {
     /// [logfmt]: https://brandur.org/logfmt
     Logfmt,
 
+    /// Encode the events using one of Mezmo's serializers
+    ///
+    /// Delegates the Serializer configuration to the MezmoSerializerConfig
+    /// types
+    Mezmo(MezmoSerializerConfig),
+
     /// Encodes an event in the [native Protocol Buffers format][vector_native_protobuf].
     ///
     /// This codec is **[experimental][experimental]**.
@@ -226,6 +235,12 @@ impl From<LogfmtSerializerConfig> for SerializerConfig {
     }
 }
 
+impl From<MezmoSerializerConfig> for SerializerConfig {
+    fn from(config: MezmoSerializerConfig) -> Self {
+        Self::Mezmo(config)
+    }
+}
+
 impl From<NativeSerializerConfig> for SerializerConfig {
     fn from(_: NativeSerializerConfig) -> Self {
         Self::Native
@@ -275,6 +290,7 @@ impl SerializerConfig {
             SerializerConfig::Gelf(config) => Ok(Serializer::Gelf(config.build())),
             SerializerConfig::Json(config) => Ok(Serializer::Json(config.build())),
             SerializerConfig::Logfmt => Ok(Serializer::Logfmt(LogfmtSerializerConfig.build())),
+            SerializerConfig::Mezmo(config) => Ok(Serializer::Mezmo(config.build())),
             SerializerConfig::Native => Ok(Serializer::Native(NativeSerializerConfig.build())),
             SerializerConfig::NativeJson => {
                 Ok(Serializer::NativeJson(NativeJsonSerializerConfig.build()))
@@ -293,6 +309,38 @@ impl SerializerConfig {
         }
     }
 
+    /// Build the `Serializer` from this configuration, encoding events in the Confluent wire
+    /// format with the schema registered under the given id. The Protobuf message type is looked
+    /// up in that schema, its first message type being used when none is given.
+    ///
+    /// Only the `avro` and `protobuf` codecs support a schema registry.
+    pub async fn build_with_schema_registry(
+        &self,
+        schema_registry: &SchemaRegistry,
+        schema_id: u32,
+        message_type: Option<&str>,
+    ) -> Result<Serializer, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        match self {
+            SerializerConfig::Avro { avro } => Ok(Serializer::Avro(
+                AvroSerializerConfig::new(avro.schema.clone())
+                    .build_with_schema_registry(schema_registry, schema_id)
+                    .await?,
+            )),
+            SerializerConfig::Protobuf(config) => Ok(Serializer::Protobuf(
+                config
+                    .build_with_schema_registry(
+                        schema_registry,
+                        schema_id,
+                        message_type.unwrap_or_default(),
+                    )
+                    .await?,
+            )),
+            _ => Err(
+                "A schema registry can only be used with the `avro` and `protobuf` codecs".into(),
+            ),
+        }
+    }
+
     /// Return an appropriate default framer for the given serializer.
     pub fn default_stream_framing(&self) -> FramingConfig {
         match self {
@@ -312,6 +360,8 @@ impl SerializerConfig {
             }
             #[cfg(feature = "opentelemetry")]
             SerializerConfig::Otlp => FramingConfig::Bytes,
+            // The requests of a batch are concatenated into a single request
+            SerializerConfig::Mezmo(_) => FramingConfig::Bytes,
             SerializerConfig::Protobuf(_) => {
                 FramingConfig::VarintLengthDelimited(VarintLengthDelimitedEncoderConfig::default())
             }
@@ -341,6 +391,7 @@ impl SerializerConfig {
             SerializerConfig::Gelf(config) => config.input_type(),
             SerializerConfig::Json(config) => config.input_type(),
             SerializerConfig::Logfmt => LogfmtSerializerConfig.input_type(),
+            SerializerConfig::Mezmo(config) => config.input_type(),
             SerializerConfig::Native => NativeSerializerConfig.input_type(),
             SerializerConfig::NativeJson => NativeJsonSerializerConfig.input_type(),
             #[cfg(feature = "opentelemetry")]
@@ -364,6 +415,7 @@ impl SerializerConfig {
             SerializerConfig::Gelf(config) => config.schema_requirement(),
             SerializerConfig::Json(config) => config.schema_requirement(),
             SerializerConfig::Logfmt => LogfmtSerializerConfig.schema_requirement(),
+            SerializerConfig::Mezmo(config) => config.schema_requirement(),
             SerializerConfig::Native => NativeSerializerConfig.schema_requirement(),
             SerializerConfig::NativeJson => NativeJsonSerializerConfig.schema_requirement(),
             #[cfg(feature = "opentelemetry")]
@@ -392,6 +444,8 @@ pub enum Serializer {
     Json(JsonSerializer),
     /// Uses a `LogfmtSerializer` for serialization.
     Logfmt(LogfmtSerializer),
+    /// Uses a `MezmoSerializer` for serialization.
+    Mezmo(MezmoSerializer),
     /// Uses a `NativeSerializer` for serialization.
     Native(NativeSerializer),
     /// Uses a `NativeJsonSerializer` for serialization.
@@ -419,6 +473,7 @@ impl Serializer {
             | Serializer::Cef(_)
             | Serializer::Csv(_)
             | Serializer::Logfmt(_)
+            | Serializer::Mezmo(_)
             | Serializer::Text(_)
             | Serializer::Native(_)
             | Serializer::Protobuf(_)
@@ -445,6 +500,7 @@ impl Serializer {
             | Serializer::Cef(_)
             | Serializer::Csv(_)
             | Serializer::Logfmt(_)
+            | Serializer::Mezmo(_)
             | Serializer::Text(_)
             | Serializer::Native(_)
             | Serializer::Protobuf(_)
@@ -478,6 +534,7 @@ impl Serializer {
         match self {
             Serializer::RawMessage(_)
             | Serializer::Avro(_)
+            | Serializer::Mezmo(_)
             | Serializer::Native(_)
             | Serializer::Protobuf(_) => true,
             #[cfg(feature = "opentelemetry")]
@@ -531,6 +588,12 @@ impl From<LogfmtSerializer> for Serializer {
     }
 }
 
+impl From<MezmoSerializer> for Serializer {
+    fn from(serializer: MezmoSerializer) -> Self {
+        Self::Mezmo(serializer)
+    }
+}
+
 impl From<NativeSerializer> for Serializer {
     fn from(serializer: NativeSerializer) -> Self {
         Self::Native(serializer)
@@ -585,6 +648,7 @@ impl tokio_util::codec::Encoder<Event> for Serializer {
             Serializer::Gelf(serializer) => serializer.encode(event, buffer),
             Serializer::Json(serializer) => serializer.encode(event, buffer),
             Serializer::Logfmt(serializer) => serializer.encode(event, buffer),
+            Serializer::Mezmo(serializer) => serializer.encode(event, buffer),
             Serializer::Native(serializer) => serializer.encode(event, buffer),
             Serializer::NativeJson(serializer) => serializer.encode(event, buffer),
             #[cfg(feature = "opentelemetry")]
@@ -20,6 +20,7 @@ pub mod encoding;
 pub mod gelf;
 pub mod internal_events;
 mod ready_frames;
+pub mod schema_registry;
 
 pub use decoder_framed_read::DecoderFramedRead;
 pub use decoding::{
@@ -46,6 +47,9 @@ pub use encoding::{
 };
 pub use gelf::{VALID_FIELD_REGEX, gelf_fields};
 pub use ready_frames::ReadyFrames;
+pub use schema_registry::{
+    SchemaRegistry, SchemaRegistryAuth, SchemaRegistryConfig, SchemaRegistryError,
+};
 use vector_config_macros::configurable_component;
 
 /// The user configuration to choose the metric tag strategy.
@@ -0,0 +1,1094 @@
+//! Support for the schemas stored in a [Confluent schema registry][schema_registry], used by the
+//! Avro and Protobuf codecs to decode and encode messages in the Confluent wire format.
+//!
+//! Schemas are fetched by id and kept in memory for the lifetime of the codec. When a cache
+//! directory is configured, the fetched schemas are also written to disk so that they can still
+//! be used while the registry is unreachable. Schemas which couldn't be fetched aren't requested
+//! again until an exponential backoff expires, the messages using them failing in between.
+//!
+//! The codecs being synchronous, they never fetch schemas themselves and only use the ones
+//! already in memory. The schemas are fetched ahead of time instead: when building the
+//! serializers, and before decoding each message for the deserializers.
+//!
+//! [schema_registry]: https://docs.confluent.io/platform/current/schema-registry/develop/api.html
+
+pub mod wire_format;
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, Instant},
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use bytes::Bytes;
+use futures::{FutureExt, future::BoxFuture};
+use prost_reflect::{
+    DescriptorPool, FileDescriptor, MessageDescriptor, prost::Message,
+    prost_types::FileDescriptorProto,
+};
+use serde::Deserialize;
+use snafu::{OptionExt, ResultExt, Snafu, ensure};
+use tracing::warn;
+use url::Url;
+use vector_common::sensitive_string::SensitiveString;
+use vector_config::configurable_component;
+
+/// The delay before requesting a schema again after a first failure.
+const INITIAL_RETRY_BACKOFF: Duration = Duration::from_secs(1);
+
+/// The longest delay before requesting a schema again after repeated failures.
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(60);
+
+const fn default_timeout_secs() -> u64 {
+    10
+}
+
+/// Schema registry configuration.
+#[configurable_component]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaRegistryConfig {
+    /// The base URL of the schema registry.
+    #[configurable(metadata(docs::examples = "http://localhost:8081"))]
+    pub url: String,
+
+    /// The basic authentication credentials of the schema registry.
+    #[configurable(derived)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<SchemaRegistryAuth>,
+
+    /// The directory where the fetched schemas are stored.
+    ///
+    /// The schemas stored in this directory are used when the schema registry can't be reached.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[configurable(metadata(docs::examples = "/var/lib/vector/schemas"))]
+    pub cache_dir: Option<PathBuf>,
+
+    /// The timeout for the schema registry requests.
+    #[serde(default = "default_timeout_secs")]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    #[configurable(metadata(docs::advanced))]
+    pub timeout_secs: u64,
+}
+
+/// Schema registry basic authentication.
+#[configurable_component]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaRegistryAuth {
+    /// The basic authentication username.
+    #[configurable(metadata(docs::examples = "${SCHEMA_REGISTRY_USERNAME}"))]
+    pub user: String,
+
+    /// The basic authentication password.
+    #[configurable(metadata(docs::examples = "${SCHEMA_REGISTRY_PASSWORD}"))]
+    pub password: SensitiveString,
+}
+
+impl SchemaRegistryConfig {
+    /// Creates a new `SchemaRegistryConfig` for the registry at `url`.
+    pub const fn new(url: String) -> Self {
+        Self {
+            url,
+            auth: None,
+            cache_dir: None,
+            timeout_secs: default_timeout_secs(),
+        }
+    }
+
+    /// Build the `SchemaRegistry` from this configuration.
+    pub fn build(&self) -> Result<SchemaRegistry, SchemaRegistryError> {
+        let url = Url::parse(&self.url).context(InvalidUrlSnafu { url: &self.url })?;
+        ensure!(!url.cannot_be_a_base(), NotABaseUrlSnafu { url: &self.url });
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(self.timeout_secs))
+            .build()
+            .context(ClientSnafu)?;
+
+        Ok(SchemaRegistry {
+            inner: Arc::new(Inner {
+                url,
+                cache_dir: self.cache_dir.clone(),
+                client,
+                auth: self.auth.clone(),
+                avro_schemas: Default::default(),
+                protobuf_schemas: Default::default(),
+                failures: Default::default(),
+            }),
+        })
+    }
+}
+
+/// Schema registry error list
+#[derive(Debug, Snafu)]
+pub enum SchemaRegistryError {
+    /// The configured URL isn't valid
+    #[snafu(display("Invalid schema registry URL {url:?}: {source}"))]
+    InvalidUrl {
+        /// The configured URL
+        url: String,
+        /// The original error
+        source: url::ParseError,
+    },
+    /// The configured URL can't have a path
+    #[snafu(display("Invalid schema registry URL {url:?}: not a base URL"))]
+    NotABaseUrl {
+        /// The configured URL
+        url: String,
+    },
+    /// The HTTP client of the registry couldn't be created
+    #[snafu(display("Failed creating the schema registry client: {source}"))]
+    Client {
+        /// The original error
+        source: reqwest::Error,
+    },
+    /// The registry request failed
+    #[snafu(display("Schema registry request to {url} failed: {source}"))]
+    Request {
+        /// The requested URL
+        url: String,
+        /// The original error
+        source: reqwest::Error,
+    },
+    /// The registry answered with an error status
+    #[snafu(display("Schema registry request to {url} failed with status {status}"))]
+    Status {
+        /// The requested URL
+        url: String,
+        /// The response status
+        status: u16,
+    },
+    /// The registry response or the cached schema isn't valid
+    #[snafu(display("Invalid schema registry response from {location}: {source}"))]
+    InvalidResponse {
+        /// The requested URL or the cache file path
+        location: String,
+        /// The original error
+        source: serde_json::Error,
+    },
+    /// The schema isn't of the expected type
+    #[snafu(display("Schema {schema_id} is a {schema_type} schema, expected {expected}"))]
+    UnexpectedSchemaType {
+        /// The schema id
+        schema_id: u32,
+        /// The registered schema type
+        schema_type: String,
+        /// The schema type supported by the codec
+        expected: &'static str,
+    },
+    /// The Avro schema references other schemas
+    #[snafu(display("Avro schema {schema_id} references other schemas, which is not supported"))]
+    AvroReferences {
+        /// The schema id
+        schema_id: u32,
+    },
+    /// The Avro schema isn't valid
+    #[snafu(display("Invalid Avro schema {schema_id}: {source}"))]
+    InvalidAvroSchema {
+        /// The schema id
+        schema_id: u32,
+        /// The original error
+        source: apache_avro::Error,
+    },
+    /// The Protobuf schema or one of its references isn't valid
+    #[snafu(display("Invalid Protobuf schema {schema_id}: {reason}"))]
+    InvalidProtobufSchema {
+        /// The schema id
+        schema_id: u32,
+        /// The reason the schema is invalid
+        reason: String,
+    },
+    /// The schema hasn't been fetched ahead of its use by a codec
+    #[snafu(display("Schema {schema_id} hasn't been fetched from the schema registry"))]
+    NotFetched {
+        /// The schema id
+        schema_id: u32,
+    },
+    /// The schema recently failed to be fetched and won't be requested again until its backoff
+    /// expires
+    #[snafu(display("Schema {schema_id} is unavailable, retrying in {retry_in:?}: {reason}"))]
+    Unavailable {
+        /// The schema id
+        schema_id: u32,
+        /// The time left before the schema is requested again
+        retry_in: Duration,
+        /// The last error fetching the schema
+        reason: String,
+    },
+    /// The message indexes don't match a message type of the Protobuf schema
+    #[snafu(display("Protobuf schema {schema_id} has no message type at indexes {indexes:?}"))]
+    UnknownMessageIndexes {
+        /// The schema id
+        schema_id: u32,
+        /// The message indexes
+        indexes: Vec<i64>,
+    },
+    /// The message type isn't defined by the Protobuf schema
+    #[snafu(display(
+        "Protobuf schema {schema_id} doesn't define the message type {message_type:?}"
+    ))]
+    UnknownMessageType {
+        /// The schema id
+        schema_id: u32,
+        /// The message type name
+        message_type: String,
+    },
+}
+
+/// A schema as returned by the registry.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RegisteredSchema {
+    /// Absent for Avro schemas.
+    #[serde(default)]
+    schema_type: Option<String>,
+    schema: String,
+    #[serde(default)]
+    references: Vec<SchemaReference>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SchemaReference {
+    name: String,
+    subject: String,
+    version: i64,
+}
+
+impl RegisteredSchema {
+    fn ensure_schema_type(
+        &self,
+        schema_id: u32,
+        expected: &'static str,
+    ) -> Result<(), SchemaRegistryError> {
+        let schema_type = self.schema_type.as_deref().unwrap_or("AVRO");
+        ensure!(
+            schema_type == expected,
+            UnexpectedSchemaTypeSnafu {
+                schema_id,
+                schema_type,
+                expected
+            }
+        );
+        Ok(())
+    }
+}
+
+/// The last failure fetching a schema.
+#[derive(Debug)]
+struct FailedFetch {
+    retry_at: Instant,
+    backoff: Duration,
+    reason: String,
+}
+
+#[derive(Debug)]
+struct Inner {
+    url: Url,
+    cache_dir: Option<PathBuf>,
+    client: reqwest::Client,
+    auth: Option<SchemaRegistryAuth>,
+    avro_schemas: RwLock<HashMap<u32, Arc<apache_avro::Schema>>>,
+    protobuf_schemas: RwLock<HashMap<u32, FileDescriptor>>,
+    failures: Mutex<HashMap<u32, FailedFetch>>,
+}
+
+/// Client of a schema registry, keeping the schemas it fetched.
+///
+/// Cloning the client shares its schemas.
+#[derive(Clone, Debug)]
+pub struct SchemaRegistry {
+    inner: Arc<Inner>,
+}
+
+impl SchemaRegistry {
+    /// Returns the Avro schema registered with the given id, fetching it unless it's already in
+    /// memory.
+    pub async fn avro_schema(
+        &self,
+        schema_id: u32,
+    ) -> Result<Arc<apache_avro::Schema>, SchemaRegistryError> {
+        if let Some(schema) = read_cache(&self.inner.avro_schemas, schema_id) {
+            return Ok(schema);
+        }
+
+        self.ensure_available(schema_id)?;
+        let schema = self.record_fetch(schema_id, self.fetch_avro_schema(schema_id).await)?;
+        Ok(write_cache(
+            &self.inner.avro_schemas,
+            schema_id,
+            Arc::new(schema),
+        ))
+    }
+
+    /// Returns the Avro schema registered with the given id, which must have been fetched
+    /// already.
+    pub fn fetched_avro_schema(
+        &self,
+        schema_id: u32,
+    ) -> Result<Arc<apache_avro::Schema>, SchemaRegistryError> {
+        self.read_fetched(&self.inner.avro_schemas, schema_id)
+    }
+
+    async fn fetch_avro_schema(
+        &self,
+        schema_id: u32,
+    ) -> Result<apache_avro::Schema, SchemaRegistryError> {
+        let registered = self
+            .fetch(
+                &["schemas", "ids", &schema_id.to_string()],
+                None,
+                &format!("schema-{schema_id}.json"),
+            )
+            .await?;
+        registered.ensure_schema_type(schema_id, "AVRO")?;
+        ensure!(
+            registered.references.is_empty(),
+            AvroReferencesSnafu { schema_id }
+        );
+        apache_avro::Schema::parse_str(&registered.schema)
+            .context(InvalidAvroSchemaSnafu { schema_id })
+    }
+
+    /// Returns the Protobuf message type found at the given message indexes of the schema
+    /// registered with the given id, which must have been fetched already.
+    pub fn fetched_protobuf_message(
+        &self,
+        schema_id: u32,
+        indexes: &[i64],
+    ) -> Result<MessageDescriptor, SchemaRegistryError> {
+        let file = self.read_fetched(&self.inner.protobuf_schemas, schema_id)?;
+
+        let (first, rest) = indexes
+            .split_first()
+            .context(UnknownMessageIndexesSnafu { schema_id, indexes })?;
+        let mut message = nth_message(file.messages(), *first);
+        for index in rest {
+            message = message.and_then(|message| nth_message(message.child_messages(), *index));
+        }
+        message.context(UnknownMessageIndexesSnafu { schema_id, indexes })
+    }
+
+    /// Returns the Protobuf message type with the given name, along with its message indexes, in
+    /// the schema registered with the given id, fetching it unless it's already in memory. An
+    /// empty name selects the first message type of the schema.
+    pub async fn protobuf_message_by_name(
+        &self,
+        schema_id: u32,
+        message_type: &str,
+    ) -> Result<(MessageDescriptor, Vec<i64>), SchemaRegistryError> {
+        let file = self.protobuf_schema(schema_id).await?;
+
+        let message = if message_type.is_empty() {
+            file.messages().next()
+        } else {
+            file.parent_pool()
+                .get_message_by_name(message_type)
+                .filter(|message| message.parent_file().name() == file.name())
+        }
+        .context(UnknownMessageTypeSnafu {
+            schema_id,
+            message_type,
+        })?;
+
+        let indexes = message_indexes(&message);
+        Ok((message, indexes))
+    }
+
+    /// Returns the Protobuf schema registered with the given id, fetching it unless it's already
+    /// in memory.
+    pub async fn protobuf_schema(
+        &self,
+        schema_id: u32,
+    ) -> Result<FileDescriptor, SchemaRegistryError> {
+        if let Some(file) = read_cache(&self.inner.protobuf_schemas, schema_id) {
+            return Ok(file);
+        }
+
+        self.ensure_available(schema_id)?;
+        let file = self.record_fetch(schema_id, self.fetch_protobuf_schema(schema_id).await)?;
+        Ok(write_cache(&self.inner.protobuf_schemas, schema_id, file))
+    }
+
+    async fn fetch_protobuf_schema(
+        &self,
+        schema_id: u32,
+    ) -> Result<FileDescriptor, SchemaRegistryError> {
+        let registered = self
+            .fetch(
+                &["schemas", "ids", &schema_id.to_string()],
+                Some("format=serialized"),
+                &format!("schema-{schema_id}.serialized.json"),
+            )
+            .await?;
+        registered.ensure_schema_type(schema_id, "PROTOBUF")?;
+
+        let mut pool = DescriptorPool::new();
+        self.add_protobuf_references(&mut pool, &registered.references, schema_id)
+            .await?;
+
+        let mut file = decode_file_descriptor(&registered.schema, schema_id)?;
+        // The registry doesn't always keep the file name, which the pool requires.
+        if file.name().is_empty() {
+            file.name = Some(format!("schema-{schema_id}.proto"));
+        }
+        let name = file.name().to_owned();
+        pool.add_file_descriptor_proto(file).map_err(|error| {
+            SchemaRegistryError::InvalidProtobufSchema {
+                schema_id,
+                reason: error.to_string(),
+            }
+        })?;
+        Ok(pool
+            .get_file_by_name(&name)
+            .expect("the schema file was just added to the pool"))
+    }
+
+    /// Reads a schema from memory, failing with the error of its last fetch while its backoff
+    /// runs.
+    fn read_fetched<T: Clone>(
+        &self,
+        cache: &RwLock<HashMap<u32, T>>,
+        schema_id: u32,
+    ) -> Result<T, SchemaRegistryError> {
+        if let Some(schema) = read_cache(cache, schema_id) {
+            return Ok(schema);
+        }
+
+        self.ensure_available(schema_id)?;
+        NotFetchedSnafu { schema_id }.fail()
+    }
+
+    /// Fails without requesting the schema while the backoff of its last failure runs.
+    fn ensure_available(&self, schema_id: u32) -> Result<(), SchemaRegistryError> {
+        let failures = self
+            .inner
+            .failures
+            .lock()
+            .expect("schema failures lock should not be poisoned");
+        match failures.get(&schema_id) {
+            Some(failure) => {
+                let retry_in = failure.retry_at.saturating_duration_since(Instant::now());
+                ensure!(
+                    retry_in.is_zero(),
+                    UnavailableSnafu {
+                        schema_id,
+                        retry_in,
+                        reason: &failure.reason,
+                    }
+                );
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Keeps track of the failures fetching a schema, doubling the backoff on each consecutive
+    /// failure.
+    fn record_fetch<T>(
+        &self,
+        schema_id: u32,
+        result: Result<T, SchemaRegistryError>,
+    ) -> Result<T, SchemaRegistryError> {
+        let mut failures = self
+            .inner
+            .failures
+            .lock()
+            .expect("schema failures lock should not be poisoned");
+        match &result {
+            Ok(_) => {
+                failures.remove(&schema_id);
+            }
+            Err(error) => {
+                let backoff = failures
+                    .get(&schema_id)
+                    .map_or(INITIAL_RETRY_BACKOFF, |failure| {
+                        (failure.backoff * 2).min(MAX_RETRY_BACKOFF)
+                    });
+                warn!(
+                    message = "Failed fetching schema, retrying later.",
+                    schema_id,
+                    retry_in = ?backoff,
+                    %error,
+                );
+                failures.insert(
+                    schema_id,
+                    FailedFetch {
+                        retry_at: Instant::now() + backoff,
+                        backoff,
+                        reason: error.to_string(),
+                    },
+                );
+            }
+        }
+        result
+    }
+
+    /// Adds the files imported by a Protobuf schema to `pool`, dependencies first.
+    fn add_protobuf_references<'a>(
+        &'a self,
+        pool: &'a mut DescriptorPool,
+        references: &'a [SchemaReference],
+        schema_id: u32,
+    ) -> BoxFuture<'a, Result<(), SchemaRegistryError>> {
+        async move {
+            for reference in references {
+                if pool.get_file_by_name(&reference.name).is_some() {
+                    continue;
+                }
+
+                let version = reference.version.to_string();
+                let registered = self
+                    .fetch(
+                        &["subjects", &reference.subject, "versions", &version],
+                        Some("format=serialized"),
+                        &format!(
+                            "subject-{}-{version}.serialized.json",
+                            sanitize_file_name(&reference.subject)
+                        ),
+                    )
+                    .await?;
+                self.add_protobuf_references(pool, &registered.references, schema_id)
+                    .await?;
+
+                let mut file = decode_file_descriptor(&registered.schema, schema_id)?;
+                // The file must be named after the import statement referencing it.
+                file.name = Some(reference.name.clone());
+                pool.add_file_descriptor_proto(file).map_err(|error| {
+                    SchemaRegistryError::InvalidProtobufSchema {
+                        schema_id,
+                        reason: format!("reference {:?}: {error}", reference.name),
+                    }
+                })?;
+            }
+            Ok(())
+        }
+        .boxed()
+    }
+
+    /// Fetches a schema from the registry, falling back to the cache directory when the registry
+    /// can't provide it.
+    async fn fetch(
+        &self,
+        segments: &[&str],
+        query: Option<&str>,
+        cache_file_name: &str,
+    ) -> Result<RegisteredSchema, SchemaRegistryError> {
+        let mut url = self.inner.url.clone();
+        url.path_segments_mut()
+            .expect("the registry URL is a base URL")
+            .pop_if_empty()
+            .extend(segments);
+        url.set_query(query);
+
+        let cache_path = self
+            .inner
+            .cache_dir
+            .as_ref()
+            .map(|dir| dir.join(cache_file_name));
+
+        match send(&self.inner.client, self.inner.auth.as_ref(), &url).await {
+            Ok(body) => {
+                let registered = serde_json::from_slice(&body).context(InvalidResponseSnafu {
+                    location: url.as_str(),
+                })?;
+                if let Some(path) = cache_path {
+                    if let Err(error) = write_cache_file(&path, &body) {
+                        warn!(
+                            message = "Failed writing schema to the cache directory.",
+                            path = %path.display(),
+                            %error,
+                        );
+                    }
+                }
+                Ok(registered)
+            }
+            Err(error) => {
+                let Some((path, body)) =
+                    cache_path.and_then(|path| fs::read(&path).ok().map(|body| (path, body)))
+                else {
+                    return Err(error);
+                };
+                warn!(
+                    message = "Schema registry unavailable, using the cached schema.",
+                    path = %path.display(),
+                    %error,
+                );
+                serde_json::from_slice(&body).context(InvalidResponseSnafu {
+                    location: path.display().to_string(),
+                })
+            }
+        }
+    }
+}
+
+async fn send(
+    client: &reqwest::Client,
+    auth: Option<&SchemaRegistryAuth>,
+    url: &Url,
+) -> Result<Bytes, SchemaRegistryError> {
+    let mut request = client
+        .get(url.clone())
+        .header("Accept", "application/vnd.schemaregistry.v1+json");
+    if let Some(auth) = auth {
+        request = request.basic_auth(&auth.user, Some(auth.password.inner()));
+    }
+
+    let response = request
+        .send()
+        .await
+        .context(RequestSnafu { url: url.as_str() })?;
+    let status = response.status();
+    ensure!(
+        status.is_success(),
+        StatusSnafu {
+            url: url.as_str(),
+            status: status.as_u16()
+        }
+    );
+    response
+        .bytes()
+        .await
+        .context(RequestSnafu { url: url.as_str() })
+}
+
+fn read_cache<T: Clone>(cache: &RwLock<HashMap<u32, T>>, schema_id: u32) -> Option<T> {
+    cache
+        .read()
+        .expect("schema cache lock should not be poisoned")
+        .get(&schema_id)
+        .cloned()
+}
+
+/// Stores a schema in memory, keeping the existing one if another codec fetched it in between.
+fn write_cache<T: Clone>(cache: &RwLock<HashMap<u32, T>>, schema_id: u32, schema: T) -> T {
+    cache
+        .write()
+        .expect("schema cache lock should not be poisoned")
+        .entry(schema_id)
+        .or_insert(schema)
+        .clone()
+}
+
+fn decode_file_descriptor(
+    schema: &str,
+    schema_id: u32,
+) -> Result<FileDescriptorProto, SchemaRegistryError> {
+    let bytes =
+        BASE64
+            .decode(schema)
+            .map_err(|error| SchemaRegistryError::InvalidProtobufSchema {
+                schema_id,
+                reason: error.to_string(),
+            })?;
+    FileDescriptorProto::decode(bytes.as_slice()).map_err(|error| {
+        SchemaRegistryError::InvalidProtobufSchema {
+            schema_id,
+            reason: error.to_string(),
+        }
+    })
+}
+
+fn nth_message(
+    mut messages: impl Iterator<Item = MessageDescriptor>,
+    index: i64,
+) -> Option<MessageDescriptor> {
+    messages.nth(usize::try_from(index).ok()?)
+}
+
+/// Computes the path of nested message indexes leading to `message` in its file.
+fn message_indexes(message: &MessageDescriptor) -> Vec<i64> {
+    fn position(
+        mut messages: impl Iterator<Item = MessageDescriptor>,
+        message: &MessageDescriptor,
+    ) -> i64 {
+        messages
+            .position(|candidate| candidate.full_name() == message.full_name())
+            .expect("a message type is defined by its parent") as i64
+    }
+
+    let mut indexes = Vec::new();
+    let mut message = message.clone();
+    while let Some(parent) = message.parent_message() {
+        indexes.push(position(parent.child_messages(), &message));
+        message = parent;
+    }
+    indexes.push(position(message.parent_file().messages(), &message));
+    indexes.reverse();
+    indexes
+}
+
+/// Subjects may contain characters which aren't valid in file names, such as the slashes of the
+/// Protobuf import paths.
+fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Writes the cache file through a temporary file so that a partially written file is never
+/// read back.
+fn write_cache_file(path: &Path, body: &[u8]) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, body)?;
+    fs::rename(&tmp_path, path)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{env, path::PathBuf};
+
+    use prost_reflect::prost_types::{
+        DescriptorProto, FieldDescriptorProto, FileDescriptorSet,
+        field_descriptor_proto::{Label, Type},
+    };
+    use serde_json::{Value as JsonValue, json};
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{basic_auth, method, path, query_param},
+    };
+
+    use super::*;
+
+    pub(crate) const AVRO_SCHEMA: &str = r#"{
+        "type": "record",
+        "name": "log",
+        "fields": [
+            { "name": "message", "type": "string" },
+            { "name": "count", "type": "long" }
+        ]
+    }"#;
+
+    /// The `test_protobuf3.proto` file, as served by the registry.
+    pub(crate) fn protobuf_schema() -> String {
+        let path = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap())
+            .join("tests/data/protobuf/protos/test_protobuf3.desc");
+        let set = FileDescriptorSet::decode(fs::read(path).unwrap().as_slice()).unwrap();
+        let file = set
+            .file
+            .into_iter()
+            .find(|file| file.name() == "test_protobuf3.proto")
+            .unwrap();
+        BASE64.encode(file.encode_to_vec())
+    }
+
+    /// Starts a registry serving the given schemas by id.
+    pub(crate) async fn mock_registry(schemas: &[(u32, JsonValue)]) -> MockServer {
+        let server = MockServer::start().await;
+        for (id, schema) in schemas {
+            Mock::given(method("GET"))
+                .and(path(format!("/schemas/ids/{id}")))
+                .respond_with(ResponseTemplate::new(200).set_body_json(schema))
+                .mount(&server)
+                .await;
+        }
+        server
+    }
+
+    fn temp_cache_dir() -> PathBuf {
+        env::temp_dir().join(format!("schema-registry-{}", uuid::Uuid::new_v4()))
+    }
+
+    fn field(
+        name: &str,
+        number: i32,
+        r#type: Type,
+        type_name: Option<&str>,
+    ) -> FieldDescriptorProto {
+        FieldDescriptorProto {
+            name: Some(name.to_owned()),
+            number: Some(number),
+            label: Some(Label::Optional as i32),
+            r#type: Some(r#type as i32),
+            type_name: type_name.map(str::to_owned),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn fetches_avro_schema_once() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/registry/schemas/ids/1"))
+            .and(basic_auth("user", "pass"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"schema": AVRO_SCHEMA})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut config = SchemaRegistryConfig::new(format!("{}/registry/", server.uri()));
+        config.auth = Some(SchemaRegistryAuth {
+            user: "user".to_owned(),
+            password: "pass".to_owned().into(),
+        });
+        let registry = config.build().unwrap();
+        assert!(matches!(
+            registry.fetched_avro_schema(1),
+            Err(SchemaRegistryError::NotFetched { schema_id: 1 })
+        ));
+
+        let schema = registry.avro_schema(1).await.unwrap();
+        assert_eq!(
+            schema,
+            Arc::new(apache_avro::Schema::parse_str(AVRO_SCHEMA).unwrap())
+        );
+        assert_eq!(registry.clone().avro_schema(1).await.unwrap(), schema);
+        assert_eq!(registry.fetched_avro_schema(1).unwrap(), schema);
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_cache_dir() {
+        let cache_dir = temp_cache_dir();
+        let server = mock_registry(&[
+            (1, json!({"schema": AVRO_SCHEMA})),
+            (
+                2,
+                json!({"schemaType": "PROTOBUF", "schema": protobuf_schema()}),
+            ),
+        ])
+        .await;
+
+        let mut config = SchemaRegistryConfig::new(server.uri());
+        config.cache_dir = Some(cache_dir.clone());
+        let registry = config.build().unwrap();
+        registry.avro_schema(1).await.unwrap();
+        registry.protobuf_schema(2).await.unwrap();
+        drop(server);
+
+        config.url = "http://127.0.0.1:1".to_owned();
+        let registry = config.build().unwrap();
+        assert!(registry.avro_schema(1).await.is_ok());
+        registry.protobuf_schema(2).await.unwrap();
+        assert_eq!(
+            registry
+                .fetched_protobuf_message(2, &[0])
+                .unwrap()
+                .full_name(),
+            "test_protobuf3.Person"
+        );
+        assert!(matches!(
+            registry.avro_schema(3).await,
+            Err(SchemaRegistryError::Request { .. })
+        ));
+
+        fs::remove_dir_all(cache_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn rejects_unexpected_schemas() {
+        let server = mock_registry(&[
+            (1, json!({"schema": AVRO_SCHEMA})),
+            (2, json!({"schemaType": "JSON", "schema": "{}"})),
+            (3, json!({"schema": "not a schema"})),
+        ])
+        .await;
+        let registry = SchemaRegistryConfig::new(server.uri()).build().unwrap();
+
+        assert!(matches!(
+            registry.protobuf_schema(1).await,
+            Err(SchemaRegistryError::UnexpectedSchemaType { .. })
+        ));
+        assert!(matches!(
+            registry.avro_schema(2).await,
+            Err(SchemaRegistryError::UnexpectedSchemaType { .. })
+        ));
+        assert!(matches!(
+            registry.avro_schema(3).await,
+            Err(SchemaRegistryError::InvalidAvroSchema { .. })
+        ));
+        assert!(matches!(
+            registry.avro_schema(4).await,
+            Err(SchemaRegistryError::Status { status: 404, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn backs_off_failed_schemas() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/schemas/ids/7"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(2)
+            .mount(&server)
+            .await;
+        let registry = SchemaRegistryConfig::new(server.uri()).build().unwrap();
+
+        assert!(matches!(
+            registry.avro_schema(7).await,
+            Err(SchemaRegistryError::Status { status: 500, .. })
+        ));
+        // The following messages fail without requesting the schema again.
+        for _ in 0..10 {
+            assert!(matches!(
+                registry.avro_schema(7).await,
+                Err(SchemaRegistryError::Unavailable { schema_id: 7, .. })
+            ));
+            assert!(matches!(
+                registry.fetched_avro_schema(7),
+                Err(SchemaRegistryError::Unavailable { schema_id: 7, .. })
+            ));
+        }
+
+        // Once the backoff expires, the schema is requested again and the backoff doubles.
+        registry
+            .inner
+            .failures
+            .lock()
+            .unwrap()
+            .get_mut(&7)
+            .unwrap()
+            .retry_at = Instant::now();
+        assert!(matches!(
+            registry.avro_schema(7).await,
+            Err(SchemaRegistryError::Status { status: 500, .. })
+        ));
+        assert_eq!(
+            registry.inner.failures.lock().unwrap()[&7].backoff,
+            INITIAL_RETRY_BACKOFF * 2
+        );
+        assert!(matches!(
+            registry.fetched_protobuf_message(7, &[0]),
+            Err(SchemaRegistryError::Unavailable { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn resolves_protobuf_message_indexes() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/schemas/ids/2"))
+            .and(query_param("format", "serialized"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({"schemaType": "PROTOBUF", "schema": protobuf_schema()})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        let registry = SchemaRegistryConfig::new(server.uri()).build().unwrap();
+        registry.protobuf_schema(2).await.unwrap();
+
+        for (indexes, message_type) in [
+            (vec![0], "test_protobuf3.Person"),
+            (vec![0, 0], "test_protobuf3.Person.PhoneNumber"),
+            (vec![1], "test_protobuf3.AddressBook"),
+        ] {
+            let message = registry.fetched_protobuf_message(2, &indexes).unwrap();
+            assert_eq!(message.full_name(), message_type);
+
+            let (message, message_indexes) = registry
+                .protobuf_message_by_name(2, message_type)
+                .await
+                .unwrap();
+            assert_eq!(message.full_name(), message_type);
+            assert_eq!(message_indexes, indexes);
+        }
+
+        let (message, indexes) = registry.protobuf_message_by_name(2, "").await.unwrap();
+        assert_eq!(message.full_name(), "test_protobuf3.Person");
+        assert_eq!(indexes, [0]);
+
+        assert!(matches!(
+            registry.fetched_protobuf_message(2, &[2]),
+            Err(SchemaRegistryError::UnknownMessageIndexes { .. })
+        ));
+        assert!(matches!(
+            registry
+                .protobuf_message_by_name(2, "test_protobuf3.Unknown")
+                .await,
+            Err(SchemaRegistryError::UnknownMessageType { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn resolves_protobuf_references() {
+        let common = FileDescriptorProto {
+            name: Some("registered-name.proto".to_owned()),
+            package: Some("common".to_owned()),
+            message_type: vec![DescriptorProto {
+                name: Some("Host".to_owned()),
+                field: vec![field("name", 1, Type::String, None)],
+                ..Default::default()
+            }],
+            syntax: Some("proto3".to_owned()),
+            ..Default::default()
+        };
+        let event = FileDescriptorProto {
+            package: Some("events".to_owned()),
+            dependency: vec!["common/host.proto".to_owned()],
+            message_type: vec![DescriptorProto {
+                name: Some("Event".to_owned()),
+                field: vec![field("host", 1, Type::Message, Some(".common.Host"))],
+                ..Default::default()
+            }],
+            syntax: Some("proto3".to_owned()),
+            ..Default::default()
+        };
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/schemas/ids/5"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "schemaType": "PROTOBUF",
+                "schema": BASE64.encode(event.encode_to_vec()),
+                "references": [
+                    {"name": "common/host.proto", "subject": "common/host.proto", "version": 3}
+                ]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/subjects/common%2Fhost.proto/versions/3"))
+            .and(query_param("format", "serialized"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "subject": "common/host.proto",
+                "version": 3,
+                "id": 4,
+                "schemaType": "PROTOBUF",
+                "schema": BASE64.encode(common.encode_to_vec())
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let cache_dir = temp_cache_dir();
+        let mut config = SchemaRegistryConfig::new(server.uri());
+        config.cache_dir = Some(cache_dir.clone());
+        let registry = config.build().unwrap();
+
+        registry.protobuf_schema(5).await.unwrap();
+        let message = registry.fetched_protobuf_message(5, &[0]).unwrap();
+        assert_eq!(message.full_name(), "events.Event");
+        let host = message.get_field_by_name("host").unwrap();
+        assert_eq!(host.kind().as_message().unwrap().full_name(), "common.Host");
+        assert!(
+            cache_dir
+                .join("subject-common_host.proto-3.serialized.json")
+                .exists()
+        );
+
+        fs::remove_dir_all(cache_dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_invalid_urls() {
+        assert!(matches!(
+            SchemaRegistryConfig::new("localhost".to_owned()).build(),
+            Err(SchemaRegistryError::InvalidUrl { .. })
+        ));
+        assert!(matches!(
+            SchemaRegistryConfig::new("mailto:registry@example.com".to_owned()).build(),
+            Err(SchemaRegistryError::NotABaseUrl { .. })
+        ));
+    }
+}
@@ -0,0 +1,125 @@
+//! The Confluent wire format, used by the schema registry aware serializers.
+//!
+//! Each message starts with a magic byte followed by the big endian 4-byte id of the schema in
+//! the registry. Protobuf messages then carry the indexes of the message type within the
+//! registered schema, as a zigzag varint encoded array whose length comes first. The common case
+//! of the first message type of the schema, `[0]`, is encoded as a single `0` byte.
+//! See https://docs.confluent.io/platform/current/schema-registry/fundamentals/serdes-develop/index.html#wire-format
+
+use bytes::{Buf, BufMut, Bytes};
+
+/// The first byte of all the messages in the Confluent wire format.
+pub const MAGIC_BYTE: u8 = 0;
+
+/// The length of the magic byte and schema id prefix.
+pub const SCHEMA_ID_PREFIX_LEN: usize = 5;
+
+/// Confluent wire format error list
+#[derive(Debug, snafu::Snafu)]
+pub enum WireFormatError {
+    /// The message doesn't start with the magic byte and schema id
+    #[snafu(display("Expected the message to be prefixed with the magic byte and schema id"))]
+    MissingSchemaId,
+    /// The protobuf message indexes are truncated or invalid
+    #[snafu(display("Invalid protobuf message indexes: {source}"))]
+    InvalidMessageIndexes {
+        /// The original error
+        source: prost::DecodeError,
+    },
+}
+
+/// Reads the schema id prefix of a message, leaving `bytes` at the start of the payload.
+pub fn decode_schema_id(bytes: &mut impl Buf) -> Result<u32, WireFormatError> {
+    if bytes.remaining() < SCHEMA_ID_PREFIX_LEN || bytes.chunk()[0] != MAGIC_BYTE {
+        return Err(WireFormatError::MissingSchemaId);
+    }
+    bytes.advance(1);
+    Ok(bytes.get_u32())
+}
+
+/// Writes the schema id prefix of a message.
+pub fn encode_schema_id(schema_id: u32, buffer: &mut impl BufMut) {
+    buffer.put_u8(MAGIC_BYTE);
+    buffer.put_u32(schema_id);
+}
+
+fn decode_zigzag_varint(bytes: &mut Bytes) -> Result<i64, WireFormatError> {
+    let value = prost::encoding::decode_varint(bytes)
+        .map_err(|source| WireFormatError::InvalidMessageIndexes { source })?;
+    Ok(((value >> 1) as i64) ^ -((value & 1) as i64))
+}
+
+fn encode_zigzag_varint(value: i64, buffer: &mut impl BufMut) {
+    prost::encoding::encode_varint(((value << 1) ^ (value >> 63)) as u64, buffer);
+}
+
+/// Reads the protobuf message indexes following the schema id, leaving `bytes` at the start of
+/// the protobuf payload.
+pub fn decode_message_indexes(bytes: &mut Bytes) -> Result<Vec<i64>, WireFormatError> {
+    let count = decode_zigzag_varint(bytes)?;
+    if count == 0 {
+        return Ok(vec![0]);
+    }
+    (0..count).map(|_| decode_zigzag_varint(bytes)).collect()
+}
+
+/// Writes the protobuf message indexes following the schema id.
+pub fn encode_message_indexes(indexes: &[i64], buffer: &mut impl BufMut) {
+    if indexes == [0] {
+        buffer.put_u8(0);
+        return;
+    }
+    encode_zigzag_varint(indexes.len() as i64, buffer);
+    for index in indexes {
+        encode_zigzag_varint(*index, buffer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+
+    use super::*;
+
+    #[test]
+    fn schema_id_roundtrip() {
+        let mut buffer = BytesMut::new();
+        encode_schema_id(0x0102_0304, &mut buffer);
+        buffer.put_slice(b"payload");
+        assert_eq!(&buffer[..SCHEMA_ID_PREFIX_LEN], [0, 1, 2, 3, 4]);
+
+        let mut bytes = buffer.freeze();
+        assert_eq!(decode_schema_id(&mut bytes).unwrap(), 0x0102_0304);
+        assert_eq!(bytes, Bytes::from_static(b"payload"));
+    }
+
+    #[test]
+    fn schema_id_missing() {
+        assert!(decode_schema_id(&mut Bytes::from_static(&[0, 0, 1])).is_err());
+        assert!(decode_schema_id(&mut Bytes::from_static(&[1, 0, 0, 0, 1, 2])).is_err());
+    }
+
+    #[test]
+    fn message_indexes_roundtrip() {
+        for (indexes, encoded) in [
+            (vec![0], vec![0]),
+            (vec![1], vec![2, 2]),
+            (vec![0, 2], vec![4, 0, 4]),
+            (vec![100], vec![2, 200, 1]),
+        ] {
+            let mut buffer = BytesMut::new();
+            encode_message_indexes(&indexes, &mut buffer);
+            assert_eq!(buffer.to_vec(), encoded);
+
+            buffer.put_slice(b"payload");
+            let mut bytes = buffer.freeze();
+            assert_eq!(decode_message_indexes(&mut bytes).unwrap(), indexes);
+            assert_eq!(bytes, Bytes::from_static(b"payload"));
+        }
+    }
+
+    #[test]
+    fn message_indexes_truncated() {
+        assert!(decode_message_indexes(&mut Bytes::from_static(&[4, 0])).is_err());
+    }
+}
@@ -0,0 +1,193 @@
+#!/usr/bin/env python3
+"""Writes the uncompressed remote-write request fixtures of this directory.
+
+The requests are hand-encoded `prometheus.WriteRequest` (1.0) and
+`io.prometheus.write.v2.Request` (2.0) messages, with the field numbers of
+`prompb/types.proto` and `prompb/io/prometheus/write/v2/types.proto`.
+"""
+
+import struct
+
+
+def varint(n):
+    n &= (1 << 64) - 1
+    out = bytearray()
+    while True:
+        b = n & 0x7F
+        n >>= 7
+        if n:
+            out.append(b | 0x80)
+        else:
+            out.append(b)
+            return bytes(out)
+
+
+def zigzag(n):
+    return (n << 1) ^ (n >> 63)
+
+
+def key(field, wire_type):
+    return varint(field << 3 | wire_type)
+
+
+def uint(field, n):
+    return key(field, 0) + varint(n)
+
+
+def sint(field, n):
+    return key(field, 0) + varint(zigzag(n))
+
+
+def double(field, f):
+    return key(field, 1) + struct.pack("<d", f)
+
+
+def length_delimited(field, data):
+    return key(field, 2) + varint(len(data)) + data
+
+
+def string(field, s):
+    return length_delimited(field, s.encode())
+
+
+def packed_varints(field, ns, encode=varint):
+    return length_delimited(field, b"".join(encode(n) for n in ns))
+
+
+def packed_sints(field, ns):
+    return packed_varints(field, ns, lambda n: varint(zigzag(n)))
+
+
+def packed_doubles(field, fs):
+    return length_delimited(field, b"".join(struct.pack("<d", f) for f in fs))
+
+
+def sample(value, timestamp):
+    return double(1, value) + uint(2, timestamp)
+
+
+def bucket_span(offset, length):
+    return sint(1, offset) + uint(2, length)
+
+
+def histogram(
+    count,
+    sum_,
+    schema,
+    zero_threshold,
+    zero_count,
+    positive_spans,
+    positive_deltas,
+    timestamp,
+    negative_spans=(),
+    negative_deltas=(),
+):
+    # Integer histogram: count_int (1), zero_count_int (6) and delta encoded buckets
+    out = uint(1, count) + double(3, sum_) + sint(4, schema) + double(5, zero_threshold)
+    out += uint(6, zero_count)
+    for span in negative_spans:
+        out += length_delimited(8, bucket_span(*span))
+    if negative_deltas:
+        out += packed_sints(9, negative_deltas)
+    for span in positive_spans:
+        out += length_delimited(11, bucket_span(*span))
+    if positive_deltas:
+        out += packed_sints(12, positive_deltas)
+    # reset_hint = NO (2)
+    out += uint(14, 2) + uint(15, timestamp)
+    return out
+
+
+TIMESTAMP = 1700000000000
+
+NATIVE_HISTOGRAM = histogram(
+    count=6,
+    sum_=11.5,
+    schema=0,
+    zero_threshold=1e-128,
+    zero_count=1,
+    positive_spans=[(0, 2), (1, 1)],
+    positive_deltas=[2, -1, 1],
+    timestamp=TIMESTAMP,
+)
+
+
+def v1_label(name, value):
+    return string(1, name) + string(2, value)
+
+
+def v1_request():
+    series = b"".join(
+        length_delimited(1, v1_label(name, value))
+        for name, value in [("__name__", "http_request_duration_seconds"), ("job", "api")]
+    )
+    exemplar = length_delimited(1, v1_label("trace_id", "abc123")) + double(2, 0.7) + uint(3, TIMESTAMP)
+    series += length_delimited(3, exemplar)
+    series += length_delimited(4, NATIVE_HISTOGRAM)
+    # type = HISTOGRAM (3)
+    metadata = (
+        uint(1, 3)
+        + string(2, "http_request_duration_seconds")
+        + string(4, "Request latency")
+        + string(5, "seconds")
+    )
+    return length_delimited(1, series) + length_delimited(3, metadata)
+
+
+def v2_request():
+    symbols = [
+        "",
+        "__name__",
+        "http_requests_total",
+        "job",
+        "api",
+        "trace_id",
+        "abc123",
+        "Total requests",
+        "http_request_duration_seconds",
+        "rpc_duration_seconds_bucket",
+        "le",
+        "0.5",
+        "+Inf",
+        "rpc_duration_seconds_sum",
+        "rpc_duration_seconds_count",
+    ]
+
+    def series(labels_refs, samples=(), histograms=(), exemplars=(), metric_type=0, help_ref=0):
+        out = packed_varints(1, labels_refs)
+        for s in samples:
+            out += length_delimited(2, sample(*s))
+        for h in histograms:
+            out += length_delimited(3, h)
+        for refs, value, timestamp in exemplars:
+            out += length_delimited(4, packed_varints(1, refs) + double(2, value) + uint(3, timestamp))
+        metadata = uint(1, metric_type)
+        if help_ref:
+            metadata += uint(3, help_ref)
+        return out + length_delimited(5, metadata)
+
+    # Metric types: COUNTER = 1, HISTOGRAM = 3
+    timeseries = [
+        series(
+            [1, 2, 3, 4],
+            samples=[(42.0, TIMESTAMP)],
+            exemplars=[([5, 6], 1.0, TIMESTAMP)],
+            metric_type=1,
+            help_ref=7,
+        ),
+        series([1, 8, 3, 4], histograms=[NATIVE_HISTOGRAM], metric_type=3),
+        series([1, 9, 3, 4, 10, 11], samples=[(3.0, TIMESTAMP)], metric_type=3),
+        series([1, 9, 3, 4, 10, 12], samples=[(5.0, TIMESTAMP)], metric_type=3),
+        series([1, 13, 3, 4], samples=[(2.5, TIMESTAMP)], metric_type=3),
+        series([1, 14, 3, 4], samples=[(5.0, TIMESTAMP)], metric_type=3),
+    ]
+    return b"".join(string(4, s) for s in symbols) + b"".join(
+        length_delimited(5, ts) for ts in timeseries
+    )
+
+
+if __name__ == "__main__":
+    with open("native_histogram_v1.pb", "wb") as f:
+        f.write(v1_request())
+    with open("request_v2.pb", "wb") as f:
+        f.write(v2_request())
@@ -1,4 +1,4 @@
-use crate::{set_pipeline_state_variable, MezmoContext};
+use crate::{pipeline_state_store::get_store, set_pipeline_state_variable, MezmoContext};
 use enrichment::{
     vrl_util::Error as EnrichmentTableError, Case, Condition, TableRegistry, TableSearch,
 };
@@ -96,6 +96,15 @@ pub fn internal_set_pipeline_state_variable(
         return Ok(Value::Null);
     }
 
+    if let (Some(store), Some(pipeline_id)) = (get_store(), &mezmo_ctx.pipeline_id) {
+        store.set(
+            &mezmo_ctx.account_id.to_string(),
+            &pipeline_id.to_string(),
+            &name,
+            &value,
+        );
+    }
+
     set_pipeline_state_variable!(Some(mezmo_ctx.clone()), vrl_position, name, value.clone());
 
     Ok(value)
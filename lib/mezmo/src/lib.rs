@@ -2,6 +2,7 @@ pub mod callsite;
 pub mod context;
 pub mod functions;
 pub mod macros;
+pub mod pipeline_state_store;
 pub mod pipeline_state_variable_change_action;
 pub mod reshape;
 pub mod user_trace;
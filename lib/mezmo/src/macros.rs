@@ -14,60 +14,79 @@ macro_rules! callsite {
 /// implementations if calling `user_log` from within Vector. If calling via the
 /// VRL implementation, it will automatically use this main `user_log` macro, and NOT
 /// the overloaded functions defined below.
+///
+/// An optional last argument attaches a stable error code to the log, see
+/// [`crate::user_trace::codes`].
 #[macro_export]
 macro_rules! user_log {
-    ("debug", $user_log:expr, $message:expr, $rate_limit_secs:expr, $captured_data:expr, $vrl_position:expr) => {{
+    ("debug", $user_log:expr, $message:expr, $rate_limit_secs:expr, $captured_data:expr, $vrl_position:expr, $code:expr) => {{
         use $crate::callsite::{Callsite, CallsiteIdentity};
         static CALLSITE: &'static Callsite = $crate::callsite!("user_log");
         $user_log.debug(
             $message,
             $rate_limit_secs,
             $captured_data,
+            $code,
             CallsiteIdentity {
                 site: CALLSITE,
                 vrl_position: $vrl_position,
             },
         );
     }};
-    ("info", $user_log:expr, $message:expr, $rate_limit_secs:expr, $captured_data:expr, $vrl_position:expr) => {{
+    ("info", $user_log:expr, $message:expr, $rate_limit_secs:expr, $captured_data:expr, $vrl_position:expr, $code:expr) => {{
         use $crate::callsite::{Callsite, CallsiteIdentity};
         static CALLSITE: &'static Callsite = $crate::callsite!("user_log");
         $user_log.info(
             $message,
             $rate_limit_secs,
             $captured_data,
+            $code,
             CallsiteIdentity {
                 site: CALLSITE,
                 vrl_position: $vrl_position,
             },
         );
     }};
-    ("warn", $user_log:expr, $message:expr, $rate_limit_secs:expr, $captured_data:expr, $vrl_position:expr) => {{
+    ("warn", $user_log:expr, $message:expr, $rate_limit_secs:expr, $captured_data:expr, $vrl_position:expr, $code:expr) => {{
         use $crate::callsite::{Callsite, CallsiteIdentity};
         static CALLSITE: &'static Callsite = $crate::callsite!("user_log");
         $user_log.warn(
             $message,
             $rate_limit_secs,
             $captured_data,
+            $code,
             CallsiteIdentity {
                 site: CALLSITE,
                 vrl_position: $vrl_position,
             },
         );
     }};
-    ("error", $user_log:expr, $message:expr, $rate_limit_secs:expr, $captured_data:expr, $vrl_position:expr) => {{
+    ("error", $user_log:expr, $message:expr, $rate_limit_secs:expr, $captured_data:expr, $vrl_position:expr, $code:expr) => {{
         use $crate::callsite::{Callsite, CallsiteIdentity};
         static CALLSITE: &'static Callsite = $crate::callsite!("user_log");
         $user_log.error(
             $message,
             $rate_limit_secs,
             $captured_data,
+            $code,
             CallsiteIdentity {
                 site: CALLSITE,
                 vrl_position: $vrl_position,
             },
         );
     }};
+    // Without an error code
+    ($level:tt, $user_log:expr, $message:expr, $rate_limit_secs:expr, $captured_data:expr, $vrl_position:expr) => {{
+        $crate::user_log!(
+            $level,
+            $user_log,
+            $message,
+            $rate_limit_secs,
+            $captured_data,
+            $vrl_position,
+            None
+        );
+    }};
 }
 
 #[macro_export]
@@ -129,6 +148,21 @@ macro_rules! user_log_warn {
             None
         );
     }};
+    // Attaching an error code, optionally with `captured_data`
+    ($user_log:expr, $message:expr, code: $code:expr) => {{
+        $crate::user_log!("warn", $user_log, $message, None, None, None, Some($code));
+    }};
+    ($user_log:expr, $message:expr, code: $code:expr, captured_data: $captured_data:expr) => {{
+        $crate::user_log!(
+            "warn",
+            $user_log,
+            $message,
+            None,
+            Some($captured_data),
+            None,
+            Some($code)
+        );
+    }};
     ($user_log:expr, $message:expr) => {{
         $crate::user_log!("warn", $user_log, $message, None, None, None);
     }};
@@ -157,6 +191,21 @@ macro_rules! user_log_error {
             None
         );
     }};
+    // Attaching an error code, optionally with `captured_data`
+    ($user_log:expr, $message:expr, code: $code:expr) => {{
+        $crate::user_log!("error", $user_log, $message, None, None, None, Some($code));
+    }};
+    ($user_log:expr, $message:expr, code: $code:expr, captured_data: $captured_data:expr) => {{
+        $crate::user_log!(
+            "error",
+            $user_log,
+            $message,
+            None,
+            Some($captured_data),
+            None,
+            Some($code)
+        );
+    }};
     ($user_log:expr, $message:expr) => {{
         $crate::user_log!("error", $user_log, $message, None, None, None);
     }};
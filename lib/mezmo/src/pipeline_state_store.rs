@@ -0,0 +1,31 @@
+use std::sync::{Arc, RwLock, Weak};
+
+use vrl::value::Value;
+
+static PIPELINE_STATE_STORE: RwLock<Option<Weak<dyn PipelineStateStore>>> = RwLock::new(None);
+
+/// A store of pipeline state variables written directly by `set_pipeline_state_variable`, so the
+/// new values can be read back by `get_pipeline_state_variable` without waiting on the pipeline DB.
+pub trait PipelineStateStore: Send + Sync {
+    /// Stores the value of the named state variable of a pipeline.
+    fn set(&self, account_id: &str, pipeline_id: &str, name: &str, value: &Value);
+}
+
+/// Registers the store written by `set_pipeline_state_variable`, replacing any previous one. Only
+/// a weak reference is kept, so the store is unregistered once its owner drops it, such as when
+/// its enrichment table is reloaded.
+pub fn register<S: PipelineStateStore + 'static>(store: &Arc<S>) {
+    let store: Weak<dyn PipelineStateStore> = Arc::downgrade(store);
+    *PIPELINE_STATE_STORE
+        .write()
+        .expect("pipeline state store lock poisoned") = Some(store);
+}
+
+/// Returns the registered store, if any and it's still alive.
+pub fn get_store() -> Option<Arc<dyn PipelineStateStore>> {
+    PIPELINE_STATE_STORE
+        .read()
+        .expect("pipeline state store lock poisoned")
+        .as_ref()
+        .and_then(Weak::upgrade)
+}
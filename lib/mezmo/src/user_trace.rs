@@ -2,25 +2,46 @@ use crate::{callsite::CallsiteIdentity, context::MezmoContext, user_log_error};
 use futures_util::{future::ready, Stream, StreamExt};
 use serde::ser::StdError;
 use std::sync::{Arc, Mutex, OnceLock};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio::sync::broadcast::{self, Receiver, Sender};
 use tokio_stream::wrappers::BroadcastStream;
-use tracing::log::Level;
 use tracing::{debug, warn};
 use vector_core::event::metric::mezmo::TransformError;
 use vector_core::event::LogEvent;
 use vrl::btreemap;
 use vrl::value::Value;
 
+pub use tracing::log::Level;
+
 static USER_LOG: OnceLock<UserLog> = OnceLock::new();
 
 const DEFAULT_RATE_LIMIT_UNINITIALIZED: u64 = 10; // 10 seconds
 const LOG_CACHE_RATE_LIMIT_MAX_CAPACITY: u64 = 5_000;
+/// How often the summaries of the duplicates suppressed in the expired rate limit windows are sent
+const SUPPRESSED_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Stable, machine-readable codes attached to user logs as `meta.mezmo.code`, allowing
+/// subscribers to tell error classes apart without parsing the message.
+pub mod codes {
+    /// Events could not be deserialized into the expected format
+    pub const DESERIALIZATION_FAILED: &str = "DESERIALIZATION_FAILED";
+}
 
-pub fn init(rate_limit: u64) {
+/// Initializes the user logs, rate limiting duplicates to once every `rate_limit` seconds and
+/// dropping the logs less severe than `min_level`. The summaries of the suppressed duplicates are
+/// sent once their rate limit window expires, even when no further duplicate is logged.
+pub fn init(rate_limit: u64, min_level: Level) {
     USER_LOG
-        .set(UserLog::new(rate_limit))
+        .set(UserLog::new(rate_limit, min_level))
         .expect("user log was already initialized");
+
+    std::thread::Builder::new()
+        .name("user-log-flusher".into())
+        .spawn(|| loop {
+            std::thread::sleep(SUPPRESSED_FLUSH_INTERVAL);
+            get_user_log().flush_suppressed();
+        })
+        .expect("failed to spawn the user log flusher thread");
 }
 
 #[derive(Eq, PartialEq, Hash, Clone, Debug)]
@@ -62,6 +83,18 @@ impl State {
     fn should_limit(&self) -> bool {
         self.start.elapsed().as_secs() < self.limit
     }
+
+    /// Takes the summary of the duplicates suppressed in the rate limit window once it expired.
+    /// The next duplicate is then sent as if it was seen for the first time.
+    fn take_expired_summary(&mut self) -> Option<LogEvent> {
+        if self.should_limit() || self.count <= 1 {
+            return None;
+        }
+
+        let summary = suppressed_summary(&self.log, self.count - 1, self.limit);
+        self.count = 0;
+        Some(summary)
+    }
 }
 
 #[derive(Debug)]
@@ -69,30 +102,34 @@ struct UserLog {
     sender: Sender<LogEvent>,
     log_rate_limit_cache: moka::sync::Cache<LogIdentifier, Arc<Mutex<State>>>,
     rate_limit: u64,
+    min_level: Level,
 }
 
 impl UserLog {
-    fn new(rate_limit: u64) -> Self {
+    fn new(rate_limit: u64, min_level: Level) -> Self {
         UserLog {
             sender: broadcast::channel(1000).0,
             log_rate_limit_cache: moka::sync::Cache::new(LOG_CACHE_RATE_LIMIT_MAX_CAPACITY),
             rate_limit,
+            min_level,
         }
     }
-}
 
-pub fn get_user_log_sender() -> &'static broadcast::Sender<LogEvent> {
-    &USER_LOG
-        .get_or_init(|| UserLog::new(DEFAULT_RATE_LIMIT_UNINITIALIZED))
-        .sender
-}
+    fn allows(&self, level: Level) -> bool {
+        level <= self.min_level
+    }
 
-pub fn try_send_user_log(log: LogEvent, rate_limit: Option<u64>, id: LogIdentifier) {
-    if let Some(user_log) = USER_LOG.get() {
-        let entry = user_log.log_rate_limit_cache.entry(id).or_insert_with(|| {
+    fn send(&self, log: LogEvent) {
+        if self.sender.send(log).is_err() {
+            debug!("failed to send user log; likely no source consuming data")
+        }
+    }
+
+    fn try_send(&self, log: LogEvent, rate_limit: Option<u64>, id: LogIdentifier) {
+        let entry = self.log_rate_limit_cache.entry(id).or_insert_with(|| {
             Arc::new(Mutex::new(State::new(
                 log.clone(),
-                rate_limit.unwrap_or(user_log.rate_limit),
+                rate_limit.unwrap_or(self.rate_limit),
             )))
         });
 
@@ -100,12 +137,7 @@ pub fn try_send_user_log(log: LogEvent, rate_limit: Option<u64>, id: LogIdentifi
             let previous_count = state.increment_count();
             if state.should_limit() {
                 match previous_count {
-                    0 => match user_log.sender.send(log) {
-                        Ok(_) => {}
-                        Err(_) => {
-                            debug!("failed to send user log; likely no source consuming data")
-                        }
-                    },
+                    0 => self.send(log),
                     1 => {
                         debug!(
                             "user log is [{:?}] is being rate limited",
@@ -115,29 +147,73 @@ pub fn try_send_user_log(log: LogEvent, rate_limit: Option<u64>, id: LogIdentifi
                     _ => {}
                 }
             } else {
-                // If we saw this event 3 or more times total, emit an event that indicates the total number of times we
-                // rate limited the event in the limit period.
+                // If duplicates of the event were suppressed in the limit period, emit a summary that indicates
+                // the number of times we rate limited the event.
                 if previous_count > 1 {
-                    debug!(
-                        "user log [{:?}] has been rate limited {} times.",
-                        state.log.get_message().unwrap_or(&("".into())),
-                        previous_count - 1
-                    );
+                    self.send(suppressed_summary(
+                        &state.log,
+                        previous_count - 1,
+                        state.limit,
+                    ));
                 }
 
                 // We're not rate limiting anymore, so we also emit the current event as normal.. but we update our rate
                 // limiting state since this is effectively equivalent to seeing the event again for the first time.
-                match user_log.sender.send(log) {
-                    Ok(_) => {}
-                    Err(_) => {
-                        debug!("failed to send user log; likely no source consuming data")
-                    }
-                }
+                self.send(log);
 
                 state.reset();
             }
         }
     }
+
+    /// Sends the summaries of the duplicates suppressed in the rate limit windows that expired
+    /// without a further duplicate.
+    fn flush_suppressed(&self) {
+        for (_, state) in self.log_rate_limit_cache.iter() {
+            let summary = state
+                .lock()
+                .ok()
+                .and_then(|mut state| state.take_expired_summary());
+            if let Some(summary) = summary {
+                self.send(summary);
+            }
+        }
+    }
+}
+
+fn get_user_log() -> &'static UserLog {
+    USER_LOG.get_or_init(|| UserLog::new(DEFAULT_RATE_LIMIT_UNINITIALIZED, Level::Trace))
+}
+
+pub fn get_user_log_sender() -> &'static broadcast::Sender<LogEvent> {
+    &get_user_log().sender
+}
+
+/// Builds the log summarizing the duplicates of `log` suppressed during the rate limit window.
+/// It keeps the metadata of the original log, including its `code`, so it is routed and classified
+/// the same way; the suppression details are attached as `meta.mezmo.suppressed`.
+fn suppressed_summary(log: &LogEvent, suppressed: u64, window: u64) -> LogEvent {
+    let mut summary = log.clone();
+    let message = log
+        .get_message()
+        .map(|message| message.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    summary.insert(
+        "message",
+        Value::from(format!(
+            "Suppressed {suppressed} duplicate(s) of the message \"{message}\" in the last {window} seconds"
+        )),
+    );
+    summary.insert("meta.mezmo.suppressed.count", Value::from(suppressed));
+    summary.insert("meta.mezmo.suppressed.window_secs", Value::from(window));
+    summary.insert("meta.mezmo.captured_data", Value::Null);
+    summary
+}
+
+pub fn try_send_user_log(log: LogEvent, rate_limit: Option<u64>, id: LogIdentifier) {
+    if let Some(user_log) = USER_LOG.get() {
+        user_log.try_send(log, rate_limit, id);
+    }
 }
 
 /// This is the struct used to obtain access to consume the user log information in other
@@ -169,6 +245,7 @@ pub trait MezmoUserLog {
         msg: Value,
         rate_limit: Option<u64>,
         captured_data: Option<Value>,
+        code: Option<&'static str>,
         identity: CallsiteIdentity,
     );
 
@@ -177,6 +254,7 @@ pub trait MezmoUserLog {
         msg: impl Into<Value>,
         rate_limit: Option<u64>,
         captured_data: Option<Value>,
+        code: Option<&'static str>,
         identity: CallsiteIdentity,
     ) {
         self.log(
@@ -184,6 +262,7 @@ pub trait MezmoUserLog {
             msg.into(),
             rate_limit,
             captured_data,
+            code,
             identity,
         );
     }
@@ -193,9 +272,17 @@ pub trait MezmoUserLog {
         msg: impl Into<Value>,
         rate_limit: Option<u64>,
         captured_data: Option<Value>,
+        code: Option<&'static str>,
         identity: CallsiteIdentity,
     ) {
-        self.log(Level::Info, msg.into(), rate_limit, captured_data, identity);
+        self.log(
+            Level::Info,
+            msg.into(),
+            rate_limit,
+            captured_data,
+            code,
+            identity,
+        );
     }
 
     fn warn(
@@ -203,9 +290,17 @@ pub trait MezmoUserLog {
         msg: impl Into<Value>,
         rate_limit: Option<u64>,
         captured_data: Option<Value>,
+        code: Option<&'static str>,
         identity: CallsiteIdentity,
     ) {
-        self.log(Level::Warn, msg.into(), rate_limit, captured_data, identity);
+        self.log(
+            Level::Warn,
+            msg.into(),
+            rate_limit,
+            captured_data,
+            code,
+            identity,
+        );
     }
 
     fn error(
@@ -213,6 +308,7 @@ pub trait MezmoUserLog {
         msg: impl Into<Value>,
         rate_limit: Option<u64>,
         captured_data: Option<Value>,
+        code: Option<&'static str>,
         identity: CallsiteIdentity,
     ) {
         self.log(
@@ -220,6 +316,7 @@ pub trait MezmoUserLog {
             msg.into(),
             rate_limit,
             captured_data,
+            code,
             identity,
         );
     }
@@ -232,11 +329,22 @@ impl MezmoUserLog for Option<MezmoContext> {
         msg: Value,
         rate_limit: Option<u64>,
         captured_data: Option<Value>,
+        code: Option<&'static str>,
         identity: CallsiteIdentity,
     ) {
         if let Some(ctx) = self {
+            if USER_LOG
+                .get()
+                .is_some_and(|user_log| !user_log.allows(level))
+            {
+                return;
+            }
+
             let mut event = LogEvent::default();
             event.insert("meta.mezmo.level", Value::from(level.to_string()));
+            if let Some(code) = code {
+                event.insert("meta.mezmo.code", Value::from(code));
+            }
             event.insert("meta.mezmo.account_id", Value::from(&ctx.account_id));
             if let Some(pipeline_id) = ctx.pipeline_id.as_ref() {
                 event.insert("meta.mezmo.pipeline_id", Value::from(pipeline_id));
@@ -286,16 +394,20 @@ impl MezmoUserLog for Option<MezmoContext> {
 /// Emits a user_log for the given error. Note that the Display implementation must be
 /// appropriate as a user-facing error.
 pub fn handle_transform_error(ctx: &Option<MezmoContext>, err: TransformError) {
-    user_log_error!(ctx, err.to_string());
+    user_log_error!(ctx, err.to_string(), code: err.code());
 }
 
 pub fn handle_deserializer_error(ctx: &Option<MezmoContext>, err: Box<dyn StdError>) {
-    user_log_error!(ctx, format!("Protobuf validation failed: {}", err));
+    user_log_error!(
+        ctx,
+        format!("Protobuf validation failed: {}", err),
+        code: codes::DESERIALIZATION_FAILED
+    );
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{user_log, user_log_debug, user_log_info, user_log_warn};
+    use crate::{callsite::Callsite, user_log, user_log_debug, user_log_info, user_log_warn};
 
     use super::*;
     use serial_test::serial;
@@ -444,6 +556,145 @@ mod tests {
         assert!(timeout, "expected a timeout because of rate limiting");
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_rate_limiting_summary() {
+        let id = "v1:kafka:internal_source:component_abc:pipeline_123:account_123".to_owned();
+        let ctx = MezmoContext::try_from(id).ok();
+        let log_stream = UserLogSubscription::subscribe().into_stream();
+
+        for _ in 0..3 {
+            user_log_error!(ctx, "summarized msg", rate_limit_secs: 1);
+        }
+        sleep(Duration::from_millis(1100)).await;
+        user_log_error!(ctx, "summarized msg", rate_limit_secs: 1);
+
+        let res: Vec<LogEvent> = log_stream.take(3).collect().await;
+
+        assert_eq!(
+            res[0].get(".message").unwrap(),
+            &Value::from("summarized msg")
+        );
+        assert_eq!(
+            res[1].get(".message").unwrap(),
+            &Value::from(
+                "Suppressed 2 duplicate(s) of the message \"summarized msg\" in the last 1 seconds"
+            )
+        );
+        assert!(res[1].get(".meta.mezmo.code").is_none());
+        assert_eq!(
+            res[1].get(".meta.mezmo.suppressed.count").unwrap(),
+            &Value::from(2)
+        );
+        assert_eq!(
+            res[1].get(".meta.mezmo.suppressed.window_secs").unwrap(),
+            &Value::from(1)
+        );
+        assert_eq!(
+            res[1].get(".meta.mezmo.level").unwrap(),
+            &Value::from("ERROR")
+        );
+        assert_eq!(
+            res[2].get(".message").unwrap(),
+            &Value::from("summarized msg")
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_codes() {
+        let id = "v1:kafka:internal_source:component_abc:pipeline_123:account_123".to_owned();
+        let ctx = MezmoContext::try_from(id).ok();
+        let log_stream = UserLogSubscription::subscribe().into_stream();
+
+        handle_transform_error(
+            &ctx,
+            TransformError::FieldNotFound {
+                field: "message".into(),
+            },
+        );
+        user_log_warn!(ctx, "coded warn", code: "CUSTOM_CODE", captured_data: Value::from(1));
+        user_log_error!(ctx, "uncoded error");
+
+        let res: Vec<LogEvent> = log_stream.take(3).collect().await;
+
+        assert_eq!(
+            res[0].get(".meta.mezmo.code").unwrap(),
+            &Value::from("FIELD_NOT_FOUND")
+        );
+        assert_eq!(
+            res[1].get(".meta.mezmo.code").unwrap(),
+            &Value::from("CUSTOM_CODE")
+        );
+        assert_eq!(
+            res[1].get(".meta.mezmo.captured_data").unwrap(),
+            &captured_data_wrapper(Value::from(1))
+        );
+        assert!(res[2].get(".meta.mezmo.code").is_none());
+    }
+
+    #[test]
+    fn test_flush_suppressed() {
+        static CALLSITE: Callsite = Callsite("test_flush_suppressed");
+        let user_log = UserLog::new(1, Level::Trace);
+        let mut rx = user_log.sender.subscribe();
+        let id = LogIdentifier {
+            component_id: "component_abc".to_owned(),
+            identity: CallsiteIdentity {
+                site: &CALLSITE,
+                vrl_position: None,
+            },
+            message: "flushed msg".to_owned(),
+        };
+        let mut log = LogEvent::from("flushed msg");
+        log.insert("meta.mezmo.code", Value::from("CUSTOM_CODE"));
+
+        for _ in 0..3 {
+            user_log.try_send(log.clone(), None, id.clone());
+        }
+        user_log.flush_suppressed();
+        assert_eq!(
+            rx.try_recv().unwrap().get(".message").unwrap(),
+            &Value::from("flushed msg")
+        );
+        assert!(rx.try_recv().is_err(), "window is still open");
+
+        // Expire the window
+        let state = user_log.log_rate_limit_cache.get(&id).unwrap();
+        state.lock().unwrap().start = Instant::now() - Duration::from_secs(1);
+
+        user_log.flush_suppressed();
+        let summary = rx.try_recv().unwrap();
+        assert_eq!(
+            summary.get(".meta.mezmo.code").unwrap(),
+            &Value::from("CUSTOM_CODE")
+        );
+        assert_eq!(
+            summary.get(".meta.mezmo.suppressed.count").unwrap(),
+            &Value::from(2)
+        );
+
+        user_log.flush_suppressed();
+        assert!(rx.try_recv().is_err(), "summary is only sent once");
+
+        // The next duplicate starts a new window
+        user_log.try_send(LogEvent::from("flushed msg"), None, id);
+        assert_eq!(
+            rx.try_recv().unwrap().get(".message").unwrap(),
+            &Value::from("flushed msg")
+        );
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_min_level() {
+        let user_log = UserLog::new(10, Level::Warn);
+        assert!(user_log.allows(Level::Error));
+        assert!(user_log.allows(Level::Warn));
+        assert!(!user_log.allows(Level::Info));
+        assert!(!user_log.allows(Level::Debug));
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_input() {
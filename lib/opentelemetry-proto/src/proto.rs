@@ -4,6 +4,12 @@ pub const TRACES_REQUEST_MESSAGE_TYPE: &str =
     "opentelemetry.proto.collector.trace.v1.ExportTraceServiceRequest";
 pub const METRICS_REQUEST_MESSAGE_TYPE: &str =
     "opentelemetry.proto.collector.metrics.v1.ExportMetricsServiceRequest";
+pub const LOGS_RESPONSE_MESSAGE_TYPE: &str =
+    "opentelemetry.proto.collector.logs.v1.ExportLogsServiceResponse";
+pub const TRACES_RESPONSE_MESSAGE_TYPE: &str =
+    "opentelemetry.proto.collector.trace.v1.ExportTraceServiceResponse";
+pub const METRICS_RESPONSE_MESSAGE_TYPE: &str =
+    "opentelemetry.proto.collector.metrics.v1.ExportMetricsServiceResponse";
 
 // JSON names (camelCase) for the same fields, used when use_json_names is enabled
 pub const RESOURCE_LOGS_JSON_FIELD: &str = "resourceLogs";
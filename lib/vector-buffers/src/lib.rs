@@ -113,11 +113,15 @@ pub trait Bufferable: InMemoryBufferable + Encodable {}
 // Blanket implementation for anything that is already bufferable.
 impl<T> Bufferable for T where T: InMemoryBufferable + Encodable {}
 
-/// Hook for observing items as they are sent into a `BufferSender`.
+/// Hook for observing items as they are sent into a `BufferSender`, and received from a
+/// `BufferReceiver`.
 pub trait BufferInstrumentation<T: Bufferable>: Send + Sync + 'static {
     /// Called immediately before the item is emitted to the underlying buffer.
     /// The underlying type is stored in an `Arc`, so we cannot have `&mut self`.
     fn on_send(&self, item: &mut T);
+
+    /// Called immediately after the item is received from the underlying buffer.
+    fn on_receive(&self, _item: &mut T) {}
 }
 
 pub trait EventCount {
@@ -1,10 +1,12 @@
 use std::{
     mem,
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll, ready},
 };
 
 use async_recursion::async_recursion;
+use derivative::Derivative;
 use futures::Stream;
 use tokio::select;
 use tokio_util::sync::ReusableBoxFuture;
@@ -12,7 +14,7 @@ use vector_common::internal_event::emit;
 
 use super::limited_queue::LimitedReceiver;
 use crate::{
-    Bufferable,
+    BufferInstrumentation, Bufferable,
     buffer_usage_data::BufferUsageHandle,
     variants::disk_v2::{self, ProductionFilesystem},
 };
@@ -72,11 +74,14 @@ where
 /// for querying the overflow buffer as well.  The ordering of events when operating in "overflow"
 /// is undefined, as the receiver will try to manage polling both its own buffer, as well as the
 /// overflow buffer, in order to fairly balance throughput.
-#[derive(Debug)]
+#[derive(Derivative)]
+#[derivative(Debug)]
 pub struct BufferReceiver<T: Bufferable> {
     base: ReceiverAdapter<T>,
     overflow: Option<Box<BufferReceiver<T>>>,
     instrumentation: Option<BufferUsageHandle>,
+    #[derivative(Debug = "ignore")]
+    custom_instrumentation: Option<Arc<dyn BufferInstrumentation<T>>>,
 }
 
 impl<T: Bufferable> BufferReceiver<T> {
@@ -86,6 +91,7 @@ impl<T: Bufferable> BufferReceiver<T> {
             base,
             overflow: None,
             instrumentation: None,
+            custom_instrumentation: None,
         }
     }
 
@@ -95,6 +101,7 @@ impl<T: Bufferable> BufferReceiver<T> {
             base,
             overflow: Some(Box::new(overflow)),
             instrumentation: None,
+            custom_instrumentation: None,
         }
     }
 
@@ -112,6 +119,11 @@ impl<T: Bufferable> BufferReceiver<T> {
         self.instrumentation = Some(handle);
     }
 
+    /// Configures this receiver to invoke a custom instrumentation hook.
+    pub fn with_custom_instrumentation(&mut self, instrumentation: impl BufferInstrumentation<T>) {
+        self.custom_instrumentation = Some(Arc::new(instrumentation));
+    }
+
     #[async_recursion]
     pub async fn next(&mut self) -> Option<T> {
         // We want to poll both our base and overflow receivers without waiting for one or the
@@ -124,7 +136,7 @@ impl<T: Bufferable> BufferReceiver<T> {
         // attached to the base receiver.
         let overflow = self.overflow.as_mut().map(Pin::new);
 
-        let (item, from_base) = match overflow {
+        let (mut item, from_base) = match overflow {
             None => match self.base.next().await {
                 Some(item) => (item, true),
                 None => return None,
@@ -149,6 +161,10 @@ impl<T: Bufferable> BufferReceiver<T> {
             );
         }
 
+        if let Some(instrumentation) = self.custom_instrumentation.as_ref() {
+            instrumentation.on_receive(&mut item);
+        }
+
         Some(item)
     }
 
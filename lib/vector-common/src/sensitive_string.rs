@@ -1,9 +1,17 @@
+use std::cell::Cell;
+
 use vector_config::{ConfigurableString, configurable_component};
 
+const REDACTED: &str = "**REDACTED**";
+
+thread_local! {
+    static SERIALIZE_REDACTED: Cell<bool> = const { Cell::new(false) };
+}
+
 /// Wrapper for sensitive strings containing credentials
 #[configurable_component(no_deser, no_ser)]
-#[derive(::serde::Deserialize, ::serde::Serialize)]
-#[serde(from = "String", into = "String")]
+#[derive(::serde::Deserialize)]
+#[serde(from = "String")]
 #[configurable(metadata(sensitive))]
 #[derive(Clone, Default, PartialEq, Eq)]
 pub struct SensitiveString(String);
@@ -22,9 +30,19 @@ impl From<SensitiveString> for String {
 
 impl ConfigurableString for SensitiveString {}
 
+impl ::serde::Serialize for SensitiveString {
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if SERIALIZE_REDACTED.get() {
+            serializer.serialize_str(REDACTED)
+        } else {
+            serializer.serialize_str(&self.0)
+        }
+    }
+}
+
 impl std::fmt::Display for SensitiveString {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "**REDACTED**")
+        write!(f, "{REDACTED}")
     }
 }
 
@@ -42,6 +60,21 @@ impl SensitiveString {
     }
 }
 
+/// Runs `f` with the sensitive strings it serializes on the current thread replaced by
+/// `**REDACTED**`, for example to expose a configuration without its credentials.
+pub fn serialize_redacted<T>(f: impl FnOnce() -> T) -> T {
+    struct Reset(bool);
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            SERIALIZE_REDACTED.set(self.0);
+        }
+    }
+
+    let _reset = Reset(SERIALIZE_REDACTED.replace(true));
+    f()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -54,6 +87,14 @@ mod tests {
         assert_eq!(result, json_value);
     }
 
+    #[test]
+    fn serialization_redacted() {
+        let value = SensitiveString("foo".to_string());
+        let result = serialize_redacted(|| serde_json::to_string(&value).unwrap());
+        assert_eq!(result, "\"**REDACTED**\"");
+        assert_eq!(serde_json::to_string(&value).unwrap(), "\"foo\"");
+    }
+
     #[test]
     fn hide_content() {
         let value = SensitiveString("hello world".to_string());
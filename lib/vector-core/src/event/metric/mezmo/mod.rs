@@ -11,7 +11,7 @@ use crate::event::{
     metric::{MetricKind, TagValue},
 };
 
-pub use vector::{TransformError, from_metric, to_metric};
+pub use vector::{TransformError, from_metric, parse_metric_value, to_metric};
 
 pub fn from_f64_or_zero(value: f64) -> Value {
     use ordered_float::NotNan;
@@ -25,6 +25,23 @@ pub enum TransformError {
     ParseIntOverflow { field: String },
     NumberTruncation { field: String },
     CardinalityLimitExceeded { limit: u32 },
+    TemplateRenderingFailed { field: String, error: String },
+}
+
+impl TransformError {
+    /// Stable, machine-readable code of the error, attached to the user logs reporting it.
+    pub const fn code(&self) -> &'static str {
+        match self {
+            TransformError::FieldNotFound { .. } => "FIELD_NOT_FOUND",
+            TransformError::FieldInvalidType { .. } => "FIELD_INVALID_TYPE",
+            TransformError::InvalidMetricType { .. } => "INVALID_METRIC_TYPE",
+            TransformError::FieldNull { .. } => "FIELD_NULL",
+            TransformError::ParseIntOverflow { .. } => "PARSE_INT_OVERFLOW",
+            TransformError::NumberTruncation { .. } => "NUMBER_TRUNCATION",
+            TransformError::CardinalityLimitExceeded { .. } => "CARDINALITY_LIMIT_EXCEEDED",
+            TransformError::TemplateRenderingFailed { .. } => "TEMPLATE_RENDERING_FAILED",
+        }
+    }
 }
 
 /// Note that the Display implementation must be appropriate as a user-facing error.
@@ -55,6 +72,9 @@ impl Display for TransformError {
             TransformError::CardinalityLimitExceeded { limit } => {
                 write!(f, "Cardinality limit of {limit} exceeded")
             }
+            TransformError::TemplateRenderingFailed { field, error } => {
+                write!(f, "Field '{field}' could not be rendered: {error}")
+            }
         }
     }
 }
@@ -160,6 +180,17 @@ fn parse_value(
     }
 }
 
+/// Parses a metric value of the given Mezmo metric type, where `value` has the same
+/// shape as the `.value.value` field of a Mezmo metric log.
+///
+/// # Errors
+///
+/// Will return `Err` if the type is not supported or the value does not match its shape
+pub fn parse_metric_value(type_name: &str, value: Value) -> Result<MetricValue, TransformError> {
+    let value_object = BTreeMap::from([(KeyString::from("value"), value)]);
+    parse_value(type_name, &value_object)
+}
+
 fn build_summary(value_object: &BTreeMap<KeyString, Value>) -> Result<MetricValue, TransformError> {
     let value_object = get_property(value_object, "value")?
         .as_object()
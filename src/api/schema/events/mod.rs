@@ -33,18 +33,36 @@ impl EventsSubscription {
         filter: Option<String>,
         #[graphql(default = 500)] interval: u32,
         #[graphql(default = 100, validator(minimum = 1, maximum = 10_000))] limit: u32,
-    ) -> impl Stream<Item = Vec<OutputEventsPayload>> + 'a {
+    ) -> async_graphql::Result<impl Stream<Item = Vec<OutputEventsPayload>> + 'a> {
         let watch_rx = ctx.data_unchecked::<WatchRx>().clone();
+        // Tapping no events would be misleading when the filter is invalid
+        let filter = filter.map(build_filter).transpose()?;
 
         let patterns = TapPatterns {
             for_outputs: outputs_patterns.into_iter().collect(),
             for_inputs: inputs_patterns.unwrap_or_default().into_iter().collect(),
         };
         // Client input is confined to `u32` to provide sensible bounds.
-        create_events_stream(watch_rx, patterns, filter, interval as u64, limit as usize)
+        Ok(create_events_stream(
+            watch_rx,
+            patterns,
+            filter,
+            interval as u64,
+            limit as usize,
+        ))
     }
 }
 
+/// Compiles the VRL source of a tap filter.
+pub(crate) fn build_filter(source: String) -> Result<Condition, String> {
+    VrlConfig {
+        source,
+        runtime: Default::default(),
+    }
+    .build(&Default::default(), &Default::default(), None)
+    .map_err(|error| format!("Invalid filter: {error}"))
+}
+
 /// Filters an [EventArray] based on a [Condition]
 fn apply_filter(condition: &Condition, events: EventArray) -> EventArray {
     match events {
@@ -81,7 +99,7 @@ fn apply_filter(condition: &Condition, events: EventArray) -> EventArray {
 pub(crate) fn create_events_stream(
     watch_rx: WatchRx,
     patterns: TapPatterns,
-    filter_source: Option<String>,
+    filter: Option<Condition>,
     interval: u64,
     limit: usize,
 ) -> impl Stream<Item = Vec<OutputEventsPayload>> {
@@ -97,21 +115,9 @@ pub(crate) fn create_events_stream(
     let (event_tx, event_rx) = mpsc::channel::<Vec<OutputEventsPayload>>(10);
 
     tokio::spawn(async move {
-        // mezmo: compile the VRL source here and provide a Fn closure to the controller instead
-        let filter = filter_source.and_then(|source| {
-            let config = VrlConfig {
-                source,
-                runtime: Default::default(),
-            };
-
-            match config.build(&Default::default(), &Default::default(), None) {
-                Ok(condition) => Some(move |events: EventArray| apply_filter(&condition, events)),
-                Err(err) => {
-                    error!(message = "Failed to build tap filter condition.", ?err,);
-                    None
-                }
-            }
-        });
+        // mezmo: provide a Fn closure applying the compiled VRL condition to the controller
+        let filter =
+            filter.map(|condition| move |events: EventArray| apply_filter(&condition, events));
 
         // Create a tap controller. When this drops out of scope, clean up will be performed on the
         // event handlers and topology observation that the tap controller provides.
@@ -0,0 +1,107 @@
+use async_graphql::{Object, SimpleObject};
+use chrono::{DateTime, Utc};
+
+use crate::{
+    config::ComponentKey,
+    event::{Metric, MetricValue},
+};
+
+/// A bucket of a latency histogram
+#[derive(SimpleObject)]
+pub struct LatencyBucket {
+    /// Upper limit of the bucket, in seconds
+    upper_limit: f64,
+
+    /// Number of latencies that fell in the bucket
+    count: i64,
+}
+
+pub struct EventTraceLatency(Metric);
+
+impl EventTraceLatency {
+    pub const fn new(m: Metric) -> Self {
+        Self(m)
+    }
+}
+
+#[Object]
+impl EventTraceLatency {
+    /// Metric timestamp
+    pub async fn timestamp(&self) -> Option<DateTime<Utc>> {
+        self.0.timestamp()
+    }
+
+    /// Number of traced events
+    pub async fn count(&self) -> i64 {
+        match self.0.value() {
+            MetricValue::AggregatedHistogram { count, .. } => *count as i64,
+            _ => 0,
+        }
+    }
+
+    /// Sum of the latencies of the traced events, in seconds
+    pub async fn sum(&self) -> f64 {
+        match self.0.value() {
+            MetricValue::AggregatedHistogram { sum, .. } => *sum,
+            _ => 0.00,
+        }
+    }
+
+    /// Mean latency of the traced events, in seconds
+    pub async fn mean(&self) -> f64 {
+        match self.0.value() {
+            MetricValue::AggregatedHistogram { count, sum, .. } if *count > 0 => {
+                sum / *count as f64
+            }
+            _ => 0.00,
+        }
+    }
+
+    /// Latency histogram buckets
+    pub async fn buckets(&self) -> Vec<LatencyBucket> {
+        match self.0.value() {
+            MetricValue::AggregatedHistogram { buckets, .. } => buckets
+                .iter()
+                .map(|bucket| LatencyBucket {
+                    upper_limit: bucket.upper_limit,
+                    count: bucket.count as i64,
+                })
+                .collect(),
+            _ => vec![],
+        }
+    }
+}
+
+pub struct ComponentEventTraceLatency {
+    component_key: ComponentKey,
+    metric: Metric,
+}
+
+impl ComponentEventTraceLatency {
+    /// Returns a new `ComponentEventTraceLatency` struct, which is a GraphQL type. The
+    /// component id is hoisted for clear field resolution in the resulting payload
+    pub fn new(metric: Metric) -> Self {
+        let component_key = metric.tag_value("component_id").expect(
+            "Returned a metric without a `component_id`, which shouldn't happen. Please report.",
+        );
+        let component_key = ComponentKey::from(component_key);
+
+        Self {
+            component_key,
+            metric,
+        }
+    }
+}
+
+#[Object]
+impl ComponentEventTraceLatency {
+    /// Component id
+    async fn component_id(&self) -> &str {
+        self.component_key.id()
+    }
+
+    /// Event trace latency metric
+    async fn metric(&self) -> EventTraceLatency {
+        EventTraceLatency::new(self.metric.clone())
+    }
+}
@@ -225,6 +225,33 @@ pub fn component_gauge_metrics(
     })
 }
 
+/// Returns a stream of `Vec<Metric>`, where `metric_name` matches the name of the metric
+/// (e.g. "mezmo_event_trace_latency_seconds"), and the value is derived from
+/// `MetricValue::AggregatedHistogram`. Uses a local cache to match against the `component_id` of
+/// a metric, to return results only when the histogram has new observations.
+pub fn component_histogram_metrics(
+    interval: i32,
+    filter_fn: &'static MetricFilterFn,
+) -> impl Stream<Item = Vec<Metric>> {
+    let mut cache = BTreeMap::new();
+
+    component_to_filtered_metrics(interval, filter_fn).map(move |map| {
+        map.into_iter()
+            .filter_map(|(id, metrics)| {
+                let m = sum_metrics_owned(metrics)?;
+                match m.value() {
+                    MetricValue::AggregatedHistogram { count, .. }
+                        if cache.insert(id, *count).unwrap_or(0) < *count =>
+                    {
+                        Some(m)
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    })
+}
+
 /// Returns the throughput of a 'counter' metric, sampled over `interval` milliseconds
 /// and filtered by the provided `filter_fn`.
 pub fn counter_throughput(
@@ -395,3 +422,51 @@ fn throughput(metric: &Metric, id: String, cache: &mut BTreeMap<String, f64>) ->
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use metrics::histogram;
+
+    use super::*;
+    use crate::test_util::trace_init;
+
+    fn histogram_count(metric: &Metric) -> u64 {
+        match metric.value() {
+            MetricValue::AggregatedHistogram { count, .. } => *count,
+            value => panic!("expected a histogram, got {value:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn component_histogram_metrics_only_reports_new_observations() {
+        trace_init();
+
+        histogram!("mezmo_event_trace_latency_seconds", "component_id" => "traced").record(0.5);
+        histogram!("mezmo_event_trace_buffer_wait_seconds", "component_id" => "traced").record(1.0);
+
+        let mut stream = Box::pin(component_histogram_metrics(10, &|m| {
+            m.name() == "mezmo_event_trace_latency_seconds"
+        }));
+
+        let metrics = stream.next().await.unwrap();
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(metrics[0].tag_value("component_id").unwrap(), "traced");
+        assert_eq!(histogram_count(&metrics[0]), 1);
+
+        // Without new observations, nothing is reported.
+        assert!(stream.next().await.unwrap().is_empty());
+
+        histogram!("mezmo_event_trace_latency_seconds", "component_id" => "traced").record(1.5);
+        histogram!("mezmo_event_trace_latency_seconds", "component_id" => "other").record(0.25);
+
+        let metrics = stream.next().await.unwrap();
+        let counts = metrics
+            .iter()
+            .map(|m| (m.tag_value("component_id").unwrap(), histogram_count(m)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            counts,
+            [("other".to_string(), 1), ("traced".to_string(), 2)]
+        );
+    }
+}
@@ -1,5 +1,6 @@
 mod allocated_bytes;
 mod errors;
+mod event_trace_latency;
 pub mod filter;
 mod output;
 mod received_bytes;
@@ -18,6 +19,7 @@ pub use allocated_bytes::{AllocatedBytes, ComponentAllocatedBytes};
 use async_graphql::{Interface, Subscription};
 use chrono::{DateTime, Utc};
 pub use errors::{ComponentErrorsTotal, ErrorsTotal};
+pub use event_trace_latency::{ComponentEventTraceLatency, EventTraceLatency, LatencyBucket};
 pub use filter::*;
 pub use output::*;
 pub use received_bytes::{
@@ -271,6 +273,30 @@ impl MetricsSubscription {
             .map(|m| m.into_iter().map(ComponentErrorsTotal::new).collect())
     }
 
+    /// Component event trace latency metrics over `interval`, only reported for the components
+    /// with event tracing enabled.
+    async fn component_event_trace_latencies(
+        &self,
+        #[graphql(default = 1000, validator(minimum = 10, maximum = 60_000))] interval: i32,
+    ) -> impl Stream<Item = Vec<ComponentEventTraceLatency>> + use<> {
+        component_histogram_metrics(interval, &|m| {
+            m.name() == "mezmo_event_trace_latency_seconds"
+        })
+        .map(|m| m.into_iter().map(ComponentEventTraceLatency::new).collect())
+    }
+
+    /// Component event trace buffer wait metrics over `interval`, the time the traced events
+    /// waited in the input buffer of the components.
+    async fn component_event_trace_buffer_waits(
+        &self,
+        #[graphql(default = 1000, validator(minimum = 10, maximum = 60_000))] interval: i32,
+    ) -> impl Stream<Item = Vec<ComponentEventTraceLatency>> + use<> {
+        component_histogram_metrics(interval, &|m| {
+            m.name() == "mezmo_event_trace_buffer_wait_seconds"
+        })
+        .map(|m| m.into_iter().map(ComponentEventTraceLatency::new).collect())
+    }
+
     /// All metrics.
     async fn metrics(
         &self,
@@ -282,3 +308,43 @@ impl MetricsSubscription {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use metrics::histogram;
+    use serde_json::json;
+    use tokio_stream::StreamExt;
+
+    use crate::{api::schema::build_schema, test_util::trace_init};
+
+    #[tokio::test]
+    async fn component_event_trace_latencies() {
+        trace_init();
+
+        histogram!("mezmo_event_trace_latency_seconds", "component_id" => "traced").record(0.5);
+        histogram!("mezmo_event_trace_latency_seconds", "component_id" => "traced").record(1.5);
+        histogram!("component_latency_seconds", "component_id" => "untraced").record(1.0);
+
+        let schema = build_schema().finish();
+        let mut stream = schema.execute_stream(
+            "subscription {
+                componentEventTraceLatencies(interval: 10) {
+                    componentId
+                    metric { count sum mean }
+                }
+            }",
+        );
+
+        let response = stream.next().await.unwrap();
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.data.into_json().unwrap(),
+            json!({
+                "componentEventTraceLatencies": [{
+                    "componentId": "traced",
+                    "metric": {"count": 2, "sum": 2.0, "mean": 1.0}
+                }]
+            })
+        );
+    }
+}
@@ -16,7 +16,10 @@ use vector_lib::{
 };
 
 use crate::{
-    api::schema::events::{create_events_stream, log, metric, output::OutputEventsPayload},
+    api::{
+        build_schema,
+        schema::events::{create_events_stream, log, metric, output::OutputEventsPayload},
+    },
     config::{Config, OutputId},
     event::{LogEvent, Metric, MetricKind, MetricValue},
     sinks::blackhole::BlackholeConfig,
@@ -595,3 +598,26 @@ async fn integration_test_tap_multiple_outputs() {
 
     assert!(default_output_found && dropped_output_found);
 }
+
+#[tokio::test]
+/// An invalid filter should be reported, rather than streaming no events
+async fn invalid_filter_error() {
+    let (_watch_tx, watch_rx) = watch::channel(TapResource::default());
+    let schema = build_schema().data(watch_rx).finish();
+
+    let mut stream = schema.execute_stream(
+        r#"subscription {
+            outputEventsByComponentIdPatterns(outputsPatterns: ["*"], filter: "invalid(.message)") {
+                __typename
+            }
+        }"#,
+    );
+
+    let response = stream.next().await.expect("an error response");
+    assert_eq!(response.errors.len(), 1);
+    assert!(
+        response.errors[0].message.starts_with("Invalid filter"),
+        "{:?}",
+        response.errors
+    );
+}
@@ -222,7 +222,7 @@ impl Application {
             opts.log_level(),
             opts.root.internal_log_rate_limit,
         );
-        ::mezmo::user_trace::init(opts.root.user_log_rate_limit);
+        ::mezmo::user_trace::init(opts.root.user_log_rate_limit, opts.root.user_log_min_level);
 
         // Set global color preference for downstream modules
         crate::set_global_color(color);
@@ -321,6 +321,10 @@ fn start_remote_task_execution(
                     return Err(exitcode::USAGE);
                 }
 
+                crate::mezmo::remote_task_execution::init_config_snapshot(
+                    _config.topology.config(),
+                );
+
                 #[cfg(test)]
                 runtime.spawn(async move {
                     crate::mezmo::remote_task_execution::start_polling_for_tasks(
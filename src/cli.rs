@@ -214,6 +214,10 @@ pub struct RootOpts {
     #[arg(short, long, env = "VECTOR_USER_LOG_RATE_LIMIT", default_value = "10")]
     pub user_log_rate_limit: u64,
 
+    /// Set the minimum level of the user logs, less severe user logs are dropped
+    #[arg(long, env = "VECTOR_USER_LOG_MIN_LEVEL", default_value = "debug")]
+    pub user_log_min_level: ::mezmo::user_trace::Level,
+
     /// Set the duration in seconds to wait for graceful shutdown after SIGINT or SIGTERM are
     /// received. After the duration has passed, Vector will force shutdown. To never force
     /// shutdown, use `--no-graceful-shutdown-limit`.
@@ -225,6 +225,9 @@ fn serializer_config_to_deserializer(
         SerializerConfig::Gelf { .. } => DeserializerConfig::Gelf(Default::default()),
         SerializerConfig::Json(_) => DeserializerConfig::Json(Default::default()),
         SerializerConfig::Logfmt => todo!(),
+        SerializerConfig::Mezmo(encoding::MezmoSerializerConfig::PrometheusRemoteWrite) => {
+            DeserializerConfig::Mezmo(decoding::MezmoDeserializer::PrometheusRemoteWrite)
+        }
         SerializerConfig::Native => DeserializerConfig::Native,
         SerializerConfig::NativeJson => DeserializerConfig::NativeJson(Default::default()),
         SerializerConfig::Protobuf(config) => {
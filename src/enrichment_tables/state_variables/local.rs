@@ -0,0 +1,171 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use ::mezmo::pipeline_state_store::PipelineStateStore;
+use chrono::Utc;
+use moka::sync::Cache;
+use rocksdb::{DB, Options};
+use serde::{Deserialize, Serialize};
+use vrl::value::Value;
+
+use super::{MAX_CACHE_ENTRIES, StateVariablesDBError, get_cache_key};
+
+/// State of a pipeline as stored in the local database, along with the time it was last written.
+#[derive(Deserialize, Serialize)]
+struct StoredState {
+    updated_at: i64,
+    state: String,
+}
+
+/// Stores the state variables of the pipelines in a local RocksDB database, keyed like the
+/// table cache. It's written directly by `set_pipeline_state_variable`, so values can be read back
+/// right away by the same instance. The state of a pipeline expires `ttl` after its last write.
+pub(super) struct LocalStateStore {
+    db: DB,
+    cache: Arc<Cache<String, String>>,
+    ttl: Duration,
+    // Serializes the read-modify-write of the state of a pipeline
+    write_lock: Mutex<()>,
+}
+
+impl LocalStateStore {
+    pub(super) fn open(path: &Path, ttl: Duration) -> Result<Self, StateVariablesDBError> {
+        std::fs::create_dir_all(path).map_err(|err| StateVariablesDBError::LocalStoreError {
+            message: format!("Could not create the local store directory: {err}"),
+        })?;
+
+        let mut db_opts = Options::default();
+        db_opts.create_if_missing(true);
+        // The RocksDB TTL is best effort, expired records are only dropped during compaction
+        let db = DB::open_with_ttl(&db_opts, path, ttl).map_err(|err| {
+            StateVariablesDBError::LocalStoreError {
+                message: format!("Could not open the local store: {err}"),
+            }
+        })?;
+
+        let cache = Cache::builder()
+            .max_capacity(MAX_CACHE_ENTRIES)
+            .time_to_live(ttl)
+            .build();
+
+        Ok(Self {
+            db,
+            cache: Arc::new(cache),
+            ttl,
+            write_lock: Mutex::new(()),
+        })
+    }
+
+    /// Returns the serialized state of a pipeline, from the cache or else the database.
+    pub(super) fn get(&self, key: &str) -> Option<String> {
+        if let Some(state) = self.cache.get(key) {
+            return Some(state);
+        }
+
+        let state = self.read(key)?;
+        self.cache.insert(key.to_owned(), state.clone());
+        Some(state)
+    }
+
+    fn read(&self, key: &str) -> Option<String> {
+        let bytes = match self.db.get(key) {
+            Ok(bytes) => bytes?,
+            Err(err) => {
+                error!("Error reading state variables from the local store: {err}");
+                return None;
+            }
+        };
+        let stored: StoredState = match serde_json::from_slice(&bytes) {
+            Ok(stored) => stored,
+            Err(err) => {
+                error!("Invalid state variables in the local store: {err}");
+                return None;
+            }
+        };
+
+        let age = Utc::now().timestamp() - stored.updated_at;
+        (age < self.ttl.as_secs() as i64).then_some(stored.state)
+    }
+}
+
+impl PipelineStateStore for LocalStateStore {
+    fn set(&self, account_id: &str, pipeline_id: &str, name: &str, value: &Value) {
+        let key = get_cache_key(account_id, pipeline_id);
+        let _guard = self.write_lock.lock().expect("local store lock poisoned");
+
+        let mut variables: serde_json::Map<String, serde_json::Value> = self
+            .get(&key)
+            .and_then(|state| serde_json::from_str(&state).ok())
+            .unwrap_or_default();
+        let value = match serde_json::to_value(value) {
+            Ok(value) => value,
+            Err(err) => {
+                error!("Could not serialize state variable '{name}': {err}");
+                return;
+            }
+        };
+        variables.insert(name.to_owned(), value);
+
+        let state = serde_json::Value::Object(variables).to_string();
+        let stored = StoredState {
+            updated_at: Utc::now().timestamp(),
+            state: state.clone(),
+        };
+        match serde_json::to_vec(&stored) {
+            Ok(bytes) => {
+                if let Err(err) = self.db.put(&key, bytes) {
+                    error!("Error writing state variables to the local store: {err}");
+                }
+            }
+            Err(err) => error!("Could not serialize the state of '{key}': {err}"),
+        }
+        debug!("LOCAL STORE INSERT: {key} / {state}");
+        self.cache.insert(key, state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn read_after_write() {
+        let dir = tempdir().unwrap();
+        let store = LocalStateStore::open(dir.path(), Duration::from_secs(60)).unwrap();
+        let key = get_cache_key("account_1", "pipeline_1");
+
+        assert_eq!(store.get(&key), None);
+
+        store.set("account_1", "pipeline_1", "var_1", &Value::from("first"));
+        store.set("account_1", "pipeline_1", "var_2", &Value::from(2));
+        store.set("account_1", "pipeline_2", "var_1", &Value::from("other"));
+
+        let state: serde_json::Value = serde_json::from_str(&store.get(&key).unwrap()).unwrap();
+        assert_eq!(state, serde_json::json!({"var_1": "first", "var_2": 2}));
+
+        // Survives a cold cache
+        store.cache.invalidate_all();
+        let state: serde_json::Value = serde_json::from_str(&store.get(&key).unwrap()).unwrap();
+        assert_eq!(state, serde_json::json!({"var_1": "first", "var_2": 2}));
+    }
+
+    #[test]
+    fn expired_state() {
+        let dir = tempdir().unwrap();
+        let store = LocalStateStore::open(dir.path(), Duration::from_secs(60)).unwrap();
+        let key = get_cache_key("account_1", "pipeline_1");
+
+        let stored = StoredState {
+            updated_at: Utc::now().timestamp() - 120,
+            state: r#"{"var_1":"stale"}"#.to_owned(),
+        };
+        store
+            .db
+            .put(&key, serde_json::to_vec(&stored).unwrap())
+            .unwrap();
+
+        assert_eq!(store.get(&key), None);
+    }
+}
@@ -1,7 +1,9 @@
 use crate::config::EnrichmentTableConfig;
+use local::LocalStateStore;
 use moka::sync::Cache;
 use snafu::Snafu;
 use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::task::JoinHandle;
@@ -10,6 +12,8 @@ use vector_lib::enrichment::{Case, Condition, Error, IndexHandle, InternalError,
 use vector_lib::mezmo;
 use vrl::value::{KeyString, Value};
 
+mod local;
+
 const QUERY_ACTIVE_STATE_VARIABLES: &str = "SELECT pipeline_state_variables.account_id::text,
             pipeline_state_variables.pipeline_id::text,
             pipeline_state_variables.state::text
@@ -25,6 +29,7 @@ const QUERY_ACTIVE_STATE_VARIABLES: &str = "SELECT pipeline_state_variables.acco
 
 const MAX_CACHE_ENTRIES: u64 = 100_000;
 const POLL_DELAY: Duration = Duration::from_secs(5);
+const DEFAULT_LOCAL_TTL_SECS: u64 = 90_000; // 25 hours
 
 /// Potential postgres connection error
 #[derive(Debug, Snafu)]
@@ -44,6 +49,12 @@ pub enum StateVariablesDBError {
         /// A message detailing the exact error
         message: String,
     },
+    /// Error when the local store can't be opened
+    #[snafu(display("Can't open the local store for state variables enrichment table: {message}"))]
+    LocalStoreError {
+        /// A message detailing the exact error
+        message: String,
+    },
 }
 
 fn get_cache_key(account_id: &str, pipeline_id: &str) -> String {
@@ -86,26 +97,67 @@ async fn fetch_states_from_db(
     }
 }
 
-/// Empty config for state variables. It's only used to implement `.build()`
+/// Configuration for the state variables enrichment table.
 #[configurable_component(enrichment_table("state_variables"))]
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
-pub struct StateVariablesConfig {}
+pub struct StateVariablesConfig {
+    /// Where the state variables are stored.
+    #[serde(default)]
+    backend: StateVariablesBackend,
+}
 impl_generate_config_from_default!(StateVariablesConfig);
 
+/// Backends storing the state variables.
+#[configurable_component]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+#[configurable(metadata(docs::enum_tag_description = "The backend storing the state variables."))]
+pub enum StateVariablesBackend {
+    /// Polls the state variables of the partition from the pipeline DB.
+    #[default]
+    Postgres,
+
+    /// Stores the state variables in a local database, written directly by
+    /// `set_pipeline_state_variable`.
+    ///
+    /// Values can then be read back right away within a single instance.
+    Local {
+        /// The directory of the local database.
+        #[configurable(metadata(docs::examples = "/var/lib/vector/state_variables"))]
+        path: PathBuf,
+
+        /// How long the state variables of a pipeline are kept after they were last written, in
+        /// seconds.
+        #[serde(default = "default_local_ttl_secs")]
+        ttl_secs: u64,
+    },
+}
+
+const fn default_local_ttl_secs() -> u64 {
+    DEFAULT_LOCAL_TTL_SECS
+}
+
 impl EnrichmentTableConfig for StateVariablesConfig {
     async fn build(
         &self,
         _globals: &crate::config::GlobalOptions,
     ) -> crate::Result<Box<dyn Table + Send + Sync>> {
-        Ok(Box::new(StateVariables::new().await?))
+        match &self.backend {
+            StateVariablesBackend::Postgres => Ok(Box::new(StateVariables::new().await?)),
+            StateVariablesBackend::Local { path, ttl_secs } => Ok(Box::new(
+                StateVariables::new_local(path, Duration::from_secs(*ttl_secs))?,
+            )),
+        }
     }
 }
 
-/// A struct that implements [enrichment::Table] to handle loading data from postgres.
+/// A struct that implements [enrichment::Table] to handle loading data from postgres, or from
+/// a local store.
 #[derive(Clone)]
 pub struct StateVariables {
     _state_poller: Option<Arc<JoinHandle<()>>>, // Saved here only to prevent dropping. It's not really used directly.
     cache: Arc<Cache<String, String>>,
+    local_store: Option<Arc<LocalStateStore>>,
 }
 
 impl StateVariables {
@@ -143,6 +195,21 @@ impl StateVariables {
         Ok(Self {
             _state_poller: Some(Arc::new(state_poller)),
             cache,
+            local_store: None,
+        })
+    }
+
+    /// Impl for the state variables enrichment table backed by a local store. The store is
+    /// registered as the one written by `set_pipeline_state_variable` for as long as the table
+    /// lives.
+    pub fn new_local(path: &Path, ttl: Duration) -> Result<Self, StateVariablesDBError> {
+        let local_store = Arc::new(LocalStateStore::open(path, ttl)?);
+        ::mezmo::pipeline_state_store::register(&local_store);
+
+        Ok(Self {
+            _state_poller: None,
+            cache: Arc::new(Cache::new(0)),
+            local_store: Some(local_store),
         })
     }
 
@@ -152,6 +219,14 @@ impl StateVariables {
         Self {
             _state_poller: None,
             cache: Arc::new(Cache::new(1000)),
+            local_store: None,
+        }
+    }
+
+    fn get_state(&self, key: &str) -> Option<String> {
+        match &self.local_store {
+            Some(local_store) => local_store.get(key),
+            None => self.cache.get(key),
         }
     }
 
@@ -208,7 +283,7 @@ impl StateVariables {
             .expect("Condition field `pipeline_id` not found");
         let key = get_cache_key(account_id, pipeline_id);
 
-        let state = self.cache.get(&key).unwrap_or("{}".to_owned());
+        let state = self.get_state(&key).unwrap_or("{}".to_owned());
 
         let json: BTreeMap<KeyString, serde_json::Value> =
             match serde_json::from_str(state.as_str()) {
@@ -300,6 +375,11 @@ impl Table for StateVariables {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ::mezmo::{
+        MezmoContext, functions::set_pipeline_state_variable::internal_set_pipeline_state_variable,
+    };
+    use serial_test::serial;
+    use tempfile::tempdir;
 
     #[test]
     fn test_gather_query_params() {
@@ -452,4 +532,110 @@ mod tests {
             "Empty JSON when no cache is found"
         );
     }
+
+    #[test]
+    #[serial]
+    fn test_local_read_after_write() {
+        let dir = tempdir().unwrap();
+        let state_variables =
+            StateVariables::new_local(dir.path(), Duration::from_secs(60)).unwrap();
+        let mezmo_ctx = MezmoContext::try_from(
+            "v1:remap:transform:component_id:pipeline_1:account_1".to_string(),
+        )
+        .unwrap();
+        let conditions = vec![
+            Condition::Equals {
+                field: "account_id",
+                value: "account_1".into(),
+            },
+            Condition::Equals {
+                field: "pipeline_id",
+                value: "pipeline_1".into(),
+            },
+        ];
+        let select = ["var_1".to_string()];
+
+        let result =
+            state_variables.find_table_row(Case::Sensitive, &conditions, Some(&select), None, None);
+        assert_eq!(
+            Ok(BTreeMap::from([("var_1".into(), Value::Null)])),
+            result,
+            "Not set yet"
+        );
+
+        internal_set_pipeline_state_variable(
+            &mezmo_ctx,
+            None,
+            "var_1".to_string(),
+            Value::from("my value"),
+        )
+        .unwrap();
+
+        let result =
+            state_variables.find_table_row(Case::Sensitive, &conditions, Some(&select), None, None);
+        assert_eq!(
+            Ok(BTreeMap::from([("var_1".into(), "my value".into())])),
+            result,
+            "Written value is read back"
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_local_reload() {
+        let dir = tempdir().unwrap();
+        let mezmo_ctx = MezmoContext::try_from(
+            "v1:remap:transform:component_id:pipeline_1:account_1".to_string(),
+        )
+        .unwrap();
+        let conditions = vec![
+            Condition::Equals {
+                field: "account_id",
+                value: "account_1".into(),
+            },
+            Condition::Equals {
+                field: "pipeline_id",
+                value: "pipeline_1".into(),
+            },
+        ];
+        let select = ["var_1".to_string()];
+        let set_var_1 = |value: &str| {
+            internal_set_pipeline_state_variable(
+                &mezmo_ctx,
+                None,
+                "var_1".to_string(),
+                Value::from(value),
+            )
+            .unwrap();
+        };
+
+        let state_variables =
+            StateVariables::new_local(dir.path(), Duration::from_secs(60)).unwrap();
+        set_var_1("before reload");
+        drop(state_variables);
+        assert!(
+            ::mezmo::pipeline_state_store::get_store().is_none(),
+            "Dropping the table unregisters its store"
+        );
+
+        // The dropped store no longer holds the database, so the reloaded table can open it again
+        let state_variables =
+            StateVariables::new_local(dir.path(), Duration::from_secs(60)).unwrap();
+        let result =
+            state_variables.find_table_row(Case::Sensitive, &conditions, Some(&select), None, None);
+        assert_eq!(
+            Ok(BTreeMap::from([("var_1".into(), "before reload".into())])),
+            result,
+            "Value written before the reload is read back"
+        );
+
+        set_var_1("after reload");
+        let result =
+            state_variables.find_table_row(Case::Sensitive, &conditions, Some(&select), None, None);
+        assert_eq!(
+            Ok(BTreeMap::from([("var_1".into(), "after reload".into())])),
+            result,
+            "Reloaded table's store is registered"
+        );
+    }
 }
@@ -153,3 +153,96 @@ impl InternalEvent for MezmoConfigReloadSignalReceive {
         counter!("mezmo_config_reload_signal_received_total").increment(1);
     }
 }
+
+#[derive(NamedInternalEvent)]
+pub struct MezmoConfigSubscriptionDropped {
+    pub error: String,
+}
+
+impl InternalEvent for MezmoConfigSubscriptionDropped {
+    fn emit(self) {
+        warn!(
+            message = format!(
+                "Revision subscription unavailable, falling back to polling: {}",
+                self.error
+            )
+        );
+        counter!("mezmo_config_subscription_dropped_total").increment(1);
+    }
+}
+
+#[derive(NamedInternalEvent)]
+pub struct MezmoConfigServedFromCache<'a> {
+    pub revisions: &'a [(String, String, u32)],
+}
+
+impl InternalEvent for MezmoConfigServedFromCache<'_> {
+    fn emit(self) {
+        warn!(
+            message = format!(
+                "Pipeline service unavailable, serving {} revisions from the config cache",
+                self.revisions.len()
+            )
+        );
+        gauge!("mezmo_config_served_from_cache").set(1.0);
+        for (pipeline_id, revision_id, toml_version) in self.revisions {
+            gauge!(
+                "mezmo_config_cached_revision",
+                "pipeline_id" => pipeline_id.clone(),
+                "revision_id" => revision_id.clone(),
+                "toml_version" => toml_version.to_string())
+            .set(1.0);
+        }
+    }
+}
+
+#[derive(NamedInternalEvent)]
+pub struct MezmoConfigCacheReconciled {
+    pub revisions: Vec<(String, String, u32)>,
+}
+
+impl InternalEvent for MezmoConfigCacheReconciled {
+    fn emit(self) {
+        info!("Pipeline service reachable again, reconciled the cached config");
+        gauge!("mezmo_config_served_from_cache").set(0.0);
+        for (pipeline_id, revision_id, toml_version) in self.revisions {
+            gauge!(
+                "mezmo_config_cached_revision",
+                "pipeline_id" => pipeline_id,
+                "revision_id" => revision_id,
+                "toml_version" => toml_version.to_string())
+            .set(0.0);
+        }
+    }
+}
+
+#[derive(NamedInternalEvent)]
+pub struct MezmoConfigCacheError {
+    pub error: String,
+}
+
+impl InternalEvent for MezmoConfigCacheError {
+    fn emit(self) {
+        warn!(message = format!("Error accessing the config cache: {}", self.error));
+        counter!("mezmo_config_cache_errors_total").increment(1);
+    }
+}
+
+#[derive(NamedInternalEvent)]
+pub struct MezmoConfigRevisionRollback<'a> {
+    pub pipeline_id: &'a str,
+    pub revision_id: &'a str,
+    pub reason: &'a str,
+}
+
+impl InternalEvent for MezmoConfigRevisionRollback<'_> {
+    fn emit(self) {
+        warn!(
+            message = "Rolling back pipeline revision after failing its probation",
+            pipeline_id = self.pipeline_id,
+            revision_id = self.revision_id,
+            reason = self.reason
+        );
+        counter!("mezmo_config_revision_rollbacks_total").increment(1);
+    }
+}
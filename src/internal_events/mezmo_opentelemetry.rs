@@ -0,0 +1,42 @@
+use metrics::counter;
+use vector_lib::{NamedInternalEvent, internal_event::InternalEvent};
+
+#[derive(Debug, NamedInternalEvent)]
+pub struct MezmoOpentelemetryPartialSuccess<'a> {
+    pub signal: &'static str,
+    pub rejected: i64,
+    pub error_message: &'a str,
+}
+
+impl InternalEvent for MezmoOpentelemetryPartialSuccess<'_> {
+    fn emit(self) {
+        warn!(
+            message = "OpenTelemetry collector partially accepted the request.",
+            signal = %self.signal,
+            rejected = %self.rejected,
+            error_message = %self.error_message,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "mezmo_opentelemetry_rejected_total",
+            "signal" => self.signal,
+        )
+        .increment(self.rejected.max(0) as u64);
+    }
+}
+
+#[derive(Debug, NamedInternalEvent)]
+pub struct MezmoOpentelemetryResponseDecodeError<'a> {
+    pub error: &'a dyn std::error::Error,
+}
+
+impl InternalEvent for MezmoOpentelemetryResponseDecodeError<'_> {
+    fn emit(self) {
+        warn!(
+            message = "Failed to decode the OpenTelemetry collector response, ignoring its partial success.",
+            error = %self.error,
+            internal_log_rate_limit = true,
+        );
+        counter!("mezmo_opentelemetry_response_decode_errors_total").increment(1);
+    }
+}
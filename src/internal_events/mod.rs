@@ -99,6 +99,8 @@ mod mezmo_datadog_agent_parser;
 pub(crate) mod mezmo_log_clustering;
 #[cfg(feature = "transforms-mezmo_log_to_trace")]
 mod mezmo_log_to_trace;
+#[cfg(feature = "sinks-mezmo_opentelemetry")]
+mod mezmo_opentelemetry;
 #[cfg(any(
     feature = "transforms-mezmo_aggregate_v2",
     feature = "transforms-mezmo_throttle",
@@ -276,6 +278,8 @@ pub(crate) use self::mezmo_aggregate_distributed::*;
 pub(crate) use self::mezmo_datadog_agent_parser::*;
 #[cfg(feature = "transforms-mezmo_log_to_trace")]
 pub(crate) use self::mezmo_log_to_trace::*;
+#[cfg(feature = "sinks-mezmo_opentelemetry")]
+pub(crate) use self::mezmo_opentelemetry::*;
 #[cfg(feature = "transforms-mezmo_tag_cardinality_limit")]
 pub(crate) use self::mezmo_tag_cardinality_limit::*;
 #[cfg(feature = "transforms-mezmo_throttle_distributed")]
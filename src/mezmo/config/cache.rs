@@ -0,0 +1,159 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::{PipelineId, Revision};
+
+/// The last configuration of the partition that was successfully loaded.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct CachedConfig {
+    pub(crate) common_config: String,
+    pub(crate) pipelines: Vec<PipelineId>,
+    pub(crate) revisions: HashMap<PipelineId, Revision>,
+}
+
+/// The file format, the checksum being the SHA-256 of `config`.
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    checksum: String,
+    config: String,
+}
+
+/// Keeps the last-known-good configuration on local disk, so that the partition can be started
+/// while the pipeline service is unreachable.
+pub(crate) struct ConfigCache {
+    path: PathBuf,
+}
+
+fn checksum(config: &str) -> String {
+    hex::encode(Sha256::digest(config.as_bytes()))
+}
+
+impl ConfigCache {
+    pub(crate) fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Writes the configuration, replacing the previous one atomically. The revisions may contain
+    /// secrets, so the file is only readable by its owner.
+    pub(crate) fn save(&self, cached: &CachedConfig) -> Result<(), String> {
+        let config = serde_json::to_string(cached).map_err(|e| e.to_string())?;
+        let file = CacheFile {
+            checksum: checksum(&config),
+            config,
+        };
+        let contents = serde_json::to_vec(&file).map_err(|e| e.to_string())?;
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let tmp_path = self.path.with_extension("tmp");
+        // A leftover file would keep its permissions
+        if let Err(e) = fs::remove_file(&tmp_path)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            return Err(e.to_string());
+        }
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options.open(&tmp_path).map_err(|e| e.to_string())?;
+        file.write_all(&contents).map_err(|e| e.to_string())?;
+        file.sync_all().map_err(|e| e.to_string())?;
+        fs::rename(&tmp_path, &self.path).map_err(|e| e.to_string())
+    }
+
+    /// Reads the configuration, if any. A corrupted or tampered file results in an error.
+    pub(crate) fn load(&self) -> Result<Option<CachedConfig>, String> {
+        let contents = match fs::read(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.to_string()),
+        };
+
+        let file: CacheFile = serde_json::from_slice(&contents).map_err(|e| e.to_string())?;
+        if checksum(&file.config) != file.checksum {
+            return Err(format!(
+                "Checksum mismatch for the config cache {}",
+                self.path.display()
+            ));
+        }
+
+        serde_json::from_str(&file.config)
+            .map(Some)
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn cached_config() -> CachedConfig {
+        CachedConfig {
+            common_config: "data_dir = \"/data/vector\"".to_string(),
+            pipelines: vec!["pipeline1".to_string()],
+            revisions: HashMap::from([(
+                "pipeline1".to_string(),
+                Revision {
+                    id: "rev1".to_string(),
+                    toml_version: 1,
+                    config: "[sources.in1]\ntype = \"test_basic\"".to_string(),
+                },
+            )]),
+        }
+    }
+
+    #[test]
+    fn save_and_load_test() {
+        let dir = tempdir().unwrap();
+        let cache = ConfigCache::new(dir.path().join("nested").join("partition.json"));
+        assert!(cache.load().unwrap().is_none(), "Nothing cached yet");
+
+        cache.save(&cached_config()).unwrap();
+        let loaded = cache.load().unwrap().expect("a cached config");
+        assert_eq!(loaded.common_config, "data_dir = \"/data/vector\"");
+        assert_eq!(loaded.pipelines, vec!["pipeline1".to_string()]);
+        assert_eq!(loaded.revisions["pipeline1"].id, "rev1");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn save_restricts_permissions_test() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("partition.json");
+        let cache = ConfigCache::new(&path);
+        cache.save(&cached_config()).unwrap();
+        cache.save(&cached_config()).unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn load_rejects_tampered_file_test() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("partition.json");
+        let cache = ConfigCache::new(&path);
+        cache.save(&cached_config()).unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        fs::write(&path, contents.replace("rev1", "rev2")).unwrap();
+
+        let error = cache.load().unwrap_err();
+        assert!(error.starts_with("Checksum mismatch"), "{error}");
+    }
+}
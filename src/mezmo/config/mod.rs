@@ -1,9 +1,12 @@
+mod cache;
+mod rollout;
 mod service;
+mod validation;
 
 use std::collections::{HashMap, HashSet};
 
 use async_stream::stream;
-use futures::Stream;
+use futures::{Stream, StreamExt};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use tokio::time::{self, Instant};
@@ -13,14 +16,20 @@ use vector_lib::schema::Definition;
 use crate::{
     config::{self, ConfigBuilder, TransformContext, provider::ProviderConfig},
     internal_events::mezmo_config::{
-        MezmoConfigBuildFailure, MezmoConfigBuilderCreate, MezmoConfigReloadSignalSend,
-        MezmoConfigVrlValidation, MezmoConfigVrlValidationError, MezmoGenerateConfigError,
+        MezmoConfigBuildFailure, MezmoConfigBuilderCreate, MezmoConfigCacheError,
+        MezmoConfigCacheReconciled, MezmoConfigReloadSignalSend, MezmoConfigRevisionRollback,
+        MezmoConfigServedFromCache, MezmoConfigSubscriptionDropped, MezmoConfigVrlValidation,
+        MezmoConfigVrlValidationError, MezmoGenerateConfigError,
     },
     providers::BuildResult,
     signal,
 };
 use mezmo::{MezmoContext, user_trace::MezmoUserLog};
 
+pub use self::rollout::RolloutConfig;
+
+use self::cache::{CachedConfig, ConfigCache};
+use self::rollout::RolloutGuard;
 use self::service::{ConfigService, DefaultConfigService};
 
 /// Request settings.
@@ -40,6 +49,22 @@ impl Default for RequestConfig {
     }
 }
 
+/// How new pipeline revisions are delivered to the provider.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigDeliveryMode {
+    /// Poll the latest revisions endpoint every `poll_interval_secs`.
+    #[default]
+    Poll,
+
+    /// Hold a request open against the subscribe endpoint until revisions are published.
+    LongPoll,
+
+    /// Subscribe to server-sent events published when revisions change.
+    Sse,
+}
+
 /// Configuration for the `mezmo_partition` provider.
 #[configurable_component(provider("mezmo_partition"))]
 #[derive(Clone, Debug)]
@@ -54,6 +79,9 @@ pub struct MezmoPartitionConfig {
     /// Url of the "GET pipelines by partition" endpoint
     pipelines_by_partition_url: String,
 
+    /// Url of the "POST rolled back revisions" endpoint
+    rolled_back_revisions_url: String,
+
     /// The partition identifier
     partition_id: String,
 
@@ -63,8 +91,31 @@ pub struct MezmoPartitionConfig {
     /// How often to poll the provider, in seconds.
     poll_interval_secs: u64,
 
-    /// Validate and reject any invalid VRL snippets (currently supports only remap transforms)
+    #[configurable(derived)]
+    delivery_mode: ConfigDeliveryMode,
+
+    /// Url of the "GET revisions subscription" endpoint, used when `delivery_mode` is not `poll`
+    subscribe_url: String,
+
+    /// The long poll request timeout, or the maximum time without any data (including
+    /// heartbeats) on the server-sent events stream, in seconds.
+    subscription_timeout_secs: u64,
+
+    /// How long to fall back to polling after the subscription dropped, before subscribing
+    /// again, in seconds.
+    resubscribe_interval_secs: u64,
+
+    /// Validate and reject any invalid VRL snippets, in the programs and conditions of the transforms
     validate_vrl: bool,
+
+    /// Path of the file keeping the last configuration successfully loaded, used to start
+    /// the partition when the pipeline service is unreachable. Disabled when not set.
+    config_cache_path: Option<String>,
+
+    /// Watch new revisions after they're loaded and roll them back automatically when their
+    /// pipeline becomes unhealthy. Disabled when not set.
+    #[configurable(derived)]
+    rollout: Option<RolloutConfig>,
 }
 
 // Serde requires Default trait
@@ -77,10 +128,20 @@ impl Default for MezmoPartitionConfig {
                 "http://pipeline-service/internal/pipelines/config/loaded_revisions".into(),
             pipelines_by_partition_url:
                 "http://pipeline-service/internal/partitions/{partition_id}/pipelines".into(),
+            rolled_back_revisions_url:
+                "http://pipeline-service/internal/pipelines/config/rolled_back_revisions".into(),
             partition_id: "sample_partition".into(),
             request: RequestConfig::default(),
             poll_interval_secs: 2,
+            delivery_mode: ConfigDeliveryMode::Poll,
+            subscribe_url:
+                "http://pipeline-service/internal/partitions/{partition_id}/revisions/subscribe"
+                    .into(),
+            subscription_timeout_secs: 60,
+            resubscribe_interval_secs: 30,
             validate_vrl: false,
+            config_cache_path: None,
+            rollout: None,
         }
     }
 }
@@ -108,27 +169,139 @@ fn poll_config(
             last_run = time::Instant::now();
 
             debug!("Building incrementally from polling loop");
-            match mezmo_config_builder.build_incrementally().await {
-                Ok((Some(config_builder), loaded)) => {
-                    emit!(MezmoConfigReloadSignalSend {});
-                    yield signal::SignalTo::ReloadFromConfigBuilder(config_builder);
-                    if !loaded.is_empty() {
-                        mezmo_config_builder.service.set_loaded_revisions(loaded).await.unwrap_or_else(|e| {
-                            error!("Error setting loaded revisions: {e}");
-                        });
+            if let Some((config_builder, loaded)) = reload_config(&mut mezmo_config_builder).await {
+                yield signal::SignalTo::ReloadFromConfigBuilder(config_builder);
+                set_loaded_revisions(&mezmo_config_builder, loaded).await;
+            }
+
+            if let Some(config_builder) = mezmo_config_builder.check_rollouts().await {
+                emit!(MezmoConfigReloadSignalSend {});
+                yield signal::SignalTo::ReloadFromConfigBuilder(config_builder);
+            }
+        }
+    }
+}
+
+/// Subscribes to revision changes, building incrementally each time new revisions are
+/// published. When the subscription can't be established or drops, it falls back to polling
+/// for `resubscribe_interval` before subscribing again.
+fn push_config(
+    poll_interval_secs: u64,
+    resubscribe_interval_secs: u64,
+    mut mezmo_config_builder: MezmoConfigBuilder,
+) -> impl Stream<Item = signal::SignalTo> {
+    let poll_interval = time::Duration::from_secs(poll_interval_secs);
+    let resubscribe_interval = time::Duration::from_secs(resubscribe_interval_secs);
+
+    stream! {
+        loop {
+            match mezmo_config_builder.service.subscribe_revisions().await {
+                Ok(mut notifications) => {
+                    info!("Subscribed to Mezmo partition revision changes");
+
+                    // Catch up with any revision published while not subscribed
+                    if let Some((config_builder, loaded)) = reload_config(&mut mezmo_config_builder).await {
+                        yield signal::SignalTo::ReloadFromConfigBuilder(config_builder);
+                        set_loaded_revisions(&mezmo_config_builder, loaded).await;
+                    }
+
+                    loop {
+                        // Revisions in probation are checked every poll interval
+                        let notification = if mezmo_config_builder.in_probation() {
+                            time::timeout(poll_interval, notifications.next()).await.ok()
+                        } else {
+                            Some(notifications.next().await)
+                        };
+                        if let Some(config_builder) = mezmo_config_builder.check_rollouts().await {
+                            emit!(MezmoConfigReloadSignalSend {});
+                            yield signal::SignalTo::ReloadFromConfigBuilder(config_builder);
+                        }
+                        let Some(notification) = notification else {
+                            continue;
+                        };
+
+                        match notification {
+                            Some(Ok(())) => {
+                                debug!("Building incrementally from revision notification");
+                                if let Some((config_builder, loaded)) = reload_config(&mut mezmo_config_builder).await {
+                                    yield signal::SignalTo::ReloadFromConfigBuilder(config_builder);
+                                    set_loaded_revisions(&mezmo_config_builder, loaded).await;
+                                }
+                            },
+                            Some(Err(e)) => {
+                                emit!(MezmoConfigSubscriptionDropped { error: e });
+                                break;
+                            },
+                            None => {
+                                emit!(MezmoConfigSubscriptionDropped { error: "Subscription ended".to_string() });
+                                break;
+                            },
+                        }
                     }
-                },
-                Ok((None, _)) => {
-                    // No changes -> keep polling
                 },
                 Err(e) => {
-                    emit!(MezmoConfigBuildFailure { error: e});
+                    emit!(MezmoConfigSubscriptionDropped { error: e });
                 },
-            };
+            }
+
+            // Fall back to polling until it's time to subscribe again
+            let fallback_start = time::Instant::now();
+            loop {
+                debug!("Building incrementally from fallback polling loop");
+                if let Some((config_builder, loaded)) = reload_config(&mut mezmo_config_builder).await {
+                    yield signal::SignalTo::ReloadFromConfigBuilder(config_builder);
+                    set_loaded_revisions(&mezmo_config_builder, loaded).await;
+                }
+
+                if let Some(config_builder) = mezmo_config_builder.check_rollouts().await {
+                    emit!(MezmoConfigReloadSignalSend {});
+                    yield signal::SignalTo::ReloadFromConfigBuilder(config_builder);
+                }
+
+                if fallback_start.elapsed() >= resubscribe_interval {
+                    break;
+                }
+                time::sleep(poll_interval.min(resubscribe_interval - fallback_start.elapsed())).await;
+            }
+        }
+    }
+}
+
+/// Builds incrementally, returning the config to reload with along with the loaded revisions.
+async fn reload_config(
+    mezmo_config_builder: &mut MezmoConfigBuilder,
+) -> Option<(ConfigBuilder, Vec<(PipelineId, RevisionId, TomlVersion)>)> {
+    match mezmo_config_builder.build_incrementally().await {
+        Ok((Some(config_builder), loaded)) => {
+            emit!(MezmoConfigReloadSignalSend {});
+            Some((config_builder, loaded))
+        }
+        Ok((None, _)) => {
+            // No changes
+            None
+        }
+        Err(e) => {
+            emit!(MezmoConfigBuildFailure { error: e });
+            None
         }
     }
 }
 
+async fn set_loaded_revisions(
+    mezmo_config_builder: &MezmoConfigBuilder,
+    loaded: Vec<(PipelineId, RevisionId, TomlVersion)>,
+) {
+    if !loaded.is_empty() {
+        mezmo_config_builder
+            .service
+            .set_loaded_revisions(loaded)
+            .await
+            .unwrap_or_else(|e| {
+                error!("Error setting loaded revisions: {e}");
+            });
+    }
+}
+
 // Alias types for readability
 type PipelineId = String;
 type RevisionId = String;
@@ -142,6 +315,14 @@ struct MezmoConfigBuilder {
     common_config: Option<String>,
 
     validate_vrl: bool,
+
+    /// Local copy of the last configuration successfully loaded
+    offline_cache: Option<ConfigCache>,
+    /// Revisions currently served from the offline cache, until reconciled with the service
+    served_from_cache: Vec<(PipelineId, RevisionId, TomlVersion)>,
+
+    /// Health gating of newly loaded revisions
+    rollout: Option<RolloutGuard>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -159,19 +340,24 @@ impl MezmoConfigBuilder {
             pipelines: None,
             common_config: None,
             validate_vrl: config.validate_vrl,
+            offline_cache: config.config_cache_path.as_ref().map(ConfigCache::new),
+            served_from_cache: Vec::new(),
+            rollout: config.rollout.clone().map(RolloutGuard::new),
         }
     }
 
-    /// Tries to build the configuration from scratch.
-    /// It errors out (crashing the process) when getting the partition info fails or no config can be fetched.
-    async fn build_all(&mut self) -> Result<ConfigBuilder, Vec<String>> {
-        debug!("Initial configuration build started");
+    fn in_probation(&self) -> bool {
+        self.rollout
+            .as_ref()
+            .is_some_and(|rollout| rollout.in_probation())
+    }
+
+    /// Fetches the pipelines of the partition along with their latest revisions.
+    async fn fetch_all(
+        &self,
+    ) -> Result<(Vec<PipelineId>, String, HashMap<PipelineId, Revision>), String> {
         trace!("Fetching pipelines by partition");
-        let (pipelines, common_config) = self
-            .service
-            .get_pipelines_by_partition()
-            .await
-            .map_err(|e| vec![e])?;
+        let (pipelines, common_config) = self.service.get_pipelines_by_partition().await?;
 
         trace!("Fetching revisions for {} pipelines", pipelines.len());
         let revisions = self
@@ -182,8 +368,20 @@ impl MezmoConfigBuilder {
                     .map(|id| (id.clone(), None, None))
                     .collect(),
             )
-            .await
-            .map_err(|e| vec![e])?;
+            .await?;
+
+        Ok((pipelines, common_config, revisions))
+    }
+
+    /// Tries to build the configuration from scratch.
+    /// When the service is unreachable, the last configuration successfully loaded is used if
+    /// available. Otherwise it errors out (crashing the process).
+    async fn build_all(&mut self) -> Result<ConfigBuilder, Vec<String>> {
+        debug!("Initial configuration build started");
+        let (pipelines, common_config, revisions) = match self.fetch_all().await {
+            Ok(fetched) => fetched,
+            Err(error) => return self.build_from_offline_cache(error).await,
+        };
 
         info!(
             "Building initial configuration for {} pipelines ({} revisions)",
@@ -211,6 +409,7 @@ impl MezmoConfigBuilder {
                     cache.len()
                 );
                 self.cache = cache;
+                self.save_offline_cache();
                 return Ok(r);
             }
             Err(errors) => {
@@ -285,6 +484,7 @@ impl MezmoConfigBuilder {
         let common_config = self.common_config.as_ref().unwrap();
         let mut result_builder = None;
         let mut loaded: Vec<(PipelineId, RevisionId, TomlVersion)> = Vec::new();
+        let mut build_failed = false;
 
         if pipelines_removed || common_config_changed {
             debug!(
@@ -301,6 +501,7 @@ impl MezmoConfigBuilder {
                     result_builder = Some(builder);
                 }
                 Err(errors) => {
+                    build_failed = true;
                     emit!(MezmoGenerateConfigError {
                         errors,
                         pipeline_id: None,
@@ -323,6 +524,18 @@ impl MezmoConfigBuilder {
         }
 
         for (pipeline_id, revision) in pipelines_with_changes.into_iter() {
+            if self
+                .rollout
+                .as_ref()
+                .is_some_and(|rollout| rollout.is_rejected(&pipeline_id, &revision))
+            {
+                debug!(
+                    "Skipping revision {} of pipeline {}, it was rolled back",
+                    &revision.id, &pipeline_id
+                );
+                continue;
+            }
+
             match self.cache.get_key_value(&pipeline_id) {
                 Some(existing)
                     if (existing.1.id != revision.id)
@@ -361,10 +574,21 @@ impl MezmoConfigBuilder {
                         revision.id.clone(),
                         revision.toml_version,
                     ));
+                    if let Some(rollout) = &mut self.rollout {
+                        let previous = self.cache.get(&pipeline_id).cloned();
+                        // Refreshing the same revision doesn't require a new probation
+                        if previous.as_ref().is_none_or(|previous| {
+                            previous.id != revision.id
+                                || previous.toml_version != revision.toml_version
+                        }) {
+                            rollout.start(&pipeline_id, previous, &revision, Instant::now());
+                        }
+                    }
                     self.cache.insert(pipeline_id, revision);
                     result_builder = Some(builder);
                 }
                 Err(errors) => {
+                    build_failed = true;
                     emit!(MezmoGenerateConfigError {
                         errors,
                         pipeline_id: Some(pipeline_id.clone()),
@@ -384,12 +608,154 @@ impl MezmoConfigBuilder {
             );
         }
 
+        if result_builder.is_some() {
+            self.save_offline_cache();
+        }
+
+        // The service is reachable: the cached config is reconciled once the changes since it
+        // was cached are built, otherwise it's still what's running.
+        if !build_failed && !self.served_from_cache.is_empty() {
+            emit!(MezmoConfigCacheReconciled {
+                revisions: std::mem::take(&mut self.served_from_cache),
+            });
+        }
+
         emit!(MezmoConfigBuilderCreate {
             revisions: self.cache.len()
         });
         Ok((result_builder, loaded))
     }
 
+    /// Rolls back the revisions that breached the health thresholds during their probation,
+    /// returning the configuration to reload with.
+    async fn check_rollouts(&mut self) -> Option<ConfigBuilder> {
+        let rollbacks = self.rollout.as_mut()?.check(Instant::now());
+        if rollbacks.is_empty() {
+            return None;
+        }
+
+        let mut restored = Vec::new();
+        for rollback in rollbacks.iter() {
+            emit!(MezmoConfigRevisionRollback {
+                pipeline_id: &rollback.pipeline_id,
+                revision_id: &rollback.revision_id,
+                reason: &rollback.reason,
+            });
+            match &rollback.previous {
+                Some(previous) => {
+                    restored.push((
+                        rollback.pipeline_id.clone(),
+                        previous.id.clone(),
+                        previous.toml_version,
+                    ));
+                    self.cache
+                        .insert(rollback.pipeline_id.clone(), previous.clone());
+                }
+                None => {
+                    self.cache.remove(&rollback.pipeline_id);
+                }
+            }
+        }
+
+        let common_config = self.common_config.as_deref().unwrap_or_default();
+        // The previous revisions were already validated when they were first loaded
+        let result = match generate_config(common_config, &self.cache, None, false).await {
+            Ok(builder) => Some(builder),
+            Err(errors) => {
+                emit!(MezmoGenerateConfigError {
+                    errors,
+                    pipeline_id: None,
+                    revision_id: None,
+                    toml_version: None,
+                    incremental: true,
+                    cache_len: self.cache.len(),
+                });
+                None
+            }
+        };
+        self.save_offline_cache();
+
+        let reported = rollbacks
+            .into_iter()
+            .map(|r| (r.pipeline_id, r.revision_id, r.toml_version, r.reason))
+            .collect();
+        self.service
+            .report_rollbacks(reported)
+            .await
+            .unwrap_or_else(|e| {
+                error!("Error reporting rolled back revisions: {e}");
+            });
+        if result.is_some() {
+            set_loaded_revisions(self, restored).await;
+        }
+
+        result
+    }
+
+    /// Builds the configuration from the offline cache, when the service couldn't be reached.
+    async fn build_from_offline_cache(
+        &mut self,
+        service_error: String,
+    ) -> Result<ConfigBuilder, Vec<String>> {
+        let Some(offline_cache) = &self.offline_cache else {
+            return Err(vec![service_error]);
+        };
+
+        let cached = match offline_cache.load() {
+            Ok(Some(cached)) => cached,
+            Ok(None) => return Err(vec![service_error]),
+            Err(error) => {
+                emit!(MezmoConfigCacheError {
+                    error: error.clone()
+                });
+                return Err(vec![service_error, error]);
+            }
+        };
+
+        let builder = generate_config(
+            &cached.common_config,
+            &cached.revisions,
+            None,
+            self.validate_vrl,
+        )
+        .await
+        .map_err(|mut errors| {
+            errors.insert(0, service_error);
+            errors
+        })?;
+
+        self.served_from_cache = cached
+            .revisions
+            .iter()
+            .map(|(pipeline_id, r)| (pipeline_id.clone(), r.id.clone(), r.toml_version))
+            .collect();
+        emit!(MezmoConfigServedFromCache {
+            revisions: &self.served_from_cache
+        });
+        self.pipelines = Some(cached.pipelines);
+        self.common_config = Some(cached.common_config);
+        self.cache = cached.revisions;
+        Ok(builder)
+    }
+
+    /// Stores the current configuration as the last-known-good one.
+    fn save_offline_cache(&self) {
+        let (Some(offline_cache), Some(pipelines), Some(common_config)) =
+            (&self.offline_cache, &self.pipelines, &self.common_config)
+        else {
+            return;
+        };
+
+        let cached = CachedConfig {
+            common_config: common_config.clone(),
+            pipelines: pipelines.clone(),
+            revisions: self.cache.clone(),
+        };
+        if let Err(error) = offline_cache.save(&cached) {
+            emit!(MezmoConfigCacheError { error });
+        }
+    }
+
     /// Extracts the current revisions of each pipeline and fetches the new configuration (if any).
     async fn get_pipeline_with_config_changes(
         &self,
@@ -425,6 +791,9 @@ impl MezmoConfigBuilder {
         for pipeline_id in diff.iter() {
             trace!("Removing pipeline {} from cache", pipeline_id);
             self.cache.remove(pipeline_id);
+            if let Some(rollout) = &mut self.rollout {
+                rollout.cancel(pipeline_id);
+            }
         }
 
         !diff.is_empty()
@@ -549,9 +918,16 @@ async fn validate_vrl_transforms(config_builder: &ConfigBuilder) -> Result<(), V
             let merged_definition = Definition::default_legacy_namespace();
 
             let transform = &transform.inner;
-            // Handling only remaps currently, but could be extended in the future
-            if transform.get_component_name() == "remap" {
-                let mezmo_ctx = MezmoContext::try_from(key.clone().into_id()).ok();
+            let mezmo_ctx = MezmoContext::try_from(key.clone().into_id()).ok();
+            // Ignore config validation for non-pipeline components (analysis)
+            let Some(pipeline_id) = mezmo_ctx.as_ref().and_then(|ctx| ctx.pipeline_id.clone())
+            else {
+                continue;
+            };
+
+            let component_name = transform.get_component_name();
+            let mut errors = Vec::new();
+            if component_name == "remap" {
                 let context = TransformContext {
                     key: Some(key.clone()),
                     globals: config.global.clone(),
@@ -565,25 +941,43 @@ async fn validate_vrl_transforms(config_builder: &ConfigBuilder) -> Result<(), V
                 };
                 // Compile the VRL snippet in the transform
                 trace!("Compiling and validating VRL for transform {key}");
-                if let Err(error) = transform.build(&context).await
-                    && let Some(ctx) = &mezmo_ctx
-                {
-                    match &ctx.pipeline_id {
-                        Some(mezmo::ContextIdentifier::Value { id: _ }) => {
-                            mezmo::user_log_error!(
-                                mezmo_ctx,
-                                "Error loading existing transform component. Please contact support"
-                            );
-                            failures
-                                .push(format!("Error validating VRL in transform {key}: {error}"));
-                        }
-                        Some(mezmo::ContextIdentifier::Shared) => {
-                            // This shouldn't happen...
-                            failures.push(format!("Invalid VRL found in shared component {key}"));
-                        }
-                        None => {
-                            // Ignore config validation for non-pipeline components (analysis)
-                        }
+                if let Err(error) = transform.build(&context).await {
+                    errors.push(("source".to_string(), error.to_string()));
+                }
+            } else if let Ok(value) = serde_json::to_value(transform) {
+                for (field, snippet) in validation::vrl_snippets(component_name, &value) {
+                    trace!("Compiling and validating VRL in field {field} of transform {key}");
+                    if let Err(error) = validation::compile_snippet(
+                        &snippet,
+                        &enrichment_tables,
+                        &merged_definition,
+                        mezmo_ctx.clone(),
+                    ) {
+                        errors.push((field, error.to_string()));
+                    }
+                }
+            }
+
+            for (field, error) in errors {
+                match &pipeline_id {
+                    mezmo::ContextIdentifier::Value { id: _ } => {
+                        let message = format!(
+                            "Invalid VRL in the `{field}` field of the {component_name} component"
+                        );
+                        let captured_data = vrl::value::Value::from(vrl::btreemap! {
+                            "field" => field.clone(),
+                            "error" => error.clone(),
+                        });
+                        mezmo::user_log_error!(mezmo_ctx, message, captured_data: captured_data);
+                        failures.push(format!(
+                            "Error validating VRL in transform {key} field {field}: {error}"
+                        ));
+                    }
+                    mezmo::ContextIdentifier::Shared => {
+                        // This shouldn't happen...
+                        failures.push(format!(
+                            "Invalid VRL found in field {field} of shared component {key}"
+                        ));
                     }
                 }
             }
@@ -606,8 +1000,17 @@ impl ProviderConfig for MezmoPartitionConfig {
         let mut mezmo_config_builder = MezmoConfigBuilder::new(self);
         let config_builder = mezmo_config_builder.build_all().await?;
 
-        // Poll for changes to remote configuration.
-        signal_handler.add(poll_config(poll_interval_secs, mezmo_config_builder));
+        if self.delivery_mode == ConfigDeliveryMode::Poll {
+            // Poll for changes to remote configuration.
+            signal_handler.add(poll_config(poll_interval_secs, mezmo_config_builder));
+        } else {
+            // Receive changes to remote configuration as they're published.
+            signal_handler.add(push_config(
+                poll_interval_secs,
+                self.resubscribe_interval_secs,
+                mezmo_config_builder,
+            ));
+        }
 
         Ok(config_builder)
     }
@@ -619,6 +1022,7 @@ mod tests {
     use crate::topology;
 
     use super::*;
+    use futures::stream::{self, BoxStream};
     use mockall::mock;
     use serde_json::json;
     use wiremock::{
@@ -645,6 +1049,13 @@ mod tests {
                 &self,
                 revisions: Vec<(PipelineId, RevisionId, TomlVersion)>,
             ) -> Result<(), String>;
+            async fn subscribe_revisions(
+                &self,
+            ) -> Result<BoxStream<'static, Result<(), String>>, String>;
+            async fn report_rollbacks(
+                &self,
+                rollbacks: Vec<(PipelineId, RevisionId, TomlVersion, String)>,
+            ) -> Result<(), String>;
         }
     }
 
@@ -734,6 +1145,9 @@ mod tests {
             pipelines: None,
             common_config: None,
             validate_vrl: true, // Validated, and should succeed
+            offline_cache: None,
+            served_from_cache: Vec::new(),
+            rollout: None,
         };
         let config_builder = b.build_all().await.expect("to build successfully");
         assert!(
@@ -780,6 +1194,9 @@ mod tests {
             pipelines: None,
             common_config: None,
             validate_vrl: false, // Validated, and should succeed
+            offline_cache: None,
+            served_from_cache: Vec::new(),
+            rollout: None,
         };
         let config_builder = b.build_all().await.expect("to build successfully");
         assert!(
@@ -823,6 +1240,9 @@ mod tests {
             pipelines: None,
             common_config: None,
             validate_vrl: false, // Expect an error
+            offline_cache: None,
+            served_from_cache: Vec::new(),
+            rollout: None,
         };
         let config_builder = b.build_all().await.expect("to build successfully");
         assert!(
@@ -877,6 +1297,9 @@ mod tests {
             pipelines: None,
             common_config: None,
             validate_vrl: true, // Expect no error to happen
+            offline_cache: None,
+            served_from_cache: Vec::new(),
+            rollout: None,
         };
         let config_builder = b.build_all().await.expect("to build successfully");
         assert!(
@@ -887,6 +1310,62 @@ mod tests {
         assert!(result.is_ok(), "expected the invalid VRL to be excluded");
     }
 
+    #[tokio::test]
+    async fn validate_vrl_transforms_should_report_component_and_field() {
+        let config = r#"
+        [sources.in]
+        type="stdin"
+
+        [transforms."v1:filter:transform:component1:pipeline1:account1"]
+        inputs=["in"]
+        type="filter"
+        condition='.level == "error"'
+
+        [transforms."v1:route:transform:component2:pipeline1:account1"]
+        inputs=["in"]
+        type="route"
+        route.valid='.level == "info"'
+        route.broken='invalid(.level)'
+
+        [transforms."v1:mezmo_aggregate_v2:transform:component3:pipeline1:account1"]
+        inputs=["in"]
+        type="mezmo_aggregate_v2"
+        event_id_fields=[".id"]
+        source='a = invalid("abc")'
+
+        [transforms."v1:mezmo_reduce:transform:component4:pipeline1:account1"]
+        inputs=["in"]
+        type="mezmo_reduce"
+        ends_when='.done == true'
+
+        [transforms.analysis_filter]
+        inputs=["in"]
+        type="filter"
+        condition='invalid(.level)'
+        "#;
+        let config_builder =
+            config::load::<_, ConfigBuilder>(config.as_bytes(), config::format::Format::Toml)
+                .expect("to load the config");
+
+        let mut failures = validate_vrl_transforms(&config_builder)
+            .await
+            .expect_err("invalid VRL to be reported");
+        failures.sort();
+        assert_eq!(failures.len(), 2, "{failures:?}");
+        assert!(
+            failures[0].starts_with(
+                "Error validating VRL in transform v1:mezmo_aggregate_v2:transform:component3:pipeline1:account1 field source:"
+            ),
+            "{failures:?}"
+        );
+        assert!(
+            failures[1].starts_with(
+                "Error validating VRL in transform v1:route:transform:component2:pipeline1:account1 field route.broken:"
+            ),
+            "{failures:?}"
+        );
+    }
+
     #[tokio::test]
     async fn build_incrementally_should_fail_when_getting_partition_info_fails_test() {
         let mut service = MockConfigService::new();
@@ -980,6 +1459,7 @@ mod tests {
             request: RequestConfig::default(),
             poll_interval_secs: 0,
             validate_vrl: false,
+            ..Default::default()
         };
 
         let service = DefaultConfigService::new(&partition_config);
@@ -1088,6 +1568,7 @@ mod tests {
             request: RequestConfig::default(),
             poll_interval_secs: 0,
             validate_vrl: false,
+            ..Default::default()
         };
 
         let service = DefaultConfigService::new(&partition_config);
@@ -1135,6 +1616,9 @@ mod tests {
             pipelines: None,
             common_config: None,
             validate_vrl: true, // Validated, and should succeed
+            offline_cache: None,
+            served_from_cache: Vec::new(),
+            rollout: None,
         };
         let (config_builder, loaded) = b
             .build_incrementally()
@@ -1182,6 +1666,9 @@ mod tests {
             pipelines: None,
             common_config: None,
             validate_vrl: true, // Expect no error to happen
+            offline_cache: None,
+            served_from_cache: Vec::new(),
+            rollout: None,
         };
         let (config_builder, loaded) = b
             .build_incrementally()
@@ -1236,6 +1723,9 @@ mod tests {
             pipelines: Some(vec![S!("pipeline1")]),
             common_config: Some(S!("data_dir = \"/data/vector\"")),
             validate_vrl: true,
+            offline_cache: None,
+            served_from_cache: Vec::new(),
+            rollout: None,
         };
         let (config_builder, loaded) = b
             .build_incrementally()
@@ -1253,6 +1743,389 @@ mod tests {
         ); // Still not loaded
     }
 
+    fn subscription_test_config(
+        mock_server: &MockServer,
+        mode: ConfigDeliveryMode,
+    ) -> MezmoPartitionConfig {
+        MezmoPartitionConfig {
+            partition_id: S!("part1"),
+            subscribe_url: format!(
+                "{}/internal/partitions/{{partition_id}}/revisions/subscribe",
+                mock_server.uri()
+            ),
+            delivery_mode: mode,
+            subscription_timeout_secs: 5,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn subscribe_revisions_with_long_poll_test() {
+        let subscribe_path = "/internal/partitions/part1/revisions/subscribe";
+        let mock_server = MockServer::start().await;
+
+        Mock::given(matchers::method("GET"))
+            .and(path(subscribe_path))
+            .and(matchers::query_param("mode", "long_poll"))
+            .and(matchers::query_param("timeout_secs", "5"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw(r#"{"changed": true}"#, "application/json"),
+            )
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&mock_server)
+            .await;
+
+        // The timeout elapsed without changes
+        Mock::given(matchers::method("GET"))
+            .and(path(subscribe_path))
+            .respond_with(ResponseTemplate::new(200))
+            .up_to_n_times(1)
+            .with_priority(2)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(matchers::method("GET"))
+            .and(path(subscribe_path))
+            .respond_with(ResponseTemplate::new(503))
+            .with_priority(3)
+            .mount(&mock_server)
+            .await;
+
+        let service = DefaultConfigService::new(&subscription_test_config(
+            &mock_server,
+            ConfigDeliveryMode::LongPoll,
+        ));
+        let mut notifications = service.subscribe_revisions().await.unwrap();
+
+        assert_eq!(
+            notifications.next().await,
+            Some(Ok(())),
+            "Revisions changed"
+        );
+        let dropped = notifications.next().await.expect("an error item");
+        assert!(dropped.unwrap_err().contains("503"), "Subscription dropped");
+        assert_eq!(notifications.next().await, None, "Stream ended");
+        assert_eq!(mock_server.received_requests().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn subscribe_revisions_with_sse_test() {
+        let subscribe_path = "/internal/partitions/part1/revisions/subscribe";
+        let mock_server = MockServer::start().await;
+
+        Mock::given(matchers::method("GET"))
+            .and(path(subscribe_path))
+            .and(matchers::query_param("mode", "sse"))
+            .and(matchers::header("Accept", "text/event-stream"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                ": heartbeat\n\nevent: revisions\ndata: {\"pipeline_id\":\"pipeline1\"}\n\ndata: {}\r\n\r\n",
+                "text/event-stream",
+            ))
+            .mount(&mock_server)
+            .await;
+
+        let service = DefaultConfigService::new(&subscription_test_config(
+            &mock_server,
+            ConfigDeliveryMode::Sse,
+        ));
+        let notifications: Vec<_> = service.subscribe_revisions().await.unwrap().collect().await;
+
+        assert_eq!(
+            notifications,
+            vec![Ok(()), Ok(()), Err(S!("Subscription stream closed"))],
+            "One notification per event, heartbeats are ignored"
+        );
+
+        // Subscriptions are refused when polling
+        let service = DefaultConfigService::new(&subscription_test_config(
+            &mock_server,
+            ConfigDeliveryMode::Poll,
+        ));
+        assert!(service.subscribe_revisions().await.is_err());
+    }
+
+    fn push_test_service(
+        subscription: fn() -> Result<BoxStream<'static, Result<(), String>>, String>,
+    ) -> MockConfigService {
+        let mut service = MockConfigService::new();
+        service.expect_subscribe_revisions().returning(subscription);
+        service
+            .expect_get_pipelines_by_partition()
+            .returning(|| Ok((vec![S!("pipeline1")], S!("data_dir = \"/data/vector\""))));
+
+        // The revision is only published after the first build
+        let calls = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        service.expect_get_new_revisions().returning(move |_| {
+            if calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0 {
+                return Ok(HashMap::new());
+            }
+            Ok(HashMap::from([(
+                S!("pipeline1"),
+                Revision {
+                    id: S!("rev1"),
+                    toml_version: 1,
+                    config: S!("[sources.in1]\ntype = \"test_basic\"\n\n[sinks.out1]\ninputs = [\"in1\"]\ntype = \"test_basic\""),
+                },
+            )]))
+        });
+        service
+            .expect_set_loaded_revisions()
+            .withf(|loaded| *loaded == [(S!("pipeline1"), S!("rev1"), 1)])
+            .returning(|_| Ok(()));
+        service
+    }
+
+    fn push_test_builder(service: MockConfigService) -> MezmoConfigBuilder {
+        // Already built once, so only revision changes trigger a reload
+        MezmoConfigBuilder {
+            pipelines: Some(vec![S!("pipeline1")]),
+            common_config: Some(S!("data_dir = \"/data/vector\"")),
+            ..new_test_builder(Box::new(service))
+        }
+    }
+
+    #[tokio::test]
+    async fn push_config_should_reload_on_notification() {
+        let service =
+            push_test_service(|| Ok(stream::iter([Ok(())]).chain(stream::pending()).boxed()));
+        let b = push_test_builder(service);
+
+        let signals = push_config(3600, 3600, b);
+        tokio::pin!(signals);
+        let signal = time::timeout(time::Duration::from_secs(5), signals.next())
+            .await
+            .expect("a signal before the timeout");
+        assert!(matches!(
+            signal,
+            Some(signal::SignalTo::ReloadFromConfigBuilder(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn push_config_should_fall_back_to_polling() {
+        let service = push_test_service(|| Err(S!("subscription unavailable")));
+        let b = push_test_builder(service);
+
+        // The first fallback poll finds no changes, the second one loads the published revision
+        let signals = push_config(0, 3600, b);
+        tokio::pin!(signals);
+        let signal = time::timeout(time::Duration::from_secs(5), signals.next())
+            .await
+            .expect("a signal before the timeout");
+        assert!(matches!(
+            signal,
+            Some(signal::SignalTo::ReloadFromConfigBuilder(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn build_all_should_use_offline_cache_when_service_is_unreachable() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let cache_path = cache_dir.path().join("partition.json");
+        let common_config = S!("data_dir = \"/data/vector\"");
+        let revision = Revision {
+            id: S!("rev1"),
+            toml_version: 1,
+            config: S!(
+                "[sources.in1]\ntype = \"test_basic\"\n\n[sinks.out1]\ninputs = [\"in1\"]\ntype = \"test_basic\""
+            ),
+        };
+
+        // A successful build stores the last-known-good config
+        let mut service = MockConfigService::new();
+        let config = common_config.clone();
+        service
+            .expect_get_pipelines_by_partition()
+            .returning(move || Ok((vec![S!("pipeline1")], config.clone())));
+        let r = revision.clone();
+        service
+            .expect_get_new_revisions()
+            .returning(move |_| Ok(HashMap::from([(S!("pipeline1"), r.clone())])));
+        let mut b = MezmoConfigBuilder {
+            offline_cache: Some(ConfigCache::new(&cache_path)),
+            ..new_test_builder(Box::new(service))
+        };
+        b.build_all().await.expect("to build successfully");
+        assert!(cache_path.exists(), "config cached");
+
+        // The service is unreachable on startup, then reachable again
+        let mut service = MockConfigService::new();
+        let calls = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        service
+            .expect_get_pipelines_by_partition()
+            .returning(move || {
+                if calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0 {
+                    return Err(S!("connection refused"));
+                }
+                Ok((vec![S!("pipeline1")], common_config.clone()))
+            });
+        service
+            .expect_get_new_revisions()
+            .withf(|current| *current == [(S!("pipeline1"), Some(S!("rev1")), Some(1))])
+            .returning(|_| Ok(HashMap::new()));
+        let mut b = MezmoConfigBuilder {
+            offline_cache: Some(ConfigCache::new(&cache_path)),
+            ..new_test_builder(Box::new(service))
+        };
+
+        let config_builder = b.build_all().await.expect("to build from the cache");
+        assert!(
+            b.cache.contains_key("pipeline1"),
+            "pipeline served from cache"
+        );
+        assert_eq!(
+            b.served_from_cache,
+            vec![(S!("pipeline1"), S!("rev1"), 1)],
+            "revision served from cache"
+        );
+        validate_config(config_builder)
+            .await
+            .expect("cached config to be valid");
+
+        // Reconciling with the service keeps the cached revision as it's still current
+        let (config_builder, loaded) = b.build_incrementally().await.unwrap();
+        assert!(config_builder.is_none(), "No new config");
+        assert!(loaded.is_empty(), "No new revision");
+        assert!(b.served_from_cache.is_empty(), "Reconciled");
+    }
+
+    #[tokio::test]
+    async fn build_incrementally_should_not_reconcile_when_build_fails() {
+        let common_config = S!("data_dir = \"/data/vector\"");
+        let mut service = MockConfigService::new();
+        let config = common_config.clone();
+        service
+            .expect_get_pipelines_by_partition()
+            .returning(move || Ok((vec![S!("pipeline1")], config.clone())));
+        service.expect_get_new_revisions().returning(|_| {
+            Ok(HashMap::from([(
+                S!("pipeline1"),
+                Revision {
+                    id: S!("rev2"),
+                    toml_version: 1,
+                    config: S!("\nTHIS_IS_INVALID"),
+                },
+            )]))
+        });
+        let mut b = MezmoConfigBuilder {
+            pipelines: Some(vec![S!("pipeline1")]),
+            common_config: Some(common_config),
+            served_from_cache: vec![(S!("pipeline1"), S!("rev1"), 1)],
+            ..new_test_builder(Box::new(service))
+        };
+
+        let (config_builder, loaded) = b.build_incrementally().await.unwrap();
+        assert!(config_builder.is_none(), "No new config");
+        assert!(loaded.is_empty(), "No new revision");
+        assert_eq!(
+            b.served_from_cache,
+            vec![(S!("pipeline1"), S!("rev1"), 1)],
+            "Still served from cache"
+        );
+    }
+
+    #[tokio::test]
+    async fn build_all_should_fail_without_offline_cache() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let mut service = MockConfigService::new();
+        service
+            .expect_get_pipelines_by_partition()
+            .returning(|| Err(S!("connection refused")));
+        let mut b = MezmoConfigBuilder {
+            offline_cache: Some(ConfigCache::new(cache_dir.path().join("partition.json"))),
+            ..new_test_builder(Box::new(service))
+        };
+
+        let errors = b.build_all().await.unwrap_err();
+        assert_eq!(errors, vec![S!("connection refused")]);
+    }
+
+    static ROLLOUT_TEST_ERRORS: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+    fn rollout_test_health() -> HashMap<PipelineId, rollout::PipelineHealth> {
+        HashMap::from([(
+            S!("pipeline1"),
+            rollout::PipelineHealth {
+                errors: ROLLOUT_TEST_ERRORS.load(std::sync::atomic::Ordering::SeqCst) as f64,
+                received_events: 0.0,
+            },
+        )])
+    }
+
+    #[tokio::test]
+    async fn check_rollouts_should_restore_previous_revision() {
+        let config = S!(
+            "[sources.in1]\ntype = \"test_basic\"\n\n[sinks.out1]\ninputs = [\"in1\"]\ntype = \"test_basic\""
+        );
+        let revision = |id: &str| Revision {
+            id: id.to_string(),
+            toml_version: 1,
+            config: config.clone(),
+        };
+
+        let mut service = MockConfigService::new();
+        service
+            .expect_get_pipelines_by_partition()
+            .returning(|| Ok((vec![S!("pipeline1")], S!("data_dir = \"/data/vector\""))));
+        let rev2 = revision("rev2");
+        service
+            .expect_get_new_revisions()
+            .returning(move |_| Ok(HashMap::from([(S!("pipeline1"), rev2.clone())])));
+        service
+            .expect_report_rollbacks()
+            .withf(|rollbacks| {
+                rollbacks.len() == 1
+                    && rollbacks[0].0 == "pipeline1"
+                    && rollbacks[0].1 == "rev2"
+                    && rollbacks[0].3.contains("component errors")
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+        service
+            .expect_set_loaded_revisions()
+            .withf(|loaded| *loaded == [(S!("pipeline1"), S!("rev1"), 1)])
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let mut b = MezmoConfigBuilder {
+            cache: HashMap::from([(S!("pipeline1"), revision("rev1"))]),
+            pipelines: Some(vec![S!("pipeline1")]),
+            common_config: Some(S!("data_dir = \"/data/vector\"")),
+            rollout: Some(RolloutGuard::with_health_source(
+                RolloutConfig {
+                    probation_secs: 3600,
+                    max_errors: 5,
+                    min_throughput_ratio: 0.0,
+                },
+                rollout_test_health,
+            )),
+            ..new_test_builder(Box::new(service))
+        };
+
+        let (_, loaded) = b.build_incrementally().await.unwrap();
+        assert_eq!(loaded, vec![(S!("pipeline1"), S!("rev2"), 1)]);
+        assert!(b.in_probation(), "New revision in probation");
+        assert!(b.check_rollouts().await.is_none(), "Healthy so far");
+
+        ROLLOUT_TEST_ERRORS.store(10, std::sync::atomic::Ordering::SeqCst);
+        let config_builder = b.check_rollouts().await.expect("a rollback");
+        validate_config(config_builder)
+            .await
+            .expect("restored config to be valid");
+        assert_eq!(
+            b.cache["pipeline1"].id, "rev1",
+            "Previous revision restored"
+        );
+        assert!(!b.in_probation());
+
+        // The rolled back revision is not applied again
+        let (config_builder, loaded) = b.build_incrementally().await.unwrap();
+        assert!(config_builder.is_none());
+        assert!(loaded.is_empty());
+        assert_eq!(b.cache["pipeline1"].id, "rev1");
+    }
+
     fn new_test_builder(service: Box<dyn ConfigService>) -> MezmoConfigBuilder {
         MezmoConfigBuilder {
             service,
@@ -1260,6 +2133,9 @@ mod tests {
             pipelines: None,
             common_config: None,
             validate_vrl: false,
+            offline_cache: None,
+            served_from_cache: Vec::new(),
+            rollout: None,
         }
     }
 
@@ -0,0 +1,333 @@
+use std::collections::HashMap;
+
+use tokio::time::{Duration, Instant};
+use vector_lib::configurable::configurable_component;
+use vector_lib::event::MetricValue;
+
+use mezmo::{ContextIdentifier, MezmoContext};
+
+use super::{PipelineId, Revision, RevisionId, TomlVersion};
+
+/// Health gating of new pipeline revisions.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields, default)]
+pub struct RolloutConfig {
+    /// How long the components of a new revision are watched after it's loaded, in seconds.
+    pub probation_secs: u64,
+
+    /// The maximum number of component errors of the pipeline during the probation period.
+    pub max_errors: u64,
+
+    /// The minimum ratio between the events received by the pipeline during the probation period
+    /// and before the new revision was loaded. Set to 0 to disable the throughput check.
+    pub min_throughput_ratio: f64,
+}
+
+impl Default for RolloutConfig {
+    fn default() -> Self {
+        Self {
+            probation_secs: 120,
+            max_errors: 100,
+            min_throughput_ratio: 0.1,
+        }
+    }
+}
+
+/// Cumulative counters of the components of a pipeline.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct PipelineHealth {
+    pub(crate) errors: f64,
+    pub(crate) received_events: f64,
+}
+
+/// Sums the error and received event counters of the components of each pipeline, from the
+/// internal metrics.
+pub(crate) fn capture_pipeline_health() -> HashMap<PipelineId, PipelineHealth> {
+    let mut health: HashMap<PipelineId, PipelineHealth> = HashMap::new();
+    let Ok(controller) = crate::metrics::Controller::get() else {
+        return health;
+    };
+
+    for metric in controller.capture_metrics() {
+        let MetricValue::Counter { value } = metric.value() else {
+            continue;
+        };
+        let is_errors = match metric.name() {
+            "component_errors_total" => true,
+            "component_received_events_total" => false,
+            _ => continue,
+        };
+        let Some(pipeline_id) = metric
+            .tag_value("component_id")
+            .and_then(|id| MezmoContext::try_from(id).ok())
+            .and_then(|ctx| match ctx.pipeline_id {
+                Some(ContextIdentifier::Value { id }) => Some(id),
+                _ => None,
+            })
+        else {
+            continue;
+        };
+
+        let pipeline = health.entry(pipeline_id).or_default();
+        if is_errors {
+            pipeline.errors += value;
+        } else {
+            pipeline.received_events += value;
+        }
+    }
+
+    health
+}
+
+/// A revision reverted after breaching the health thresholds.
+#[derive(Debug)]
+pub(crate) struct Rollback {
+    pub(crate) pipeline_id: PipelineId,
+    /// The revision to restore, none when the pipeline didn't exist before.
+    pub(crate) previous: Option<Revision>,
+    pub(crate) revision_id: RevisionId,
+    pub(crate) toml_version: TomlVersion,
+    pub(crate) reason: String,
+}
+
+#[derive(Debug)]
+struct Probation {
+    previous: Option<Revision>,
+    revision_id: RevisionId,
+    toml_version: TomlVersion,
+    started: Instant,
+    start_health: PipelineHealth,
+    /// Events received per second before the new revision was loaded, when known.
+    baseline_rate: Option<f64>,
+}
+
+/// Watches newly loaded revisions for a probation period, reporting the ones to roll back.
+pub(crate) struct RolloutGuard {
+    config: RolloutConfig,
+    health_source: fn() -> HashMap<PipelineId, PipelineHealth>,
+    last_health: Option<(Instant, HashMap<PipelineId, PipelineHealth>)>,
+    probations: HashMap<PipelineId, Probation>,
+    rejected: HashMap<PipelineId, (RevisionId, TomlVersion)>,
+}
+
+impl RolloutGuard {
+    pub(crate) fn new(config: RolloutConfig) -> Self {
+        Self::with_health_source(config, capture_pipeline_health)
+    }
+
+    pub(crate) fn with_health_source(
+        config: RolloutConfig,
+        health_source: fn() -> HashMap<PipelineId, PipelineHealth>,
+    ) -> Self {
+        Self {
+            config,
+            health_source,
+            last_health: None,
+            probations: HashMap::new(),
+            rejected: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn in_probation(&self) -> bool {
+        !self.probations.is_empty()
+    }
+
+    /// Whether the revision was rolled back before, so it should not be applied again.
+    pub(crate) fn is_rejected(&self, pipeline_id: &str, revision: &Revision) -> bool {
+        self.rejected
+            .get(pipeline_id)
+            .is_some_and(|(id, toml_version)| {
+                *id == revision.id && *toml_version == revision.toml_version
+            })
+    }
+
+    /// Starts the probation of a revision that was just loaded.
+    pub(crate) fn start(
+        &mut self,
+        pipeline_id: &str,
+        previous: Option<Revision>,
+        revision: &Revision,
+        now: Instant,
+    ) {
+        let health = (self.health_source)();
+        let start_health = health.get(pipeline_id).copied().unwrap_or_default();
+        let baseline_rate = self.last_health.as_ref().and_then(|(at, last)| {
+            let elapsed = now.duration_since(*at).as_secs_f64();
+            let last = last.get(pipeline_id)?;
+            (elapsed > 0.0).then(|| (start_health.received_events - last.received_events) / elapsed)
+        });
+
+        debug!(
+            message = "Starting revision probation",
+            pipeline_id,
+            revision_id = %revision.id,
+            ?baseline_rate
+        );
+        self.probations.insert(
+            pipeline_id.to_string(),
+            Probation {
+                previous,
+                revision_id: revision.id.clone(),
+                toml_version: revision.toml_version,
+                started: now,
+                start_health,
+                baseline_rate,
+            },
+        );
+    }
+
+    /// Stops watching a pipeline, for example when it was removed from the partition.
+    pub(crate) fn cancel(&mut self, pipeline_id: &str) {
+        self.probations.remove(pipeline_id);
+    }
+
+    /// Checks the health of the pipelines in probation, returning the revisions to roll back.
+    /// Revisions that completed their probation without breaching the thresholds are kept.
+    pub(crate) fn check(&mut self, now: Instant) -> Vec<Rollback> {
+        let health = (self.health_source)();
+        let probation = Duration::from_secs(self.config.probation_secs);
+        let mut rollbacks = Vec::new();
+
+        self.probations.retain(|pipeline_id, p| {
+            let current = health.get(pipeline_id).copied().unwrap_or_default();
+            let errors = current.errors - p.start_health.errors;
+            let elapsed = now.duration_since(p.started);
+
+            let reason = if errors > self.config.max_errors as f64 {
+                Some(format!(
+                    "{errors} component errors during probation (max {})",
+                    self.config.max_errors
+                ))
+            } else if elapsed >= probation {
+                let received = current.received_events - p.start_health.received_events;
+                let rate = received / elapsed.as_secs_f64();
+                match p.baseline_rate {
+                    Some(baseline)
+                        if baseline > 0.0 && rate / baseline < self.config.min_throughput_ratio =>
+                    {
+                        Some(format!(
+                            "throughput dropped from {baseline:.2} to {rate:.2} events/s"
+                        ))
+                    }
+                    _ => {
+                        info!(
+                            message = "Revision completed its probation",
+                            %pipeline_id,
+                            revision_id = %p.revision_id
+                        );
+                        return false;
+                    }
+                }
+            } else {
+                None
+            };
+
+            let Some(reason) = reason else {
+                return true;
+            };
+            rollbacks.push(Rollback {
+                pipeline_id: pipeline_id.clone(),
+                previous: p.previous.take(),
+                revision_id: p.revision_id.clone(),
+                toml_version: p.toml_version,
+                reason,
+            });
+            false
+        });
+
+        for rollback in rollbacks.iter() {
+            self.rejected.insert(
+                rollback.pipeline_id.clone(),
+                (rollback.revision_id.clone(), rollback.toml_version),
+            );
+        }
+        self.last_health = Some((now, health));
+        rollbacks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    // Health snapshots are returned by a plain function, tests share them through a static
+    static HEALTH: Mutex<Vec<(&str, PipelineHealth)>> = Mutex::new(Vec::new());
+
+    fn test_health() -> HashMap<PipelineId, PipelineHealth> {
+        HEALTH
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, h)| (id.to_string(), *h))
+            .collect()
+    }
+
+    fn set_health(pipeline_id: &'static str, errors: f64, received_events: f64) {
+        let mut health = HEALTH.lock().unwrap();
+        health.retain(|(id, _)| *id != pipeline_id);
+        health.push((
+            pipeline_id,
+            PipelineHealth {
+                errors,
+                received_events,
+            },
+        ));
+    }
+
+    fn revision(id: &str) -> Revision {
+        Revision {
+            id: id.to_string(),
+            toml_version: 1,
+            config: String::new(),
+        }
+    }
+
+    #[test]
+    fn rollout_guard_test() {
+        let mut guard = RolloutGuard::with_health_source(
+            RolloutConfig {
+                probation_secs: 60,
+                max_errors: 10,
+                min_throughput_ratio: 0.5,
+            },
+            test_health,
+        );
+        let now = Instant::now();
+
+        // Baseline of 10 events/s for every pipeline
+        set_health("errors", 0.0, 0.0);
+        set_health("throughput", 0.0, 0.0);
+        set_health("healthy", 0.0, 0.0);
+        assert!(guard.check(now).is_empty());
+        set_health("errors", 0.0, 100.0);
+        set_health("throughput", 0.0, 100.0);
+        set_health("healthy", 0.0, 100.0);
+
+        let now = now + Duration::from_secs(10);
+        guard.start("errors", Some(revision("a")), &revision("b"), now);
+        guard.start("throughput", None, &revision("c"), now);
+        guard.start("healthy", Some(revision("d")), &revision("e"), now);
+        assert!(guard.in_probation());
+
+        // Errors are detected during the probation
+        set_health("errors", 11.0, 200.0);
+        set_health("throughput", 0.0, 110.0);
+        set_health("healthy", 2.0, 700.0);
+        let rollbacks = guard.check(now + Duration::from_secs(30));
+        assert_eq!(rollbacks.len(), 1);
+        assert_eq!(rollbacks[0].pipeline_id, "errors");
+        assert_eq!(rollbacks[0].previous.as_ref().unwrap().id, "a");
+        assert!(guard.is_rejected("errors", &revision("b")));
+        assert!(!guard.is_rejected("errors", &revision("a")));
+
+        // Throughput is compared at the end of the probation
+        let rollbacks = guard.check(now + Duration::from_secs(60));
+        assert_eq!(rollbacks.len(), 1);
+        assert_eq!(rollbacks[0].pipeline_id, "throughput");
+        assert!(rollbacks[0].previous.is_none(), "new pipeline");
+        assert!(!guard.in_probation(), "healthy revision kept");
+    }
+}
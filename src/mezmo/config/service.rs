@@ -1,5 +1,11 @@
-use std::{collections::HashMap, env, time::Instant};
+use std::{
+    collections::HashMap,
+    env,
+    time::{Duration, Instant},
+};
 
+use async_stream::stream;
+use futures::stream::BoxStream;
 use http_body::Body as _;
 use hyper::Body;
 use indexmap::IndexMap;
@@ -14,7 +20,12 @@ use crate::{
     built_info, http::HttpClient, internal_events::mezmo_config::MezmoConfigServiceResponse,
 };
 
-use super::{MezmoPartitionConfig, PipelineId, Revision, RevisionId, TomlVersion};
+use super::{
+    ConfigDeliveryMode, MezmoPartitionConfig, PipelineId, Revision, RevisionId, TomlVersion,
+};
+
+/// Extra time given to the server to answer a long poll request after its timeout elapsed.
+const LONG_POLL_GRACE: Duration = Duration::from_secs(5);
 
 #[async_trait::async_trait]
 pub(crate) trait ConfigService: Send + Sync {
@@ -33,6 +44,17 @@ pub(crate) trait ConfigService: Send + Sync {
         &self,
         revisions: Vec<(PipelineId, RevisionId, TomlVersion)>,
     ) -> Result<(), String>;
+
+    /// Subscribes to revision changes of the partition. Each item of the returned stream signals
+    /// that new revisions were published; an error item means the subscription dropped.
+    async fn subscribe_revisions(&self) -> Result<BoxStream<'static, Result<(), String>>, String>;
+
+    /// Reports the revisions that were rolled back after failing their probation, along with
+    /// the reason.
+    async fn report_rollbacks(
+        &self,
+        rollbacks: Vec<(PipelineId, RevisionId, TomlVersion, String)>,
+    ) -> Result<(), String>;
 }
 
 pub(crate) struct DefaultConfigService {
@@ -40,6 +62,10 @@ pub(crate) struct DefaultConfigService {
     latest_revisions_url: Url,
     loaded_revisions_url: Url,
     pipelines_by_partition_url: Url,
+    rolled_back_revisions_url: Url,
+    subscribe_url: Url,
+    delivery_mode: ConfigDeliveryMode,
+    subscription_timeout: Duration,
     headers: IndexMap<String, String>,
 }
 
@@ -70,12 +96,28 @@ impl DefaultConfigService {
         let mut loaded_revisions_url = Url::parse(&partition_config.loaded_revisions_url)
             .expect("a valid pipeline by partition url");
         loaded_revisions_url.set_query(Some(query_string.as_str()));
+        let mut rolled_back_revisions_url = Url::parse(&partition_config.rolled_back_revisions_url)
+            .expect("a valid rolled back revisions url");
+        rolled_back_revisions_url.set_query(Some(query_string.as_str()));
+        let mut subscribe_url = Url::parse(
+            &partition_config
+                .subscribe_url
+                .replace("{partition_id}", &partition_config.partition_id),
+        )
+        .expect("a valid subscribe url");
+        subscribe_url.set_query(Some(query_string.as_str()));
 
         Self {
             http_client,
             latest_revisions_url,
             loaded_revisions_url,
             pipelines_by_partition_url,
+            rolled_back_revisions_url,
+            subscribe_url,
+            delivery_mode: partition_config.delivery_mode,
+            subscription_timeout: Duration::from_secs(
+                partition_config.subscription_timeout_secs.max(1),
+            ),
             headers: partition_config.request.clone().headers,
         }
     }
@@ -113,6 +155,146 @@ struct LoadedRevisionRequestItem {
     toml_version: TomlVersion,
 }
 
+#[derive(Serialize, Deserialize)]
+struct RolledBackRevisionsRequest {
+    revisions: Vec<RolledBackRevisionRequestItem>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RolledBackRevisionRequestItem {
+    pipeline_id: PipelineId,
+    revision_id: RevisionId,
+    toml_version: TomlVersion,
+    reason: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LongPollResponse {
+    #[serde(default)]
+    changed: bool,
+}
+
+impl DefaultConfigService {
+    /// Repeatedly holds a request against the subscribe endpoint, which the server answers
+    /// with `{"changed": true}` once revisions are published, or with an empty body or
+    /// `{"changed": false}` when the timeout elapses without changes.
+    fn long_poll_revisions(&self) -> BoxStream<'static, Result<(), String>> {
+        let http_client = self.http_client.clone();
+        let headers = self.headers.clone();
+        let timeout = self.subscription_timeout;
+        let mut url = self.subscribe_url.clone();
+        url.query_pairs_mut()
+            .append_pair("mode", "long_poll")
+            .append_pair("timeout_secs", &timeout.as_secs().to_string());
+
+        Box::pin(stream! {
+            loop {
+                let request = http_request(&http_client, &url, &headers, None);
+                match tokio::time::timeout(timeout + LONG_POLL_GRACE, request).await {
+                    Ok(Ok(body)) if body.is_empty() => {},
+                    Ok(Ok(body)) => match serde_json::from_slice::<LongPollResponse>(&body) {
+                        Ok(response) => {
+                            if response.changed {
+                                yield Ok(());
+                            }
+                        },
+                        Err(e) => {
+                            yield Err(format!("Invalid long poll response: {e}"));
+                            break;
+                        },
+                    },
+                    Ok(Err(e)) => {
+                        yield Err(e);
+                        break;
+                    },
+                    Err(_) => {
+                        yield Err("Long poll request timed out".to_string());
+                        break;
+                    },
+                }
+            }
+        })
+    }
+
+    /// Opens a server-sent events stream against the subscribe endpoint. Every event other
+    /// than comments (used as heartbeats) signals that revisions were published.
+    async fn sse_revisions(&self) -> Result<BoxStream<'static, Result<(), String>>, String> {
+        let mut url = self.subscribe_url.clone();
+        url.query_pairs_mut().append_pair("mode", "sse");
+
+        let mut builder = http::request::Builder::new()
+            .uri(url.as_str())
+            .header("Accept", "text/event-stream");
+        for (header, value) in self.headers.iter() {
+            builder = builder.header(header.as_str(), value.as_str());
+        }
+        let request = builder
+            .body(Body::empty())
+            .map_err(|_| "Couldn't create HTTP request".to_string())?;
+
+        let response = self
+            .http_client
+            .send(request)
+            .await
+            .map_err(|err| format!("HTTP error. Error: {err:?}"))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!(
+                "Subscription request resulted in {} error",
+                status.as_u16()
+            ));
+        }
+
+        let idle_timeout = self.subscription_timeout;
+        let mut body = response.into_body();
+        Ok(Box::pin(stream! {
+            let mut buffer = String::new();
+            loop {
+                match tokio::time::timeout(idle_timeout, body.data()).await {
+                    Ok(Some(Ok(chunk))) => {
+                        buffer.push_str(&String::from_utf8_lossy(&chunk));
+                        for _ in 0..take_sse_events(&mut buffer) {
+                            yield Ok(());
+                        }
+                    },
+                    Ok(Some(Err(e))) => {
+                        yield Err(format!("Subscription stream error: {e}"));
+                        break;
+                    },
+                    Ok(None) => {
+                        yield Err("Subscription stream closed".to_string());
+                        break;
+                    },
+                    Err(_) => {
+                        yield Err("No data received on the subscription stream".to_string());
+                        break;
+                    },
+                }
+            }
+        }))
+    }
+}
+
+/// Removes the complete events from the buffer, returning how many of them are not comments.
+fn take_sse_events(buffer: &mut String) -> usize {
+    let normalized = buffer.replace("\r\n", "\n");
+    let Some(end) = normalized.rfind("\n\n") else {
+        *buffer = normalized;
+        return 0;
+    };
+
+    let count = normalized[..end]
+        .split("\n\n")
+        .filter(|event| {
+            event
+                .lines()
+                .any(|line| !line.is_empty() && !line.starts_with(':'))
+        })
+        .count();
+    *buffer = normalized[end + 2..].to_string();
+    count
+}
+
 #[async_trait::async_trait]
 impl ConfigService for DefaultConfigService {
     async fn get_pipelines_by_partition(&self) -> Result<(Vec<PipelineId>, String), String> {
@@ -188,6 +370,43 @@ impl ConfigService for DefaultConfigService {
 
         Ok(())
     }
+
+    async fn report_rollbacks(
+        &self,
+        rollbacks: Vec<(PipelineId, RevisionId, TomlVersion, String)>,
+    ) -> Result<(), String> {
+        let revisions: Vec<RolledBackRevisionRequestItem> = rollbacks
+            .into_iter()
+            .map(
+                |(pipeline_id, revision_id, toml_version, reason)| RolledBackRevisionRequestItem {
+                    pipeline_id,
+                    revision_id,
+                    toml_version,
+                    reason,
+                },
+            )
+            .collect();
+        let body = serde_json::to_vec(&RolledBackRevisionsRequest { revisions })
+            .map_err(|e| e.to_string())?;
+
+        http_request(
+            &self.http_client,
+            &self.rolled_back_revisions_url,
+            &self.headers,
+            Some(body.into()),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn subscribe_revisions(&self) -> Result<BoxStream<'static, Result<(), String>>, String> {
+        match self.delivery_mode {
+            ConfigDeliveryMode::Poll => Err("Revision subscriptions are disabled".to_string()),
+            ConfigDeliveryMode::LongPoll => Ok(self.long_poll_revisions()),
+            ConfigDeliveryMode::Sse => self.sse_revisions().await,
+        }
+    }
 }
 
 fn adapt_revisions(mut revisions: HashMap<PipelineId, Revision>) -> HashMap<PipelineId, Revision> {
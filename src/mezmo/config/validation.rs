@@ -0,0 +1,160 @@
+use serde_json::Value as JsonValue;
+use vector_lib::enrichment::TableRegistry;
+use vector_lib::schema::Definition;
+
+use mezmo::MezmoContext;
+
+use crate::{conditions::AnyCondition, transforms::remap::RemapConfig};
+
+/// A VRL snippet found in the config of a transform.
+#[derive(Debug)]
+pub(crate) enum VrlSnippet {
+    /// A VRL program, like the merge `source` of the aggregate transform.
+    Program(String),
+    /// A condition, either a VRL boolean expression or a fully-specified condition.
+    Condition(AnyCondition),
+}
+
+/// Collects the VRL snippets in the config of a transform, serialized as JSON, along with the
+/// path of the field holding each snippet. Remap transforms are validated by building them
+/// instead, as their programs can also be read from files.
+pub(crate) fn vrl_snippets(component_name: &str, config: &JsonValue) -> Vec<(String, VrlSnippet)> {
+    let mut snippets = Vec::new();
+    match component_name {
+        "filter" => push_condition(&mut snippets, "condition".to_string(), &config["condition"]),
+        "route" => {
+            if let Some(routes) = config["route"].as_object() {
+                for (name, condition) in routes {
+                    push_condition(&mut snippets, format!("route.{name}"), condition);
+                }
+            }
+        }
+        "mezmo_aggregate_v2" | "sliding_aggregate" => {
+            if let Some(source) = config["source"].as_str() {
+                snippets.push((
+                    "source".to_string(),
+                    VrlSnippet::Program(source.to_string()),
+                ));
+            }
+            push_condition(
+                &mut snippets,
+                "flush_condition".to_string(),
+                &config["flush_condition"],
+            );
+        }
+        "mezmo_reduce" | "reduce" => {
+            for field in ["starts_when", "ends_when"] {
+                push_condition(&mut snippets, field.to_string(), &config[field]);
+            }
+        }
+        "trace_tail_sample" => {
+            if let Some(conditionals) = config["conditionals"].as_array() {
+                for (i, conditional) in conditionals.iter().enumerate() {
+                    push_condition(
+                        &mut snippets,
+                        format!("conditionals[{i}].condition"),
+                        &conditional["condition"],
+                    );
+                }
+            }
+        }
+        _ => {}
+    }
+    snippets
+}
+
+fn push_condition(snippets: &mut Vec<(String, VrlSnippet)>, field: String, value: &JsonValue) {
+    if value.is_null() {
+        return;
+    }
+    // The config was deserialized from the same value, so this only skips unexpected shapes
+    if let Ok(condition) = serde_json::from_value::<AnyCondition>(value.clone()) {
+        snippets.push((field, VrlSnippet::Condition(condition)));
+    }
+}
+
+/// Compiles a VRL snippet, the same way the transform does when it's built.
+pub(crate) fn compile_snippet(
+    snippet: &VrlSnippet,
+    enrichment_tables: &TableRegistry,
+    merged_definition: &Definition,
+    mezmo_ctx: Option<MezmoContext>,
+) -> crate::Result<()> {
+    match snippet {
+        VrlSnippet::Program(source) => RemapConfig {
+            source: Some(source.clone()),
+            ..Default::default()
+        }
+        .compile_vrl_program(
+            enrichment_tables.clone(),
+            Default::default(),
+            merged_definition.clone(),
+            mezmo_ctx,
+        )
+        .map(|_| ()),
+        VrlSnippet::Condition(condition) => condition
+            .build(enrichment_tables, &Default::default(), mezmo_ctx)
+            .map(|_| ()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn fields(snippets: &[(String, VrlSnippet)]) -> Vec<&str> {
+        snippets.iter().map(|(field, _)| field.as_str()).collect()
+    }
+
+    #[test]
+    fn vrl_snippets_test() {
+        let route = json!({
+            "type": "route",
+            "route": { "errors": ".level == \"error\"", "other": { "type": "is_log" } }
+        });
+        assert_eq!(
+            fields(&vrl_snippets("route", &route)),
+            vec!["route.errors", "route.other"]
+        );
+
+        let aggregate = json!({
+            "type": "mezmo_aggregate_v2",
+            "source": ".message = .accum",
+            "flush_condition": null
+        });
+        let snippets = vrl_snippets("mezmo_aggregate_v2", &aggregate);
+        assert_eq!(fields(&snippets), vec!["source"]);
+        assert!(matches!(&snippets[0].1, VrlSnippet::Program(s) if s == ".message = .accum"));
+
+        let tail_sample = json!({
+            "type": "trace_tail_sample",
+            "conditionals": [
+                { "rate": 1, "condition": "true", "output_name": "a" },
+                { "rate": 2, "condition": { "type": "vrl", "source": "false" }, "output_name": "b" }
+            ]
+        });
+        assert_eq!(
+            fields(&vrl_snippets("trace_tail_sample", &tail_sample)),
+            vec!["conditionals[0].condition", "conditionals[1].condition"]
+        );
+
+        assert!(vrl_snippets("stdout", &json!({ "type": "stdout" })).is_empty());
+    }
+
+    #[test]
+    fn compile_snippet_test() {
+        let tables = TableRegistry::default();
+        let definition = Definition::default_legacy_namespace();
+
+        let valid = VrlSnippet::Condition(AnyCondition::String(".level == \"error\"".into()));
+        assert!(compile_snippet(&valid, &tables, &definition, None).is_ok());
+
+        let invalid = VrlSnippet::Condition(AnyCondition::String("invalid(.level)".into()));
+        assert!(compile_snippet(&invalid, &tables, &definition, None).is_err());
+
+        let invalid = VrlSnippet::Program("a = invalid(\"abc\")".into());
+        assert!(compile_snippet(&invalid, &tables, &definition, None).is_err());
+    }
+}
@@ -4,31 +4,100 @@ use crate::transforms::{SyncTransform, TaskTransform, TransformOutputsBuf};
 use bytes::Buf;
 use futures::Stream;
 use futures_util::StreamExt;
+use metrics::{Histogram, histogram};
 use mezmo::MezmoContext;
+use std::collections::HashSet;
 use std::env;
 use std::pin::Pin;
 use std::sync::OnceLock;
-use std::time::SystemTime;
+use std::time::{Instant, SystemTime};
+use vector_lib::buffers::BufferInstrumentation;
 use vector_lib::event::EventArray;
 use vrl::core::Value;
 
+mod spans;
+
+pub use spans::{EventTraceSubscription, MezmoSinkTraceCollector};
+
 const MEZMO_EVENT_TRACE_ENABLED: &str = "MEZMO_EVENT_TRACE_ENABLED";
+const MEZMO_EVENT_TRACE_PIPELINES: &str = "MEZMO_EVENT_TRACE_PIPELINES";
+const MEZMO_EVENT_TRACE_SAMPLE_RATE: &str = "MEZMO_EVENT_TRACE_SAMPLE_RATE";
+const MEZMO_EVENT_TRACE_SPAN_SAMPLE_RATE: &str = "MEZMO_EVENT_TRACE_SPAN_SAMPLE_RATE";
 const MEZMO_TRACE_KEY: &str = "mezmo_trace";
+const MEZMO_TRACE_ENQUEUED_KEY: &str = "__mezmo_trace_enqueued";
+const MEZMO_EVENT_TRACE_LATENCY: &str = "mezmo_event_trace_latency_seconds";
+const MEZMO_EVENT_TRACE_BUFFER_WAIT: &str = "mezmo_event_trace_buffer_wait_seconds";
+
+/// Which components are traced and how many of their events, read once from the environment.
+#[derive(Debug)]
+struct TraceSettings {
+    /// Trace the components of every pipeline.
+    enabled: bool,
+    /// Trace only the components of these pipelines, when tracing isn't enabled globally.
+    pipelines: HashSet<String>,
+    /// The fraction of the events that are traced.
+    sample_rate: f64,
+    /// The fraction of the traced events emitted as spans once they reach a sink.
+    span_sample_rate: f64,
+}
 
-static TRACE_ENABLED: OnceLock<bool> = OnceLock::new();
-fn is_enabled() -> bool {
-    *TRACE_ENABLED.get_or_init(|| {
-        if let Ok(value) = env::var(MEZMO_EVENT_TRACE_ENABLED) {
+impl TraceSettings {
+    fn from_env() -> Self {
+        let enabled = env::var(MEZMO_EVENT_TRACE_ENABLED).is_ok_and(|value| {
             let value = value.to_ascii_lowercase();
-            if value == "1" || value == "true" || value == "t" {
-                info!("event tracing is enabled");
-                return true;
-            }
+            value == "1" || value == "true" || value == "t"
+        });
+        let pipelines: HashSet<String> = env::var(MEZMO_EVENT_TRACE_PIPELINES)
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(String::from)
+            .collect();
+
+        if enabled {
+            info!("event tracing is enabled");
+        } else if !pipelines.is_empty() {
+            info!("event tracing is enabled for pipelines {pipelines:?}");
+        } else {
+            info!("event tracing is globally disabled");
         }
 
-        info!("event tracing is globally disabled");
-        false
-    })
+        Self {
+            enabled,
+            pipelines,
+            sample_rate: rate_from_env(MEZMO_EVENT_TRACE_SAMPLE_RATE, 1.0),
+            span_sample_rate: rate_from_env(MEZMO_EVENT_TRACE_SPAN_SAMPLE_RATE, 0.0),
+        }
+    }
+
+    fn is_traced(&self, ctx: &MezmoContext) -> bool {
+        self.enabled
+            || ctx
+                .pipeline_id
+                .as_ref()
+                .is_some_and(|id| self.pipelines.contains(&id.to_string()))
+    }
+}
+
+fn rate_from_env(name: &str, default: f64) -> f64 {
+    match env::var(name).map(|value| value.parse::<f64>()) {
+        Ok(Ok(rate)) if (0.0..=1.0).contains(&rate) => rate,
+        Ok(_) => {
+            warn!("{name} must be a number between 0 and 1, using {default}");
+            default
+        }
+        Err(_) => default,
+    }
+}
+
+static TRACE_SETTINGS: OnceLock<TraceSettings> = OnceLock::new();
+fn settings() -> &'static TraceSettings {
+    TRACE_SETTINGS.get_or_init(TraceSettings::from_env)
+}
+
+fn is_sampled(rate: f64) -> bool {
+    rate >= 1.0 || (rate > 0.0 && rand::random::<f64>() < rate)
 }
 
 fn current_time() -> u128 {
@@ -38,22 +107,203 @@ fn current_time() -> u128 {
         .as_nanos()
 }
 
-fn add_trace_data(id: &str, internal: bool, elapsed: i64, metadata: &mut EventMetadata) {
-    let metadata_val = metadata.value_mut();
-    let trace_rec = Value::from(btreemap! {
+/// The part of the trip of an event through a pipeline covered by a trace record.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TraceStage {
+    /// From the source emitting the event to the topology forwarding it.
+    Source,
+    /// From being sent to the input buffer of a component until being received from it.
+    Buffer,
+    /// Processed by a transform.
+    Transform,
+    /// From a sink receiving the event to the completion of the request delivering it.
+    Sink,
+}
+
+impl TraceStage {
+    const fn as_str(self) -> &'static str {
+        match self {
+            TraceStage::Source => "source",
+            TraceStage::Buffer => "buffer",
+            TraceStage::Transform => "transform",
+            TraceStage::Sink => "sink",
+        }
+    }
+}
+
+/// A trace record, with the `elapsed` nanoseconds of the stage until its `end` as a unix time in
+/// nanoseconds.
+fn trace_record(id: &str, internal: bool, stage: TraceStage, elapsed: i64, end: i64) -> Value {
+    Value::from(btreemap! {
         "id" => id.to_owned(),
         "internal" => internal,
+        "stage" => stage.as_str(),
         "elapsed" => Value::from(elapsed),
-    });
+        "end" => Value::from(end),
+    })
+}
+
+/// Appends a trace record for the component to the event metadata, returning whether the event
+/// is traced. Events are sampled the first time they are seen, the trace of an event left out of
+/// the sample is set to null so that the following components skip it as well.
+fn add_trace_data(
+    id: &str,
+    internal: bool,
+    stage: TraceStage,
+    elapsed: i64,
+    end: i64,
+    metadata: &mut EventMetadata,
+) -> bool {
+    let metadata_val = metadata.value_mut();
+    let trace_rec = trace_record(id, internal, stage, elapsed, end);
     match metadata_val.get_mut(MEZMO_TRACE_KEY) {
         None => {
+            if !is_sampled(settings().sample_rate) {
+                metadata_val.insert(MEZMO_TRACE_KEY, Value::Null);
+                return false;
+            }
             metadata_val.insert(MEZMO_TRACE_KEY, Value::from(vec![trace_rec]));
+            true
         }
         Some(Value::Array(x)) => {
             x.push(trace_rec);
+            true
         }
+        Some(Value::Null) => false,
         val => {
             warn!("{MEZMO_TRACE_KEY} value for id={id} is not an array: {val:?} ");
+            false
+        }
+    }
+}
+
+/// Whether the event was sampled when it was first traced.
+fn is_traced(metadata: &EventMetadata) -> bool {
+    matches!(metadata.value().get(MEZMO_TRACE_KEY), Some(Value::Array(_)))
+}
+
+fn record_latency(latency: &Histogram, elapsed: i64) {
+    if elapsed >= 0 {
+        latency.record(elapsed as f64 / 1_000_000_000.0);
+    }
+}
+
+/// Adds the trace records of a component, and records their latencies in the histograms of the
+/// component.
+#[derive(Clone)]
+struct ComponentTracer {
+    id: String,
+    internal: bool,
+    stage: TraceStage,
+    latency: Histogram,
+    buffer_wait: Histogram,
+}
+
+impl ComponentTracer {
+    fn maybe_new(key: ComponentKey, stage: TraceStage) -> Option<Self> {
+        match MezmoContext::try_from(key.into_id()) {
+            Ok(ctx) if settings().is_traced(&ctx) => Some(Self::new(&ctx, stage)),
+            _ => None,
+        }
+    }
+
+    // The histograms are registered while building the component, so they're tagged with its id
+    fn new(ctx: &MezmoContext, stage: TraceStage) -> Self {
+        Self {
+            id: ctx.component_id.clone(),
+            internal: ctx.internal,
+            stage,
+            latency: histogram!(MEZMO_EVENT_TRACE_LATENCY),
+            buffer_wait: histogram!(MEZMO_EVENT_TRACE_BUFFER_WAIT),
+        }
+    }
+
+    /// Records the time spent by the event in the component.
+    fn trace(&self, elapsed: i64, end: i64, metadata: &mut EventMetadata) -> bool {
+        let traced = add_trace_data(&self.id, self.internal, self.stage, elapsed, end, metadata);
+        if traced {
+            record_latency(&self.latency, elapsed);
+        }
+        traced
+    }
+
+    /// Records the time the event waited in the input buffer of the component.
+    fn trace_buffer_wait(&self, wait: i64, end: i64, metadata: &mut EventMetadata) -> bool {
+        let traced = add_trace_data(
+            &self.id,
+            self.internal,
+            TraceStage::Buffer,
+            wait,
+            end,
+            metadata,
+        );
+        if traced {
+            record_latency(&self.buffer_wait, wait);
+        }
+        traced
+    }
+}
+
+/// Traces the time the events wait in the input buffer of a transform or a sink, from being
+/// sent to the buffer until being received from it. Only the events that are already traced are
+/// timed, the others are sampled by the component itself.
+#[derive(Clone)]
+pub struct MezmoBufferTrace {
+    tracer: ComponentTracer,
+}
+
+impl MezmoBufferTrace {
+    pub fn maybe_new(key: &ComponentKey) -> Option<Self> {
+        ComponentTracer::maybe_new(key.clone(), TraceStage::Buffer).map(|tracer| Self { tracer })
+    }
+}
+
+impl BufferInstrumentation<EventArray> for MezmoBufferTrace {
+    fn on_send(&self, events: &mut EventArray) {
+        // The time is digested into 16 bytes to retain its u128 precision, as for the time the
+        // events enter a task transform
+        let enqueued = Value::from(current_time().to_ne_bytes());
+        for mut event in events.iter_events_mut() {
+            let metadata = event.metadata_mut();
+            if is_traced(metadata) {
+                metadata
+                    .value_mut()
+                    .insert(MEZMO_TRACE_ENQUEUED_KEY, enqueued.clone());
+            }
+        }
+    }
+
+    fn on_receive(&self, events: &mut EventArray) {
+        let now = current_time();
+        for mut event in events.iter_events_mut() {
+            let metadata = event.metadata_mut();
+            if let Some(Value::Bytes(mut enqueued)) =
+                metadata.value_mut().remove(MEZMO_TRACE_ENQUEUED_KEY, true)
+            {
+                let wait = now.saturating_sub(enqueued.get_u128_ne()) as i64;
+                self.tracer.trace_buffer_wait(wait, now as i64, metadata);
+            }
+        }
+    }
+}
+
+/// Traces the batches emitted by a source, when they are forwarded to the topology.
+#[derive(Clone)]
+pub struct MezmoSourceTrace {
+    tracer: ComponentTracer,
+}
+
+impl MezmoSourceTrace {
+    pub fn maybe_new(key: &ComponentKey) -> Option<Self> {
+        ComponentTracer::maybe_new(key.clone(), TraceStage::Source).map(|tracer| Self { tracer })
+    }
+
+    /// Starts the trace of the events, with the time since the source sent them.
+    pub fn on_emit(&self, events: &mut EventArray, send_reference: Instant) {
+        let elapsed = send_reference.elapsed().as_nanos() as i64;
+        let end = current_time() as i64;
+        for mut event in events.iter_events_mut() {
+            self.tracer.trace(elapsed, end, event.metadata_mut());
         }
     }
 }
@@ -61,41 +311,46 @@ fn add_trace_data(id: &str, internal: bool, elapsed: i64, metadata: &mut EventMe
 #[derive(Clone)]
 pub struct MezmoSyncTransformTrace {
     inner: Box<dyn SyncTransform>,
-    key: String,
-    internal: bool,
+    tracer: ComponentTracer,
 }
 
 impl MezmoSyncTransformTrace {
     pub fn maybe_wrap(key: ComponentKey, inner: Box<dyn SyncTransform>) -> Box<dyn SyncTransform> {
-        match MezmoContext::try_from(key.into_id()) {
-            Ok(ctx) if is_enabled() => Box::new(Self {
-                key: ctx.component_id,
-                internal: ctx.internal,
-                inner,
-            }),
-            _ => inner,
+        match ComponentTracer::maybe_new(key, TraceStage::Transform) {
+            Some(tracer) => Box::new(Self { inner, tracer }),
+            None => inner,
         }
     }
 }
 
 impl SyncTransform for MezmoSyncTransformTrace {
     fn transform(&mut self, event: Event, output: &mut TransformOutputsBuf) {
+        // The buffers hold the events of the previous calls for the same batch, these were
+        // already traced
+        let traced: Vec<usize> = output
+            .primary_buffer
+            .iter()
+            .chain(output.named_buffers.values())
+            .map(|buffer| buffer.len())
+            .collect();
+
         let start = current_time();
         self.inner.as_mut().transform(event, output);
 
         // Ignoring the overflow here is probably fine for tracing because i64::MAX nanoseconds is
         // approximately 2,562,047 hours. We're more likely to have a vector release or pod roll
         // before we would need to worry about silent precision loss.
-        let duration = (current_time() - start) as i64;
-
-        if let Some(primary_buffer) = &mut output.primary_buffer {
-            for mut event in primary_buffer.events_mut() {
-                add_trace_data(&self.key, self.internal, duration, event.metadata_mut());
-            }
-        }
-        for buffer in &mut output.named_buffers.values_mut() {
-            for mut event in buffer.events_mut() {
-                add_trace_data(&self.key, self.internal, duration, event.metadata_mut());
+        let end = current_time();
+        let duration = (end - start) as i64;
+        let end = end as i64;
+
+        let buffers = output
+            .primary_buffer
+            .iter_mut()
+            .chain(output.named_buffers.values_mut());
+        for (buffer, traced) in buffers.zip(traced) {
+            for mut event in buffer.events_mut().skip(traced) {
+                self.tracer.trace(duration, end, event.metadata_mut());
             }
         }
     }
@@ -103,8 +358,7 @@ impl SyncTransform for MezmoSyncTransformTrace {
 
 pub struct MezmoTaskTransformTrace {
     inner: Box<dyn TaskTransform<EventArray>>,
-    key: String,
-    internal: bool,
+    tracer: ComponentTracer,
 }
 
 impl MezmoTaskTransformTrace {
@@ -112,13 +366,9 @@ impl MezmoTaskTransformTrace {
         key: ComponentKey,
         inner: Box<dyn TaskTransform<EventArray>>,
     ) -> Box<dyn TaskTransform<EventArray>> {
-        match MezmoContext::try_from(key.into_id()) {
-            Ok(ctx) if is_enabled() => Box::new(Self {
-                key: ctx.component_id,
-                internal: ctx.internal,
-                inner,
-            }),
-            _ => inner,
+        match ComponentTracer::maybe_new(key, TraceStage::Transform) {
+            Some(tracer) => Box::new(Self { inner, tracer }),
+            None => inner,
         }
     }
 }
@@ -128,25 +378,26 @@ impl TaskTransform<EventArray> for MezmoTaskTransformTrace {
         self: Box<Self>,
         stream: Pin<Box<dyn Stream<Item = EventArray> + Send>>,
     ) -> Pin<Box<dyn Stream<Item = EventArray> + Send>> {
-        let key = self.key.clone();
-        let internal = self.internal;
+        let Self { inner, tracer } = *self;
         let stream = stream
-            .map(|mut events| {
+            .map(move |mut events| {
+                let now = current_time();
                 for mut event in events.iter_events_mut() {
+                    let metadata = event.metadata_mut();
                     // To retain the u128 precision through the Value boundary, digest it into 16 bytes.
                     // The endianness doesn't matter as long as it's consistent with the post process logic.
-                    let trace_start = Value::from(current_time().to_ne_bytes());
-                    event
-                        .metadata_mut()
+                    let trace_start = Value::from(now.to_ne_bytes());
+                    metadata
                         .value_mut()
                         .insert("__mezmo_trace_start", trace_start);
                 }
                 events
             })
             .boxed();
-        self.inner
+        inner
             .transform(stream)
             .map(move |mut events| {
+                let end = current_time();
                 for mut event in events.iter_events_mut() {
                     let metadata = event.metadata_mut();
                     let elapsed = match metadata.value_mut().remove("__mezmo_trace_start", true) {
@@ -154,11 +405,11 @@ impl TaskTransform<EventArray> for MezmoTaskTransformTrace {
                             // Ignoring the overflow here is probably fine for tracing because i64::MAX nanoseconds is
                             // approximately 2,562,047 hours. We're more likely to have a vector release or pod roll
                             // before we would need to worry about silent precision loss.
-                            (current_time() - start.get_u128_ne()) as i64
+                            (end - start.get_u128_ne()) as i64
                         }
                         _ => -1,
                     };
-                    add_trace_data(&key, internal, elapsed, metadata);
+                    tracer.trace(elapsed, end as i64, metadata);
                 }
                 events
             })
@@ -176,8 +427,10 @@ mod tests {
     use futures::Stream;
     use futures_util::stream;
     use std::collections::HashMap;
+    use std::num::NonZeroUsize;
     use std::pin::Pin;
-    use vector_lib::event::{EventArray, EventMetadata};
+    use vector_lib::buffers::{WhenFull, topology::builder::TopologyBuilder};
+    use vector_lib::event::{EventArray, EventContainer, EventMetadata};
     use vrl::core::Value;
 
     #[test]
@@ -185,7 +438,14 @@ mod tests {
         let mut metadata = EventMetadata::default();
         assert!(metadata.value().get(MEZMO_TRACE_KEY).is_none());
 
-        add_trace_data("key-1", false, 1000, &mut metadata);
+        assert!(add_trace_data(
+            "key-1",
+            false,
+            TraceStage::Transform,
+            1000,
+            5000,
+            &mut metadata
+        ));
         let trace_arr = metadata
             .value()
             .get(MEZMO_TRACE_KEY)
@@ -194,12 +454,19 @@ mod tests {
             .as_array()
             .expect("MEZMO_TRACE_KEY value should be an array");
         assert_eq!(
-            "[{\"elapsed\":1000,\"id\":\"key-1\",\"internal\":false}]",
+            "[{\"elapsed\":1000,\"end\":5000,\"id\":\"key-1\",\"internal\":false,\"stage\":\"transform\"}]",
             serde_json::to_string(&trace_arr)
                 .expect("mezmo trace data should be json serializable")
         );
 
-        add_trace_data("key-2", true, 2000, &mut metadata);
+        assert!(add_trace_data(
+            "key-2",
+            true,
+            TraceStage::Buffer,
+            2000,
+            7000,
+            &mut metadata
+        ));
         let trace_arr = metadata
             .value()
             .get(MEZMO_TRACE_KEY)
@@ -208,7 +475,7 @@ mod tests {
             .as_array()
             .expect("MEZMO_TRACE_KEY value should be an array");
         assert_eq!(
-            "[{\"elapsed\":1000,\"id\":\"key-1\",\"internal\":false},{\"elapsed\":2000,\"id\":\"key-2\",\"internal\":true}]",
+            "[{\"elapsed\":1000,\"end\":5000,\"id\":\"key-1\",\"internal\":false,\"stage\":\"transform\"},{\"elapsed\":2000,\"end\":7000,\"id\":\"key-2\",\"internal\":true,\"stage\":\"buffer\"}]",
             serde_json::to_string(&trace_arr)
                 .expect("mezmo trace data should be json serializable")
         );
@@ -275,6 +542,128 @@ mod tests {
         );
     }
 
+    #[assay(env = [("MEZMO_EVENT_TRACE_PIPELINES", "pipeline-xyz, pipeline-abc")])]
+    fn sync_transform_trace_pipelines() {
+        let key = ComponentKey::from("v1:filter:transform:node-1:pipeline-abc:acct1");
+        let mut xform = MezmoSyncTransformTrace::maybe_wrap(key, Box::new(TestSyncTransform));
+        let mut outputs = new_output_buf();
+        xform.transform(LogEvent::from("test-event").into(), &mut outputs);
+        let actual = outputs.drain().next().expect("output should have an event");
+        assert!(actual.metadata().value().get(MEZMO_TRACE_KEY).is_some());
+
+        let key = ComponentKey::from("v1:filter:transform:node-1:pipeline-other:acct1");
+        let mut xform = MezmoSyncTransformTrace::maybe_wrap(key, Box::new(TestSyncTransform));
+        let mut outputs = new_output_buf();
+        xform.transform(LogEvent::from("test-event").into(), &mut outputs);
+        let actual = outputs.drain().next().expect("output should have an event");
+        assert!(actual.metadata().value().get(MEZMO_TRACE_KEY).is_none());
+    }
+
+    #[assay(env = [
+        ("MEZMO_EVENT_TRACE_ENABLED", "true"),
+        ("MEZMO_EVENT_TRACE_SAMPLE_RATE", "0"),
+    ])]
+    fn sync_transform_trace_not_sampled() {
+        let key = ComponentKey::from("v1:filter:transform:node-1:pipeline-abc:acct1");
+        let mut xform = MezmoSyncTransformTrace::maybe_wrap(key, Box::new(TestSyncTransform));
+
+        let mut outputs = new_output_buf();
+        xform.transform(LogEvent::from("test-event").into(), &mut outputs);
+
+        let actual = outputs.drain().next().expect("output should have an event");
+        let actual = actual.metadata().value().get(MEZMO_TRACE_KEY);
+        assert_eq!(actual, Some(&Value::Null));
+    }
+
+    #[assay(env = [("MEZMO_EVENT_TRACE_ENABLED", "true")])]
+    fn sync_transform_trace_batch() {
+        let key = ComponentKey::from("v1:filter:transform:node-1:pipeline-abc:acct1");
+        let mut xform = MezmoSyncTransformTrace::maybe_wrap(key, Box::new(TestSyncTransform));
+
+        // Events of the same batch share the output buffer
+        let mut outputs = new_output_buf();
+        xform.transform(LogEvent::from("event-1").into(), &mut outputs);
+        xform.transform(LogEvent::from("event-2").into(), &mut outputs);
+
+        let events: Vec<Event> = outputs.drain().collect();
+        assert_eq!(events.len(), 2);
+        for event in events {
+            let trace = event
+                .metadata()
+                .value()
+                .get(MEZMO_TRACE_KEY)
+                .and_then(Value::as_array)
+                .expect("trace object should be an array value");
+            assert_eq!(1, trace.len());
+        }
+    }
+
+    #[assay(env = [("MEZMO_EVENT_TRACE_ENABLED", "true")])]
+    async fn source_and_buffer_trace() {
+        let key = ComponentKey::from("v1:http:source:source-1:pipeline-abc:acct1");
+        let source = MezmoSourceTrace::maybe_new(&key).expect("source should be traced");
+        let mut events = EventArray::from(LogEvent::from("test-event"));
+        source.on_emit(&mut events, Instant::now());
+
+        let key = ComponentKey::from("v1:filter:transform:node-1:pipeline-abc:acct1");
+        let buffer_trace = MezmoBufferTrace::maybe_new(&key).expect("buffer should be traced");
+        let (mut tx, mut rx) = TopologyBuilder::standalone_memory(
+            NonZeroUsize::new(1).unwrap(),
+            WhenFull::Block,
+            &tracing::Span::none(),
+            None,
+            None,
+        );
+        tx.with_custom_instrumentation(buffer_trace.clone());
+        rx.with_custom_instrumentation(buffer_trace);
+        tx.send(events, None).await.unwrap();
+        let events = rx.next().await.expect("buffer should have an event");
+
+        let mut xform = MezmoSyncTransformTrace::maybe_wrap(key, Box::new(TestSyncTransform));
+        let mut outputs = new_output_buf();
+        let event = events
+            .into_events()
+            .next()
+            .expect("array should have an event");
+        assert!(
+            event
+                .metadata()
+                .value()
+                .get(MEZMO_TRACE_ENQUEUED_KEY)
+                .is_none()
+        );
+        xform.transform(event, &mut outputs);
+
+        let actual = outputs.drain().next().expect("output should have an event");
+        let trace = actual
+            .metadata()
+            .value()
+            .get(MEZMO_TRACE_KEY)
+            .and_then(Value::as_array)
+            .expect("trace object should be an array value");
+        let hops: Vec<(String, String)> = trace
+            .iter()
+            .map(|hop| {
+                let field =
+                    |name: &str| hop.get(name).and_then(Value::as_str).unwrap().into_owned();
+                (field("id"), field("stage"))
+            })
+            .collect();
+        assert_eq!(
+            hops,
+            vec![
+                ("source-1".to_string(), "source".to_string()),
+                ("node-1".to_string(), "buffer".to_string()),
+                ("node-1".to_string(), "transform".to_string()),
+            ]
+        );
+        assert!(
+            trace[1]
+                .get("elapsed")
+                .is_some_and(|v| v.as_integer().unwrap() >= 0)
+        );
+    }
+
     #[derive(Clone)]
     struct TestTaskTransform;
     impl TaskTransform<EventArray> for TestTaskTransform {
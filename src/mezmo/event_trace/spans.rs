@@ -0,0 +1,365 @@
+use std::sync::OnceLock;
+
+use chrono::{DateTime, Utc};
+use futures_util::{Stream, StreamExt, future::ready};
+use mezmo::MezmoContext;
+use tokio::sync::broadcast::{self, Receiver, Sender};
+use tokio_stream::wrappers::BroadcastStream;
+use vector_lib::event::{EventArray, LogEvent};
+use vector_lib::finalization::{BatchNotifier, EventFinalizer};
+use vrl::value::{ObjectMap, Value};
+
+use super::{
+    ComponentTracer, MEZMO_TRACE_KEY, TraceStage, current_time, is_sampled, is_traced,
+    record_latency, settings, trace_record,
+};
+use crate::config::ComponentKey;
+
+const SCOPE_NAME: &str = "mezmo_event_trace";
+
+static SPAN_SENDER: OnceLock<Sender<LogEvent>> = OnceLock::new();
+fn span_sender() -> &'static Sender<LogEvent> {
+    SPAN_SENDER.get_or_init(|| broadcast::channel(1000).0)
+}
+
+/// Gives access to the spans of the traced events, in the shape of the OpenTelemetry traces
+/// accepted by the `mezmo_opentelemetry` sink.
+pub struct EventTraceSubscription {
+    rx: Receiver<LogEvent>,
+}
+
+impl EventTraceSubscription {
+    pub fn subscribe() -> Self {
+        Self {
+            rx: span_sender().subscribe(),
+        }
+    }
+
+    /// Consumes the subscription to produce a Stream of span events.
+    pub fn into_stream(self) -> impl Stream<Item = LogEvent> + Unpin {
+        BroadcastStream::new(self.rx).filter_map(|e| ready(e.ok()))
+    }
+}
+
+/// Collects the trace of the events received by a sink. The time until the request delivering
+/// them completes is recorded once their finalizers are dropped. A sample of the traced events are
+/// then emitted as a span covering the trip through the pipeline, with a child span for each
+/// record.
+pub struct MezmoSinkTraceCollector {
+    ctx: MezmoContext,
+    tracer: ComponentTracer,
+}
+
+impl MezmoSinkTraceCollector {
+    pub fn maybe_new(key: &ComponentKey) -> Option<Self> {
+        match MezmoContext::try_from(key.clone().into_id()) {
+            Ok(ctx) if settings().is_traced(&ctx) => Some(Self {
+                tracer: ComponentTracer::new(&ctx, TraceStage::Sink),
+                ctx,
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn collect(&self, events: &mut EventArray) {
+        // Nothing to emit until a source subscribes to the spans
+        let span_sample_rate = match span_sender().receiver_count() {
+            0 => 0.0,
+            _ => settings().span_sample_rate,
+        };
+
+        let received = current_time();
+        let mut traced = 0;
+        let mut sampled = Vec::new();
+        let mut notifier = None;
+        for mut event in events.iter_events_mut() {
+            let metadata = event.metadata_mut();
+            if !is_traced(metadata) {
+                continue;
+            }
+            traced += 1;
+            let (batch, _) = notifier.get_or_insert_with(BatchNotifier::new_with_receiver);
+            metadata.add_finalizer(EventFinalizer::new(batch.clone()));
+            if is_sampled(span_sample_rate)
+                && let Some(Value::Array(trace)) = metadata.value().get(MEZMO_TRACE_KEY)
+            {
+                sampled.push(trace.clone());
+            }
+        }
+
+        // The receiver resolves once the finalizers of all the traced events are dropped, after
+        // the sink is done with them
+        let Some((batch, receiver)) = notifier else {
+            return;
+        };
+        drop(batch);
+        let tracer = self.tracer.clone();
+        let ctx = self.ctx.clone();
+        tokio::spawn(async move {
+            receiver.await;
+            let end = current_time();
+            let elapsed = (end - received) as i64;
+            for _ in 0..traced {
+                record_latency(&tracer.latency, elapsed);
+            }
+            for mut trace in sampled {
+                trace.push(trace_record(
+                    &tracer.id,
+                    tracer.internal,
+                    tracer.stage,
+                    elapsed,
+                    end as i64,
+                ));
+                for span in spans(&ctx, &trace) {
+                    // Sending only fails when there are no subscribers anymore
+                    _ = span_sender().send(span);
+                }
+            }
+        });
+    }
+}
+
+fn spans(ctx: &MezmoContext, trace: &[Value]) -> Vec<LogEvent> {
+    let trace_id = hex::encode(rand::random::<[u8; 16]>());
+    let root_id = hex::encode(rand::random::<[u8; 8]>());
+
+    let hops: Vec<(&Value, i64, i64)> = trace
+        .iter()
+        .filter_map(|hop| {
+            let elapsed = hop.get("elapsed")?.as_integer()?;
+            let end = hop.get("end")?.as_integer()?;
+            Some((hop, end - elapsed.max(0), end))
+        })
+        .collect();
+    let start = hops.iter().map(|(_, start, _)| *start).min().unwrap_or(0);
+    let end = hops.iter().map(|(_, _, end)| *end).max().unwrap_or(0);
+
+    let mut attributes: ObjectMap = btreemap! {
+        "mezmo.account_id" => Value::from(&ctx.account_id),
+        "mezmo.sink_id" => ctx.component_id.clone(),
+    };
+    if let Some(pipeline_id) = &ctx.pipeline_id {
+        attributes.insert("mezmo.pipeline_id".into(), Value::from(pipeline_id));
+    }
+
+    let mut spans = Vec::with_capacity(hops.len() + 1);
+    spans.push(span_event(
+        &trace_id,
+        &root_id,
+        "",
+        "pipeline",
+        (start, end),
+        attributes,
+    ));
+    for (hop, start, end) in hops {
+        let field = |name: &str| hop.get(name).cloned().unwrap_or(Value::Null);
+        let name = format!(
+            "{} {}",
+            field("stage").as_str().unwrap_or_default(),
+            field("id").as_str().unwrap_or_default()
+        );
+        spans.push(span_event(
+            &trace_id,
+            &hex::encode(rand::random::<[u8; 8]>()),
+            &root_id,
+            name.trim(),
+            (start, end),
+            btreemap! {
+                "mezmo.component_id" => field("id"),
+                "mezmo.internal" => field("internal"),
+                "mezmo.stage" => field("stage"),
+            },
+        ));
+    }
+    spans
+}
+
+/// A span event. Its trace is set to null, as for the events left out of the sample, so that the
+/// spans of a traced pipeline aren't traced themselves once emitted by a source.
+fn span_event(
+    trace_id: &str,
+    span_id: &str,
+    parent_span_id: &str,
+    name: &str,
+    (start, end): (i64, i64),
+    attributes: ObjectMap,
+) -> LogEvent {
+    let mut span = LogEvent::from(btreemap! {
+        "message" => btreemap! {
+            "trace_id" => trace_id,
+            "span_id" => span_id,
+            "parent_span_id" => parent_span_id,
+            "name" => name,
+            // SpanKind::Internal
+            "kind" => 1,
+            "start_timestamp" => DateTime::<Utc>::from_timestamp_nanos(start),
+            "end_timestamp" => DateTime::<Utc>::from_timestamp_nanos(end),
+            "events" => Vec::<Value>::new(),
+            "links" => Vec::<Value>::new(),
+        },
+        "metadata" => btreemap! {
+            "resource" => btreemap! {
+                "attributes" => btreemap! {
+                    "service.name" => crate::get_app_name(),
+                },
+            },
+            "scope" => btreemap! {
+                "name" => SCOPE_NAME,
+            },
+            "attributes" => attributes,
+            "span_uniq_id" => span_id,
+        },
+    });
+    span.metadata_mut()
+        .value_mut()
+        .insert(MEZMO_TRACE_KEY, Value::Null);
+    span
+}
+
+#[cfg(test)]
+mod tests {
+    use assay::assay;
+    use vector_lib::event::{EventContainer, EventStatus};
+
+    use super::*;
+    use crate::event::Event;
+
+    fn sink_key() -> ComponentKey {
+        ComponentKey::from("v1:http:sink:sink-1:pipeline-abc:acct1")
+    }
+
+    #[test]
+    fn spans_test() {
+        let ctx = MezmoContext::try_from(sink_key().into_id()).unwrap();
+        let trace = vec![
+            Value::from(btreemap! {
+                "id" => "node-1",
+                "internal" => false,
+                "stage" => "transform",
+                "elapsed" => 100,
+                "end" => 1_000,
+            }),
+            Value::from(btreemap! {
+                "id" => "sink-1",
+                "internal" => false,
+                "stage" => "sink",
+                "elapsed" => 200,
+                "end" => 1_500,
+            }),
+        ];
+
+        let spans = spans(&ctx, &trace);
+        assert_eq!(spans.len(), 3);
+
+        let root = &spans[0];
+        let trace_id = root.get("message.trace_id").unwrap();
+        let root_id = root.get("message.span_id").unwrap();
+        assert_eq!(root.get("message.name"), Some(&Value::from("pipeline")));
+        assert_eq!(
+            root.get("message.start_timestamp"),
+            Some(&Value::from(DateTime::<Utc>::from_timestamp_nanos(900)))
+        );
+        assert_eq!(
+            root.get("message.end_timestamp"),
+            Some(&Value::from(DateTime::<Utc>::from_timestamp_nanos(1_500)))
+        );
+        assert_eq!(
+            root.get("metadata.attributes.\"mezmo.pipeline_id\""),
+            Some(&Value::from("pipeline-abc"))
+        );
+
+        let hop = &spans[2];
+        assert_eq!(hop.get("message.trace_id"), Some(trace_id));
+        assert_eq!(hop.get("message.parent_span_id"), Some(root_id));
+        assert_eq!(hop.get("message.name"), Some(&Value::from("sink sink-1")));
+        assert_eq!(
+            hop.get("message.start_timestamp"),
+            Some(&Value::from(DateTime::<Utc>::from_timestamp_nanos(1_300)))
+        );
+        assert_eq!(
+            hop.get("metadata.attributes.\"mezmo.stage\""),
+            Some(&Value::from("sink"))
+        );
+    }
+
+    #[assay(env = [("MEZMO_EVENT_TRACE_ENABLED", "true")])]
+    fn spans_are_not_traced() {
+        let ctx = MezmoContext::try_from(sink_key().into_id()).unwrap();
+        let trace = vec![Value::from(btreemap! {
+            "id" => "sink-1",
+            "internal" => false,
+            "stage" => "sink",
+            "elapsed" => 200,
+            "end" => 1_500,
+        })];
+
+        // The spans go through the components of a traced pipeline, from the source onwards
+        let source = ComponentTracer::maybe_new(
+            ComponentKey::from("v1:mezmo_event_traces:source:source-1:pipeline-abc:acct1"),
+            TraceStage::Source,
+        )
+        .unwrap();
+        let collector = MezmoSinkTraceCollector::maybe_new(&sink_key()).unwrap();
+        for span in spans(&ctx, &trace) {
+            let mut events = EventArray::from(vec![span]);
+            for mut event in events.iter_events_mut() {
+                assert!(!source.trace(100, 1_000, event.metadata_mut()));
+            }
+            collector.collect(&mut events);
+
+            for event in events.into_events() {
+                assert_eq!(
+                    event.metadata().value().get(MEZMO_TRACE_KEY),
+                    Some(&Value::Null)
+                );
+            }
+        }
+    }
+
+    #[assay(env = [
+        ("MEZMO_EVENT_TRACE_ENABLED", "true"),
+        ("MEZMO_EVENT_TRACE_SPAN_SAMPLE_RATE", "1"),
+    ])]
+    async fn collects_spans_on_completion() {
+        let mut spans = EventTraceSubscription::subscribe().into_stream();
+        let collector = MezmoSinkTraceCollector::maybe_new(&sink_key()).unwrap();
+
+        let mut traced = LogEvent::from("traced");
+        assert!(super::super::add_trace_data(
+            "node-1",
+            false,
+            TraceStage::Transform,
+            100,
+            current_time() as i64,
+            traced.metadata_mut()
+        ));
+        let mut events = EventArray::from(vec![traced, LogEvent::from("not traced")]);
+        collector.collect(&mut events);
+
+        let mut events = events.into_events();
+        let traced = events.next().unwrap();
+        let Event::Log(untraced) = events.next().unwrap() else {
+            panic!("unexpected event type");
+        };
+        assert!(untraced.metadata().value().get(MEZMO_TRACE_KEY).is_none());
+
+        // The spans are emitted once the sink is done with the event
+        traced.metadata().update_status(EventStatus::Delivered);
+        drop(traced);
+
+        let names: Vec<Value> = spans
+            .by_ref()
+            .take(3)
+            .map(|span| span.get("message.name").cloned().unwrap())
+            .collect()
+            .await;
+        assert_eq!(
+            names,
+            vec![
+                Value::from("pipeline"),
+                Value::from("transform node-1"),
+                Value::from("sink sink-1"),
+            ]
+        );
+    }
+}
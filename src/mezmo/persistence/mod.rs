@@ -4,6 +4,7 @@ use mezmo::MezmoContext;
 
 pub(crate) use rocksdb::RocksDBConnection;
 pub(crate) use rocksdb::RocksDBPersistenceConnection;
+pub(crate) use rocksdb::component_state;
 
 /// The [PersistenceConnection] trait defines the specifics on how to create the state that connects
 /// to the persistence layer, e.g. a DB connection, that can then be used for individual operations.
@@ -1,9 +1,9 @@
 use rocksdb::statistics::{Histogram, StatsLevel, Ticker};
-use rocksdb::{BlockBasedOptions, Cache, DB, DBCompactionStyle, Options};
+use rocksdb::{BlockBasedOptions, Cache, DB, DBCompactionStyle, Direction, IteratorMode, Options};
 use snafu::{ResultExt, Snafu};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::ops::{Deref, DerefMut};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
@@ -304,13 +304,56 @@ fn namespaced_key(mezmo_ctx: &MezmoContext, key: &str) -> String {
     format!("{}:{}", mezmo_ctx.component_id(), key)
 }
 
+/// Reads all the entries stored by a component, from the open databases of its account. Keys are
+/// returned without the component namespace.
+pub(crate) fn component_state(mezmo_ctx: &MezmoContext) -> Result<BTreeMap<String, String>, Error> {
+    let Some(account_id) = mezmo_ctx.account_id() else {
+        return Err(Box::new(RocksDBPersistenceError::InvalidContext {
+            mezmo_ctx: mezmo_ctx.clone(),
+        }));
+    };
+
+    let db_prefix = format!("{account_id}.");
+    let connections = ROCKSDB_CONNECTION_REGISTRY
+        .lock()
+        .expect("Could not acquire lock on RocksDB persistence registry")
+        .iter()
+        .filter(|(path, _)| {
+            Path::new(path)
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with(&db_prefix))
+        })
+        .map(|(_, conn)| Arc::clone(conn))
+        .collect::<Vec<_>>();
+
+    let prefix = namespaced_key(mezmo_ctx, "");
+    let mut entries = BTreeMap::new();
+    for conn in connections {
+        let iter = conn
+            .db
+            .iterator(IteratorMode::From(prefix.as_bytes(), Direction::Forward));
+        for item in iter {
+            let (key, value) = item.context(RocksDBSnafu)?;
+            let Some(key) = key.strip_prefix(prefix.as_bytes()) else {
+                break;
+            };
+            entries.insert(
+                String::from_utf8_lossy(key).into_owned(),
+                String::from_utf8(value.into_vec()).context(ConversionSnafu)?,
+            );
+        }
+    }
+
+    Ok(entries)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::assert_downcast_matches;
     use assay::assay;
     use serde::Serialize;
-    use std::{collections::BTreeMap, thread};
+    use std::thread;
     use tempfile::tempdir;
     use uuid::Uuid;
 
@@ -491,6 +534,38 @@ mod tests {
         assert!(obj_actual.is_none());
     }
 
+    #[assay(env = [("POD_NAME", "vector-test0-0")])]
+    fn test_component_state() {
+        #[allow(deprecated)]
+        let tmp_path = tempdir().expect("Could not create temp dir").into_path();
+        let base_path = tmp_path.to_str().unwrap();
+
+        let account_id = Uuid::new_v4().to_string();
+        let ctx = test_mezmo_context_for_account(&account_id);
+        let other_ctx = MezmoContext::try_from(format!(
+            "v1:reduce:transform:other_component:pipeline_id:{account_id}"
+        ))
+        .unwrap();
+
+        let db = RocksDBPersistenceConnection::new(base_path, &ctx).unwrap();
+        let other_db = RocksDBPersistenceConnection::new(base_path, &other_ctx).unwrap();
+        db.set("state", &to_json(&123)).unwrap();
+        db.set("timestamp", &to_json("2024-01-01")).unwrap();
+        other_db.set("state", &to_json(&456)).unwrap();
+
+        let entries = component_state(&ctx).unwrap();
+        assert_eq!(
+            entries,
+            BTreeMap::from([
+                ("state".to_owned(), to_json(&123)),
+                ("timestamp".to_owned(), to_json("2024-01-01")),
+            ])
+        );
+
+        let unknown = test_mezmo_context();
+        assert!(component_state(&unknown).unwrap().is_empty());
+    }
+
     #[assay(env = [("POD_NAME", "vector-test0-0")])]
     fn test_from_multiple_threads_for_the_same_account() {
         #[allow(deprecated)]
@@ -0,0 +1,130 @@
+use std::sync::{
+    RwLock,
+    atomic::{AtomicBool, Ordering},
+};
+
+use serde::Serialize;
+use serde_json::Value;
+use url::Url;
+use vector_lib::sensitive_string::serialize_redacted;
+
+use crate::config::Config;
+
+const REDACTED: &str = "**REDACTED**";
+
+// Sensitive strings are redacted when serialized, these cover the secrets held in plain string
+// fields: fields with a name containing any of these are considered secrets
+const SECRET_FIELDS: [&str; 11] = [
+    "password",
+    "passphrase",
+    "secret",
+    "token",
+    "api_key",
+    "apikey",
+    "access_key",
+    "private_key",
+    "key_pass",
+    "credential",
+    "authorization",
+];
+
+// The snapshot is only kept when remote tasks are enabled, serializing the config on every
+// reload is wasteful otherwise
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static SNAPSHOT: RwLock<Option<Value>> = RwLock::new(None);
+
+/// Starts keeping a redacted copy of the running config, for the config dump task.
+pub(crate) fn init_config_snapshot(config: &Config) {
+    ENABLED.store(true, Ordering::Relaxed);
+    update_config_snapshot(config);
+}
+
+/// Replaces the copy of the running config after a reload.
+pub(crate) fn update_config_snapshot(config: &Config) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+
+    match to_redacted_value(config) {
+        Ok(value) => {
+            *SNAPSHOT.write().expect("Config snapshot poisoned") = Some(value);
+        }
+        Err(error) => {
+            warn!(message = "Unable to serialize the running config.", %error);
+        }
+    }
+}
+
+/// Returns the redacted running config, optionally restricted to the components whose id
+/// contains `filter`.
+pub(super) fn config_dump(filter: Option<&str>) -> Result<Value, String> {
+    let mut config = SNAPSHOT
+        .read()
+        .expect("Config snapshot poisoned")
+        .clone()
+        .ok_or_else(|| "The running config is not available".to_string())?;
+
+    if let Some(filter) = filter {
+        for kind in ["enrichment_tables", "sources", "transforms", "sinks"] {
+            if let Some(components) = config.get_mut(kind).and_then(Value::as_object_mut) {
+                components.retain(|id, _| id.contains(filter));
+            }
+        }
+    }
+
+    Ok(config)
+}
+
+/// Serializes `value` with its secrets redacted.
+pub(super) fn to_redacted_value<T: Serialize>(value: &T) -> serde_json::Result<Value> {
+    let mut value = serialize_redacted(|| serde_json::to_value(value))?;
+    redact_secrets(&mut value);
+    Ok(value)
+}
+
+fn is_secret_field(name: &str) -> bool {
+    let name = name.to_lowercase();
+    SECRET_FIELDS.iter().any(|field| name.contains(field))
+}
+
+// Header maps, such as `request.headers`, commonly hold API keys under arbitrary names
+// (`X-API-Key`, `DD-API-KEY`, ...), so all their values are considered secrets
+fn is_header_map(name: &str) -> bool {
+    name.to_lowercase().ends_with("headers")
+}
+
+/// Replaces all the values found in `value`.
+fn redact_all(value: &mut Value) {
+    match value {
+        Value::Null => {}
+        Value::Object(fields) => fields.values_mut().for_each(redact_all),
+        Value::Array(items) => items.iter_mut().for_each(redact_all),
+        _ => *value = Value::String(REDACTED.to_string()),
+    }
+}
+
+/// Replaces the values of secret fields and header maps, and the credentials embedded in URLs.
+fn redact_secrets(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            for (name, value) in fields.iter_mut() {
+                match value {
+                    Value::Object(_) if is_header_map(name) => redact_all(value),
+                    Value::Null | Value::Object(_) => redact_secrets(value),
+                    _ if is_secret_field(name) => *value = Value::String(REDACTED.to_string()),
+                    _ => redact_secrets(value),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_secrets),
+        Value::String(s) => {
+            if let Ok(mut url) = Url::parse(s)
+                && url.password().is_some()
+                && url.set_password(Some(REDACTED)).is_ok()
+            {
+                *s = url.to_string();
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use vector_lib::event::MetricValue;
+
+/// The counters of a component, as shown by `vector top`.
+#[derive(Debug, Default, Serialize)]
+pub(super) struct ComponentMetrics {
+    pub(super) component_id: String,
+    pub(super) component_kind: String,
+    pub(super) component_type: String,
+    pub(super) received_events_total: f64,
+    pub(super) received_bytes_total: f64,
+    pub(super) sent_events_total: f64,
+    pub(super) sent_bytes_total: f64,
+    pub(super) discarded_events_total: f64,
+    pub(super) errors_total: f64,
+    /// Only reported when allocation tracing is enabled.
+    pub(super) allocated_bytes: Option<f64>,
+}
+
+/// A snapshot of the health of the local deployment.
+#[derive(Debug, Serialize)]
+pub(super) struct HealthSnapshot {
+    /// Whether the API reports the topology as running.
+    pub(super) healthy: bool,
+    pub(super) version: String,
+    pub(super) uptime_secs: Option<f64>,
+    pub(super) components: usize,
+    pub(super) errors_total: f64,
+}
+
+/// Sums the internal metrics of each component, optionally restricted to the components whose
+/// id contains `filter`.
+pub(super) fn capture_component_metrics(
+    filter: Option<&str>,
+) -> Result<Vec<ComponentMetrics>, String> {
+    let controller = crate::metrics::Controller::get()
+        .map_err(|e| format!("Internal metrics unavailable: {e}"))?;
+
+    let mut components: BTreeMap<String, ComponentMetrics> = BTreeMap::new();
+    for metric in controller.capture_metrics() {
+        let Some(component_id) = metric.tag_value("component_id") else {
+            continue;
+        };
+        if filter.is_some_and(|filter| !component_id.contains(filter)) {
+            continue;
+        }
+        let value = match metric.value() {
+            MetricValue::Counter { value } | MetricValue::Gauge { value } => *value,
+            _ => continue,
+        };
+
+        let component =
+            components
+                .entry(component_id.clone())
+                .or_insert_with(|| ComponentMetrics {
+                    component_id,
+                    component_kind: metric.tag_value("component_kind").unwrap_or_default(),
+                    component_type: metric.tag_value("component_type").unwrap_or_default(),
+                    ..Default::default()
+                });
+        match metric.name() {
+            "component_received_events_total" => component.received_events_total += value,
+            "component_received_bytes_total" => component.received_bytes_total += value,
+            "component_sent_events_total" => component.sent_events_total += value,
+            "component_sent_bytes_total" => component.sent_bytes_total += value,
+            "component_discarded_events_total" => component.discarded_events_total += value,
+            "component_errors_total" => component.errors_total += value,
+            "component_allocated_bytes" => *component.allocated_bytes.get_or_insert(0.0) += value,
+            _ => {}
+        }
+    }
+
+    Ok(components.into_values().collect())
+}
+
+/// Reads the uptime of the process from the internal metrics.
+pub(super) fn uptime_secs() -> Option<f64> {
+    crate::metrics::Controller::get()
+        .ok()?
+        .capture_metrics()
+        .into_iter()
+        .find(|metric| metric.name() == "uptime_seconds")
+        .and_then(|metric| match metric.value() {
+            MetricValue::Gauge { value } => Some(*value),
+            _ => None,
+        })
+}
@@ -0,0 +1,67 @@
+use serde::Serialize;
+use serde_json::Value;
+use vector_lib::{TimeZone, enrichment::TableRegistry, schema::Definition};
+use vrl::compiler::runtime::{Runtime, Terminate};
+
+use crate::{
+    config::LogNamespace,
+    event::{Event, LogEvent, TargetEvents, VrlTarget},
+    transforms::remap::RemapConfig,
+};
+
+/// The outcome of a VRL program run against an event.
+#[derive(Debug, Serialize)]
+pub(super) struct VrlEvaluation {
+    /// The value of the last expression, none when the program failed.
+    pub(super) result: Option<Value>,
+    /// The runtime error or abort message.
+    pub(super) error: Option<String>,
+    /// The events resulting from the program.
+    pub(super) events: Vec<Value>,
+}
+
+/// Compiles the VRL program and runs it against the event. Compilation errors are returned as
+/// errors, while runtime errors are part of the evaluation.
+pub(super) fn evaluate(source: &str, event: Value) -> Result<VrlEvaluation, String> {
+    let log = LogEvent::try_from(event).map_err(|e| format!("Invalid event: {e}"))?;
+
+    let (program, _, _) = RemapConfig {
+        source: Some(source.to_string()),
+        ..Default::default()
+    }
+    .compile_vrl_program(
+        TableRegistry::default(),
+        Default::default(),
+        Definition::default_legacy_namespace(),
+        None,
+    )
+    .map_err(|e| e.to_string())?;
+
+    let mut target = VrlTarget::new(Event::Log(log), program.info(), false);
+    let (result, error) =
+        match Runtime::default().resolve(&mut target, &program, &TimeZone::default()) {
+            Ok(value) => (
+                Some(serde_json::to_value(&value).map_err(|e| e.to_string())?),
+                None,
+            ),
+            Err(Terminate::Abort(error)) => (None, Some(format!("aborted: {error}"))),
+            Err(Terminate::Error(error)) => (None, Some(error.to_string())),
+        };
+
+    let events: Vec<Event> = match target.into_events(LogNamespace::Legacy) {
+        TargetEvents::One(event) => vec![event],
+        TargetEvents::Logs(events) => events.collect(),
+        TargetEvents::Traces(events) => events.collect(),
+    };
+    let events = events
+        .into_iter()
+        .filter_map(Event::try_into_log)
+        .map(|log| serde_json::to_value(&log).map_err(|e| e.to_string()))
+        .collect::<Result<_, _>>()?;
+
+    Ok(VrlEvaluation {
+        result,
+        error,
+        events,
+    })
+}
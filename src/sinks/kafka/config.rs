@@ -4,7 +4,8 @@ use futures::FutureExt;
 use rdkafka::ClientConfig;
 use serde_with::serde_as;
 use vector_lib::{
-    codecs::JsonSerializerConfig, configurable::configurable_component,
+    codecs::{JsonSerializerConfig, SchemaRegistryConfig, encoding::Serializer},
+    configurable::configurable_component,
     lookup::lookup_v2::ConfigTargetPath,
 };
 use vrl::value::Kind;
@@ -66,6 +67,15 @@ pub struct KafkaSinkConfig {
     #[configurable(derived)]
     pub encoding: EncodingConfig,
 
+    /// The schema registry holding the schema used to encode the events.
+    ///
+    /// When set, the events are encoded in the Confluent wire format, prefixed by the id of their
+    /// schema, with the schema fetched from the registry when the sink starts. Only the `avro` and
+    /// `protobuf` encoding codecs are supported. The registered schema replaces the one of their
+    /// `avro` or `protobuf` options, which can then be left out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_registry: Option<KafkaSchemaRegistryConfig>,
+
     // These batching options will **not** override librdkafka_options values.
     #[configurable(derived)]
     #[configurable(metadata(docs::advanced))]
@@ -139,6 +149,27 @@ pub struct KafkaSinkConfig {
     pub acknowledgements: AcknowledgementsConfig,
 }
 
+/// Schema registry configuration for the `kafka` sink.
+#[configurable_component]
+#[derive(Clone, Debug)]
+pub struct KafkaSchemaRegistryConfig {
+    #[configurable(derived)]
+    #[serde(flatten)]
+    pub registry: SchemaRegistryConfig,
+
+    /// The id of the registered schema used to encode the events.
+    #[configurable(metadata(docs::examples = 1))]
+    pub schema_id: u32,
+
+    /// The name of the message type of the registered schema used to encode the events.
+    ///
+    /// Only used by the `protobuf` encoding codec. Defaults to the first message type of the
+    /// schema.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[configurable(metadata(docs::examples = "package.Message"))]
+    pub message_type: Option<String>,
+}
+
 const fn default_socket_timeout_ms() -> Duration {
     Duration::from_millis(60000) // default in librdkafka
 }
@@ -164,6 +195,22 @@ fn example_librdkafka_options() -> HashMap<String, String> {
 }
 
 impl KafkaSinkConfig {
+    pub(crate) async fn build_serializer(&self) -> crate::Result<Serializer> {
+        match &self.schema_registry {
+            Some(schema_registry) => {
+                self.encoding
+                    .config()
+                    .build_with_schema_registry(
+                        &schema_registry.registry.build()?,
+                        schema_registry.schema_id,
+                        schema_registry.message_type.as_deref(),
+                    )
+                    .await
+            }
+            None => self.encoding.build(),
+        }
+    }
+
     pub(crate) fn to_rdkafka(&self) -> crate::Result<ClientConfig> {
         let mut client_config = ClientConfig::new();
         client_config
@@ -261,6 +308,7 @@ impl GenerateConfig for KafkaSinkConfig {
             healthcheck_topic: None,
             key_field: Some(ConfigTargetPath::try_from("user_id".to_owned()).unwrap()),
             encoding: JsonSerializerConfig::default().into(),
+            schema_registry: None,
             batch: Default::default(),
             compression: KafkaCompression::None,
             auth: Default::default(),
@@ -281,7 +329,7 @@ impl GenerateConfig for KafkaSinkConfig {
 impl SinkConfig for KafkaSinkConfig {
     async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
         let healthcheck_clone = cx.healthcheck.clone();
-        let sink = KafkaSink::new(self.clone(), cx)?;
+        let sink = KafkaSink::new(self.clone(), cx).await?;
         let hc = healthcheck(self.clone(), healthcheck_clone).boxed();
         Ok((VectorSink::from_event_streamsink(sink), hc))
     }
@@ -300,10 +348,79 @@ impl SinkConfig for KafkaSinkConfig {
 
 #[cfg(test)]
 mod tests {
+    use bytes::BytesMut;
+    use serde_json::json;
+    use tokio_util::codec::Encoder as _;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{method, path},
+    };
+
     use super::*;
 
     #[test]
     fn generate_config() {
         KafkaSinkConfig::generate_config();
     }
+
+    #[tokio::test]
+    async fn encodes_with_schema_registry() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/schemas/ids/1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "schema": r#"{"type": "record", "name": "log", "fields": [{"name": "message", "type": "string"}]}"#
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config: KafkaSinkConfig = toml::from_str(&format!(
+            r#"
+            bootstrap_servers = "localhost:9092"
+            topic = "topic"
+            encoding.codec = "avro"
+            schema_registry.url = "{}"
+            schema_registry.schema_id = 1
+            "#,
+            server.uri()
+        ))
+        .unwrap();
+        let mut serializer = config.build_serializer().await.unwrap();
+
+        let mut bytes = BytesMut::new();
+        serializer
+            .encode(LogEvent::from("hello").into(), &mut bytes)
+            .unwrap();
+        // The magic byte and schema id 1, then the Avro datum.
+        assert_eq!(&bytes[..], b"\x00\x00\x00\x00\x01\x0ahello");
+    }
+
+    #[tokio::test]
+    async fn schema_registry_requires_schema_codec() {
+        let config: KafkaSinkConfig = toml::from_str(
+            r#"
+            bootstrap_servers = "localhost:9092"
+            topic = "topic"
+            encoding.codec = "json"
+            schema_registry.url = "http://localhost:8081"
+            schema_registry.schema_id = 1
+            "#,
+        )
+        .unwrap();
+        assert!(config.build_serializer().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn schema_required_without_schema_registry() {
+        let config: KafkaSinkConfig = toml::from_str(
+            r#"
+            bootstrap_servers = "localhost:9092"
+            topic = "topic"
+            encoding.codec = "avro"
+            "#,
+        )
+        .unwrap();
+        assert!(config.build_serializer().await.is_err());
+    }
 }
@@ -63,6 +63,7 @@ async fn kafka_mezmo_does_not_reshape_messages() {
             JsonSerializerOptions::default(),
         )
         .into(),
+        schema_registry: None,
         batch: BatchConfig::default(),
         compression: KafkaCompression::None,
         auth: kafka_auth.clone(),
@@ -82,7 +83,9 @@ async fn kafka_mezmo_does_not_reshape_messages() {
     let (events, stream) = random_message_object_events_with_stream(100, num_events, Some(batch));
 
     assert_sink_compliance(&SINK_TAGS, async move {
-        let sink = KafkaSink::new(config, SinkContext::default()).unwrap();
+        let sink = KafkaSink::new(config, SinkContext::default())
+            .await
+            .unwrap();
         let sink = VectorSink::from_event_streamsink(sink);
         sink.run(stream).await
     })
@@ -178,6 +181,7 @@ async fn kafka_mezmo_reshapes_messages() {
             JsonSerializerOptions::default(),
         )
         .into(),
+        schema_registry: None,
         batch: BatchConfig::default(),
         compression: KafkaCompression::None,
         auth: kafka_auth.clone(),
@@ -198,7 +202,9 @@ async fn kafka_mezmo_reshapes_messages() {
         random_message_object_events_with_stream(100, num_events, Some(batch));
 
     assert_sink_compliance(&SINK_TAGS, async move {
-        let sink = KafkaSink::new(config, SinkContext::default()).unwrap();
+        let sink = KafkaSink::new(config, SinkContext::default())
+            .await
+            .unwrap();
         let sink = VectorSink::from_event_streamsink(sink);
         sink.run(stream).await
     })
@@ -46,10 +46,10 @@ pub(crate) fn create_producer(
 }
 
 impl KafkaSink {
-    pub(crate) fn new(config: KafkaSinkConfig, cx: SinkContext) -> crate::Result<Self> {
+    pub(crate) async fn new(config: KafkaSinkConfig, cx: SinkContext) -> crate::Result<Self> {
         let producer_config = config.to_rdkafka()?;
         let producer = create_producer(producer_config)?;
-        let serializer = config.encoding.build()?;
+        let serializer = config.build_serializer().await?;
         let transformer =
             Transformer::new_with_mezmo_reshape(config.encoding.transformer(), Some(&serializer));
         let encoder = Encoder::<()>::new(serializer);
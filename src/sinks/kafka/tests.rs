@@ -55,6 +55,7 @@ mod integration_test {
             healthcheck_topic: None,
             key_field: None,
             encoding: TextSerializerConfig::default().into(),
+            schema_registry: None,
             batch: BatchConfig::default(),
             compression: KafkaCompression::None,
             auth: KafkaAuthConfig::default(),
@@ -83,6 +84,7 @@ mod integration_test {
             healthcheck_topic: Some(String::from("topic-1234")),
             key_field: None,
             encoding: TextSerializerConfig::default().into(),
+            schema_registry: None,
             batch: BatchConfig::default(),
             compression: KafkaCompression::None,
             auth: KafkaAuthConfig::default(),
@@ -186,6 +188,7 @@ mod integration_test {
             compression: KafkaCompression::None,
             healthcheck_topic: None,
             encoding: TextSerializerConfig::default().into(),
+            schema_registry: None,
             key_field: None,
             auth: KafkaAuthConfig {
                 sasl: None,
@@ -202,7 +205,7 @@ mod integration_test {
         };
         config.clone().to_rdkafka()?;
         self::sink::healthcheck(config.clone(), Default::default()).await?;
-        KafkaSink::new(config, SinkContext::default())
+        KafkaSink::new(config, SinkContext::default()).await
     }
 
     #[tokio::test]
@@ -345,6 +348,7 @@ mod integration_test {
             healthcheck_topic: None,
             key_field: None,
             encoding: TextSerializerConfig::default().into(),
+            schema_registry: None,
             batch: BatchConfig::default(),
             compression,
             auth: kafka_auth.clone(),
@@ -380,7 +384,9 @@ mod integration_test {
 
         if test_telemetry_tags {
             assert_data_volume_sink_compliance(&DATA_VOLUME_SINK_TAGS, async move {
-                let sink = KafkaSink::new(config, SinkContext::default()).unwrap();
+                let sink = KafkaSink::new(config, SinkContext::default())
+                    .await
+                    .unwrap();
                 let sink = VectorSink::from_event_streamsink(sink);
                 sink.run(input_events).await
             })
@@ -388,7 +394,9 @@ mod integration_test {
             .expect("Running sink failed");
         } else {
             assert_sink_compliance(&SINK_TAGS, async move {
-                let sink = KafkaSink::new(config, SinkContext::default()).unwrap();
+                let sink = KafkaSink::new(config, SinkContext::default())
+                    .await
+                    .unwrap();
                 let sink = VectorSink::from_event_streamsink(sink);
                 sink.run(input_events).await
             })
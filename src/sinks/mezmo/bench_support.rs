@@ -0,0 +1,242 @@
+//! Benchmark support for the `mezmo` sink.
+//!
+//! Keeps the `HttpSink` based implementation the sink was migrated from, as the `mezmo_legacy`
+//! sink, so the stream-based sink can be benchmarked against it, both end to end and by the
+//! requests each of them builds from a batch of events.
+
+use std::time::SystemTime;
+
+use bytes::Bytes;
+use futures::{FutureExt, SinkExt, future};
+use http::Request;
+use serde_json::json;
+use vector_lib::{configurable::configurable_component, sensitive_string::SensitiveString};
+
+use super::{
+    config::MezmoConfig,
+    encoder::{MezmoEventEncoder, PartitionKey},
+    request_builder::MezmoRequestBuilder,
+    service::MezmoHttpRequestBuilder,
+};
+use crate::{
+    config::{AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext},
+    event::Event,
+    http::{Auth, HttpClient},
+    sinks::{
+        Healthcheck, VectorSink,
+        util::{
+            Batch, BoxedRawValue, JsonArrayBuffer, PartitionBuffer, PartitionInnerBuffer,
+            PushResult, RequestBuilder,
+            http::{HttpEventEncoder, HttpServiceRequestBuilder, HttpSink, PartitionHttpSink},
+        },
+    },
+};
+
+/// Configuration for the `mezmo_legacy` sink.
+#[configurable_component(sink(
+    "mezmo_legacy",
+    "Deliver log event data to Mezmo with the legacy `HttpSink` based sink."
+))]
+#[derive(Clone, Debug)]
+pub struct MezmoLegacyConfig(MezmoConfig);
+
+impl From<MezmoConfig> for MezmoLegacyConfig {
+    fn from(config: MezmoConfig) -> Self {
+        Self(config)
+    }
+}
+
+impl GenerateConfig for MezmoLegacyConfig {
+    fn generate_config() -> toml::Value {
+        <MezmoConfig as GenerateConfig>::generate_config()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "mezmo_legacy")]
+impl SinkConfig for MezmoLegacyConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let request_settings = self.0.request.into_settings();
+        let batch_settings = self.0.batch.into_batch_settings()?;
+        let client = HttpClient::new(None, cx.proxy())?;
+
+        let mezmo_sink = LegacyMezmoSink::new(&self.0, &cx);
+
+        let sink = PartitionHttpSink::new(
+            mezmo_sink,
+            PartitionBuffer::new(JsonArrayBuffer::new(batch_settings.size)),
+            request_settings,
+            batch_settings.timeout,
+            client,
+            cx,
+        )
+        .sink_map_err(|error| error!(message = "Fatal mezmo sink error.", %error, internal_log_rate_limit = false));
+
+        #[allow(deprecated)]
+        Ok((VectorSink::from_event_sink(sink), future::ok(()).boxed()))
+    }
+
+    fn input(&self) -> Input {
+        self.0.input()
+    }
+
+    fn acknowledgements(&self) -> &AcknowledgementsConfig {
+        self.0.acknowledgements()
+    }
+}
+
+/// The lines of the `HttpSink` based sink, encoded one at a time into a `JsonArrayBuffer`.
+struct LegacyMezmoEventEncoder(MezmoEventEncoder);
+
+impl HttpEventEncoder<PartitionInnerBuffer<serde_json::Value, PartitionKey>>
+    for LegacyMezmoEventEncoder
+{
+    fn encode_event(
+        &mut self,
+        event: Event,
+    ) -> Option<PartitionInnerBuffer<serde_json::Value, PartitionKey>> {
+        self.0
+            .encode_event(event)
+            .map(|(key, line)| PartitionInnerBuffer::new(line, key))
+    }
+}
+
+#[derive(Clone, derivative::Derivative)]
+#[derivative(Debug)]
+struct LegacyMezmoSink {
+    #[derivative(Debug = "ignore")]
+    cx: SinkContext,
+    cfg: MezmoConfig,
+}
+
+impl LegacyMezmoSink {
+    fn new(cfg: &MezmoConfig, cx: &SinkContext) -> Self {
+        Self {
+            cx: cx.clone(),
+            cfg: cfg.clone(),
+        }
+    }
+}
+
+impl HttpSink for LegacyMezmoSink {
+    type Input = PartitionInnerBuffer<serde_json::Value, PartitionKey>;
+    type Output = PartitionInnerBuffer<Vec<BoxedRawValue>, PartitionKey>;
+    type Encoder = LegacyMezmoEventEncoder;
+
+    fn build_encoder(&self) -> Self::Encoder {
+        LegacyMezmoEventEncoder(self.cfg.build_encoder(&self.cx))
+    }
+
+    async fn build_request(&self, output: Self::Output) -> crate::Result<Request<Bytes>> {
+        let (events, key) = output.into_parts();
+        let mut query = url::form_urlencoded::Serializer::new(String::new());
+
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time can't drift behind the epoch!")
+            .as_millis();
+
+        query.append_pair("hostname", &key.hostname);
+        query.append_pair("now", &now.to_string());
+
+        if let Some(mac) = &key.mac {
+            query.append_pair("mac", mac);
+        }
+
+        if let Some(ip) = &key.ip {
+            query.append_pair("ip", ip);
+        }
+
+        if let Some(tags) = &key.tags {
+            let tags = tags.join(",");
+            query.append_pair("tags", &tags);
+        }
+
+        let query = query.finish();
+
+        let body = crate::serde::json::to_bytes(&json!({
+            "lines": events,
+        }))
+        .unwrap()
+        .freeze();
+
+        let uri = self.cfg.build_uri(&query);
+
+        let mut request = Request::builder()
+            .uri(uri)
+            .method("POST")
+            .header("Content-Type", "application/json")
+            .header("x-response-format", "pipeline")
+            .body(body)
+            .unwrap();
+
+        let auth = Auth::Basic {
+            user: self.cfg.api_key.inner().to_string(),
+            password: SensitiveString::default(),
+        };
+
+        auth.apply(&mut request);
+
+        Ok(request)
+    }
+}
+
+/// Builds the ingestion request of `events` as the `HttpSink` based sink does: the events are
+/// encoded into a `JsonArrayBuffer`, which is then turned into a request. The events must all be
+/// sent in the same request.
+pub async fn build_legacy_request(config: &MezmoConfig, events: Vec<Event>) -> Request<Bytes> {
+    let sink = LegacyMezmoSink::new(config, &SinkContext::default());
+    let batch_settings = config
+        .batch
+        .into_batch_settings()
+        .expect("Batch settings should be valid");
+
+    let mut encoder = sink.build_encoder();
+    let mut buffer = PartitionBuffer::new(JsonArrayBuffer::new(batch_settings.size));
+    for event in events {
+        if let Some(line) = encoder.encode_event(event) {
+            assert!(
+                matches!(buffer.push(line), PushResult::Ok(_)),
+                "The events should fit in a single request"
+            );
+        }
+    }
+
+    sink.build_request(buffer.finish())
+        .await
+        .expect("Building the request should succeed")
+}
+
+/// Builds the ingestion request of `events` as the stream-based sink does. The events must all be
+/// sent in the same request.
+pub fn build_request(config: &MezmoConfig, events: Vec<Event>) -> Request<Bytes> {
+    let encoder = config.build_encoder(&SinkContext::default());
+    let lines: Vec<_> = events
+        .into_iter()
+        .filter_map(|event| encoder.encode_line(event))
+        .collect();
+    let key = lines
+        .first()
+        .expect("At least one event should be encoded")
+        .key
+        .clone();
+
+    let request_builder = MezmoRequestBuilder {
+        encoder: Default::default(),
+        compression: config.compression,
+    };
+    let (metadata, request_metadata_builder, lines) = request_builder.split_input((key, lines));
+    let payload = request_builder
+        .encode_events(lines)
+        .expect("Encoding the lines should succeed");
+    let request_metadata = request_metadata_builder.build(&payload);
+    let request = request_builder.build_request(metadata, request_metadata, payload);
+
+    MezmoHttpRequestBuilder {
+        uri: config.build_uri(""),
+        api_key: config.api_key.clone(),
+        compression: config.compression,
+    }
+    .build(request)
+    .expect("Building the request should succeed")
+}
@@ -0,0 +1,390 @@
+use futures::FutureExt;
+use http::{Request, StatusCode, Uri};
+use tower::ServiceBuilder;
+use vector_lib::{configurable::configurable_component, sensitive_string::SensitiveString};
+use vrl::value::Kind;
+
+use super::{
+    encoder::MezmoEventEncoder,
+    request_builder::MezmoRequestBuilder,
+    service::{MezmoHttpRequestBuilder, MezmoService},
+    sink::MezmoSink,
+};
+use crate::{
+    codecs::Transformer,
+    config::{AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext},
+    http::HttpClient,
+    schema,
+    sinks::{
+        Healthcheck, VectorSink,
+        util::{
+            BatchConfig, Compression, RealtimeSizeBasedDefaultBatchSettings, ServiceBuilderExt,
+            TowerRequestConfig, UriSerde, http::http_response_retry_logic,
+        },
+    },
+    template::Template,
+};
+
+const DEFAULT_ROUTE: &str = "logs/ingest";
+const DEFAULT_MAX_LINE_BYTES: usize = 32_768;
+
+/// Configuration for the `logdna` sink.
+#[configurable_component(sink("logdna", "Deliver log event data to LogDNA."))]
+#[configurable(metadata(
+    deprecated = "The `logdna` sink has been renamed. Please use `mezmo` instead."
+))]
+#[derive(Clone, Debug)]
+pub struct LogdnaConfig(MezmoConfig);
+
+impl GenerateConfig for LogdnaConfig {
+    fn generate_config() -> toml::Value {
+        <MezmoConfig as GenerateConfig>::generate_config()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "logdna")]
+impl SinkConfig for LogdnaConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        warn!("DEPRECATED: The `logdna` sink has been renamed. Please use `mezmo` instead.");
+        self.0.build(cx).await
+    }
+
+    fn input(&self) -> Input {
+        self.0.input()
+    }
+
+    fn acknowledgements(&self) -> &AcknowledgementsConfig {
+        self.0.acknowledgements()
+    }
+}
+
+/// Configuration for the `mezmo` (formerly `logdna`) sink.
+#[configurable_component(sink("mezmo", "Deliver log event data to Mezmo."))]
+#[derive(Clone, Debug)]
+pub struct MezmoConfig {
+    /// Connection config
+    /// The Ingestion API key.
+    #[configurable(metadata(docs::examples = "${LOGDNA_API_KEY}"))]
+    #[configurable(metadata(docs::examples = "ef8d5de700e7989468166c40fc8a0ccd"))]
+    pub(super) api_key: SensitiveString,
+
+    /// The HTTP endpoint to send logs to.
+    ///
+    /// Both IP address and hostname are accepted formats.
+    #[serde(alias = "host")]
+    #[serde(default = "default_endpoint")]
+    #[configurable(metadata(docs::examples = "http://127.0.0.1"))]
+    #[configurable(metadata(docs::examples = "http://example.com"))]
+    pub(super) endpoint: UriSerde,
+
+    /// The HTTP route to use for the ingestion endpoint
+    #[serde(default = "default_route")]
+    route: String,
+
+    /// Line object config options
+    /// Whether or not to use the entire message object as the line,
+    /// mutually exclusive with below line options
+    use_message_as_line: Option<bool>,
+
+    /// Optional line field selector, only one of `line_field` and `line_template` can be specified
+    line_field: Option<String>,
+
+    /// Optional line template, only one of `line_field` and `line_template` can be specified
+    line_template: Option<Template>,
+
+    /// Optional meta field location
+    meta_field: Option<String>,
+
+    /// Optional field selector for the log line's timestamp
+    timestamp_field: Option<String>,
+
+    /// Optional app template
+    app_template: Option<Template>,
+
+    /// Optional template for the file that supplied the log line
+    file_template: Option<Template>,
+
+    /// Optional template for the environment the log line came from
+    env_template: Option<Template>,
+
+    /// field selector for the originating user agent to forward to Mezmo
+    #[configurable(metadata(docs::examples = ".metadata.headers.\"user-agent\""))]
+    #[serde(default = "default_originating_user_agent_field")]
+    originating_user_agent_field: String,
+
+    /// Query config options
+    /// The hostname that will be attached to each batch of events.
+    #[configurable(metadata(docs::examples = "${HOSTNAME}"))]
+    #[configurable(metadata(docs::examples = "my-local-machine"))]
+    hostname: Template,
+
+    /// Template used for MAC addressing
+    mac_template: Option<Template>,
+
+    /// The MAC address that will be attached to each batch of events.
+    #[configurable(metadata(docs::examples = "my-mac-address"))]
+    #[configurable(metadata(docs::human_name = "MAC Address"))]
+    mac: Option<String>,
+
+    /// Templating used for IP addressing
+    ip_template: Option<Template>,
+
+    /// The IP address that will be attached to each batch of events.
+    #[configurable(metadata(docs::examples = "0.0.0.0"))]
+    #[configurable(metadata(docs::human_name = "IP Address"))]
+    ip: Option<String>,
+
+    /// The tags that are attached to each batch of events.
+    #[configurable(metadata(docs::examples = "tag1"))]
+    #[configurable(metadata(docs::examples = "tag2"))]
+    tags: Option<Vec<Template>>,
+
+    #[configurable(derived)]
+    #[serde(default, skip_serializing_if = "crate::serde::is_default")]
+    pub encoding: Transformer,
+
+    /// The default app that is set for events that do not contain a `file` or `app` field.
+    #[serde(default = "default_app")]
+    #[configurable(metadata(docs::examples = "my-app"))]
+    default_app: String,
+
+    /// The default environment that is set for events that do not contain an `env` field.
+    #[serde(default = "default_env")]
+    #[configurable(metadata(docs::examples = "staging"))]
+    default_env: String,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub(super) batch: BatchConfig<RealtimeSizeBasedDefaultBatchSettings>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub(super) request: TowerRequestConfig,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub(super) compression: Compression,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    oversized_lines: OversizedLinesConfig,
+
+    #[configurable(derived)]
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::is_default"
+    )]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+/// What to do with lines over the ingestion API's size limit.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OversizedLineAction {
+    /// Send the line as is, leaving it to the ingestion API to reject it.
+    #[default]
+    Reject,
+
+    /// Truncate the line to `max_bytes`.
+    Truncate,
+
+    /// Split the line into several lines of up to `max_bytes`, each with the same metadata.
+    Split,
+}
+
+/// Client-side handling of oversized lines.
+#[configurable_component]
+#[derive(Clone, Copy, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct OversizedLinesConfig {
+    #[configurable(derived)]
+    #[serde(default)]
+    pub action: OversizedLineAction,
+
+    /// The maximum size of the `line` of an event, in bytes.
+    ///
+    /// Only string lines are truncated or split, at UTF-8 character boundaries.
+    #[serde(default = "default_max_line_bytes")]
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    pub max_bytes: usize,
+}
+
+impl Default for OversizedLinesConfig {
+    fn default() -> Self {
+        Self {
+            action: OversizedLineAction::default(),
+            max_bytes: default_max_line_bytes(),
+        }
+    }
+}
+
+const fn default_max_line_bytes() -> usize {
+    DEFAULT_MAX_LINE_BYTES
+}
+
+fn default_endpoint() -> UriSerde {
+    UriSerde {
+        uri: Uri::from_static("https://logs.mezmo.com"),
+        auth: None,
+    }
+}
+
+fn default_app() -> String {
+    "vector".to_owned()
+}
+
+fn default_route() -> String {
+    DEFAULT_ROUTE.to_owned()
+}
+
+fn default_env() -> String {
+    "production".to_owned()
+}
+
+fn default_originating_user_agent_field() -> String {
+    ".metadata.headers.\"user-agent\"".to_owned()
+}
+
+impl GenerateConfig for MezmoConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"hostname = "hostname"
+            api_key = "${LOGDNA_API_KEY}""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "mezmo")]
+impl SinkConfig for MezmoConfig {
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        if self.use_message_as_line.unwrap_or(false)
+            && (self.line_field.is_some()
+                || self.line_template.is_some()
+                || self.meta_field.is_some()
+                || self.timestamp_field.is_some()
+                || self.app_template.is_some()
+                || self.file_template.is_some()
+                || self.env_template.is_some())
+        {
+            return Err(
+                "`use_message_as_line` may not be specified with other line config options".into(),
+            );
+        }
+
+        if self.line_field.is_some() && self.line_template.is_some() {
+            return Err("only one of `line_field` and `line_template` can be provided".into());
+        }
+
+        if !matches!(
+            self.compression,
+            Compression::None | Compression::Gzip(_) | Compression::Zstd(_)
+        ) {
+            return Err("only `gzip` and `zstd` compression are supported".into());
+        }
+
+        if self.oversized_lines.max_bytes == 0 {
+            return Err("`oversized_lines.max_bytes` must be greater than zero".into());
+        }
+
+        let request_settings = self.request.into_settings();
+        let batch_settings = self.batch.into_batcher_settings()?;
+        let client = HttpClient::new(None, cx.proxy())?;
+
+        let http_request_builder = MezmoHttpRequestBuilder {
+            uri: self.build_uri(""),
+            api_key: self.api_key.clone(),
+            compression: self.compression,
+        };
+        let service = ServiceBuilder::new()
+            .settings(request_settings, http_response_retry_logic())
+            .service(MezmoService::new(
+                client.clone(),
+                http_request_builder,
+                cx.mezmo_ctx.clone(),
+            ));
+
+        let request_builder = MezmoRequestBuilder {
+            encoder: Default::default(),
+            compression: self.compression,
+        };
+
+        let sink = MezmoSink {
+            service,
+            encoder: self.build_encoder(&cx),
+            request_builder,
+            batch_settings,
+        };
+
+        let healthcheck = healthcheck(self.clone(), client).boxed();
+
+        Ok((VectorSink::from_event_streamsink(sink), healthcheck))
+    }
+
+    fn input(&self) -> Input {
+        let requirement = schema::Requirement::empty()
+            .optional_meaning("timestamp", Kind::timestamp())
+            .optional_meaning("message", Kind::bytes());
+
+        Input::log().with_schema_requirement(requirement)
+    }
+
+    fn acknowledgements(&self) -> &AcknowledgementsConfig {
+        &self.acknowledgements
+    }
+}
+
+impl MezmoConfig {
+    pub(super) fn build_encoder(&self, cx: &SinkContext) -> MezmoEventEncoder {
+        MezmoEventEncoder {
+            mezmo_ctx: cx.mezmo_ctx.clone(),
+            use_message_as_line: self.use_message_as_line,
+            line_field: self.line_field.clone(),
+            line_template: self.line_template.clone(),
+            meta_field: self.meta_field.clone(),
+            timestamp_field: self.timestamp_field.clone(),
+            app_template: self.app_template.clone(),
+            file_template: self.file_template.clone(),
+            env_template: self.env_template.clone(),
+            hostname: self.hostname.clone(),
+            tags: self.tags.clone(),
+            ip_template: self.ip_template.clone(),
+            mac_template: self.mac_template.clone(),
+            transformer: self.encoding.clone(),
+            default_app: self.default_app.clone(),
+            default_env: self.default_env.clone(),
+            originating_user_agent_field: self.originating_user_agent_field.clone(),
+            oversized_lines: self.oversized_lines,
+        }
+    }
+
+    pub(super) fn build_uri(&self, query: &str) -> Uri {
+        let host = &self.endpoint.uri;
+
+        let uri = format!("{}{}?{}", host, self.route.trim_matches('/'), query);
+
+        uri.parse::<http::Uri>()
+            .expect("This should be a valid uri")
+    }
+}
+
+async fn healthcheck(config: MezmoConfig, client: HttpClient) -> crate::Result<()> {
+    let uri = config.build_uri("");
+    let req = Request::post(uri).body(hyper::Body::empty()).unwrap();
+
+    let res = client.send(req).await?;
+
+    if res.status().is_server_error() {
+        return Err("Server returned a server error".into());
+    }
+
+    if res.status() == StatusCode::FORBIDDEN {
+        return Err("Token is not valid, 403 returned.".into());
+    }
+
+    Ok(())
+}
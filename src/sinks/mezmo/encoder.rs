@@ -0,0 +1,436 @@
+use std::collections::BTreeMap;
+
+use serde_json::json;
+use vector_lib::{
+    ByteSizeOf, EstimatedJsonEncodedSizeOf,
+    event::{EventFinalizers, Finalizable},
+    internal_event::TaggedEventsSent,
+    json_size::JsonSize,
+    lookup::PathPrefix,
+    request_metadata::GetEventCountTags,
+    stream::batcher::limiter::ItemBatchSize,
+};
+use vrl::value::Value;
+
+use super::config::{OversizedLineAction, OversizedLinesConfig};
+use crate::{
+    codecs::Transformer,
+    event::Event,
+    sinks::util::BoxedRawValue,
+    template::{Template, TemplateRenderingError},
+};
+use mezmo::{MezmoContext, user_log_error, user_trace::MezmoUserLog};
+
+const LINE_KEY: &str = "line";
+const META_KEY: &str = "meta";
+const TIMESTAMP_KEY: &str = "timestamp";
+const APP_KEY: &str = "app";
+const FILE_KEY: &str = "file";
+const ENV_KEY: &str = "env";
+pub(super) const ORIGINATING_USER_AGENT_KEY: &str = "_originating_user_agent";
+const DEFAULT_VALUE: Value = Value::Null;
+
+/// The query parameters of an ingestion request, shared by all of the lines it sends.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PartitionKey {
+    pub(super) hostname: String,
+    pub(super) tags: Option<Vec<String>>,
+    pub(super) ip: Option<String>,
+    pub(super) mac: Option<String>,
+}
+
+/// Reshapes events into Mezmo ingestion lines.
+pub struct MezmoEventEncoder {
+    pub(super) mezmo_ctx: Option<MezmoContext>,
+    pub(super) use_message_as_line: Option<bool>,
+    pub(super) line_field: Option<String>,
+    pub(super) line_template: Option<Template>,
+    pub(super) meta_field: Option<String>,
+    pub(super) timestamp_field: Option<String>,
+    pub(super) app_template: Option<Template>,
+    pub(super) file_template: Option<Template>,
+    pub(super) env_template: Option<Template>,
+    pub(super) hostname: Template,
+    pub(super) tags: Option<Vec<Template>>,
+    pub(super) ip_template: Option<Template>,
+    pub(super) mac_template: Option<Template>,
+    pub(super) transformer: Transformer,
+    pub(super) default_app: String,
+    pub(super) default_env: String,
+    pub(super) originating_user_agent_field: String,
+    pub(super) oversized_lines: OversizedLinesConfig,
+}
+
+impl MezmoEventEncoder {
+    pub(super) fn render_key(&self, event: &Event) -> Option<PartitionKey> {
+        let tags = self
+            .tags
+            .as_ref()
+            .map(|tags| {
+                let mut vec = Vec::with_capacity(tags.len());
+                for tag in tags {
+                    let t = tag.render_string(event);
+                    match t {
+                        Ok(t) => {
+                            let tags = serde_json::from_str(&t).unwrap_or_else(|_| vec![t]);
+                            vec.extend_from_slice(&tags);
+                        }
+                        Err(error) => {
+                            self.log_template_error("tag", error, false);
+                        }
+                    }
+                }
+                if !vec.is_empty() { Some(vec) } else { None }
+            })
+            .unwrap_or(None);
+        let ip = self
+            .ip_template
+            .as_ref()
+            .map(|i| {
+                let m = i.render_string(event);
+                match m {
+                    Ok(m) => Some(m),
+                    Err(error) => {
+                        self.log_template_error("ip", error, false);
+                        None
+                    }
+                }
+            })
+            .unwrap_or(None);
+        let mac = self
+            .mac_template
+            .as_ref()
+            .map(|m| {
+                let s = m.render_string(event);
+                match s {
+                    Ok(s) => Some(s),
+                    Err(error) => {
+                        self.log_template_error("mac", error, false);
+                        None
+                    }
+                }
+            })
+            .unwrap_or(None);
+
+        let hostname_result = self.hostname.render_string(event);
+        match hostname_result {
+            Ok(hostname) => Some(PartitionKey {
+                hostname,
+                tags,
+                ip,
+                mac,
+            }),
+            Err(error) => {
+                self.log_template_error("hostname", error, false);
+                None // hostname is required by ingest API, so fail key generation without it
+            }
+        }
+    }
+    fn log_template_error(&self, field: &str, error: TemplateRenderingError, drop_event: bool) {
+        emit!(crate::internal_events::TemplateRenderingError {
+            error: error.clone(),
+            field: Some(field),
+            drop_event,
+        });
+        user_log_error!(
+            self.mezmo_ctx,
+            Value::from(format!("{field} template error - {error}"))
+        );
+    }
+
+    /// Encodes an event as an ingestion line, along with the key of the batch it's sent in. Events
+    /// are dropped when their hostname can't be rendered.
+    pub(super) fn encode_event(
+        &self,
+        mut event: Event,
+    ) -> Option<(PartitionKey, serde_json::Value)> {
+        let key = self.render_key(&event)?;
+        let message_key = crate::config::log_schema()
+            .message_key_target_path()
+            .unwrap();
+
+        self.transformer.transform(&mut event);
+        let mut log = event.into_log();
+
+        let mut map = serde_json::map::Map::new();
+
+        if let Some(user_agent) = log.get(self.originating_user_agent_field.as_str()) {
+            map.insert(ORIGINATING_USER_AGENT_KEY.to_string(), json!(user_agent));
+        }
+
+        if self.use_message_as_line.unwrap_or(false) {
+            log.remove(message_key)
+                .unwrap_or(Value::Object(BTreeMap::new()))
+                .as_object()
+                .unwrap_or(&BTreeMap::new())
+                .iter()
+                .for_each(|(key, value)| {
+                    let map_key = key.to_string();
+                    // Ensure the line property is a string
+                    // Note: Value stores strings as bytes
+                    if map_key == *LINE_KEY && !value.is_bytes() {
+                        map.insert(map_key, json!(value.to_string()));
+                    } else {
+                        map.insert(map_key, json!(value));
+                    }
+                });
+        } else {
+            let mut paths_to_remove = Vec::new();
+            // line
+            if let Some(line_template) = &self.line_template {
+                match line_template.render_string(&log) {
+                    Ok(line) => {
+                        // Remove the template parts later so we don't put them in the meta
+                        let parts = line_template.get_fields().unwrap_or_default();
+                        for path in &parts {
+                            paths_to_remove.push(path.to_owned());
+                        }
+                        map.insert(LINE_KEY.to_string(), json!(line));
+                    }
+                    Err(error) => {
+                        self.log_template_error("line", error, true);
+                    }
+                };
+            } else if let Some(path) = &self.line_field {
+                paths_to_remove.push(path.to_string());
+                let line = log.get(path.as_str()).unwrap_or(&DEFAULT_VALUE);
+                match line.is_object() {
+                    false => map.insert(LINE_KEY.to_string(), json!(line)),
+                    true => {
+                        let encoded = serde_json::to_string(&line)
+                            .ok()
+                            .unwrap_or_else(|| "".into());
+                        map.insert(LINE_KEY.to_string(), json!(encoded))
+                    }
+                };
+            }
+            // meta
+            if let Some(path) = &self.meta_field
+                && let Some(meta) = log.get(path.as_str())
+            {
+                paths_to_remove.push(path.to_string());
+                map.insert(META_KEY.to_string(), json!(meta));
+            }
+            // timestamp
+            if let Some(path) = &self.timestamp_field {
+                if let Some(ts) = log.get(path.as_str()) {
+                    paths_to_remove.push(path.to_string());
+                    map.insert(TIMESTAMP_KEY.to_string(), json!(ts));
+                }
+            } else {
+                let timestamp = match crate::config::log_schema().timestamp_key() {
+                    Some(timestamp_key) => match log.remove((PathPrefix::Event, timestamp_key)) {
+                        Some(timestamp) => timestamp,
+                        None => chrono::Utc::now().into(),
+                    },
+                    None => chrono::Utc::now().into(),
+                };
+                map.insert(TIMESTAMP_KEY.to_string(), json!(timestamp));
+            }
+            // app
+            if let Some(app_template) = &self.app_template {
+                match app_template.render_string(&log) {
+                    Ok(app) => {
+                        // Remove the template parts so we don't put them in the meta
+                        let parts = app_template.get_fields().unwrap_or_default();
+                        for path in &parts {
+                            paths_to_remove.push(path.to_owned());
+                        }
+                        map.insert(APP_KEY.to_string(), json!(app));
+                    }
+                    Err(error) => {
+                        self.log_template_error("app", error, false);
+                    }
+                };
+            }
+            // file
+            if let Some(file_template) = &self.file_template {
+                match file_template.render_string(&log) {
+                    Ok(file) => {
+                        // Remove the template parts so we don't put them in the meta
+                        let parts = file_template.get_fields().unwrap_or_default();
+                        for path in &parts {
+                            paths_to_remove.push(path.to_owned());
+                        }
+                        map.insert(FILE_KEY.to_string(), json!(file));
+                    }
+                    Err(error) => {
+                        self.log_template_error("file", error, false);
+                    }
+                };
+            }
+            // app fallback
+            if !map.contains_key(APP_KEY) && !map.contains_key(FILE_KEY) {
+                map.insert(APP_KEY.to_string(), json!(self.default_app));
+            }
+            // env
+            if let Some(env_template) = &self.env_template {
+                match env_template.render_string(&log) {
+                    Ok(env) => {
+                        // Remove the template parts so we don't put them in the meta
+                        let parts = env_template.get_fields().unwrap_or_default();
+                        for path in &parts {
+                            paths_to_remove.push(path.to_owned());
+                        }
+                        map.insert(ENV_KEY.to_string(), json!(env));
+                    }
+                    Err(error) => {
+                        self.log_template_error("env", error, false);
+                    }
+                };
+            }
+            if !map.contains_key(ENV_KEY) {
+                map.insert(ENV_KEY.to_string(), json!(self.default_env));
+            }
+            //
+            // Handle catch-all cases
+            //
+            // Remove used properties
+            for path in paths_to_remove {
+                log.remove(path.as_str());
+            }
+            // Handle the default whole message as line or remaining message as meta cases
+            //  after removing other used properties if either is unassigned
+            let catch_all_key = if !map.contains_key(LINE_KEY) {
+                Some(LINE_KEY)
+            } else if !map.contains_key(META_KEY) {
+                Some(META_KEY)
+            } else {
+                None
+            };
+            if let (Some(message), Some(catch_all_key)) = (log.remove(message_key), catch_all_key) {
+                if message.is_object() {
+                    let encoded = serde_json::to_string(&message)
+                        .ok()
+                        .unwrap_or_else(|| "".into());
+                    map.insert(catch_all_key.to_string(), json!(encoded));
+                } else {
+                    map.insert(catch_all_key.to_string(), json!(message));
+                }
+            }
+        };
+
+        Some((key, map.into()))
+    }
+
+    /// Encodes an event as serialized ingestion lines, keeping what the request metadata needs
+    /// from the original event. Events are only encoded as several lines when their oversized
+    /// line is split.
+    pub(super) fn encode_line(&self, mut event: Event) -> Option<MezmoLine> {
+        let finalizers = event.take_finalizers();
+        let byte_size = event.allocated_bytes();
+        let json_size = event.estimated_json_encoded_size_of();
+        let tags = event.get_tags();
+
+        let (key, line) = self.encode_event(event)?;
+        let lines = match self
+            .limit_line(line)
+            .iter()
+            .map(serde_json::value::to_raw_value)
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(lines) => lines,
+            Err(error) => {
+                error!(message = "Failed to serialize Mezmo line.", %error);
+                return None;
+            }
+        };
+
+        Some(MezmoLine {
+            key,
+            lines,
+            finalizers,
+            byte_size,
+            json_size,
+            tags,
+        })
+    }
+
+    /// Truncates or splits the `line` of an encoded event when it's over the configured size.
+    pub(super) fn limit_line(&self, mut line: serde_json::Value) -> Vec<serde_json::Value> {
+        let OversizedLinesConfig { action, max_bytes } = self.oversized_lines;
+        let text = match line.get_mut(LINE_KEY) {
+            Some(serde_json::Value::String(text)) if text.len() > max_bytes => text,
+            _ => return vec![line],
+        };
+
+        match action {
+            OversizedLineAction::Reject => vec![line],
+            OversizedLineAction::Truncate => {
+                let end = char_boundary_before(text, max_bytes);
+                text.truncate(end);
+                vec![line]
+            }
+            OversizedLineAction::Split => {
+                let text = std::mem::take(text);
+                let mut rest = text.as_str();
+                let mut lines = Vec::with_capacity(text.len().div_ceil(max_bytes));
+                while !rest.is_empty() {
+                    let (chunk, tail) = rest.split_at(char_boundary_before(rest, max_bytes));
+                    let mut chunk_line = line.clone();
+                    chunk_line[LINE_KEY] = serde_json::Value::from(chunk);
+                    lines.push(chunk_line);
+                    rest = tail;
+                }
+                lines
+            }
+        }
+    }
+}
+
+/// The largest char boundary of `text` up to `max`, taking at least one character so that
+/// splitting always makes progress.
+fn char_boundary_before(text: &str, max: usize) -> usize {
+    if text.len() <= max {
+        return text.len();
+    }
+    match (1..=max).rev().find(|&end| text.is_char_boundary(end)) {
+        Some(end) => end,
+        None => text.chars().next().map_or(0, char::len_utf8),
+    }
+}
+
+/// An event encoded as one or more ingestion lines.
+pub struct MezmoLine {
+    pub(super) key: PartitionKey,
+    pub(super) lines: Vec<BoxedRawValue>,
+    finalizers: EventFinalizers,
+    byte_size: usize,
+    json_size: JsonSize,
+    tags: TaggedEventsSent,
+}
+
+impl Finalizable for MezmoLine {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        std::mem::take(&mut self.finalizers)
+    }
+}
+
+impl ByteSizeOf for MezmoLine {
+    fn allocated_bytes(&self) -> usize {
+        self.byte_size
+    }
+}
+
+impl EstimatedJsonEncodedSizeOf for MezmoLine {
+    fn estimated_json_encoded_size_of(&self) -> JsonSize {
+        self.json_size
+    }
+}
+
+impl GetEventCountTags for MezmoLine {
+    fn get_tags(&self) -> TaggedEventsSent {
+        self.tags.clone()
+    }
+}
+
+/// Sizes batches by the length of the serialized lines, as they're sent in the request body.
+#[derive(Clone, Copy, Default)]
+pub struct MezmoLineSizer;
+
+impl ItemBatchSize<MezmoLine> for MezmoLineSizer {
+    fn size(&self, item: &MezmoLine) -> usize {
+        // Accounts for the separating commas
+        item.lines.iter().map(|line| line.get().len() + 1).sum()
+    }
+}
@@ -0,0 +1,17 @@
+//! The `mezmo` (formerly `logdna`) sink.
+//!
+//! Sends log events to the Mezmo ingestion API, batched by their hostname, tags, IP and MAC
+//! address, which are passed in the query string of the requests.
+
+#[cfg(feature = "mezmo-benches")]
+pub mod bench_support;
+mod config;
+mod encoder;
+mod request_builder;
+mod service;
+mod sink;
+
+#[cfg(test)]
+mod tests;
+
+pub use config::{LogdnaConfig, MezmoConfig};
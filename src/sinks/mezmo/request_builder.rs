@@ -0,0 +1,83 @@
+use std::io;
+
+use bytes::Bytes;
+use serde::Serialize;
+
+use super::encoder::{MezmoLine, PartitionKey};
+use crate::sinks::{
+    prelude::*,
+    util::{BoxedRawValue, http::HttpRequest},
+};
+
+/// The body of an ingestion request.
+#[derive(Serialize)]
+struct MezmoLines<'a> {
+    lines: &'a [BoxedRawValue],
+}
+
+/// Encodes a batch of lines as the JSON body of an ingestion request.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MezmoLinesEncoder;
+
+impl encoding::Encoder<Vec<MezmoLine>> for MezmoLinesEncoder {
+    fn encode_input(
+        &self,
+        input: Vec<MezmoLine>,
+        writer: &mut dyn io::Write,
+    ) -> io::Result<(usize, GroupedCountByteSize)> {
+        let mut byte_size = telemetry().create_request_count_byte_size();
+        for line in &input {
+            byte_size.add_event(line, line.estimated_json_encoded_size_of());
+        }
+
+        let events = input.len();
+        let lines: Vec<BoxedRawValue> = input.into_iter().flat_map(|line| line.lines).collect();
+        let body = serde_json::to_vec(&MezmoLines { lines: &lines })?;
+        write_all(writer, events, &body)?;
+
+        Ok((body.len(), byte_size))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MezmoRequestBuilder {
+    pub(super) encoder: MezmoLinesEncoder,
+    pub(super) compression: Compression,
+}
+
+impl RequestBuilder<(PartitionKey, Vec<MezmoLine>)> for MezmoRequestBuilder {
+    type Metadata = (PartitionKey, EventFinalizers);
+    type Events = Vec<MezmoLine>;
+    type Encoder = MezmoLinesEncoder;
+    type Payload = Bytes;
+    type Request = HttpRequest<PartitionKey>;
+    type Error = io::Error;
+
+    fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    fn encoder(&self) -> &Self::Encoder {
+        &self.encoder
+    }
+
+    fn split_input(
+        &self,
+        input: (PartitionKey, Vec<MezmoLine>),
+    ) -> (Self::Metadata, RequestMetadataBuilder, Self::Events) {
+        let (key, mut lines) = input;
+        let finalizers = lines.take_finalizers();
+        let builder = RequestMetadataBuilder::from_events(&lines);
+
+        ((key, finalizers), builder, lines)
+    }
+
+    fn build_request(
+        &self,
+        (key, finalizers): Self::Metadata,
+        metadata: RequestMetadata,
+        payload: EncodeResult<Self::Payload>,
+    ) -> Self::Request {
+        HttpRequest::new(payload.into_payload(), finalizers, metadata, key)
+    }
+}
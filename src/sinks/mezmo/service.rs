@@ -0,0 +1,146 @@
+use std::{
+    task::{Context, Poll},
+    time::SystemTime,
+};
+
+use bytes::Bytes;
+use http::{
+    Request, Uri,
+    header::{CONTENT_ENCODING, CONTENT_TYPE},
+};
+use vector_lib::sensitive_string::SensitiveString;
+
+use super::encoder::PartitionKey;
+use crate::{
+    http::{Auth, HttpClient},
+    mezmo::user_trace::MezmoHttpBatchLoggingService,
+    sinks::{
+        prelude::*,
+        util::http::{HttpBatchService, HttpRequest, HttpResponse, HttpServiceRequestBuilder},
+    },
+};
+
+/// Builds the ingestion requests, passing the partition key in the query string.
+#[derive(Clone, Debug)]
+pub struct MezmoHttpRequestBuilder {
+    pub(super) uri: Uri,
+    pub(super) api_key: SensitiveString,
+    pub(super) compression: Compression,
+}
+
+impl MezmoHttpRequestBuilder {
+    fn build_uri(&self, key: &PartitionKey) -> Uri {
+        let mut query = url::form_urlencoded::Serializer::new(String::new());
+
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time can't drift behind the epoch!")
+            .as_millis();
+
+        query.append_pair("hostname", &key.hostname);
+        query.append_pair("now", &now.to_string());
+
+        if let Some(mac) = &key.mac {
+            query.append_pair("mac", mac);
+        }
+
+        if let Some(ip) = &key.ip {
+            query.append_pair("ip", ip);
+        }
+
+        if let Some(tags) = &key.tags {
+            let tags = tags.join(",");
+            query.append_pair("tags", &tags);
+        }
+
+        format!("{}{}", self.uri, query.finish())
+            .parse::<Uri>()
+            .expect("This should be a valid uri")
+    }
+}
+
+impl HttpServiceRequestBuilder<PartitionKey> for MezmoHttpRequestBuilder {
+    fn build(
+        &self,
+        mut request: HttpRequest<PartitionKey>,
+    ) -> Result<Request<Bytes>, crate::Error> {
+        let uri = self.build_uri(request.get_additional_metadata());
+
+        let mut builder = Request::builder()
+            .uri(uri)
+            .method("POST")
+            .header(CONTENT_TYPE, "application/json")
+            .header("x-response-format", "pipeline");
+        if let Some(content_encoding) = self.compression.content_encoding() {
+            builder = builder.header(CONTENT_ENCODING, content_encoding);
+        }
+        let mut request = builder.body(request.take_payload())?;
+
+        let auth = Auth::Basic {
+            user: self.api_key.inner().to_string(),
+            password: SensitiveString::default(),
+        };
+
+        auth.apply(&mut request);
+
+        Ok(request)
+    }
+}
+
+type MezmoBatchService = MezmoHttpBatchLoggingService<
+    BoxFuture<'static, Result<Request<Bytes>, crate::Error>>,
+    HttpRequest<PartitionKey>,
+>;
+
+/// Sends the ingestion requests, reporting the failed ones in the user logs.
+#[derive(Clone)]
+pub struct MezmoService {
+    batch_service: MezmoBatchService,
+}
+
+impl MezmoService {
+    pub fn new(
+        client: HttpClient,
+        http_request_builder: MezmoHttpRequestBuilder,
+        mezmo_ctx: Option<MezmoContext>,
+    ) -> Self {
+        let batch_service = HttpBatchService::new(client, move |req: HttpRequest<PartitionKey>| {
+            let request_builder = http_request_builder.clone();
+            let future: BoxFuture<'static, Result<Request<Bytes>, crate::Error>> =
+                Box::pin(async move { request_builder.build(req) });
+            future
+        });
+
+        Self {
+            batch_service: MezmoHttpBatchLoggingService::new(batch_service, mezmo_ctx),
+        }
+    }
+}
+
+impl Service<HttpRequest<PartitionKey>> for MezmoService {
+    type Response = HttpResponse;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut request: HttpRequest<PartitionKey>) -> Self::Future {
+        let mut batch_service = self.batch_service.clone();
+
+        let metadata = std::mem::take(request.metadata_mut());
+        let raw_byte_size = metadata.request_encoded_size();
+        let events_byte_size = metadata.into_events_estimated_json_encoded_byte_size();
+
+        Box::pin(async move {
+            let http_response = batch_service.call(request).await?;
+
+            Ok(HttpResponse {
+                http_response,
+                events_byte_size,
+                raw_byte_size,
+            })
+        })
+    }
+}
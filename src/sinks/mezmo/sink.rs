@@ -0,0 +1,74 @@
+use std::fmt;
+
+use super::{
+    encoder::{MezmoEventEncoder, MezmoLine, MezmoLineSizer, PartitionKey},
+    request_builder::MezmoRequestBuilder,
+};
+use crate::sinks::{prelude::*, util::http::HttpRequest};
+
+/// Partitions the lines by the query parameters of their ingestion request.
+struct MezmoPartitioner;
+
+impl Partitioner for MezmoPartitioner {
+    type Item = MezmoLine;
+    type Key = PartitionKey;
+
+    fn partition(&self, item: &Self::Item) -> Self::Key {
+        item.key.clone()
+    }
+}
+
+pub struct MezmoSink<S> {
+    pub(super) service: S,
+    pub(super) encoder: MezmoEventEncoder,
+    pub(super) request_builder: MezmoRequestBuilder,
+    pub(super) batch_settings: BatcherSettings,
+}
+
+impl<S> MezmoSink<S>
+where
+    S: Service<HttpRequest<PartitionKey>> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let encoder = self.encoder;
+        let batch_settings = self.batch_settings;
+
+        input
+            .filter_map(move |event| future::ready(encoder.encode_line(event)))
+            .batched_partitioned(MezmoPartitioner, || {
+                batch_settings.as_item_size_config(MezmoLineSizer)
+            })
+            .request_builder(
+                default_request_builder_concurrency_limit(),
+                self.request_builder,
+            )
+            .filter_map(|request| async move {
+                match request {
+                    Err(error) => {
+                        emit!(SinkRequestBuildError { error });
+                        None
+                    }
+                    Ok(req) => Some(req),
+                }
+            })
+            .into_driver(self.service)
+            .run()
+            .await
+    }
+}
+
+#[async_trait]
+impl<S> StreamSink<Event> for MezmoSink<S>
+where
+    S: Service<HttpRequest<PartitionKey>> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
@@ -0,0 +1,859 @@
+use std::io::Read;
+
+use futures::{StreamExt, channel::mpsc};
+use futures_util::stream;
+use http::{StatusCode, request::Parts};
+use serde_json::json;
+use temp_env::with_var;
+use vector_lib::event::{BatchNotifier, BatchStatus, Event, LogEvent};
+
+use super::{MezmoConfig, encoder::ORIGINATING_USER_AGENT_KEY};
+use crate::{
+    config::SinkConfig,
+    sinks::util::{
+        UriSerde,
+        test::{build_test_server_status, load_sink},
+    },
+    test_util::{
+        addr::next_addr,
+        components::{HTTP_SINK_TAGS, assert_sink_compliance},
+        random_lines,
+    },
+};
+
+#[test]
+fn generate_config() {
+    crate::test_util::test_generate_config::<MezmoConfig>();
+}
+
+#[tokio::test]
+async fn build_config_both_line_options() {
+    let (config, cx) = load_sink::<MezmoConfig>(
+        r#"
+        api_key = "mylogtoken"
+        hostname = "vector"
+        default_env = "acceptance"
+        codec.except_fields = ["magic"]
+        line_field = ".message.line"
+        line_template = "{{.message.line}} - {{.message.other.thing}}"
+    "#,
+    )
+    .unwrap();
+
+    let built = config.build(cx).await;
+    assert!(built.is_err());
+}
+
+#[tokio::test]
+async fn build_config_whole_message_line_field() {
+    let (config, cx) = load_sink::<MezmoConfig>(
+        r#"
+        api_key = "mylogtoken"
+        hostname = "vector"
+        default_env = "acceptance"
+        codec.except_fields = ["magic"]
+        line_field = ".message.line"
+        use_message_as_line = true
+    "#,
+    )
+    .unwrap();
+
+    let built = config.build(cx).await;
+    assert!(built.is_err());
+}
+
+#[test]
+fn encode_event_message_as_line_string_line() {
+    let (config, cx) = load_sink::<MezmoConfig>(
+        r#"
+        api_key = "mylogtoken"
+        hostname = "vector"
+        codec.except_fields = ["magic"]
+        use_message_as_line = true
+    "#,
+    )
+    .unwrap();
+    let encoder = config.build_encoder(&cx);
+
+    let payload = json!({"code": 200});
+    let mut event = Event::Log(LogEvent::try_from(payload).unwrap());
+
+    let message = json!({
+        "line": "hello world",
+        "app": "awesome_app",
+        "other": "stuff",
+        "meta": {
+            "thing": "things"
+        }
+    });
+    event.as_mut_log().insert("message", message);
+
+    let event_out = encoder.encode_event(event).unwrap().1;
+    let event_out = event_out.as_object().unwrap();
+
+    assert_eq!(event_out.get("line"), Some(&json!("hello world")));
+    assert_eq!(event_out.get("app"), Some(&json!("awesome_app")));
+    assert_eq!(event_out.get("meta"), Some(&json!({"thing": "things"})));
+    assert_eq!(event_out.get("other"), Some(&json!("stuff")));
+}
+
+#[test]
+fn encode_event_message_as_line_object_line() {
+    let (config, cx) = load_sink::<MezmoConfig>(
+        r#"
+        api_key = "mylogtoken"
+        hostname = "vector"
+        codec.except_fields = ["magic"]
+        use_message_as_line = true
+    "#,
+    )
+    .unwrap();
+    let encoder = config.build_encoder(&cx);
+
+    let payload = json!({"code": 200});
+    let mut event = Event::Log(LogEvent::try_from(payload).unwrap());
+
+    let message = json!({
+        "line": {
+          "log": "hello world",
+          "stream": "stdout"
+        },
+        "app": "awesome_app",
+        "other": "stuff",
+        "meta": {
+            "thing": "things"
+        }
+    });
+    event.as_mut_log().insert("message", message);
+
+    let event_out = encoder.encode_event(event).unwrap().1;
+    let event_out = event_out.as_object().unwrap();
+
+    assert_eq!(
+        event_out.get("line"),
+        Some(&json!(
+            "{ \"log\": \"hello world\", \"stream\": \"stdout\" }"
+        ))
+    );
+    assert_eq!(event_out.get("app"), Some(&json!("awesome_app")));
+    assert_eq!(event_out.get("meta"), Some(&json!({"thing": "things"})));
+    assert_eq!(event_out.get("other"), Some(&json!("stuff")));
+}
+
+#[test]
+fn encode_event_defaults() {
+    let (config, cx) = load_sink::<MezmoConfig>(
+        r#"
+        api_key = "mylogtoken"
+        meta_field = ".message._meta"
+        hostname = "vector"
+        app_template = "{{ .message.app }}"
+        file_template = "{{ .message.file }}"
+        env_template = "{{ .message.env }}"
+        timestamp_field = ".message._ts"
+        default_env = "default"
+        default_app = "default"
+        codec.except_fields = ["magic"]
+    "#,
+    )
+    .unwrap();
+    let encoder = config.build_encoder(&cx);
+
+    let event1 = Event::Log(LogEvent::from("hello world"));
+    let event1_out = encoder.encode_event(event1).unwrap().1;
+    let event1_out = event1_out.as_object().unwrap();
+    assert_eq!(event1_out.get("app"), Some(&json!("default")));
+    assert!(event1_out.get("file").is_none());
+    assert_eq!(event1_out.get("env"), Some(&json!("default")));
+    assert_eq!(event1_out.get("line"), Some(&json!("hello world")));
+    assert!(event1_out.get("meta").is_none());
+
+    let message_object = json!({
+    "message": "hello world",
+    "app": "notvector",
+    "file": "log.txt",
+    "env": "staging",
+    "first": "prop",
+    "_ts": "1682022085309",
+    "_meta": {
+        "thing": "stuff"
+    }
+    });
+    let mut event2 = Event::Log(LogEvent::from("hello world"));
+    event2.as_mut_log().insert(".message", message_object);
+    let event2_out = encoder.encode_event(event2).unwrap().1;
+    let event2_out = event2_out.as_object().unwrap();
+    assert_eq!(event2_out.get("app"), Some(&json!("notvector")));
+    assert_eq!(event2_out.get("file"), Some(&json!("log.txt")));
+    assert_eq!(event2_out.get("env"), Some(&json!("staging")));
+    assert_eq!(event2_out.get("timestamp"), Some(&json!("1682022085309")));
+    assert_eq!(
+        event2_out.get("line"),
+        Some(&json!("{\"first\":\"prop\",\"message\":\"hello world\"}"))
+    );
+    assert_eq!(event2_out.get("meta"), Some(&json!({"thing": "stuff"})));
+}
+
+#[test]
+fn encode_event_line_field() {
+    let (config, cx) = load_sink::<MezmoConfig>(
+        r#"
+        api_key = "mylogtoken"
+        hostname = "vector"
+        default_env = "acceptance"
+        codec.except_fields = ["magic"]
+        line_field = ".message.line"
+    "#,
+    )
+    .unwrap();
+    let encoder = config.build_encoder(&cx);
+
+    let payload = json!({
+    "code": 200,
+    "success": true,
+    "payload": {
+        "features": [
+            "serde",
+            "json"
+        ]
+    }});
+    let mut event = Event::Log(LogEvent::try_from(payload).unwrap());
+
+    let message = json!({
+        "line": "hello world",
+        "other": "stuff"
+    });
+    event.as_mut_log().insert("message", message);
+
+    let event_out = encoder.encode_event(event).unwrap().1;
+    let event_out = event_out.as_object().unwrap();
+
+    assert_eq!(event_out.get("line"), Some(&json!("hello world")));
+    assert_eq!(event_out.get("meta"), Some(&json!("{\"other\":\"stuff\"}")));
+}
+
+#[test]
+fn encode_event_line_template() {
+    let (config, cx) = load_sink::<MezmoConfig>(
+        r#"
+        api_key = "mylogtoken"
+        hostname = "vector"
+        default_env = "acceptance"
+        codec.except_fields = ["magic"]
+        line_template = "{{.message.line}} - {{.message.other.thing}}"
+    "#,
+    )
+    .unwrap();
+    let encoder = config.build_encoder(&cx);
+    let mut event = Event::Log(LogEvent::from("goodbye world"));
+
+    let message = json!({
+        "line": "hello world",
+        "other": {
+            "thing": "stuff",
+            "nested": "remaining"
+        },
+        "third": "thing"
+    });
+    event.as_mut_log().insert("message", message);
+
+    let event_out = encoder.encode_event(event).unwrap().1;
+    let event_out = event_out.as_object().unwrap();
+
+    assert_eq!(event_out.get("line"), Some(&json!("hello world - stuff")));
+    assert_eq!(
+        event_out.get("meta"),
+        Some(&json!(
+            "{\"other\":{\"nested\":\"remaining\"},\"third\":\"thing\"}"
+        ))
+    );
+}
+
+#[test]
+fn encode_event_object_line() {
+    let (config, cx) = load_sink::<MezmoConfig>(
+        r#"
+        api_key = "mylogtoken"
+        hostname = "vector"
+        default_env = "acceptance"
+        codec.except_fields = ["magic"]
+    "#,
+    )
+    .unwrap();
+    let encoder = config.build_encoder(&cx);
+    let mut event = Event::Log(LogEvent::from("goodbye world"));
+
+    let message = json!({
+        "line": "hello world",
+        "other": "stuff",
+        "third": "thing"
+    });
+    event.as_mut_log().insert("message", message);
+
+    let event_out = encoder.encode_event(event).unwrap().1;
+    let event_out = event_out.as_object().unwrap();
+
+    assert_eq!(
+        event_out.get("line"),
+        Some(&json!(
+            "{\"line\":\"hello world\",\"other\":\"stuff\",\"third\":\"thing\"}"
+        ))
+    );
+    assert!(event_out.get("meta").is_none());
+}
+
+#[test]
+fn encode_event_app_template() {
+    let (config, cx) = load_sink::<MezmoConfig>(
+        r#"
+        api_key = "mylogtoken"
+        hostname = "vector"
+        default_env = "acceptance"
+        codec.except_fields = ["magic"]
+        default_app = "app-name"
+        app_template = "{{.message.third}} - {{.message.other.thing}}"
+    "#,
+    )
+    .unwrap();
+    let encoder = config.build_encoder(&cx);
+    let mut event = Event::Log(LogEvent::from("goodbye world"));
+
+    let message = json!({
+        "line": "hello world",
+        "other": {
+            "thing": "stuff",
+            "nested": "remaining"
+        },
+        "third": "thing"
+    });
+    event.as_mut_log().insert("message", message);
+
+    let event_out = encoder.encode_event(event).unwrap().1;
+    let event_out = event_out.as_object().unwrap();
+
+    assert_eq!(event_out.get("app"), Some(&json!("thing - stuff")));
+    assert_eq!(
+        event_out.get("line"),
+        Some(&json!(
+            "{\"line\":\"hello world\",\"other\":{\"nested\":\"remaining\"}}"
+        ))
+    );
+    assert!(event_out.get("meta").is_none()); // whole .message went to the line
+}
+
+#[test]
+fn encode_event_nothing_to_reshape() {
+    // Since Log Analysis root-level properties don't contain `message`, there should
+    // be nothing to reshape even if the env var is set.
+
+    with_var("MEZMO_RESHAPE_MESSAGE", Some("1"), || {
+        let (config, cx) = load_sink::<MezmoConfig>(
+            r#"
+            api_key = "mylogtoken"
+            hostname = "vector"
+            app_template = "{{ .message.app }}"
+            file_template = "{{ .message.file }}"
+            env_template = "{{ .message.env }}"
+            default_env = "acceptance"
+            codec.except_fields = ["magic"]
+        "#,
+        )
+        .unwrap();
+        let encoder = config.build_encoder(&cx);
+
+        let mut event1 = Event::Log(LogEvent::from("hello world"));
+        event1.as_mut_log().insert(".message.app", "notvector");
+        event1.as_mut_log().insert("magic", "vector");
+
+        let mut event2 = Event::Log(LogEvent::from("hello world"));
+        event2.as_mut_log().insert(".message.file", "log.txt");
+
+        let event3 = Event::Log(LogEvent::from("hello world"));
+
+        let mut event4 = Event::Log(LogEvent::from("hello world"));
+        event4.as_mut_log().insert(".message.env", "staging");
+
+        let event1_out = encoder.encode_event(event1).unwrap().1;
+        let event1_out = event1_out.as_object().unwrap();
+        let event2_out = encoder.encode_event(event2).unwrap().1;
+        let event2_out = event2_out.as_object().unwrap();
+        let event3_out = encoder.encode_event(event3).unwrap().1;
+        let event3_out = event3_out.as_object().unwrap();
+        let event4_out = encoder.encode_event(event4).unwrap().1;
+        let event4_out = event4_out.as_object().unwrap();
+
+        assert_eq!(event1_out.get("app"), Some(&json!("notvector")));
+        assert_eq!(event2_out.get("file"), Some(&json!("log.txt")));
+        assert_eq!(event3_out.get("app"), Some(&json!("vector")));
+        assert_eq!(event3_out.get("env"), Some(&json!("acceptance")));
+        assert_eq!(event4_out.get("env"), Some(&json!("staging")));
+    });
+}
+
+#[test]
+fn render_key_la_values() {
+    let (config, cx) = load_sink::<MezmoConfig>(
+        r#"
+        api_key = "mylogtoken"
+        hostname = "vector"
+        mac_template = "{{ .metadata.query.mac }}"
+        ip_template = "{{ .metadata.query.ip }}"
+        tags = ["{{ .metadata.query.tags }}", "tag_3"]
+    "#,
+    )
+    .unwrap();
+    let encoder = config.build_encoder(&cx);
+
+    let message_object = json!({
+    "message": "hello world",
+    "_file": "log.txt",
+    "env": "staging",
+    "_ts": "1682022085309",
+    "_meta": {
+        "first": "prop"
+    }
+    });
+    let metadata_object = json!({
+        "query": {
+            "app": "la_app",
+            "ip": "127.0.0.1",
+            "mac": "some-mac-addr",
+            "tags": ["tag_1", "tag_2"]
+        }
+    });
+    let mut event = Event::Log(LogEvent::from("hello world"));
+    event.as_mut_log().insert(".message", message_object);
+    event.as_mut_log().insert(".metadata", metadata_object);
+
+    let key = encoder.render_key(&event).unwrap();
+
+    assert_eq!(key.hostname, "vector".to_string());
+    assert_eq!(key.ip, Some("127.0.0.1".to_string()));
+    assert_eq!(key.mac, Some("some-mac-addr".to_string()));
+    assert_eq!(
+        key.tags,
+        Some(vec![
+            "tag_1".to_string(),
+            "tag_2".to_string(),
+            "tag_3".to_string()
+        ])
+    );
+}
+
+#[test]
+fn encode_event_render_key_host_error() {
+    let (config, cx) = load_sink::<MezmoConfig>(
+        r#"
+        api_key = "mylogtoken"
+        hostname = "{{ .metadata.query.host }}"
+        mac_template = "{{ .metadata.query.mac }}"
+        ip_template = "{{ .metadata.query.ip }}"
+        line_field = ".message.message"
+    "#,
+    )
+    .unwrap();
+    let encoder = config.build_encoder(&cx);
+
+    let message_object = json!({
+    "message": "hello world",
+    "_file": "log.txt",
+    "env": "staging",
+    "_ts": "1682022085309",
+    "_meta": {
+        "first": "prop"
+    }
+    });
+    let metadata_object = json!({
+        "query": {
+            "app": "la_app"
+        }
+    });
+    let mut event = Event::Log(LogEvent::from("hello world"));
+    event.as_mut_log().insert(".message", message_object);
+    event.as_mut_log().insert(".metadata", metadata_object);
+
+    let event_out = encoder.encode_event(event);
+    assert!(event_out.is_none());
+}
+
+#[test]
+fn encode_event_render_key_optional_error() {
+    let (config, cx) = load_sink::<MezmoConfig>(
+        r#"
+        api_key = "mylogtoken"
+        hostname = "vector"
+        tags = ["{{ .metadata.query.tags }}"]
+        ip_template = "{{ .metadata.query.ip }}"
+        mac_template = "{{ .metadata.query.mac }}"
+        line_field = ".message.message"
+    "#,
+    )
+    .unwrap();
+    let encoder = config.build_encoder(&cx);
+
+    let message_object = json!({
+    "message": "hello world",
+    "_file": "log.txt",
+    "env": "staging",
+    "_ts": "1682022085309",
+    "_meta": {
+        "first": "prop"
+    }
+    });
+    let metadata_object = json!({
+        "query": {
+            "app": "la_app"
+        }
+    });
+    let mut event = Event::Log(LogEvent::from("hello world"));
+    event.as_mut_log().insert(".message", message_object);
+    event.as_mut_log().insert(".metadata", metadata_object);
+
+    let event_out = encoder.encode_event(event).unwrap().1;
+    let event_out = event_out.as_object().unwrap();
+    // Template errors on optional params don't stop encoding
+    assert_eq!(event_out.get("line"), Some(&json!("hello world")));
+    assert_eq!(event_out.get(ORIGINATING_USER_AGENT_KEY), None);
+}
+
+#[test]
+fn encode_event_from_logdna_agent_default_user_agent_field() {
+    let (config, cx) = load_sink::<MezmoConfig>(
+        r#"
+        api_key = "mylogtoken"
+        hostname = "vector"
+        tags = ["{{ .metadata.query.tags }}"]
+        ip_template = "{{ .metadata.query.ip }}"
+        mac_template = "{{ .metadata.query.mac }}"
+        line_field = ".message.message"
+    "#,
+    )
+    .unwrap();
+    let encoder = config.build_encoder(&cx);
+
+    let message_object = json!({
+    "message": "hello world",
+    "_file": "log.txt",
+    "env": "staging",
+    "_ts": "1682022085309",
+    "_meta": {
+        "first": "prop"
+    }
+    });
+    let metadata_object = json!({
+        "query": {
+            "app": "la_app"
+        },
+        "headers": {
+            "accept-charset": "utf8",
+            "connection": "keep-alive",
+            "content-length": "572",
+            "content-type": "application/json",
+            "host": "pipeline.use.dev.logdna.net",
+            "user-agent": "logdna-agent/3.9.1 (Linux Mint/20.3)",
+            "x-pipeline-source-type": "mezmo-agent"
+        }
+    });
+    let mut event = Event::Log(LogEvent::from("hello world"));
+    event.as_mut_log().insert(".message", message_object);
+    event.as_mut_log().insert(".metadata", metadata_object);
+
+    let event_out = encoder.encode_event(event).unwrap().1;
+    let event_out = event_out.as_object().unwrap();
+
+    assert_eq!(event_out.get("line"), Some(&json!("hello world")));
+    assert_eq!(
+        event_out.get(ORIGINATING_USER_AGENT_KEY),
+        Some(&json!("logdna-agent/3.9.1 (Linux Mint/20.3)"))
+    );
+}
+
+#[test]
+fn encode_event_from_logdna_agent_configured_user_agent_field() {
+    let (config, cx) = load_sink::<MezmoConfig>(
+        r#"
+        api_key = "mylogtoken"
+        hostname = "vector"
+        tags = ["{{ .metadata.query.tags }}"]
+        ip_template = "{{ .metadata.query.ip }}"
+        mac_template = "{{ .metadata.query.mac }}"
+        line_field = ".message.message"
+        originating_user_agent_field = ".metadata.query.user_agent_query"
+    "#,
+    )
+    .unwrap();
+    let encoder = config.build_encoder(&cx);
+
+    let message_object = json!({
+    "message": "hello world",
+    "_file": "log.txt",
+    "env": "staging",
+    "_ts": "1682022085309",
+    "_meta": {
+        "first": "prop"
+    }
+    });
+    let metadata_object = json!({
+        "query": {
+            "app": "la_app",
+            "user_agent_query": "query-agent"
+        },
+        "headers": {
+            "accept-charset": "utf8",
+            "connection": "keep-alive",
+            "content-length": "572",
+            "content-type": "application/json",
+            "host": "pipeline.use.dev.logdna.net",
+            "user-agent": "logdna-agent/3.9.1 (Linux Mint/20.3)",
+            "x-pipeline-source-type": "mezmo-agent"
+        }
+    });
+    let mut event = Event::Log(LogEvent::from("hello world"));
+    event.as_mut_log().insert(".message", message_object);
+    event.as_mut_log().insert(".metadata", metadata_object);
+
+    let event_out = encoder.encode_event(event).unwrap().1;
+    let event_out = event_out.as_object().unwrap();
+
+    assert_eq!(event_out.get("line"), Some(&json!("hello world")));
+    assert_eq!(
+        event_out.get(ORIGINATING_USER_AGENT_KEY),
+        Some(&json!("query-agent"))
+    );
+}
+
+#[tokio::test]
+async fn build_config_unsupported_compression() {
+    let (config, cx) = load_sink::<MezmoConfig>(
+        r#"
+        api_key = "mylogtoken"
+        hostname = "vector"
+        compression = "snappy"
+    "#,
+    )
+    .unwrap();
+
+    let built = config.build(cx).await;
+    assert!(built.is_err());
+}
+
+#[test]
+fn limit_line_rejects_oversized_line() {
+    let (config, cx) = load_sink::<MezmoConfig>(
+        r#"
+        api_key = "mylogtoken"
+        hostname = "vector"
+        oversized_lines.max_bytes = 5
+    "#,
+    )
+    .unwrap();
+    let encoder = config.build_encoder(&cx);
+
+    let line = json!({"line": "hello world", "app": "vector"});
+    assert_eq!(encoder.limit_line(line.clone()), vec![line]);
+}
+
+#[test]
+fn limit_line_truncates_oversized_line() {
+    let (config, cx) = load_sink::<MezmoConfig>(
+        r#"
+        api_key = "mylogtoken"
+        hostname = "vector"
+        oversized_lines.action = "truncate"
+        oversized_lines.max_bytes = 8
+    "#,
+    )
+    .unwrap();
+    let encoder = config.build_encoder(&cx);
+
+    // The `ö` spans the 8th and 9th bytes, so it's dropped entirely
+    let lines = encoder.limit_line(json!({"line": "hello wörld", "app": "vector"}));
+    assert_eq!(lines, vec![json!({"line": "hello w", "app": "vector"})]);
+
+    let lines = encoder.limit_line(json!({"line": "hello", "app": "vector"}));
+    assert_eq!(lines, vec![json!({"line": "hello", "app": "vector"})]);
+}
+
+#[test]
+fn encode_line_splits_oversized_line() {
+    let (config, cx) = load_sink::<MezmoConfig>(
+        r#"
+        api_key = "mylogtoken"
+        hostname = "vector"
+        line_field = ".message"
+        oversized_lines.action = "split"
+        oversized_lines.max_bytes = 4
+    "#,
+    )
+    .unwrap();
+    let encoder = config.build_encoder(&cx);
+
+    let event = Event::Log(LogEvent::from("hello wörld"));
+    let encoded = encoder.encode_line(event).unwrap();
+
+    let lines: Vec<String> = encoded
+        .lines
+        .iter()
+        .map(|line| {
+            let line: serde_json::Value = serde_json::from_str(line.get()).unwrap();
+            assert_eq!(line["app"], json!("vector"));
+            line["line"].as_str().unwrap().to_owned()
+        })
+        .collect();
+    assert_eq!(lines, vec!["hell", "o w", "örl", "d"]);
+}
+
+#[tokio::test]
+async fn compresses_request_body() {
+    let (mut config, cx) = load_sink::<MezmoConfig>(
+        r#"
+        api_key = "mylogtoken"
+        hostname = "vector"
+        compression = "gzip"
+    "#,
+    )
+    .unwrap();
+
+    let (_guard, addr) = next_addr();
+    config.endpoint = UriSerde {
+        uri: format!("http://{addr}").parse::<http::Uri>().unwrap(),
+        auth: None,
+    };
+    let (sink, _) = config.build(cx).await.unwrap();
+
+    let (mut rx, _trigger, server) = build_test_server_status(addr, StatusCode::OK);
+    tokio::spawn(server);
+
+    let events = stream::iter(vec![Event::Log(LogEvent::from("hello world"))]).map(Into::into);
+    sink.run(events).await.expect("Running sink failed");
+
+    let (parts, body) = rx.next().await.unwrap();
+    assert_eq!(parts.headers.get("content-encoding").unwrap(), "gzip");
+
+    let mut decoded = String::new();
+    flate2::read::MultiGzDecoder::new(&body[..])
+        .read_to_string(&mut decoded)
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_str(&decoded).unwrap();
+    assert_eq!(body["lines"][0]["line"], json!("hello world"));
+}
+
+async fn smoke_start(
+    status_code: StatusCode,
+    batch_status: BatchStatus,
+) -> (
+    Vec<&'static str>,
+    Vec<Vec<String>>,
+    mpsc::Receiver<(Parts, bytes::Bytes)>,
+) {
+    let (mut config, cx) = load_sink::<MezmoConfig>(
+        r#"
+        api_key = "mylogtoken"
+        ip_template = "127.0.0.1"
+        mac_template = "some-mac-addr"
+        hostname = "{{ hostname }}"
+        route = "/test"
+        tags = ["{{ test }}", "maybeanothertest"]
+    "#,
+    )
+    .unwrap();
+
+    // Make sure we can build the config
+    _ = config.build(cx.clone()).await.unwrap();
+
+    let (_guard, addr) = next_addr();
+    // Swap out the host so we can force send it
+    // to our local server
+    let endpoint = UriSerde {
+        uri: format!("http://{addr}").parse::<http::Uri>().unwrap(),
+        auth: None,
+    };
+    config.endpoint = endpoint;
+
+    let (sink, _) = config.build(cx).await.unwrap();
+
+    let (rx, _trigger, server) = build_test_server_status(addr, status_code);
+    tokio::spawn(server);
+
+    let lines = random_lines(100).take(10).collect::<Vec<_>>();
+    let mut events = Vec::new();
+    let hosts = vec!["host0", "host1"];
+
+    let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+    let mut partitions = vec![Vec::new(), Vec::new()];
+    // Create 10 events where the first one contains custom
+    // fields that are not just `message`.
+    for (i, line) in lines.iter().enumerate() {
+        let mut event = LogEvent::from(line.as_str()).with_batch_notifier(&batch);
+        let p = i % 2;
+        event.insert("hostname", hosts[p]);
+        event.insert("test", "stuff");
+
+        partitions[p].push(line.into());
+        events.push(Event::Log(event));
+    }
+    drop(batch);
+
+    let events = stream::iter(events).map(Into::into);
+    sink.run(events).await.expect("Running sink failed");
+
+    assert_eq!(receiver.try_recv(), Ok(batch_status));
+
+    (hosts, partitions, rx)
+}
+
+#[tokio::test]
+async fn smoke_fails() {
+    let (_hosts, _partitions, mut rx) =
+        smoke_start(StatusCode::FORBIDDEN, BatchStatus::Rejected).await;
+    assert!(matches!(rx.try_next(), Err(mpsc::TryRecvError { .. })));
+}
+
+#[tokio::test]
+async fn smoke() {
+    assert_sink_compliance(&HTTP_SINK_TAGS, async {
+        let (hosts, partitions, mut rx) = smoke_start(StatusCode::OK, BatchStatus::Delivered).await;
+
+        for _ in 0..partitions.len() {
+            let output = rx.next().await.unwrap();
+
+            let request = &output.0;
+            let body: serde_json::Value = serde_json::from_slice(&output.1[..]).unwrap();
+
+            let query = request.uri.query().unwrap();
+            let path = request.uri.path().to_string();
+            assert_eq!(path, "/test");
+
+            let (p, _) = hosts
+                .iter()
+                .enumerate()
+                .find(|(_, host)| query.contains(&format!("hostname={host}")))
+                .expect("invalid hostname");
+            let lines = &partitions[p];
+
+            assert!(query.contains("ip=127.0.0.1"));
+            assert!(query.contains("mac=some-mac-addr"));
+            assert!(query.contains("tags=stuff%2Cmaybeanothertest"));
+
+            let output = body
+                .as_object()
+                .unwrap()
+                .get("lines")
+                .unwrap()
+                .as_array()
+                .unwrap();
+
+            for (i, line) in output.iter().enumerate() {
+                // All lines are json objects
+                let line = line.as_object().unwrap();
+
+                assert_eq!(line.get("app"), Some(&json!("vector")));
+                assert_eq!(line.get("env"), Some(&json!("production")));
+                assert_eq!(line.get("line"), Some(&json!(lines[i])));
+
+                assert!(line.get("meta").is_none());
+            }
+        }
+    })
+    .await;
+}
@@ -0,0 +1,259 @@
+use std::time::{Duration, Instant};
+
+use http::{Request, Uri, header::CONTENT_TYPE};
+use http_body::Body as _;
+use hyper::Body;
+use serde::Deserialize;
+use tokio::sync::Mutex;
+use vector_lib::sensitive_string::SensitiveString;
+
+use super::sink::OpentelemetrySinkError;
+use crate::http::HttpClient;
+
+/// An access token issued by the token endpoint.
+#[derive(Debug)]
+struct CachedToken {
+    access_token: String,
+    expires_at: Option<Instant>,
+}
+
+impl CachedToken {
+    /// Whether the token can still be used for `margin`. Tokens issued without an expiry are
+    /// used until the collector rejects them, see `OAuth2TokenProvider::invalidate`.
+    fn is_fresh(&self, margin: Duration) -> bool {
+        self.expires_at
+            .is_none_or(|expires_at| Instant::now() + margin < expires_at)
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+}
+
+/// Requests access tokens with the OAuth2 client credentials grant, caching them until
+/// `refresh_margin` before they expire.
+#[derive(Debug)]
+pub struct OAuth2TokenProvider {
+    client: HttpClient,
+    token_endpoint: Uri,
+    client_id: String,
+    client_secret: SensitiveString,
+    scopes: Vec<String>,
+    refresh_margin: Duration,
+    // Held while a token is requested, so concurrent requests wait on a single refresh
+    token: Mutex<Option<CachedToken>>,
+}
+
+impl OAuth2TokenProvider {
+    pub fn new(
+        client: HttpClient,
+        token_endpoint: Uri,
+        client_id: String,
+        client_secret: SensitiveString,
+        scopes: Vec<String>,
+        refresh_margin: Duration,
+    ) -> Self {
+        Self {
+            client,
+            token_endpoint,
+            client_id,
+            client_secret,
+            scopes,
+            refresh_margin,
+            token: Mutex::new(None),
+        }
+    }
+
+    /// Returns the cached access token, requesting a new one when it's about to expire.
+    pub async fn token(&self) -> Result<String, OpentelemetrySinkError> {
+        let mut cached = self.token.lock().await;
+        if let Some(token) = cached
+            .as_ref()
+            .filter(|token| token.is_fresh(self.refresh_margin))
+        {
+            return Ok(token.access_token.clone());
+        }
+
+        let token = self.fetch().await?;
+        let access_token = token.access_token.clone();
+        *cached = Some(token);
+        Ok(access_token)
+    }
+
+    /// Drops the cached access token after the collector rejected it, so that the next request
+    /// gets a new one.
+    pub async fn invalidate(&self) {
+        *self.token.lock().await = None;
+    }
+
+    async fn fetch(&self) -> Result<CachedToken, OpentelemetrySinkError> {
+        let mut form = url::form_urlencoded::Serializer::new(String::new());
+        form.append_pair("grant_type", "client_credentials")
+            .append_pair("client_id", &self.client_id)
+            .append_pair("client_secret", self.client_secret.inner());
+        if !self.scopes.is_empty() {
+            form.append_pair("scope", &self.scopes.join(" "));
+        }
+
+        let request = Request::post(&self.token_endpoint)
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from(form.finish()))
+            .expect("building token request failed unexpectedly");
+
+        let requested_at = Instant::now();
+        let response = self.client.send(request).await.map_err(|error| {
+            OpentelemetrySinkError::retriable(&format!("OAuth2 token request error: {error}"), None)
+        })?;
+
+        let status = response.status();
+        let body = response
+            .into_body()
+            .collect()
+            .await
+            .map(|collected| collected.to_bytes())
+            .map_err(|error| {
+                OpentelemetrySinkError::retriable(
+                    &format!("OAuth2 token response error: {error}"),
+                    None,
+                )
+            })?;
+
+        if !status.is_success() {
+            let message = format!(
+                "OAuth2 token endpoint respond with error status: {}, body: {}",
+                status.as_str(),
+                String::from_utf8_lossy(&body),
+            );
+            return Err(if status.is_server_error() || status.as_u16() == 429 {
+                OpentelemetrySinkError::retriable(&message, None)
+            } else {
+                OpentelemetrySinkError::new(&message)
+            });
+        }
+
+        let response: TokenResponse = serde_json::from_slice(&body).map_err(|error| {
+            OpentelemetrySinkError::new(&format!("Invalid OAuth2 token response: {error}"))
+        })?;
+
+        Ok(CachedToken {
+            access_token: response.access_token,
+            expires_at: response
+                .expires_in
+                .map(|secs| requested_at + Duration::from_secs(secs)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use futures::StreamExt;
+    use http::{Response, StatusCode};
+    use vector_lib::config::proxy::ProxyConfig;
+
+    use super::*;
+    use crate::{
+        sinks::util::test::build_test_server_generic,
+        test_util::{addr::next_addr, trace_init},
+    };
+
+    fn provider(addr: std::net::SocketAddr, refresh_margin: Duration) -> OAuth2TokenProvider {
+        OAuth2TokenProvider::new(
+            HttpClient::new(None, &ProxyConfig::default()).unwrap(),
+            format!("http://{addr}/token").parse().unwrap(),
+            "client".to_owned(),
+            "secret".to_owned().into(),
+            vec!["logs.write".to_owned(), "metrics.write".to_owned()],
+            refresh_margin,
+        )
+    }
+
+    #[tokio::test]
+    async fn caches_token_until_expiry() {
+        trace_init();
+
+        let (_guard, addr) = next_addr();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let responder_calls = Arc::clone(&calls);
+        let (mut rx, trigger, server) = build_test_server_generic(addr, move || {
+            let call = responder_calls.fetch_add(1, Ordering::SeqCst);
+            Response::new(Body::from(format!(
+                r#"{{"access_token":"token-{call}","token_type":"Bearer","expires_in":3600}}"#
+            )))
+        });
+        tokio::spawn(server);
+
+        let provider = provider(addr, Duration::from_secs(60));
+        assert_eq!(provider.token().await.unwrap(), "token-0");
+        assert_eq!(provider.token().await.unwrap(), "token-0");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let (parts, body) = rx.next().await.unwrap();
+        assert_eq!(parts.uri.path(), "/token");
+        let form: Vec<(String, String)> = url::form_urlencoded::parse(&body).into_owned().collect();
+        assert_eq!(
+            form,
+            vec![
+                ("grant_type".to_owned(), "client_credentials".to_owned()),
+                ("client_id".to_owned(), "client".to_owned()),
+                ("client_secret".to_owned(), "secret".to_owned()),
+                ("scope".to_owned(), "logs.write metrics.write".to_owned()),
+            ]
+        );
+        drop(trigger);
+    }
+
+    #[tokio::test]
+    async fn refreshes_token_before_expiry() {
+        trace_init();
+
+        let (_guard, addr) = next_addr();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let responder_calls = Arc::clone(&calls);
+        let (_rx, trigger, server) = build_test_server_generic(addr, move || {
+            let call = responder_calls.fetch_add(1, Ordering::SeqCst);
+            Response::new(Body::from(format!(
+                r#"{{"access_token":"token-{call}","expires_in":30}}"#
+            )))
+        });
+        tokio::spawn(server);
+
+        // The tokens expire within the refresh margin, so each one is only used once
+        let provider = provider(addr, Duration::from_secs(60));
+        assert_eq!(provider.token().await.unwrap(), "token-0");
+        assert_eq!(provider.token().await.unwrap(), "token-1");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        drop(trigger);
+    }
+
+    #[tokio::test]
+    async fn token_endpoint_errors() {
+        trace_init();
+
+        let (_guard, addr) = next_addr();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let responder_calls = Arc::clone(&calls);
+        let (_rx, trigger, server) = build_test_server_generic(addr, move || {
+            let status = match responder_calls.fetch_add(1, Ordering::SeqCst) {
+                0 => StatusCode::UNAUTHORIZED,
+                _ => StatusCode::SERVICE_UNAVAILABLE,
+            };
+            Response::builder()
+                .status(status)
+                .body(Body::from("nope"))
+                .unwrap()
+        });
+        tokio::spawn(server);
+
+        let provider = provider(addr, Duration::from_secs(60));
+        assert!(!provider.token().await.unwrap_err().is_retriable());
+        assert!(provider.token().await.unwrap_err().is_retriable());
+        drop(trigger);
+    }
+}
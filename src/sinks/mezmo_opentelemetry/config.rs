@@ -14,13 +14,18 @@ use crate::{
 };
 
 use async_trait::async_trait;
-use http::{HeaderName, HeaderValue, Uri, header::AUTHORIZATION, uri::InvalidUri};
+use http::{HeaderName, HeaderValue, Uri, uri::InvalidUri};
 use indexmap::IndexMap;
 use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
 use tower::ServiceBuilder;
+use vector_lib::codecs::decoding::OpenTelemetryPayloadFormat;
 use vector_lib::configurable::configurable_component;
 use vector_lib::tls::{TlsConfig, TlsSettings};
 
+use super::auth::OAuth2TokenProvider;
+use super::grpc::OpentelemetryGrpcService;
 use super::models::OpentelemetryModelType;
 use super::service::OpentelemetryApiRequest;
 use super::service::OpentelemetryApiResponse;
@@ -40,8 +45,12 @@ impl RetryLogic for OpentelemetryRetry {
     type Response = OpentelemetryApiResponse;
     type Request = OpentelemetryApiRequest;
 
-    fn is_retriable_error(&self, _error: &Self::Error) -> bool {
-        false
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        error.is_retriable()
+    }
+
+    fn retry_after(&self, error: &Self::Error) -> Option<std::time::Duration> {
+        error.retry_after()
     }
 }
 
@@ -172,6 +181,45 @@ impl TryFrom<&OpentelemetrySinkConfig> for OpentelemetryEndpoint {
     }
 }
 
+/// The protocol used to send data to the collector.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OpentelemetrySinkProtocol {
+    /// OTLP/HTTP, with requests sent to the `/v1/logs`, `/v1/metrics` and `/v1/traces` paths of
+    /// the endpoint.
+    #[default]
+    Http,
+
+    /// OTLP/gRPC. Only the scheme and authority of the endpoint are used.
+    Grpc,
+}
+
+/// The encoding of the OTLP/HTTP requests.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OpentelemetrySinkEncoding {
+    /// OTLP/protobuf, sent with the `application/x-protobuf` content type.
+    #[default]
+    Protobuf,
+
+    /// OTLP/JSON, sent with the `application/json` content type.
+    ///
+    /// Field names are lowerCamelCase and trace and span ids are hex encoded, as the OTLP spec
+    /// requires. Only supported by the `http` protocol.
+    Json,
+}
+
+impl From<OpentelemetrySinkEncoding> for OpenTelemetryPayloadFormat {
+    fn from(encoding: OpentelemetrySinkEncoding) -> Self {
+        match encoding {
+            OpentelemetrySinkEncoding::Protobuf => Self::Protobuf,
+            OpentelemetrySinkEncoding::Json => Self::Json,
+        }
+    }
+}
+
 /// Configuration for the `opentelemetry_logs` sink.
 #[configurable_component(sink("mezmo_opentelemetry"))]
 #[derive(Clone, Debug)]
@@ -183,6 +231,14 @@ pub struct OpentelemetrySinkConfig {
     #[configurable(metadata(docs::examples = "https://localhost:8087"))]
     pub endpoint: String,
 
+    #[configurable(derived)]
+    #[serde(default)]
+    pub protocol: OpentelemetrySinkProtocol,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub encoding: OpentelemetrySinkEncoding,
+
     #[configurable(derived)]
     pub auth: Option<OpentelemetrySinkAuth>,
 
@@ -208,6 +264,13 @@ pub struct OpentelemetrySinkConfig {
     #[configurable(metadata(docs::advanced))]
     pub buckets: Vec<f64>,
 
+    /// Quantiles to use for aggregating [distribution][dist_metric_docs] metrics into summaries.
+    ///
+    /// [dist_metric_docs]: https://vector.dev/docs/about/under-the-hood/architecture/data-model/metric/#distribution
+    #[serde(default = "super::default_summary_quantiles")]
+    #[configurable(metadata(docs::advanced))]
+    pub quantiles: Vec<f64>,
+
     /// Acknowlegements option
     #[configurable(derived)]
     #[serde(
@@ -224,6 +287,48 @@ impl OpentelemetrySinkConfig {
         let client = HttpClient::new(tls, cx.proxy())?;
         Ok(client)
     }
+
+    fn build_auth(&self, cx: &SinkContext) -> crate::Result<Option<Auth>> {
+        let auth = match &self.auth {
+            Some(OpentelemetrySinkAuth::Basic { user, password }) => {
+                Auth::Basic(crate::http::Auth::Basic {
+                    user: user.clone(),
+                    password: password.clone().into(),
+                })
+            }
+            Some(OpentelemetrySinkAuth::Bearer { token }) => {
+                Auth::Basic(crate::http::Auth::Bearer {
+                    token: token.clone(),
+                })
+            }
+            Some(OpentelemetrySinkAuth::Header { name, value }) => {
+                let name = HeaderName::from_bytes(name.as_bytes())?;
+                let mut value = HeaderValue::from_str(value.inner())?;
+                value.set_sensitive(true);
+                Auth::Header(name, value)
+            }
+            Some(OpentelemetrySinkAuth::Oauth2 {
+                token_endpoint,
+                client_id,
+                client_secret,
+                scopes,
+                refresh_margin_secs,
+            }) => {
+                // The token endpoint is reached with the TLS settings of the sink
+                let client = self.build_client(cx.clone())?;
+                Auth::OAuth2(Arc::new(OAuth2TokenProvider::new(
+                    client,
+                    token_endpoint.parse::<Uri>()?,
+                    client_id.clone(),
+                    client_secret.clone(),
+                    scopes.clone(),
+                    Duration::from_secs(*refresh_margin_secs),
+                )))
+            }
+            None => return Ok(None),
+        };
+        Ok(Some(auth))
+    }
 }
 
 impl GenerateConfig for OpentelemetrySinkConfig {
@@ -239,6 +344,7 @@ impl GenerateConfig for OpentelemetrySinkConfig {
 #[derive(Debug, Clone)]
 pub struct OpentelemetryMetricConfig {
     pub buckets: Vec<f64>,
+    pub quantiles: Vec<f64>,
 }
 
 #[async_trait]
@@ -247,20 +353,7 @@ impl SinkConfig for OpentelemetrySinkConfig {
     async fn build(&self, ctx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
         let endpoint = OpentelemetryEndpoint::try_from(self)?;
 
-        let auth = match &self.auth {
-            Some(OpentelemetrySinkAuth::Basic { user, password }) => {
-                Some(Auth::Basic(crate::http::Auth::Basic {
-                    user: user.clone(),
-                    password: password.clone().into(),
-                }))
-            }
-            Some(OpentelemetrySinkAuth::Bearer { token }) => {
-                Some(Auth::Basic(crate::http::Auth::Bearer {
-                    token: token.clone(),
-                }))
-            }
-            None => None,
-        };
+        let auth = self.build_auth(&ctx)?;
 
         let batcher_settings = self
             .batch
@@ -270,41 +363,80 @@ impl SinkConfig for OpentelemetrySinkConfig {
 
         let request_settings = self.request.tower.into_settings();
 
-        let client = self.build_client(ctx.clone())?;
-
         let healthcheck = healthcheck();
 
-        let headers = validate_headers(&self.request.headers, self.auth.is_some())?;
-
-        let service = ServiceBuilder::new()
-            .settings(request_settings, OpentelemetryRetry)
-            .service(MezmoLoggingService::new(
-                OpentelemetryService {
-                    endpoint: endpoint.clone(),
-                    client,
-                    auth,
-                    headers,
-                },
-                ctx.mezmo_ctx.clone(),
-            ));
+        let headers =
+            validate_headers(&self.request.headers, auth.as_ref().map(Auth::header_name))?;
 
         let metric_config = OpentelemetryMetricConfig {
             buckets: self.buckets.clone(),
+            quantiles: self.quantiles.clone(),
         };
 
-        let compression = self.compression;
-        let sink = OpentelemetrySink {
-            service,
-            encoder: OpentelemetryEncoder,
-            compression,
-            batcher_settings,
-            metric_config,
-            mezmo_ctx: ctx.mezmo_ctx,
+        let sink = match self.protocol {
+            OpentelemetrySinkProtocol::Http => {
+                let client = self.build_client(ctx.clone())?;
+                let service = ServiceBuilder::new()
+                    .settings(request_settings, OpentelemetryRetry)
+                    .service(MezmoLoggingService::new(
+                        OpentelemetryService {
+                            endpoint: endpoint.clone(),
+                            client,
+                            auth,
+                            headers,
+                            format: self.encoding.into(),
+                        },
+                        ctx.mezmo_ctx.clone(),
+                    ));
+
+                VectorSink::from_event_streamsink(OpentelemetrySink {
+                    service,
+                    encoder: OpentelemetryEncoder::new(self.encoding.into()),
+                    compression: self.compression,
+                    batcher_settings,
+                    metric_config,
+                    mezmo_ctx: ctx.mezmo_ctx,
+                })
+            }
+            OpentelemetrySinkProtocol::Grpc => {
+                if self.encoding != OpentelemetrySinkEncoding::Protobuf {
+                    return Err("Only protobuf encoding is supported with the grpc protocol".into());
+                }
+                // gRPC compresses the messages itself, so the payloads are left uncompressed
+                let compression = match self.compression {
+                    Compression::None => false,
+                    Compression::Gzip(_) => true,
+                    _ => {
+                        return Err(
+                            "Only gzip compression is supported with the grpc protocol".into()
+                        );
+                    }
+                };
+                let tls = TlsSettings::from_options(self.tls.as_ref())?;
+                let client = OpentelemetryGrpcService::new(
+                    &self.endpoint,
+                    tls,
+                    ctx.proxy(),
+                    compression,
+                    auth,
+                    headers,
+                )?;
+                let service = ServiceBuilder::new()
+                    .settings(request_settings, OpentelemetryRetry)
+                    .service(MezmoLoggingService::new(client, ctx.mezmo_ctx.clone()));
+
+                VectorSink::from_event_streamsink(OpentelemetrySink {
+                    service,
+                    encoder: OpentelemetryEncoder::default(),
+                    compression: Compression::None,
+                    batcher_settings,
+                    metric_config,
+                    mezmo_ctx: ctx.mezmo_ctx,
+                })
+            }
         };
-        Ok((
-            VectorSink::from_event_streamsink(sink),
-            Box::pin(healthcheck),
-        ))
+
+        Ok((sink, Box::pin(healthcheck)))
     }
 
     fn input(&self) -> Input {
@@ -322,17 +454,17 @@ pub(crate) async fn healthcheck() -> crate::Result<()> {
 
 fn validate_headers(
     headers: &BTreeMap<String, String>,
-    configures_auth: bool,
+    auth_header: Option<HeaderName>,
 ) -> crate::Result<IndexMap<HeaderName, HeaderValue>> {
     // util returns BTreeMap<OrderedHeaderName, HeaderValue>
     let headers = crate::sinks::util::http::validate_headers(headers)?;
 
-    for name in headers.keys() {
-        let header_name = name.clone();
-        let fmted_name = format!("{header_name:?}");
-        if configures_auth && *fmted_name == AUTHORIZATION {
-            return Err("Authorization header can not be used with defined auth options".into());
-        }
+    if let Some(auth_header) =
+        auth_header.filter(|auth_header| headers.keys().any(|name| name.inner() == auth_header))
+    {
+        return Err(
+            format!("{auth_header} header can not be used with defined auth options").into(),
+        );
     }
 
     let headers: IndexMap<HeaderName, HeaderValue> = headers
@@ -506,4 +638,58 @@ mod test {
             "https://localhost:8087/some_intermediate_path/v1/traces?query=val"
         );
     }
+
+    #[tokio::test]
+    async fn test_otlp_sink_grpc_protocol() {
+        let config = indoc! {r#"
+            endpoint = "http://localhost:4317"
+            protocol = "grpc"
+            compression = "gzip"
+        "#};
+        let (config, cx) =
+            load_sink::<OpentelemetrySinkConfig>(config).expect("Config parsing error");
+        assert_eq!(config.protocol, OpentelemetrySinkProtocol::Grpc);
+        assert!(config.build(cx).await.is_ok());
+
+        // gRPC only supports gzip compression
+        let config = indoc! {r#"
+            endpoint = "http://localhost:4317"
+            protocol = "grpc"
+            compression = "zstd"
+        "#};
+        let (config, cx) =
+            load_sink::<OpentelemetrySinkConfig>(config).expect("Config parsing error");
+        assert!(config.build(cx).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_otlp_sink_json_encoding() {
+        let config = indoc! {r#"
+            endpoint = "http://localhost:4318"
+            encoding = "json"
+        "#};
+        let (config, cx) =
+            load_sink::<OpentelemetrySinkConfig>(config).expect("Config parsing error");
+        assert_eq!(config.encoding, OpentelemetrySinkEncoding::Json);
+        assert!(config.build(cx).await.is_ok());
+
+        // gRPC only supports protobuf encoding
+        let config = indoc! {r#"
+            endpoint = "http://localhost:4317"
+            protocol = "grpc"
+            encoding = "json"
+        "#};
+        let (config, cx) =
+            load_sink::<OpentelemetrySinkConfig>(config).expect("Config parsing error");
+        assert!(config.build(cx).await.is_err());
+    }
+
+    #[test]
+    fn test_otlp_sink_auth_header_conflict() {
+        let headers = BTreeMap::from([("X-Api-Key".to_owned(), "other".to_owned())]);
+
+        assert!(validate_headers(&headers, None).is_ok());
+        assert!(validate_headers(&headers, Some(http::header::AUTHORIZATION)).is_ok());
+        assert!(validate_headers(&headers, Some(HeaderName::from_static("x-api-key"))).is_err());
+    }
 }
@@ -2,6 +2,12 @@ use std::io;
 
 use crate::sinks::prelude::*;
 use crate::sinks::util::encoding::{Encoder, as_tracked_write};
+use vector_lib::{
+    codecs::decoding::OpenTelemetryPayloadFormat,
+    opentelemetry::proto::{
+        LOGS_REQUEST_MESSAGE_TYPE, METRICS_REQUEST_MESSAGE_TYPE, TRACES_REQUEST_MESSAGE_TYPE,
+    },
+};
 
 use super::{
     logs::encoding::encode as encode_log, metrics::encoding::encode as encode_metrics,
@@ -9,8 +15,16 @@ use super::{
     traces::encoding::encode as encode_traces,
 };
 
-#[derive(Clone, Debug)]
-pub struct OpentelemetryEncoder;
+#[derive(Clone, Debug, Default)]
+pub struct OpentelemetryEncoder {
+    format: OpenTelemetryPayloadFormat,
+}
+
+impl OpentelemetryEncoder {
+    pub const fn new(format: OpenTelemetryPayloadFormat) -> Self {
+        Self { format }
+    }
+}
 
 impl Encoder<Result<OpentelemetryModel, OpentelemetrySinkError>> for OpentelemetryEncoder {
     fn encode_input(
@@ -18,11 +32,21 @@ impl Encoder<Result<OpentelemetryModel, OpentelemetrySinkError>> for Opentelemet
         input: Result<OpentelemetryModel, OpentelemetrySinkError>,
         writer: &mut dyn io::Write,
     ) -> io::Result<(usize, GroupedCountByteSize)> {
-        let output = match input? {
-            OpentelemetryModel::Logs(log_models) => encode_log(log_models)?,
-            OpentelemetryModel::Metrics(metric_models) => encode_metrics(metric_models)?,
-            OpentelemetryModel::Traces(trace_models) => encode_traces(trace_models)?,
+        let (output, message_type) = match input? {
+            OpentelemetryModel::Logs(log_models) => {
+                (encode_log(log_models)?, LOGS_REQUEST_MESSAGE_TYPE)
+            }
+            OpentelemetryModel::Metrics(metric_models) => {
+                (encode_metrics(metric_models)?, METRICS_REQUEST_MESSAGE_TYPE)
+            }
+            OpentelemetryModel::Traces(trace_models) => {
+                (encode_traces(trace_models)?, TRACES_REQUEST_MESSAGE_TYPE)
+            }
         };
+        let output = self
+            .format
+            .protobuf_to_payload(output, message_type)
+            .map_err(io::Error::other)?;
         let size = as_tracked_write::<_, _, io::Error>(writer, &output, |writer, output| {
             writer.write_all(output)?;
             Ok(())
@@ -35,3 +59,56 @@ impl Encoder<Result<OpentelemetryModel, OpentelemetrySinkError>> for Opentelemet
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value as JsonValue, json};
+    use vector_lib::{
+        codecs::decoding::{MezmoDeserializer, format::Deserializer},
+        config::LogNamespace,
+    };
+
+    use super::*;
+    use crate::sinks::mezmo_opentelemetry::traces::model::OpentelemetryTracesModel;
+
+    #[test]
+    fn encodes_otlp_json() {
+        let request = json!({
+            "resourceSpans": [{
+                "scopeSpans": [{
+                    "spans": [{
+                        "traceId": "5b8efff798038103d269b633813fc60c",
+                        "spanId": "eee19b7ec3c1b174",
+                        "parentSpanId": "eee19b7ec3c1b173",
+                        "name": "checkout",
+                        "kind": 2,
+                        "startTimeUnixNano": "1579134612000000011",
+                        "endTimeUnixNano": "1579134612000000012"
+                    }]
+                }]
+            }]
+        });
+        let events = MezmoDeserializer::OpenTelemetryTraces
+            .build_for_content_type(Some("application/json"))
+            .parse(request.to_string().into(), LogNamespace::Legacy)
+            .unwrap();
+        let models = events
+            .into_iter()
+            .map(|event| OpentelemetryTracesModel::try_from(event).unwrap())
+            .collect();
+
+        let mut output = vec![];
+        OpentelemetryEncoder::new(OpenTelemetryPayloadFormat::Json)
+            .encode_input(Ok(OpentelemetryModel::Traces(models)), &mut output)
+            .unwrap();
+
+        let output: JsonValue = serde_json::from_slice(&output).unwrap();
+        let span = &output["resourceSpans"][0]["scopeSpans"][0]["spans"][0];
+        assert_eq!(span["traceId"], "5b8efff798038103d269b633813fc60c");
+        assert_eq!(span["spanId"], "eee19b7ec3c1b174");
+        assert_eq!(span["parentSpanId"], "eee19b7ec3c1b173");
+        assert_eq!(span["name"], "checkout");
+        assert_eq!(span["kind"], 2);
+        assert_eq!(span["startTimeUnixNano"], "1579134612000000011");
+    }
+}
@@ -0,0 +1,455 @@
+use std::task::{Context, Poll};
+
+use bytes::{Buf, BufMut, Bytes};
+use futures::future::BoxFuture;
+use http::{
+    HeaderMap, HeaderName, HeaderValue, Uri,
+    uri::{Authority, PathAndQuery, Scheme},
+};
+use hyper_openssl::HttpsConnector;
+use hyper_proxy::ProxyConnector;
+use indexmap::IndexMap;
+use tonic::{
+    Code, Status,
+    body::BoxBody,
+    client::Grpc,
+    codec::{Codec, CompressionEncoding, DecodeBuf, Decoder, EncodeBuf, Encoder},
+    metadata::MetadataMap,
+};
+use tower::Service;
+use vector_lib::{
+    config::proxy::ProxyConfig,
+    request_metadata::MetaDescriptive,
+    tls::{MaybeTlsSettings, TlsSettings},
+};
+
+use super::{
+    Auth,
+    config::OpentelemetrySinkEndpointError,
+    models::OpentelemetryModelType,
+    service::{OpentelemetryApiRequest, OpentelemetryApiResponse},
+    sink::OpentelemetrySinkError,
+};
+use crate::http::{VectorHttpConnector, build_proxy_connector};
+
+const LOGS_EXPORT_PATH: &str = "/opentelemetry.proto.collector.logs.v1.LogsService/Export";
+const METRICS_EXPORT_PATH: &str = "/opentelemetry.proto.collector.metrics.v1.MetricsService/Export";
+const TRACES_EXPORT_PATH: &str = "/opentelemetry.proto.collector.trace.v1.TraceService/Export";
+
+type GrpcHyperClient = hyper::Client<ProxyConnector<HttpsConnector<VectorHttpConnector>>, BoxBody>;
+
+/// The gRPC method exporting the given model type.
+const fn export_path(model_type: OpentelemetryModelType) -> Option<&'static str> {
+    match model_type {
+        OpentelemetryModelType::Logs => Some(LOGS_EXPORT_PATH),
+        OpentelemetryModelType::Metrics { .. } => Some(METRICS_EXPORT_PATH),
+        OpentelemetryModelType::Traces { .. } => Some(TRACES_EXPORT_PATH),
+        OpentelemetryModelType::Unknown => None,
+    }
+}
+
+/// Whether a gRPC status can be retried, as listed by the OTLP/gRPC spec.
+const fn is_retriable_code(code: Code) -> bool {
+    matches!(
+        code,
+        Code::Cancelled
+            | Code::DeadlineExceeded
+            | Code::ResourceExhausted
+            | Code::Aborted
+            | Code::OutOfRange
+            | Code::Unavailable
+            | Code::DataLoss
+    )
+}
+
+/// A codec passing the messages through as-is. The export requests are already protobuf
+/// encoded by the model encoders, and the export responses are decoded by
+/// `OpentelemetryApiResponse`, the same way for both transports.
+#[derive(Clone, Copy, Debug, Default)]
+struct RawCodec;
+
+impl Codec for RawCodec {
+    type Encode = Bytes;
+    type Decode = Bytes;
+    type Encoder = RawCodec;
+    type Decoder = RawCodec;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        RawCodec
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        RawCodec
+    }
+}
+
+impl Encoder for RawCodec {
+    type Item = Bytes;
+    type Error = Status;
+
+    fn encode(&mut self, item: Self::Item, dst: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
+        dst.put(item);
+        Ok(())
+    }
+}
+
+impl Decoder for RawCodec {
+    type Item = Bytes;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        Ok(Some(src.copy_to_bytes(src.remaining())))
+    }
+}
+
+/// Sends the gRPC requests to the scheme and authority of the collector endpoint.
+#[derive(Clone, Debug)]
+pub struct GrpcHyperSvc {
+    scheme: Scheme,
+    authority: Authority,
+    client: GrpcHyperClient,
+}
+
+impl Service<hyper::Request<BoxBody>> for GrpcHyperSvc {
+    type Response = hyper::Response<hyper::Body>;
+    type Error = hyper::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut req: hyper::Request<BoxBody>) -> Self::Future {
+        let path_and_query = req
+            .uri()
+            .path_and_query()
+            .cloned()
+            .unwrap_or_else(|| PathAndQuery::from_static("/"));
+        *req.uri_mut() = Uri::builder()
+            .scheme(self.scheme.clone())
+            .authority(self.authority.clone())
+            .path_and_query(path_and_query)
+            .build()
+            .expect("building gRPC URI failed unexpectedly");
+
+        Box::pin(self.client.request(req))
+    }
+}
+
+/// Sends the export requests to the collector with OTLP/gRPC.
+#[derive(Clone, Debug)]
+pub struct OpentelemetryGrpcService {
+    client: Grpc<GrpcHyperSvc>,
+    auth: Option<Auth>,
+    metadata: HeaderMap,
+}
+
+impl OpentelemetryGrpcService {
+    pub fn new(
+        endpoint: &str,
+        tls: TlsSettings,
+        proxy: &ProxyConfig,
+        compression: bool,
+        auth: Option<Auth>,
+        headers: IndexMap<HeaderName, HeaderValue>,
+    ) -> crate::Result<Self> {
+        let uri = endpoint
+            .parse::<Uri>()
+            .map_err(OpentelemetrySinkEndpointError::from)?;
+        let scheme = uri.scheme().cloned().ok_or("Endpoint scheme is invalid")?;
+        let authority = uri
+            .authority()
+            .cloned()
+            .ok_or("Endpoint authority is invalid")?;
+
+        let proxy = build_proxy_connector(MaybeTlsSettings::from(tls), proxy)?;
+        let client = hyper::Client::builder().http2_only(true).build(proxy);
+
+        let mut client = Grpc::new(GrpcHyperSvc {
+            scheme,
+            authority,
+            client,
+        });
+        if compression {
+            client = client.send_compressed(CompressionEncoding::Gzip);
+        }
+
+        let mut metadata = HeaderMap::new();
+        for (name, value) in headers {
+            metadata.insert(name, value);
+        }
+
+        Ok(Self {
+            client,
+            auth,
+            metadata,
+        })
+    }
+}
+
+impl Service<OpentelemetryApiRequest> for OpentelemetryGrpcService {
+    type Response = OpentelemetryApiResponse;
+    type Error = OpentelemetrySinkError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    // Readiness of the client is awaited in `call()`, as in the `vector` sink.
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut request: OpentelemetryApiRequest) -> Self::Future {
+        let model_type = request.model_type;
+        let Some(path) = export_path(model_type) else {
+            return Box::pin(async move {
+                Err(OpentelemetrySinkError::new(
+                    "Endpoint is not defined for model type: Unknown",
+                ))
+            });
+        };
+
+        let mut client = self.client.clone();
+        let metadata = std::mem::take(request.metadata_mut());
+        let events_byte_size = metadata
+            .clone()
+            .into_events_estimated_json_encoded_byte_size();
+
+        let payload = request.payload;
+        let mut headers = self.metadata.clone();
+        let auth = self.auth.clone();
+
+        Box::pin(async move {
+            if let Some(auth) = &auth {
+                auth.apply(&mut headers).await?;
+            }
+            let mut grpc_request = tonic::Request::new(payload);
+            *grpc_request.metadata_mut() = MetadataMap::from_headers(headers);
+
+            client.ready().await.map_err(|error| {
+                OpentelemetrySinkError::retriable(
+                    &format!("gRPC client was not ready: {error}"),
+                    None,
+                )
+            })?;
+
+            match client
+                .unary(grpc_request, PathAndQuery::from_static(path), RawCodec)
+                .await
+            {
+                Ok(response) => Ok(OpentelemetryApiResponse::delivered(
+                    model_type,
+                    metadata,
+                    events_byte_size,
+                    response.get_ref(),
+                )),
+                Err(status) => {
+                    if status.code() == Code::Unauthenticated
+                        && let Some(auth) = &auth
+                    {
+                        auth.invalidate().await;
+                    }
+
+                    let message = format!(
+                        "OpenTelemetry collector respond with error status: {:?}, message: {}",
+                        status.code(),
+                        status.message(),
+                    );
+                    if is_retriable_code(status.code()) {
+                        Err(OpentelemetrySinkError::retriable(&message, None))
+                    } else {
+                        Err(OpentelemetrySinkError::new(&message))
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use prost::Message;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tonic::{Request, Response};
+    use tower::{ServiceBuilder, ServiceExt};
+    use vector_lib::{
+        event::Value,
+        finalization::{EventFinalizers, EventStatus},
+        opentelemetry::proto::collector::logs::v1::{
+            ExportLogsPartialSuccess, ExportLogsServiceRequest, ExportLogsServiceResponse,
+            logs_service_server::{LogsService, LogsServiceServer},
+        },
+        request_metadata::RequestMetadata,
+        stream::DriverResponse,
+    };
+
+    use super::*;
+    use crate::{
+        mezmo::user_trace::UserLoggingResponse,
+        sinks::{
+            mezmo_opentelemetry::config::OpentelemetryRetry,
+            util::{Compression, ServiceBuilderExt, http::RequestConfig},
+        },
+        test_util::trace_init,
+    };
+
+    #[derive(Clone, Default)]
+    struct Collector {
+        calls: Arc<AtomicUsize>,
+        requests: Arc<Mutex<Vec<(MetadataMap, ExportLogsServiceRequest)>>>,
+    }
+
+    #[tonic::async_trait]
+    impl LogsService for Collector {
+        async fn export(
+            &self,
+            request: Request<ExportLogsServiceRequest>,
+        ) -> Result<Response<ExportLogsServiceResponse>, Status> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                return Err(Status::unavailable("warming up"));
+            }
+
+            let metadata = request.metadata().clone();
+            self.requests
+                .lock()
+                .unwrap()
+                .push((metadata, request.into_inner()));
+            Ok(Response::new(ExportLogsServiceResponse {
+                partial_success: Some(ExportLogsPartialSuccess {
+                    rejected_log_records: 1,
+                    error_message: "missing body".to_owned(),
+                }),
+            }))
+        }
+    }
+
+    async fn start_collector(collector: Collector) -> std::net::SocketAddr {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(
+                    LogsServiceServer::new(collector).accept_compressed(CompressionEncoding::Gzip),
+                )
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+        addr
+    }
+
+    fn service(addr: std::net::SocketAddr) -> OpentelemetryGrpcService {
+        let headers = IndexMap::from([(
+            HeaderName::from_static("x-custom"),
+            HeaderValue::from_static("custom"),
+        )]);
+        let auth = Auth::Basic(crate::http::Auth::Bearer {
+            token: "token".to_owned().into(),
+        });
+        OpentelemetryGrpcService::new(
+            &format!("http://{addr}"),
+            TlsSettings::from_options(None).unwrap(),
+            &ProxyConfig::default(),
+            true,
+            Some(auth),
+            headers,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn retriable_codes() {
+        for code in [Code::Unavailable, Code::ResourceExhausted, Code::Aborted] {
+            assert!(is_retriable_code(code));
+        }
+        for code in [Code::InvalidArgument, Code::Unauthenticated, Code::NotFound] {
+            assert!(!is_retriable_code(code));
+        }
+    }
+
+    #[tokio::test]
+    async fn exports_logs() {
+        trace_init();
+
+        let collector = Collector::default();
+        let addr = start_collector(collector.clone()).await;
+
+        let export = ExportLogsServiceRequest::default();
+        let request = OpentelemetryApiRequest {
+            payload: export.encode_to_vec().into(),
+            compression: Compression::None,
+            metadata: RequestMetadata::default(),
+            finalizers: EventFinalizers::default(),
+            model_type: OpentelemetryModelType::Logs,
+        };
+
+        let request_settings = RequestConfig::default().tower.into_settings();
+        let service = ServiceBuilder::new()
+            .settings(request_settings, OpentelemetryRetry)
+            .service(service(addr));
+        let response = service.oneshot(request).await.unwrap();
+
+        assert_eq!(response.event_status(), EventStatus::Delivered);
+        assert_eq!(
+            response.log_msg(),
+            Some(Value::from(
+                "OpenTelemetry collector rejected 1 log records: missing body"
+            ))
+        );
+        assert_eq!(collector.calls.load(Ordering::SeqCst), 2);
+
+        let requests = collector.requests.lock().unwrap();
+        let (metadata, received) = &requests[0];
+        assert_eq!(received, &export);
+        assert_eq!(metadata.get("x-custom").unwrap(), "custom");
+        assert_eq!(metadata.get("authorization").unwrap(), "Bearer token");
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_invalid_argument() {
+        trace_init();
+
+        #[derive(Clone, Default)]
+        struct Rejecting(Arc<AtomicUsize>);
+
+        #[tonic::async_trait]
+        impl LogsService for Rejecting {
+            async fn export(
+                &self,
+                _request: Request<ExportLogsServiceRequest>,
+            ) -> Result<Response<ExportLogsServiceResponse>, Status> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                Err(Status::invalid_argument("bad request"))
+            }
+        }
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let collector = Rejecting::default();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(
+                    LogsServiceServer::new(collector.clone())
+                        .accept_compressed(CompressionEncoding::Gzip),
+                )
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+
+        let request = OpentelemetryApiRequest {
+            payload: Bytes::new(),
+            compression: Compression::None,
+            metadata: RequestMetadata::default(),
+            finalizers: EventFinalizers::default(),
+            model_type: OpentelemetryModelType::Logs,
+        };
+        let request_settings = RequestConfig::default().tower.into_settings();
+        let service = ServiceBuilder::new()
+            .settings(request_settings, OpentelemetryRetry)
+            .service(service(addr));
+
+        let error = service.oneshot(request).await.unwrap_err();
+        assert!(error.to_string().contains("bad request"));
+        assert_eq!(collector.0.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,14 +1,41 @@
 use opentelemetry_proto::tonic::collector::metrics::v1::ExportMetricsServiceRequest;
+use opentelemetry_proto::tonic::metrics::v1::{Summary, metric::Data};
 use prost::Message;
 
-use super::model::OpentelemetryResourceMetrics;
+use super::model::{OpentelemetryResourceMetrics, OpentelemetrySummary};
 use crate::sinks::mezmo_opentelemetry::sink::OpentelemetrySinkError;
 
 pub fn encode(model: OpentelemetryResourceMetrics) -> Result<Vec<u8>, OpentelemetrySinkError> {
     // Metric model to Protobuf encoding
     // https://github.com/open-telemetry/opentelemetry-rust/blob/936c46639aa1521bf49dbffba49bbd9795f8ea58/opentelemetry-otlp/src/exporter/http/metrics.rs#L52-L61
 
-    let req: ExportMetricsServiceRequest = (&model.0).into();
+    let mut req: ExportMetricsServiceRequest = (&model.0).into();
+
+    // Summaries aren't known to the conversion, which leaves their data empty.
+    // The metrics are converted in order, so they're matched with the model ones.
+    for resource_metrics in &mut req.resource_metrics {
+        for (scope_metrics, model_scope_metrics) in resource_metrics
+            .scope_metrics
+            .iter_mut()
+            .zip(&model.0.scope_metrics)
+        {
+            for (metric, model_metric) in scope_metrics
+                .metrics
+                .iter_mut()
+                .zip(&model_scope_metrics.metrics)
+            {
+                if let Some(summary) = model_metric
+                    .data
+                    .as_any()
+                    .downcast_ref::<OpentelemetrySummary>()
+                {
+                    metric.data = Some(Data::Summary(Summary {
+                        data_points: summary.data_points.clone(),
+                    }));
+                }
+            }
+        }
+    }
 
     let mut buf = vec![];
     req.encode(&mut buf).map_err(OpentelemetrySinkError::from)?;
@@ -1,7 +1,9 @@
+use std::any::Any;
 use std::borrow::Cow;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::sync::LazyLock;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::sinks::mezmo_opentelemetry::{
     config::OpentelemetryMetricConfig,
@@ -11,10 +13,16 @@ use crate::sinks::mezmo_opentelemetry::{
     },
     sink::OpentelemetrySinkError,
 };
+use crate::sinks::util::statistic::DistributionStatistic;
+use opentelemetry_proto::{
+    tonic::metrics::v1::{SummaryDataPoint, summary_data_point::ValueAtQuantile},
+    transform::common::tonic::Attributes,
+};
 use opentelemetry_sdk::{
     Resource,
     metrics::data::{
-        Aggregation, DataPoint, Exemplar, Gauge, Histogram, HistogramDataPoint, Metric,
+        Aggregation, DataPoint, Exemplar, ExponentialBucket, ExponentialHistogram,
+        ExponentialHistogramDataPoint, Gauge, Histogram, HistogramDataPoint, Metric,
         ResourceMetrics, ScopeMetrics, Sum, Temporality,
     },
 };
@@ -25,10 +33,15 @@ use vector_lib::{
     config::log_schema,
     event::{
         Event, KeyString, MetricKind, MetricValue, StatisticKind, Value,
-        metric::{Metric as MezmoMetric, samples_to_buckets},
+        metric::{Metric as MezmoMetric, MetricSketch, samples_to_buckets},
     },
+    metrics::AgentDDSketch,
 };
 
+/// Scale of the exponential histograms converted from sketches. Its base, 2^(2^-5) ~= 1.0219,
+/// is the closest one to the sketches' gamma (1 + 1/64) that doesn't split their bins.
+const SKETCH_EXPONENTIAL_SCALE: i8 = 5;
+
 static WORD_TO_UCUM: LazyLock<HashMap<&'static str, &'static str>> = LazyLock::new(|| {
     vec![
         // Time
@@ -162,6 +175,37 @@ fn get_object(value_object: &BTreeMap<KeyString, Value>, name: &str) -> BTreeMap
         .clone()
 }
 
+fn get_exponential_buckets(
+    value_object: &BTreeMap<KeyString, Value>,
+    name: &str,
+) -> Result<OpentelemetryExponentialBuckets, OpentelemetrySinkError> {
+    let buckets = get_object(value_object, name);
+
+    let offset = match buckets.get("offset") {
+        Some(Value::Integer(offset)) => i32::try_from(*offset).map_err(|_| {
+            OpentelemetrySinkError::new(
+                format!("NumberTruncation field: {name}.offset is out of range").as_str(),
+            )
+        })?,
+        _ => 0,
+    };
+
+    let counts = match buckets.get("bucket_counts") {
+        Some(Value::Array(counts)) => counts
+            .iter()
+            .map(|count| parse_u64(count, "bucket_counts"))
+            .collect::<Result<Vec<u64>, _>>()?,
+        _ => Vec::new(),
+    };
+
+    Ok(OpentelemetryExponentialBuckets { offset, counts })
+}
+
+fn to_unix_nanos(time: Option<SystemTime>) -> u64 {
+    time.and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |duration| duration.as_nanos() as u64)
+}
+
 fn get_string_or_defailt<'a>(value_object: &BTreeMap<KeyString, Value>, key: &'a str) -> String {
     match value_object.get(key) {
         Some(Value::Bytes(bytes)) => String::from_utf8_lossy(bytes).into_owned(),
@@ -304,27 +348,14 @@ pub struct OpentelemetryMetricsModel {
 impl OpentelemetryModelMatch for OpentelemetryMetricsModel {
     fn maybe_match(event: &Event) -> Option<OpentelemetryModelType> {
         if let Some(metric) = event.clone().try_into_metric() {
-            match metric.value() {
-                // Skip Summary and Sketch metrics
-                MetricValue::Distribution {
-                    statistic: StatisticKind::Summary,
-                    ..
-                }
-                | MetricValue::AggregatedSummary { .. }
-                | MetricValue::Sketch { .. } => {
-                    return None;
-                }
-                _ => {
-                    let arbitrary = metric.arbitrary_value().value();
-                    let user_metadata = get_object(arbitrary, log_schema().user_metadata_key());
-                    let resource = get_object(&user_metadata, "resource");
-                    let partitioner_key: OpentelemetrySpanId = resource.get("uniq_id").into();
-
-                    return Some(OpentelemetryModelType::Metrics {
-                        partitioner_key: partitioner_key.into(),
-                    });
-                }
-            }
+            let arbitrary = metric.arbitrary_value().value();
+            let user_metadata = get_object(arbitrary, log_schema().user_metadata_key());
+            let resource = get_object(&user_metadata, "resource");
+            let partitioner_key: OpentelemetrySpanId = resource.get("uniq_id").into();
+
+            return Some(OpentelemetryModelType::Metrics {
+                partitioner_key: partitioner_key.into(),
+            });
         }
 
         None
@@ -379,12 +410,19 @@ impl TryFrom<(Event, &OpentelemetryMetricConfig)> for OpentelemetryMetricsModel
                     temporality = Some(Temporality::Cumulative);
                 }
             }
-            OpentelemetryDataPoint::Histogram(..) => {
+            OpentelemetryDataPoint::Histogram(..)
+            | OpentelemetryDataPoint::ExponentialHistogram(..) => {
                 if temporality.is_none() {
-                    temporality = Some(Temporality::Cumulative);
+                    // Sketches hold the values of their own interval when incremental
+                    temporality = match (metric.value(), metric.kind()) {
+                        (MetricValue::Sketch { .. }, MetricKind::Incremental) => {
+                            Some(Temporality::Delta)
+                        }
+                        _ => Some(Temporality::Cumulative),
+                    };
                 }
             }
-            OpentelemetryDataPoint::Gauge(..) => {}
+            OpentelemetryDataPoint::Gauge(..) | OpentelemetryDataPoint::Summary(..) => {}
         };
 
         let mut unit_word = String::new();
@@ -423,6 +461,9 @@ pub enum OpentelemetryDataPoint {
     Gauge(DataPoint<f64>),
     Sum(DataPoint<f64>),
     Histogram(HistogramDataPoint<f64>),
+    ExponentialHistogram(OpentelemetryExponentialHistogramDataPoint),
+    // The SDK has no summary data points, the protobuf ones are used instead
+    Summary(SummaryDataPoint),
 }
 
 impl From<&OpentelemetryDataPoint> for String {
@@ -431,10 +472,152 @@ impl From<&OpentelemetryDataPoint> for String {
             OpentelemetryDataPoint::Gauge(..) => "gauge".into(),
             OpentelemetryDataPoint::Sum(..) => "sum".into(),
             OpentelemetryDataPoint::Histogram(..) => "histogram".into(),
+            OpentelemetryDataPoint::ExponentialHistogram(..) => "exponential_histogram".into(),
+            OpentelemetryDataPoint::Summary(..) => "summary".into(),
         }
     }
 }
 
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OpentelemetryExponentialBuckets {
+    pub offset: i32,
+    pub counts: Vec<u64>,
+}
+
+impl From<BTreeMap<i32, u64>> for OpentelemetryExponentialBuckets {
+    fn from(buckets: BTreeMap<i32, u64>) -> Self {
+        let (Some((&first, _)), Some((&last, _))) =
+            (buckets.first_key_value(), buckets.last_key_value())
+        else {
+            return Self::default();
+        };
+
+        let mut counts = vec![0; (last - first + 1) as usize];
+        for (index, count) in buckets {
+            counts[(index - first) as usize] = count;
+        }
+
+        Self {
+            offset: first,
+            counts,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OpentelemetryExponentialHistogramDataPoint {
+    pub attributes: Vec<KeyValue>,
+    pub start_time: SystemTime,
+    pub time: SystemTime,
+    pub count: u64,
+    pub sum: f64,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub scale: i8,
+    pub zero_count: u64,
+    pub zero_threshold: f64,
+    pub positive: OpentelemetryExponentialBuckets,
+    pub negative: OpentelemetryExponentialBuckets,
+    pub exemplars: Vec<Exemplar<f64>>,
+}
+
+impl OpentelemetryExponentialHistogramDataPoint {
+    /// Converts an agent sketch into an exponential histogram. Each bin of the sketch is counted
+    /// in the bucket of its lower bound, the value the sketch stands its samples for.
+    fn from_sketch(
+        sketch: &AgentDDSketch,
+        attributes: Vec<KeyValue>,
+        start_time: SystemTime,
+        time: SystemTime,
+        exemplars: Vec<Exemplar<f64>>,
+    ) -> Self {
+        let config = sketch.config();
+        // The bucket at index `i` covers the (base^i, base^(i+1)] range, base = 2^(2^-scale)
+        let index_factor = f64::from(SKETCH_EXPONENTIAL_SCALE).exp2();
+
+        let mut zero_count = 0;
+        let mut positive = BTreeMap::<i32, u64>::new();
+        let mut negative = BTreeMap::<i32, u64>::new();
+
+        let (keys, counts) = sketch.bin_map().into_parts();
+        for (key, count) in keys.into_iter().zip(counts) {
+            if key == 0 {
+                zero_count += u64::from(count);
+                continue;
+            }
+
+            let value = config.bin_lower_bound(key).abs().min(f64::MAX);
+            let index = ((value.log2() * index_factor).ceil() - 1.0) as i32;
+            let buckets = if key > 0 {
+                &mut positive
+            } else {
+                &mut negative
+            };
+            *buckets.entry(index).or_default() += u64::from(count);
+        }
+
+        Self {
+            attributes,
+            start_time,
+            time,
+            count: u64::from(sketch.count()),
+            sum: sketch.sum().unwrap_or_default(),
+            min: sketch.min(),
+            max: sketch.max(),
+            scale: SKETCH_EXPONENTIAL_SCALE,
+            zero_count,
+            // Values smaller than the first bin's lower bound are counted in the zero bin
+            zero_threshold: config.bin_lower_bound(1),
+            positive: positive.into(),
+            negative: negative.into(),
+            exemplars,
+        }
+    }
+}
+
+impl From<&OpentelemetryExponentialHistogramDataPoint> for ExponentialHistogramDataPoint<f64> {
+    fn from(data_point: &OpentelemetryExponentialHistogramDataPoint) -> Self {
+        Self {
+            attributes: data_point.attributes.as_slice().into(),
+            start_time: data_point.start_time,
+            time: data_point.time,
+            count: data_point.count as usize,
+            min: data_point.min,
+            max: data_point.max,
+            sum: data_point.sum,
+            scale: data_point.scale,
+            zero_count: data_point.zero_count,
+            positive_bucket: ExponentialBucket {
+                offset: data_point.positive.offset,
+                counts: data_point.positive.counts.clone(),
+            },
+            negative_bucket: ExponentialBucket {
+                offset: data_point.negative.offset,
+                counts: data_point.negative.counts.clone(),
+            },
+            zero_threshold: data_point.zero_threshold,
+            exemplars: data_point.exemplars.clone(),
+        }
+    }
+}
+
+/// Summaries aren't one of the SDK aggregations, so they're set on the protobuf request
+/// after the conversion of the resource metrics.
+#[derive(Debug)]
+pub struct OpentelemetrySummary {
+    pub data_points: Vec<SummaryDataPoint>,
+}
+
+impl Aggregation for OpentelemetrySummary {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
 impl TryFrom<(&MezmoMetric, &OpentelemetryMetricConfig)> for OpentelemetryDataPoint {
     type Error = OpentelemetrySinkError;
 
@@ -516,6 +699,11 @@ impl TryFrom<(&MezmoMetric, &OpentelemetryMetricConfig)> for OpentelemetryDataPo
             None
         };
 
+        let flags = match arbitrary.get("flags") {
+            Some(Value::Integer(flags)) => u32::try_from(*flags).unwrap_or_default(),
+            _ => 0,
+        };
+
         match metric.value() {
             MetricValue::Gauge { value } => {
                 if is_otlp_event && original_type == "sum" {
@@ -597,6 +785,36 @@ impl TryFrom<(&MezmoMetric, &OpentelemetryMetricConfig)> for OpentelemetryDataPo
                 count,
                 sum,
             } => {
+                if is_otlp_event && original_type == "exponential_histogram" {
+                    // OTLP source converts exponential histograms into aggregated histograms,
+                    // the original buckets are kept as arbitrary fields.
+                    let scale = get_property(arbitrary, "scale")?
+                        .as_integer()
+                        .and_then(|scale| i8::try_from(scale).ok())
+                        .ok_or_else(|| Self::Error::new("FieldInvalidType field: scale"))?;
+
+                    return Ok(Self::ExponentialHistogram(
+                        OpentelemetryExponentialHistogramDataPoint {
+                            attributes,
+                            start_time: start_time.unwrap(),
+                            time: time.unwrap(),
+                            count: *count,
+                            sum: *sum,
+                            min,
+                            max,
+                            scale,
+                            zero_count: parse_u64(
+                                get_property(arbitrary, "zero_count")?,
+                                "zero_count",
+                            )?,
+                            zero_threshold: get_float(arbitrary, "zero_threshold")?,
+                            positive: get_exponential_buckets(arbitrary, "positive")?,
+                            negative: get_exponential_buckets(arbitrary, "negative")?,
+                            exemplars,
+                        },
+                    ));
+                }
+
                 let mut bounds: Vec<f64> = vec![];
                 let mut bucket_counts: Vec<u64> = vec![];
 
@@ -646,16 +864,63 @@ impl TryFrom<(&MezmoMetric, &OpentelemetryMetricConfig)> for OpentelemetryDataPo
             }
             MetricValue::Distribution {
                 statistic: StatisticKind::Summary,
-                ..
-            } => Err(Self::Error::new(
-                "Summary metric type does is not supported",
-            )),
-            MetricValue::AggregatedSummary { .. } => Err(Self::Error::new(
-                "Summary metric type does is not supported",
-            )),
-            MetricValue::Sketch { .. } => {
-                Err(Self::Error::new("Sketch metric type does is not supported"))
+                samples,
+            } => {
+                // convert distributions into summaries
+                let (count, sum, quantile_values) =
+                    match DistributionStatistic::from_samples(samples, &metric_config.quantiles) {
+                        Some(statistic) => (
+                            statistic.count,
+                            statistic.sum,
+                            statistic
+                                .quantiles
+                                .into_iter()
+                                .map(|(quantile, value)| ValueAtQuantile { quantile, value })
+                                .collect(),
+                        ),
+                        None => (0, 0.0, vec![]),
+                    };
+
+                Ok(Self::Summary(SummaryDataPoint {
+                    attributes: Attributes::from(attributes).0,
+                    start_time_unix_nano: to_unix_nanos(start_time),
+                    time_unix_nano: to_unix_nanos(time),
+                    count,
+                    sum,
+                    quantile_values,
+                    flags,
+                }))
             }
+            MetricValue::AggregatedSummary {
+                quantiles,
+                count,
+                sum,
+            } => Ok(Self::Summary(SummaryDataPoint {
+                attributes: Attributes::from(attributes).0,
+                start_time_unix_nano: to_unix_nanos(start_time),
+                time_unix_nano: to_unix_nanos(time),
+                count: *count,
+                sum: *sum,
+                quantile_values: quantiles
+                    .iter()
+                    .map(|quantile| ValueAtQuantile {
+                        quantile: quantile.quantile,
+                        value: quantile.value,
+                    })
+                    .collect(),
+                flags,
+            })),
+            MetricValue::Sketch { sketch } => match sketch {
+                MetricSketch::AgentDDSketch(ddsketch) => Ok(Self::ExponentialHistogram(
+                    OpentelemetryExponentialHistogramDataPoint::from_sketch(
+                        ddsketch,
+                        attributes,
+                        start_time.unwrap(),
+                        time.unwrap(),
+                        exemplars,
+                    ),
+                )),
+            },
         }
     }
 }
@@ -723,6 +988,27 @@ impl TryFrom<Vec<OpentelemetryMetricsModel>> for OpentelemetryResourceMetrics {
                         // Maybe we should set Temporality::Delta by default
                         temporality: metric.temporality.unwrap_or(Temporality::Cumulative),
                     }),
+                    "exponential_histogram" => Box::new(ExponentialHistogram {
+                        data_points: metric
+                            .data_points
+                            .iter()
+                            .filter_map(|data_point| match data_point {
+                                OpentelemetryDataPoint::ExponentialHistogram(v) => Some(v.into()),
+                                _ => None,
+                            })
+                            .collect::<Vec<ExponentialHistogramDataPoint<f64>>>(),
+                        temporality: metric.temporality.unwrap_or(Temporality::Cumulative),
+                    }),
+                    "summary" => Box::new(OpentelemetrySummary {
+                        data_points: metric
+                            .data_points
+                            .iter()
+                            .filter_map(|data_point| match data_point {
+                                OpentelemetryDataPoint::Summary(v) => Some(v.clone()),
+                                _ => None,
+                            })
+                            .collect::<Vec<SummaryDataPoint>>(),
+                    }),
                     &_ => todo!(),
                 };
 
@@ -881,11 +1167,21 @@ impl OpentelemetryDataPointGroup {
 mod test {
     use super::*;
     use crate::event::Value;
-    use crate::sinks::mezmo_opentelemetry::default_histogram_buckets;
+    use crate::sinks::mezmo_opentelemetry::{
+        default_histogram_buckets, default_summary_quantiles, metrics::encoding::encode,
+    };
     use chrono::DateTime;
+    use opentelemetry_proto::tonic::{
+        collector::metrics::v1::ExportMetricsServiceRequest,
+        common::v1::{AnyValue as ProtoAnyValue, KeyValue as ProtoKeyValue, any_value},
+        metrics::v1::{self as proto, metric::Data},
+    };
+    use proptest::prelude::*;
+    use prost::Message;
     use std::collections::BTreeMap;
     use std::time::SystemTime;
-    use vector_lib::config::log_schema;
+    use vector_lib::codecs::decoding::MezmoDeserializer;
+    use vector_lib::config::{LogNamespace, log_schema};
     use vector_lib::event::metric::mezmo::{from_f64_or_zero, to_metric};
     use vector_lib::event::{Event, LogEvent};
     use vector_lib::lookup::PathPrefix;
@@ -1366,6 +1662,7 @@ mod test {
         let mut metrics: Vec<OpentelemetryMetricsModel> = vec![];
         let config = OpentelemetryMetricConfig {
             buckets: default_histogram_buckets(),
+            quantiles: default_summary_quantiles(),
         };
         for event in generate_events(generator, gen_settings.len()) {
             match OpentelemetryMetricsModel::try_from((event.clone(), &config)) {
@@ -1450,6 +1747,7 @@ mod test {
         let mut metrics: Vec<OpentelemetryMetricsModel> = vec![];
         let config = OpentelemetryMetricConfig {
             buckets: default_histogram_buckets(),
+            quantiles: default_summary_quantiles(),
         };
         for event in generate_events(generator, gen_settings.len()) {
             match OpentelemetryMetricsModel::try_from((event.clone(), &config)) {
@@ -1536,6 +1834,7 @@ mod test {
         let mut metrics: Vec<OpentelemetryMetricsModel> = vec![];
         let config = OpentelemetryMetricConfig {
             buckets: default_histogram_buckets(),
+            quantiles: default_summary_quantiles(),
         };
         for event in generate_events(generator, gen_settings.len()) {
             match OpentelemetryMetricsModel::try_from((event.clone(), &config)) {
@@ -1603,6 +1902,7 @@ mod test {
         let mut metrics: Vec<OpentelemetryMetricsModel> = vec![];
         let config = OpentelemetryMetricConfig {
             buckets: default_histogram_buckets(),
+            quantiles: default_summary_quantiles(),
         };
         for event in generate_events(generator, gen_settings.len()) {
             match OpentelemetryMetricsModel::try_from((event.clone(), &config)) {
@@ -1676,6 +1976,7 @@ mod test {
         let mut metrics: Vec<OpentelemetryMetricsModel> = vec![];
         let config = OpentelemetryMetricConfig {
             buckets: default_histogram_buckets(),
+            quantiles: default_summary_quantiles(),
         };
         for event in generate_events(generator, gen_settings.len()) {
             match OpentelemetryMetricsModel::try_from((event.clone(), &config)) {
@@ -1775,6 +2076,7 @@ mod test {
         let mut metrics: Vec<OpentelemetryMetricsModel> = vec![];
         let config = OpentelemetryMetricConfig {
             buckets: default_histogram_buckets(),
+            quantiles: default_summary_quantiles(),
         };
         for event in generate_events(generator, gen_settings.len()) {
             match OpentelemetryMetricsModel::try_from((event.clone(), &config)) {
@@ -1892,6 +2194,7 @@ mod test {
         let mut metrics: Vec<OpentelemetryMetricsModel> = vec![];
         let config = OpentelemetryMetricConfig {
             buckets: default_histogram_buckets(),
+            quantiles: default_summary_quantiles(),
         };
         for event in generate_events(generator, gen_settings.len()) {
             match OpentelemetryMetricsModel::try_from((event.clone(), &config)) {
@@ -1964,6 +2267,7 @@ mod test {
         let mut metrics: Vec<OpentelemetryMetricsModel> = vec![];
         let config = OpentelemetryMetricConfig {
             buckets: default_histogram_buckets(),
+            quantiles: default_summary_quantiles(),
         };
         for event in generate_events(generator, gen_settings.len()) {
             match OpentelemetryMetricsModel::try_from((event.clone(), &config)) {
@@ -1991,8 +2295,16 @@ mod test {
     }
 
     #[tokio::test]
-    async fn test_otlp_sink_event_to_metric_model_unsupported_events() {
+    async fn test_otlp_sink_event_to_metric_model_summary_events() {
+        let uniq_id: [u8; 8] = [76, 114, 27, 243, 62, 60, 175, 143];
         let gen_settings = vec![
+            (
+                &TestMetricGenerator::AggregatedSummary,
+                "absolute",
+                true,
+                Some("summary"),
+                Some(uniq_id),
+            ),
             (
                 &TestMetricGenerator::AggregatedSummary,
                 "absolute",
@@ -2026,9 +2338,311 @@ mod test {
 
         let config = OpentelemetryMetricConfig {
             buckets: default_histogram_buckets(),
+            quantiles: default_summary_quantiles(),
         };
-        for event in generate_events(generator, gen_settings.len()) {
-            assert!(OpentelemetryMetricsModel::try_from((event.clone(), &config)).is_err());
+        let data_points = generate_events(generator, gen_settings.len())
+            .into_iter()
+            .map(
+                |event| match OpentelemetryMetricsModel::try_from((event, &config)) {
+                    Ok(model) => model.data_point,
+                    Err(err) => panic!("Metric event cannot be converted to a model: {err:#?}"),
+                },
+            )
+            .collect::<Vec<_>>();
+
+        let expected_time_nanos = 1_579_134_612_000_000_011;
+
+        match &data_points[0] {
+            OpentelemetryDataPoint::Summary(data_point) => {
+                assert_eq!(data_point.start_time_unix_nano, expected_time_nanos);
+                assert_eq!(data_point.time_unix_nano, expected_time_nanos);
+                assert_eq!(data_point.count, 10);
+                assert_eq!(data_point.sum, 3.7);
+                assert_eq!(
+                    data_point.quantile_values,
+                    [ValueAtQuantile {
+                        quantile: 0.005,
+                        value: 10.0,
+                    }]
+                );
+                assert_eq!(data_point.flags, 1);
+                assert_eq!(data_point.attributes.len(), 1);
+            }
+            other => panic!("Expected a summary data point: {other:#?}"),
+        }
+
+        match &data_points[1] {
+            OpentelemetryDataPoint::Summary(data_point) => {
+                assert_eq!(data_point.count, 10);
+                assert_eq!(data_point.sum, 3.7);
+                assert_eq!(data_point.quantile_values.len(), 1);
+                assert_eq!(data_point.flags, 0);
+            }
+            other => panic!("Expected a summary data point: {other:#?}"),
+        }
+
+        match &data_points[2] {
+            OpentelemetryDataPoint::Summary(data_point) => {
+                assert_eq!(data_point.count, 8);
+                assert_eq!(data_point.sum, 15.0);
+                assert_eq!(
+                    data_point
+                        .quantile_values
+                        .iter()
+                        .map(|quantile| quantile.quantile)
+                        .collect::<Vec<_>>(),
+                    default_summary_quantiles()
+                );
+            }
+            other => panic!("Expected a summary data point: {other:#?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_otlp_sink_event_to_metric_model_sketch_events() {
+        let mut sketch = AgentDDSketch::with_agent_defaults();
+        sketch.insert_many(&[-3.0, 0.0, 0.5, 2.0, 2.0, 100.0]);
+
+        let metric = MezmoMetric::new(
+            "latency",
+            MetricKind::Incremental,
+            MetricValue::Sketch {
+                sketch: MetricSketch::AgentDDSketch(sketch),
+            },
+        )
+        .with_timestamp(Some(chrono::Utc::now()));
+
+        let config = OpentelemetryMetricConfig {
+            buckets: default_histogram_buckets(),
+            quantiles: default_summary_quantiles(),
+        };
+        let model = OpentelemetryMetricsModel::try_from((Event::Metric(metric.clone()), &config))
+            .expect("Metric event cannot be converted to a model");
+        assert_eq!(model.temporality, Some(Temporality::Delta));
+
+        let absolute =
+            OpentelemetryMetricsModel::try_from((Event::Metric(metric.into_absolute()), &config))
+                .expect("Metric event cannot be converted to a model");
+        assert_eq!(absolute.temporality, Some(Temporality::Cumulative));
+
+        match model.data_point {
+            OpentelemetryDataPoint::ExponentialHistogram(data_point) => {
+                assert_eq!(data_point.scale, SKETCH_EXPONENTIAL_SCALE);
+                assert_eq!(data_point.count, 6);
+                assert_eq!(data_point.sum, 101.5);
+                assert_eq!(data_point.min, Some(-3.0));
+                assert_eq!(data_point.max, Some(100.0));
+                assert_eq!(data_point.zero_count, 1);
+                assert_eq!(data_point.negative.counts, [1]);
+                assert_eq!(data_point.positive.counts.iter().sum::<u64>(), 4);
+                assert_eq!(data_point.positive.counts.first(), Some(&1));
+                assert_eq!(data_point.positive.counts.last(), Some(&1));
+            }
+            other => panic!("Expected an exponential histogram data point: {other:#?}"),
+        }
+    }
+
+    /// The index of the exponential histogram bucket a value falls into.
+    fn exponential_bucket_index(value: f64, scale: i8) -> i32 {
+        (value.abs().log2() * f64::from(scale).exp2()).ceil() as i32 - 1
+    }
+
+    fn otlp_round_trip(request: &ExportMetricsServiceRequest) -> ExportMetricsServiceRequest {
+        let events = MezmoDeserializer::OpenTelemetryMetrics
+            .build()
+            .parse(request.encode_to_vec().into(), LogNamespace::Legacy)
+            .expect("Failed to decode the OTLP request");
+
+        let config = OpentelemetryMetricConfig {
+            buckets: default_histogram_buckets(),
+            quantiles: default_summary_quantiles(),
+        };
+        let models = events
+            .iter()
+            .map(|event| {
+                let metric = to_metric(event.as_log()).expect("Failed to convert log to metric");
+                OpentelemetryMetricsModel::try_from((Event::Metric(metric), &config))
+                    .expect("Metric event cannot be converted to a model")
+            })
+            .collect::<Vec<_>>();
+
+        let resource_metrics = OpentelemetryResourceMetrics::try_from(models)
+            .expect("Failed to convert metrics to OpentelemetryResourceMetrics");
+        let encoded = encode(resource_metrics).expect("Failed to encode the metrics");
+
+        ExportMetricsServiceRequest::decode(encoded.as_slice())
+            .expect("Failed to decode the encoded metrics")
+    }
+
+    fn otlp_request(metric: proto::Metric) -> ExportMetricsServiceRequest {
+        ExportMetricsServiceRequest {
+            resource_metrics: vec![proto::ResourceMetrics {
+                scope_metrics: vec![proto::ScopeMetrics {
+                    metrics: vec![metric],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        }
+    }
+
+    fn otlp_attributes() -> Vec<ProtoKeyValue> {
+        vec![ProtoKeyValue {
+            key: "foo".into(),
+            value: Some(ProtoAnyValue {
+                value: Some(any_value::Value::StringValue("bar".into())),
+            }),
+        }]
+    }
+
+    fn single_metric_data(request: ExportMetricsServiceRequest) -> Option<Data> {
+        let mut metrics = request
+            .resource_metrics
+            .into_iter()
+            .flat_map(|resource_metrics| resource_metrics.scope_metrics)
+            .flat_map(|scope_metrics| scope_metrics.metrics)
+            .collect::<Vec<_>>();
+        assert_eq!(metrics.len(), 1);
+        metrics.pop().and_then(|metric| metric.data)
+    }
+
+    prop_compose! {
+        fn otlp_summary_data_point()(
+            start_time_unix_nano in 1_000_000_000..1_500_000_000_000_000_000_u64,
+            time_unix_nano in 1_000_000_000..1_500_000_000_000_000_000_u64,
+            count in 0..1_000_000_u64,
+            sum in -1.0e9..1.0e9_f64,
+            quantile_values in prop::collection::vec((0.0..=1.0_f64, -1.0e9..1.0e9_f64), 0..6),
+            flags in 0..2_u32,
+        ) -> SummaryDataPoint {
+            SummaryDataPoint {
+                attributes: otlp_attributes(),
+                start_time_unix_nano,
+                time_unix_nano,
+                count,
+                sum,
+                quantile_values: quantile_values
+                    .into_iter()
+                    .map(|(quantile, value)| ValueAtQuantile { quantile, value })
+                    .collect(),
+                flags,
+            }
+        }
+    }
+
+    prop_compose! {
+        fn otlp_exponential_buckets()(
+            offset in -20..20_i32,
+            bucket_counts in prop::collection::vec(0..1_000_u64, 0..10),
+        ) -> proto::exponential_histogram_data_point::Buckets {
+            proto::exponential_histogram_data_point::Buckets { offset, bucket_counts }
+        }
+    }
+
+    prop_compose! {
+        fn otlp_exponential_histogram_data_point()(
+            start_time_unix_nano in 1_000_000_000..1_500_000_000_000_000_000_u64,
+            time_unix_nano in 1_000_000_000..1_500_000_000_000_000_000_u64,
+            sum in -1.0e9..1.0e9_f64,
+            scale in -2..=8_i32,
+            zero_count in 0..1_000_u64,
+            zero_threshold in 0.0..1.0e-6_f64,
+            positive in otlp_exponential_buckets(),
+            negative in otlp_exponential_buckets(),
+            min in -1.0e9..0.0_f64,
+            max in 0.0..1.0e9_f64,
+        ) -> proto::ExponentialHistogramDataPoint {
+            proto::ExponentialHistogramDataPoint {
+                attributes: otlp_attributes(),
+                start_time_unix_nano,
+                time_unix_nano,
+                count: zero_count
+                    + positive.bucket_counts.iter().sum::<u64>()
+                    + negative.bucket_counts.iter().sum::<u64>(),
+                sum: Some(sum),
+                scale,
+                zero_count,
+                zero_threshold,
+                positive: Some(positive),
+                negative: Some(negative),
+                min: Some(min),
+                max: Some(max),
+                ..Default::default()
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn otlp_summary_round_trip(data_point in otlp_summary_data_point()) {
+            let request = otlp_request(proto::Metric {
+                name: "test.summary".into(),
+                description: "test_description".into(),
+                unit: "ms".into(),
+                data: Some(Data::Summary(proto::Summary {
+                    data_points: vec![data_point.clone()],
+                })),
+                ..Default::default()
+            });
+
+            match single_metric_data(otlp_round_trip(&request)) {
+                Some(Data::Summary(summary)) => {
+                    prop_assert_eq!(summary.data_points, vec![data_point]);
+                }
+                other => prop_assert!(false, "Expected a summary: {:?}", other),
+            }
+        }
+
+        #[test]
+        fn otlp_exponential_histogram_round_trip(
+            data_point in otlp_exponential_histogram_data_point(),
+            aggregation_temporality in 1..=2_i32,
+        ) {
+            let request = otlp_request(proto::Metric {
+                name: "test.exponential_histogram".into(),
+                description: "test_description".into(),
+                unit: "ms".into(),
+                data: Some(Data::ExponentialHistogram(proto::ExponentialHistogram {
+                    data_points: vec![data_point.clone()],
+                    aggregation_temporality,
+                })),
+                ..Default::default()
+            });
+
+            match single_metric_data(otlp_round_trip(&request)) {
+                Some(Data::ExponentialHistogram(histogram)) => {
+                    prop_assert_eq!(histogram.aggregation_temporality, aggregation_temporality);
+                    // The SDK data points have no flags, these are left unset in the input
+                    prop_assert_eq!(histogram.data_points, vec![data_point]);
+                }
+                other => prop_assert!(false, "Expected an exponential histogram: {:?}", other),
+            }
+        }
+
+        #[test]
+        fn sketch_bins_land_in_their_exponential_bucket(
+            value in prop_oneof![-1.0e12..-1.0e-6_f64, 1.0e-6..1.0e12_f64],
+        ) {
+            let mut sketch = AgentDDSketch::with_agent_defaults();
+            sketch.insert(value);
+
+            let data_point = OpentelemetryExponentialHistogramDataPoint::from_sketch(
+                &sketch,
+                vec![],
+                SystemTime::UNIX_EPOCH,
+                SystemTime::UNIX_EPOCH,
+                vec![],
+            );
+            let buckets = if value > 0.0 {
+                &data_point.positive
+            } else {
+                &data_point.negative
+            };
+
+            prop_assert_eq!(&buckets.counts, &vec![1]);
+            // The sketch's relative accuracy keeps the value within a bucket of its own
+            let expected_index = exponential_bucket_index(value, SKETCH_EXPONENTIAL_SCALE);
+            prop_assert!((buckets.offset - expected_index).abs() <= 1);
         }
     }
 
@@ -1,5 +1,7 @@
+pub(crate) mod auth;
 pub(crate) mod config;
 pub(crate) mod encoding;
+pub(crate) mod grpc;
 pub(crate) mod models;
 pub(crate) mod service;
 pub(crate) mod sink;
@@ -12,15 +14,28 @@ pub(crate) mod traces;
 #[cfg(test)]
 pub(crate) mod integration_tests;
 
+use std::sync::Arc;
+
+use http::{HeaderMap, HeaderName, HeaderValue, header::AUTHORIZATION};
 use vector_lib::configurable::configurable_component;
 use vector_lib::sensitive_string::SensitiveString;
 
+use self::{auth::OAuth2TokenProvider, sink::OpentelemetrySinkError};
+
+const fn default_refresh_margin_secs() -> u64 {
+    60
+}
+
 fn default_histogram_buckets() -> Vec<f64> {
     vec![
         0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
     ]
 }
 
+fn default_summary_quantiles() -> Vec<f64> {
+    vec![0.5, 0.75, 0.9, 0.95, 0.99]
+}
+
 /// Authentication strategies.
 #[configurable_component]
 #[derive(Clone, Debug)]
@@ -43,9 +58,90 @@ pub enum OpentelemetrySinkAuth {
         /// The bearer token to send.
         token: SensitiveString,
     },
+
+    /// Custom header authentication.
+    ///
+    /// The value is sent as-is in the named header, such as the API key header of a SaaS
+    /// collector.
+    Header {
+        /// The name of the header to send.
+        #[configurable(metadata(docs::examples = "x-honeycomb-team"))]
+        name: String,
+
+        /// The value of the header.
+        value: SensitiveString,
+    },
+
+    /// OAuth2 client credentials authentication.
+    ///
+    /// Access tokens are requested from the token endpoint and sent as bearer tokens. They're
+    /// cached and refreshed before they expire.
+    Oauth2 {
+        /// The URL of the token endpoint.
+        #[configurable(metadata(docs::examples = "https://auth.example.com/oauth2/token"))]
+        token_endpoint: String,
+
+        /// The client ID.
+        client_id: String,
+
+        /// The client secret.
+        client_secret: SensitiveString,
+
+        /// The scopes to request.
+        #[serde(default)]
+        scopes: Vec<String>,
+
+        /// How long before they expire access tokens are refreshed, in seconds.
+        #[serde(default = "default_refresh_margin_secs")]
+        #[configurable(metadata(docs::advanced))]
+        refresh_margin_secs: u64,
+    },
 }
 
 #[derive(Debug, Clone)]
 pub enum Auth {
     Basic(crate::http::Auth),
+    Header(HeaderName, HeaderValue),
+    OAuth2(Arc<OAuth2TokenProvider>),
+}
+
+impl Auth {
+    /// The header carrying the credentials.
+    pub fn header_name(&self) -> HeaderName {
+        match self {
+            Auth::Header(name, _) => name.clone(),
+            Auth::Basic(_) | Auth::OAuth2(_) => AUTHORIZATION,
+        }
+    }
+
+    /// Adds the credentials to the headers of a request, requesting an access token first when
+    /// the cached one is about to expire.
+    pub async fn apply(&self, headers: &mut HeaderMap) -> Result<(), OpentelemetrySinkError> {
+        match self {
+            Auth::Basic(http_auth) => http_auth.apply_headers_map(headers),
+            Auth::Header(name, value) => {
+                headers.insert(name.clone(), value.clone());
+            }
+            Auth::OAuth2(provider) => {
+                let token = provider.token().await?;
+                let mut value =
+                    HeaderValue::from_str(&format!("Bearer {token}")).map_err(|_| {
+                        OpentelemetrySinkError::new(
+                            "OAuth2 access token is not a valid header value",
+                        )
+                    })?;
+                value.set_sensitive(true);
+                headers.insert(AUTHORIZATION, value);
+            }
+        }
+        Ok(())
+    }
+
+    /// Drops the cached credentials after the collector rejected them. Only access tokens are
+    /// renewed, the other credentials are static.
+    pub async fn invalidate(&self) {
+        if let Auth::OAuth2(provider) = self {
+            provider.invalidate().await;
+        }
+    }
 }
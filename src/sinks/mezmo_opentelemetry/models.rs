@@ -6,6 +6,9 @@ use vector_lib::{
     config::log_schema,
     event::{Event, LogEvent, Metric, Value},
     lookup::PathPrefix,
+    opentelemetry::proto::{
+        LOGS_RESPONSE_MESSAGE_TYPE, METRICS_RESPONSE_MESSAGE_TYPE, TRACES_RESPONSE_MESSAGE_TYPE,
+    },
 };
 
 use super::{
@@ -440,6 +443,18 @@ pub enum OpentelemetryModelType {
     Unknown,
 }
 
+impl OpentelemetryModelType {
+    /// The name of the message the collector answers exports of this model type with.
+    pub const fn response_message_type(self) -> Option<&'static str> {
+        match self {
+            Self::Logs => Some(LOGS_RESPONSE_MESSAGE_TYPE),
+            Self::Traces { .. } => Some(TRACES_RESPONSE_MESSAGE_TYPE),
+            Self::Metrics { .. } => Some(METRICS_RESPONSE_MESSAGE_TYPE),
+            Self::Unknown => None,
+        }
+    }
+}
+
 pub trait OpentelemetryModelMatch {
     fn maybe_match(event: &Event) -> Option<OpentelemetryModelType>
     where
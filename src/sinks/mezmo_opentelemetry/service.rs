@@ -1,8 +1,10 @@
 use indexmap::IndexMap;
 use std::task::{Context, Poll};
+use std::time::Duration;
 
 use crate::{
     http::HttpClient,
+    internal_events::{MezmoOpentelemetryPartialSuccess, MezmoOpentelemetryResponseDecodeError},
     mezmo::user_trace::UserLoggingResponse,
     sinks::{
         mezmo_opentelemetry::{
@@ -14,13 +16,20 @@ use crate::{
 use bytes::Bytes;
 use futures::future::BoxFuture;
 use http::{
-    HeaderName, HeaderValue, Request,
-    header::{CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE},
+    HeaderMap, HeaderName, HeaderValue, Request, StatusCode,
+    header::{CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE, RETRY_AFTER},
 };
 use http_body::Body as _;
 use hyper::Body;
+use opentelemetry_proto::tonic::collector::{
+    logs::v1::ExportLogsServiceResponse, metrics::v1::ExportMetricsServiceResponse,
+    trace::v1::ExportTraceServiceResponse,
+};
+use prost::Message;
 use tower::Service;
 use vector_lib::{
+    codecs::decoding::OpenTelemetryPayloadFormat,
+    event::Value,
     finalization::{EventFinalizers, EventStatus, Finalizable},
     request_metadata::{GroupedCountByteSize, MetaDescriptive, RequestMetadata},
     stream::DriverResponse,
@@ -58,11 +67,122 @@ impl MetaDescriptive for OpentelemetryApiRequest {
     }
 }
 
+/// The `partial_success` of an OTLP export response, reporting the data points, spans or log
+/// records the collector rejected.
+#[derive(Debug, PartialEq)]
+pub struct OpentelemetryPartialSuccess {
+    pub signal: &'static str,
+    pub rejected: i64,
+    pub error_message: String,
+}
+
+impl OpentelemetryPartialSuccess {
+    /// Decodes the `partial_success` of the response to an export of the given model type. Per the
+    /// OTLP spec, an empty `partial_success` means the request was fully accepted.
+    fn decode(
+        model_type: OpentelemetryModelType,
+        body: &[u8],
+    ) -> Result<Option<Self>, prost::DecodeError> {
+        let (signal, rejected, error_message) = match model_type {
+            OpentelemetryModelType::Logs => {
+                match ExportLogsServiceResponse::decode(body)?.partial_success {
+                    Some(partial) => (
+                        "log records",
+                        partial.rejected_log_records,
+                        partial.error_message,
+                    ),
+                    None => return Ok(None),
+                }
+            }
+            OpentelemetryModelType::Metrics { .. } => {
+                match ExportMetricsServiceResponse::decode(body)?.partial_success {
+                    Some(partial) => (
+                        "data points",
+                        partial.rejected_data_points,
+                        partial.error_message,
+                    ),
+                    None => return Ok(None),
+                }
+            }
+            OpentelemetryModelType::Traces { .. } => {
+                match ExportTraceServiceResponse::decode(body)?.partial_success {
+                    Some(partial) => ("spans", partial.rejected_spans, partial.error_message),
+                    None => return Ok(None),
+                }
+            }
+            OpentelemetryModelType::Unknown => return Ok(None),
+        };
+
+        Ok((rejected > 0 || !error_message.is_empty()).then_some(Self {
+            signal,
+            rejected,
+            error_message,
+        }))
+    }
+}
+
+/// Whether a collector response status can be retried, as listed by the OTLP/HTTP spec.
+const fn is_retriable_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Parses the `Retry-After` header, either a number of seconds or an HTTP date.
+fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    (date.with_timezone(&chrono::Utc) - chrono::Utc::now())
+        .to_std()
+        .ok()
+}
+
 #[derive(Debug)]
 pub struct OpentelemetryApiResponse {
     event_status: EventStatus,
     metadata: RequestMetadata,
     events_byte_size: GroupedCountByteSize,
+    partial_success: Option<OpentelemetryPartialSuccess>,
+}
+
+impl OpentelemetryApiResponse {
+    /// The response to a request the collector accepted, with the `partial_success` decoded from
+    /// the protobuf encoded export response body. Bodies that can't be decoded are reported, and
+    /// treated as a full success.
+    pub(super) fn delivered(
+        model_type: OpentelemetryModelType,
+        metadata: RequestMetadata,
+        events_byte_size: GroupedCountByteSize,
+        body: &[u8],
+    ) -> Self {
+        let partial_success =
+            OpentelemetryPartialSuccess::decode(model_type, body).unwrap_or_else(|error| {
+                emit!(MezmoOpentelemetryResponseDecodeError { error: &error });
+                None
+            });
+        if let Some(partial) = &partial_success {
+            emit!(MezmoOpentelemetryPartialSuccess {
+                signal: partial.signal,
+                rejected: partial.rejected,
+                error_message: &partial.error_message,
+            });
+        }
+
+        Self {
+            event_status: EventStatus::Delivered,
+            metadata,
+            events_byte_size,
+            partial_success,
+        }
+    }
 }
 
 impl DriverResponse for OpentelemetryApiResponse {
@@ -79,7 +199,20 @@ impl DriverResponse for OpentelemetryApiResponse {
     }
 }
 
-impl UserLoggingResponse for OpentelemetryApiResponse {}
+impl UserLoggingResponse for OpentelemetryApiResponse {
+    fn log_msg(&self) -> Option<Value> {
+        self.partial_success
+            .as_ref()
+            .filter(|partial| partial.rejected > 0)
+            .map(|partial| {
+                format!(
+                    "OpenTelemetry collector rejected {} {}: {}",
+                    partial.rejected, partial.signal, partial.error_message
+                )
+                .into()
+            })
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct OpentelemetryService {
@@ -87,6 +220,7 @@ pub struct OpentelemetryService {
     pub client: HttpClient,
     pub auth: Option<Auth>,
     pub headers: IndexMap<HeaderName, HeaderValue>,
+    pub format: OpenTelemetryPayloadFormat,
 }
 
 impl Service<OpentelemetryApiRequest> for OpentelemetryService {
@@ -113,6 +247,7 @@ impl Service<OpentelemetryApiRequest> for OpentelemetryService {
             }
         };
 
+        let model_type = request.get_model_type();
         let metadata = std::mem::take(request.metadata_mut());
         let events_byte_size = metadata
             .clone()
@@ -127,7 +262,7 @@ impl Service<OpentelemetryApiRequest> for OpentelemetryService {
 
         let mut http_request = http_request
             .header(CONTENT_LENGTH, request.payload.len())
-            .header(CONTENT_TYPE, "application/x-protobuf")
+            .header(CONTENT_TYPE, self.format.content_type())
             .body(Body::from(request.payload))
             .expect("building HTTP request failed unexpectedly");
 
@@ -137,24 +272,56 @@ impl Service<OpentelemetryApiRequest> for OpentelemetryService {
             headers.insert(name, value.clone());
         }
 
-        if let Some(auth) = &self.auth {
-            match auth {
-                Auth::Basic(http_auth) => http_auth.apply(&mut http_request),
-            }
-        }
+        let auth = self.auth.clone();
+        let format = self.format;
 
         Box::pin(async move {
+            if let Some(auth) = &auth {
+                auth.apply(http_request.headers_mut()).await?;
+            }
+
             match client.call(http_request).await {
                 Ok(response) => {
                     let status = response.status();
                     if status.is_success() || status.is_redirection() {
-                        return Ok(OpentelemetryApiResponse {
-                            event_status: EventStatus::Delivered,
-                            metadata: metadata.clone(),
+                        // Collectors may answer in another encoding than the request's, so the
+                        // body is decoded by its own content type when it has a known one
+                        let response_format = response
+                            .headers()
+                            .get(CONTENT_TYPE)
+                            .and_then(|content_type| content_type.to_str().ok())
+                            .and_then(OpenTelemetryPayloadFormat::from_content_type)
+                            .unwrap_or(format);
+                        let body = response
+                            .into_body()
+                            .collect()
+                            .await
+                            .map(|collected| collected.to_bytes())
+                            .unwrap_or_default();
+                        let body = match model_type.response_message_type() {
+                            Some(message_type) if !body.is_empty() => response_format
+                                .payload_to_protobuf(body, message_type)
+                                .unwrap_or_else(|error| {
+                                    emit!(MezmoOpentelemetryResponseDecodeError { error: &error });
+                                    Bytes::new()
+                                }),
+                            _ => body,
+                        };
+                        return Ok(OpentelemetryApiResponse::delivered(
+                            model_type,
+                            metadata,
                             events_byte_size,
-                        });
+                            &body,
+                        ));
                     }
 
+                    if status == StatusCode::UNAUTHORIZED
+                        && let Some(auth) = &auth
+                    {
+                        auth.invalidate().await;
+                    }
+
+                    let retry_after = parse_retry_after(response.headers());
                     let body = response.into_body();
                     let body = match body.collect().await {
                         Ok(collected) => {
@@ -163,16 +330,411 @@ impl Service<OpentelemetryApiRequest> for OpentelemetryService {
                         Err(err) => err.to_string(),
                     };
 
-                    Err(OpentelemetrySinkError::new(&format!(
+                    let message = format!(
                         "OpenTelemetry collector respond with error status: {}, body: {}",
                         status.as_str(),
                         body,
-                    )))
+                    );
+                    if is_retriable_status(status) {
+                        Err(OpentelemetrySinkError::retriable(&message, retry_after))
+                    } else {
+                        Err(OpentelemetrySinkError::new(&message))
+                    }
                 }
-                Err(error) => Err(OpentelemetrySinkError::new(&format!(
-                    "HTTP request error: {error}",
-                ))),
+                // Transport errors, such as a timeout or a refused connection, are transient
+                Err(error) => Err(OpentelemetrySinkError::retriable(
+                    &format!("HTTP request error: {error}"),
+                    None,
+                )),
             }
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+    use std::time::Instant;
+
+    use futures::StreamExt;
+    use http::{Response, header::AUTHORIZATION};
+    use opentelemetry_proto::tonic::collector::logs::v1::ExportLogsPartialSuccess;
+    use serial_test::serial;
+    use tower::{ServiceBuilder, ServiceExt};
+    use vector_lib::config::proxy::ProxyConfig;
+
+    use super::*;
+    use crate::{
+        event::metric::MetricValue,
+        metrics::Controller,
+        sinks::{
+            mezmo_opentelemetry::auth::OAuth2TokenProvider,
+            mezmo_opentelemetry::config::{OpentelemetryRetry, OpentelemetrySinkConfig},
+            util::{ServiceBuilderExt, http::RequestConfig, test::build_test_server_generic},
+        },
+        test_util::{addr::next_addr, trace_init},
+    };
+
+    fn service(addr: std::net::SocketAddr) -> OpentelemetryService {
+        let config: OpentelemetrySinkConfig =
+            toml::from_str(&format!(r#"endpoint = "http://{addr}""#)).unwrap();
+        OpentelemetryService {
+            endpoint: OpentelemetryEndpoint::try_from(&config).unwrap(),
+            client: HttpClient::new(None, &ProxyConfig::default()).unwrap(),
+            auth: None,
+            headers: IndexMap::new(),
+            format: OpenTelemetryPayloadFormat::Protobuf,
+        }
+    }
+
+    fn request() -> OpentelemetryApiRequest {
+        OpentelemetryApiRequest {
+            payload: Bytes::new(),
+            compression: Compression::None,
+            metadata: RequestMetadata::default(),
+            finalizers: EventFinalizers::default(),
+            model_type: OpentelemetryModelType::Logs,
+        }
+    }
+
+    #[test]
+    fn retriable_statuses() {
+        for status in [429, 502, 503, 504] {
+            assert!(is_retriable_status(StatusCode::from_u16(status).unwrap()));
+        }
+        for status in [400, 401, 404, 500] {
+            assert!(!is_retriable_status(StatusCode::from_u16(status).unwrap()));
+        }
+    }
+
+    #[test]
+    fn retry_after_header() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(parse_retry_after(&headers), None);
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("3"));
+        assert_eq!(parse_retry_after(&headers), Some(Duration::from_secs(3)));
+
+        let date = (chrono::Utc::now() + chrono::Duration::seconds(60)).to_rfc2822();
+        headers.insert(RETRY_AFTER, HeaderValue::from_str(&date).unwrap());
+        let retry_after = parse_retry_after(&headers).unwrap();
+        assert!(retry_after > Duration::from_secs(50) && retry_after <= Duration::from_secs(60));
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("not a delay"));
+        assert_eq!(parse_retry_after(&headers), None);
+    }
+
+    #[tokio::test]
+    async fn retries_unavailable_collector_after_delay() {
+        trace_init();
+
+        let (_guard, addr) = next_addr();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let responder_calls = Arc::clone(&calls);
+        let (mut rx, trigger, server) = build_test_server_generic(addr, move || {
+            match responder_calls.fetch_add(1, Ordering::SeqCst) {
+                0 => Response::builder()
+                    .status(StatusCode::SERVICE_UNAVAILABLE)
+                    .header(RETRY_AFTER, "2")
+                    .body(Body::empty())
+                    .unwrap(),
+                _ => Response::new(Body::empty()),
+            }
+        });
+        tokio::spawn(server);
+
+        let request_settings = RequestConfig::default().tower.into_settings();
+        let service = ServiceBuilder::new()
+            .settings(request_settings, OpentelemetryRetry)
+            .service(service(addr));
+
+        let start = Instant::now();
+        let response = service.oneshot(request()).await.unwrap();
+
+        assert_eq!(response.event_status(), EventStatus::Delivered);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(start.elapsed() >= Duration::from_secs(2));
+        assert!(rx.next().await.is_some());
+        drop(trigger);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_bad_request() {
+        trace_init();
+
+        let (_guard, addr) = next_addr();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let responder_calls = Arc::clone(&calls);
+        let (_rx, trigger, server) = build_test_server_generic(addr, move || {
+            responder_calls.fetch_add(1, Ordering::SeqCst);
+            Response::builder()
+                .status(StatusCode::BAD_REQUEST)
+                .body(Body::empty())
+                .unwrap()
+        });
+        tokio::spawn(server);
+
+        let request_settings = RequestConfig::default().tower.into_settings();
+        let service = ServiceBuilder::new()
+            .settings(request_settings, OpentelemetryRetry)
+            .service(service(addr));
+
+        assert!(service.oneshot(request()).await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        drop(trigger);
+    }
+
+    #[tokio::test]
+    async fn reports_partial_success() {
+        trace_init();
+
+        let (_guard, addr) = next_addr();
+        let body = ExportLogsServiceResponse {
+            partial_success: Some(ExportLogsPartialSuccess {
+                rejected_log_records: 2,
+                error_message: "missing timestamp".to_owned(),
+            }),
+        }
+        .encode_to_vec();
+        let (_rx, trigger, server) =
+            build_test_server_generic(addr, move || Response::new(Body::from(body.clone())));
+        tokio::spawn(server);
+
+        let response = service(addr).oneshot(request()).await.unwrap();
+
+        assert_eq!(response.event_status(), EventStatus::Delivered);
+        assert_eq!(
+            response.partial_success,
+            Some(OpentelemetryPartialSuccess {
+                signal: "log records",
+                rejected: 2,
+                error_message: "missing timestamp".to_owned(),
+            })
+        );
+        assert_eq!(
+            response.log_msg(),
+            Some(Value::from(
+                "OpenTelemetry collector rejected 2 log records: missing timestamp"
+            ))
+        );
+        drop(trigger);
+    }
+
+    #[tokio::test]
+    async fn reports_json_partial_success() {
+        trace_init();
+
+        let (_guard, addr) = next_addr();
+        let (mut rx, trigger, server) = build_test_server_generic(addr, || {
+            Response::new(Body::from(
+                r#"{"partialSuccess":{"rejectedLogRecords":"2","errorMessage":"missing timestamp"}}"#,
+            ))
+        });
+        tokio::spawn(server);
+
+        let mut service = service(addr);
+        service.format = OpenTelemetryPayloadFormat::Json;
+        let response = service.oneshot(request()).await.unwrap();
+
+        let (parts, _) = rx.next().await.unwrap();
+        assert_eq!(parts.headers.get(CONTENT_TYPE).unwrap(), "application/json");
+        assert_eq!(
+            response.partial_success,
+            Some(OpentelemetryPartialSuccess {
+                signal: "log records",
+                rejected: 2,
+                error_message: "missing timestamp".to_owned(),
+            })
+        );
+        drop(trigger);
+    }
+
+    /// The number of collector responses that couldn't be decoded so far.
+    fn decode_errors() -> f64 {
+        Controller::get()
+            .unwrap()
+            .capture_metrics()
+            .into_iter()
+            .find(|metric| metric.name() == "mezmo_opentelemetry_response_decode_errors_total")
+            .map_or(0.0, |metric| match metric.value() {
+                MetricValue::Counter { value } => *value,
+                other => panic!("Expected a counter: {other:?}"),
+            })
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn reports_undecodable_response() {
+        trace_init();
+
+        let (_guard, addr) = next_addr();
+        let (_rx, trigger, server) =
+            build_test_server_generic(addr, || Response::new(Body::from("not otlp")));
+        tokio::spawn(server);
+
+        let mut service = service(addr);
+        service.format = OpenTelemetryPayloadFormat::Json;
+        let before = decode_errors();
+        let response = service.oneshot(request()).await.unwrap();
+
+        assert_eq!(response.event_status(), EventStatus::Delivered);
+        assert_eq!(response.partial_success, None);
+        assert_eq!(decode_errors() - before, 1.0);
+        drop(trigger);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn decodes_response_by_its_content_type() {
+        trace_init();
+
+        let (_guard, addr) = next_addr();
+        let (_rx, trigger, server) = build_test_server_generic(addr, || {
+            Response::builder()
+                .header(CONTENT_TYPE, "application/json; charset=utf-8")
+                .body(Body::from("{}"))
+                .unwrap()
+        });
+        tokio::spawn(server);
+
+        // The request is sent as protobuf, the response is an empty JSON export response
+        let before = decode_errors();
+        let response = service(addr).oneshot(request()).await.unwrap();
+
+        assert_eq!(response.event_status(), EventStatus::Delivered);
+        assert_eq!(response.partial_success, None);
+        assert_eq!(decode_errors() - before, 0.0);
+        drop(trigger);
+    }
+
+    #[tokio::test]
+    async fn applies_auth() {
+        trace_init();
+
+        let (_token_guard, token_addr) = next_addr();
+        let (_token_rx, token_trigger, token_server) =
+            build_test_server_generic(token_addr, || {
+                Response::new(Body::from(
+                    r#"{"access_token":"abc","token_type":"Bearer","expires_in":3600}"#,
+                ))
+            });
+        tokio::spawn(token_server);
+
+        let (_guard, addr) = next_addr();
+        let (mut rx, trigger, server) =
+            build_test_server_generic(addr, || Response::new(Body::empty()));
+        tokio::spawn(server);
+
+        let provider = OAuth2TokenProvider::new(
+            HttpClient::new(None, &ProxyConfig::default()).unwrap(),
+            format!("http://{token_addr}/token").parse().unwrap(),
+            "client".to_owned(),
+            "secret".to_owned().into(),
+            Vec::new(),
+            Duration::from_secs(60),
+        );
+        let auths = [
+            (
+                Auth::Header(
+                    HeaderName::from_static("x-api-key"),
+                    HeaderValue::from_static("key"),
+                ),
+                "x-api-key",
+                "key",
+            ),
+            (
+                Auth::OAuth2(Arc::new(provider)),
+                "authorization",
+                "Bearer abc",
+            ),
+        ];
+
+        for (auth, name, value) in auths {
+            let mut service = service(addr);
+            service.auth = Some(auth);
+            service.oneshot(request()).await.unwrap();
+
+            let (parts, _) = rx.next().await.unwrap();
+            assert_eq!(parts.headers.get(name).unwrap(), value);
+        }
+        drop(trigger);
+        drop(token_trigger);
+    }
+
+    #[tokio::test]
+    async fn renews_rejected_token() {
+        trace_init();
+
+        // The tokens are issued without an expiry, so they're used until the collector rejects
+        // them.
+        let (_token_guard, token_addr) = next_addr();
+        let token_calls = Arc::new(AtomicUsize::new(0));
+        let responder_token_calls = Arc::clone(&token_calls);
+        let (_token_rx, token_trigger, token_server) =
+            build_test_server_generic(token_addr, move || {
+                let call = responder_token_calls.fetch_add(1, Ordering::SeqCst);
+                Response::new(Body::from(format!(r#"{{"access_token":"token-{call}"}}"#)))
+            });
+        tokio::spawn(token_server);
+
+        let (_guard, addr) = next_addr();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let responder_calls = Arc::clone(&calls);
+        let (mut rx, trigger, server) = build_test_server_generic(addr, move || {
+            match responder_calls.fetch_add(1, Ordering::SeqCst) {
+                1 => Response::builder()
+                    .status(StatusCode::UNAUTHORIZED)
+                    .body(Body::empty())
+                    .unwrap(),
+                _ => Response::new(Body::empty()),
+            }
+        });
+        tokio::spawn(server);
+
+        let mut service = service(addr);
+        service.auth = Some(Auth::OAuth2(Arc::new(OAuth2TokenProvider::new(
+            HttpClient::new(None, &ProxyConfig::default()).unwrap(),
+            format!("http://{token_addr}/token").parse().unwrap(),
+            "client".to_owned(),
+            "secret".to_owned().into(),
+            Vec::new(),
+            Duration::from_secs(60),
+        ))));
+
+        service.clone().oneshot(request()).await.unwrap();
+        assert!(service.clone().oneshot(request()).await.is_err());
+        service.oneshot(request()).await.unwrap();
+
+        let mut authorizations = Vec::new();
+        for _ in 0..3 {
+            let (parts, _) = rx.next().await.unwrap();
+            authorizations.push(parts.headers.get(AUTHORIZATION).unwrap().clone());
+        }
+        assert_eq!(
+            authorizations,
+            ["Bearer token-0", "Bearer token-0", "Bearer token-1"]
+        );
+        assert_eq!(token_calls.load(Ordering::SeqCst), 2);
+        drop(trigger);
+        drop(token_trigger);
+    }
+
+    #[test]
+    fn empty_partial_success() {
+        let body = ExportLogsServiceResponse {
+            partial_success: Some(ExportLogsPartialSuccess::default()),
+        }
+        .encode_to_vec();
+        assert_eq!(
+            OpentelemetryPartialSuccess::decode(OpentelemetryModelType::Logs, &body),
+            Ok(None)
+        );
+        assert_eq!(
+            OpentelemetryPartialSuccess::decode(OpentelemetryModelType::Logs, &[]),
+            Ok(None)
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::{fmt::Debug, num::NonZeroUsize};
+use std::{fmt::Debug, num::NonZeroUsize, time::Duration};
 
 use super::{
     config::OpentelemetryMetricConfig,
@@ -39,19 +39,39 @@ use vector_lib::{
 #[derive(Debug)]
 pub struct OpentelemetrySinkError {
     message: String,
+    retriable: bool,
+    retry_after: Option<Duration>,
 }
 
 impl OpentelemetrySinkError {
     pub fn new(msg: &str) -> Self {
         OpentelemetrySinkError {
             message: String::from(msg),
+            retriable: false,
+            retry_after: None,
         }
     }
 
     pub fn boxed(msg: &str) -> Box<Self> {
-        Box::new(OpentelemetrySinkError {
+        Box::new(Self::new(msg))
+    }
+
+    /// An error the request can be retried after, no sooner than `retry_after` when the
+    /// collector requested a delay.
+    pub fn retriable(msg: &str, retry_after: Option<Duration>) -> Self {
+        OpentelemetrySinkError {
             message: String::from(msg),
-        })
+            retriable: true,
+            retry_after,
+        }
+    }
+
+    pub const fn is_retriable(&self) -> bool {
+        self.retriable
+    }
+
+    pub const fn retry_after(&self) -> Option<Duration> {
+        self.retry_after
     }
 }
 
@@ -47,6 +47,13 @@ pub trait RetryLogic: Clone + Send + Sync + 'static {
 
     /// Optional hook run when an error is determined to be retriable.
     fn on_retriable_error(&self, _error: &Self::Error) {}
+
+    /// When a retriable error comes with a delay requested by the downstream service, such as
+    /// the `Retry-After` HTTP header, this function allows implementors to wait at least that
+    /// long before retrying. The delay is capped to the maximum retry backoff.
+    fn retry_after(&self, _error: &Self::Error) -> Option<Duration> {
+        None
+    }
 }
 
 /// The jitter mode to use for retry backoff behavior.
@@ -126,12 +133,18 @@ impl<L: RetryLogic> FibonacciRetryPolicy<L> {
         self.current_jitter_duration = Self::add_full_jitter(next_duration);
     }
 
-    fn build_retry(&mut self) -> RetryPolicyFuture {
+    fn build_retry(&mut self, min_delay: Option<Duration>) -> RetryPolicyFuture {
         self.advance();
-        let delay = Box::pin(sleep(self.backoff()));
-
-        debug!(message = "Retrying request.", delay_ms = %self.backoff().as_millis());
-        RetryPolicyFuture { delay }
+        // The requested delay is capped, so that a large `Retry-After` doesn't stall the request
+        // beyond the configured maximum backoff.
+        let delay = min_delay.map_or(self.backoff(), |min_delay| {
+            cmp::max(cmp::min(min_delay, self.max_duration), self.backoff())
+        });
+
+        debug!(message = "Retrying request.", delay_ms = %delay.as_millis());
+        RetryPolicyFuture {
+            delay: Box::pin(sleep(delay)),
+        }
     }
 }
 
@@ -157,7 +170,7 @@ where
                     }
 
                     warn!(message = "Retrying after response.", reason = %reason);
-                    Some(self.build_retry())
+                    Some(self.build_retry(None))
                 }
                 RetryAction::RetryPartial(modify_request) => {
                     if self.remaining_attempts == 0 {
@@ -169,7 +182,7 @@ where
                     }
                     *req = modify_request(req.clone());
                     warn!("OK/retrying partial after response.");
-                    Some(self.build_retry())
+                    Some(self.build_retry(None))
                 }
                 RetryAction::DontRetry(reason) => {
                     error!(message = "Not retriable; dropping the request.", ?reason);
@@ -188,7 +201,7 @@ where
                     if self.logic.is_retriable_error(expected) {
                         self.logic.on_retriable_error(expected);
                         warn!(message = "Retrying after error.", error = %expected);
-                        Some(self.build_retry())
+                        Some(self.build_retry(self.logic.retry_after(expected)))
                     } else {
                         error!(
                             message = "Non-retriable error; dropping the request.",
@@ -200,7 +213,7 @@ where
                     warn!(
                         "Request timed out. If this happens often while the events are actually reaching their destination, try decreasing `batch.max_bytes` and/or using `compression` if applicable. Alternatively `request.timeout_secs` can be increased."
                     );
-                    Some(self.build_retry())
+                    Some(self.build_retry(None))
                 } else {
                     error!(
                         message = "Unexpected error type; dropping the request.",
@@ -309,6 +322,29 @@ mod tests {
         assert_ready_err!(fut.poll());
     }
 
+    #[tokio::test]
+    async fn retry_after_is_capped_to_max_duration() {
+        time::pause();
+
+        let mut policy = FibonacciRetryPolicy::new(
+            5,
+            Duration::from_secs(1),
+            Duration::from_secs(10),
+            SvcRetryLogic,
+            JitterMode::None,
+        );
+
+        let delay = |retry: RetryPolicyFuture| retry.delay.deadline() - time::Instant::now();
+        assert_eq!(
+            delay(policy.build_retry(Some(Duration::from_secs(3)))),
+            Duration::from_secs(3)
+        );
+        assert_eq!(
+            delay(policy.build_retry(Some(Duration::from_secs(3600)))),
+            Duration::from_secs(10)
+        );
+    }
+
     #[tokio::test]
     async fn timeout_error() {
         trace_init();
@@ -40,7 +40,7 @@ use tracing::{Instrument, Span};
 use vector_lib::{
     EstimatedJsonEncodedSizeOf,
     codecs::{
-        DecoderFramedRead, StreamDecodingError,
+        DecoderFramedRead, SchemaRegistryConfig, StreamDecodingError,
         decoding::{DeserializerConfig, FramingConfig},
     },
     config::{LegacyKey, LogNamespace},
@@ -253,6 +253,15 @@ pub struct KafkaSourceConfig {
     #[derivative(Default(value = "default_decoding()"))]
     decoding: DeserializerConfig,
 
+    /// The schema registry holding the schemas of the messages.
+    ///
+    /// When set, the messages are expected to be in the Confluent wire format, prefixed by the id
+    /// of their schema, and are decoded with the schema fetched from the registry the first time
+    /// it's used. Only the `avro` and `protobuf` decoding codecs are supported. Their `avro` or
+    /// `protobuf` options can then be left out, the schemas coming from the registry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    schema_registry: Option<SchemaRegistryConfig>,
+
     #[configurable(derived)]
     #[serde(default, deserialize_with = "bool_or_struct")]
     acknowledgements: SourceAcknowledgementsConfig,
@@ -271,6 +280,12 @@ impl KafkaSourceConfig {
     fn keys(&self) -> Keys {
         Keys::from(log_schema(), self)
     }
+
+    fn decoder(&self, log_namespace: LogNamespace) -> crate::Result<Decoder> {
+        DecodingConfig::new(self.framing.clone(), self.decoding.clone(), log_namespace)
+            .with_schema_registry(self.schema_registry.clone())
+            .build()
+    }
 }
 
 const fn default_session_timeout_ms() -> Duration {
@@ -341,9 +356,7 @@ impl SourceConfig for KafkaSourceConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
         let log_namespace = cx.log_namespace(self.log_namespace);
 
-        let decoder =
-            DecodingConfig::new(self.framing.clone(), self.decoding.clone(), log_namespace)
-                .build()?;
+        let decoder = self.decoder(log_namespace)?;
         let acknowledgements = cx.do_acknowledgements(self.acknowledgements);
 
         if let Some(d) = self.drain_timeout_ms {
@@ -1026,6 +1039,11 @@ async fn parse_message(
     finalizer: &Option<OrderedFinalizer<FinalizerEntry>>,
     log_namespace: LogNamespace,
 ) {
+    if let Some(payload) = msg.payload() {
+        // Failing to fetch the schema makes decoding the message fail, which reports the error.
+        _ = decoder.deserializer.fetch_schema(payload).await;
+    }
+
     if let Some((count, stream)) = parse_stream(&msg, decoder, keys, log_namespace) {
         let (batch, receiver) = BatchNotifier::new_with_receiver();
         let mut stream = stream.map(|event| {
@@ -1529,7 +1547,12 @@ impl ConsumerContext for KafkaSourceContext {
 
 #[cfg(test)]
 mod test {
+    use serde_json::json;
     use vector_lib::{lookup::OwnedTargetPath, schema::Definition};
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{method, path},
+    };
 
     use super::*;
 
@@ -1674,6 +1697,69 @@ mod test {
         };
         assert!(create_consumer(&config, true).is_err());
     }
+
+    #[tokio::test]
+    async fn decodes_with_schema_registry() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/schemas/ids/1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "schema": r#"{"type": "record", "name": "log", "fields": [{"name": "message", "type": "string"}]}"#
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config: KafkaSourceConfig = toml::from_str(&format!(
+            r#"
+            bootstrap_servers = "localhost:9092"
+            topics = ["topic"]
+            group_id = "group"
+            decoding.codec = "avro"
+            schema_registry.url = "{}"
+            "#,
+            server.uri()
+        ))
+        .unwrap();
+        let decoder = config.decoder(LogNamespace::Legacy).unwrap();
+
+        // The magic byte and schema id 1, then the Avro datum.
+        let message = Bytes::from_static(b"\x00\x00\x00\x00\x01\x0ahello");
+        decoder.deserializer.fetch_schema(&message).await.unwrap();
+        for _ in 0..2 {
+            let (events, _) = decoder.deserializer_parse(message.clone()).unwrap();
+            assert_eq!(events[0].as_log()["message"], "hello".into());
+        }
+    }
+
+    #[test]
+    fn schema_registry_requires_schema_codec() {
+        let config: KafkaSourceConfig = toml::from_str(
+            r#"
+            bootstrap_servers = "localhost:9092"
+            topics = ["topic"]
+            group_id = "group"
+            decoding.codec = "json"
+            schema_registry.url = "http://localhost:8081"
+            "#,
+        )
+        .unwrap();
+        assert!(config.decoder(LogNamespace::Legacy).is_err());
+    }
+
+    #[test]
+    fn schema_required_without_schema_registry() {
+        let config: KafkaSourceConfig = toml::from_str(
+            r#"
+            bootstrap_servers = "localhost:9092"
+            topics = ["topic"]
+            group_id = "group"
+            decoding.codec = "avro"
+            "#,
+        )
+        .unwrap();
+        assert!(config.decoder(LogNamespace::Legacy).is_err());
+    }
 }
 
 #[cfg(feature = "kafka-integration-tests")]
@@ -1949,13 +2035,7 @@ mod integration_test {
     ) -> (Trigger, Tripwire) {
         let (trigger_shutdown, shutdown, shutdown_done) = ShutdownSignal::new_wired();
 
-        let decoder = DecodingConfig::new(
-            config.framing.clone(),
-            config.decoding.clone(),
-            log_namespace,
-        )
-        .build()
-        .unwrap();
+        let decoder = config.decoder(log_namespace).unwrap();
 
         tokio::spawn(kafka_source(
             config,
@@ -0,0 +1,112 @@
+use chrono::Utc;
+use futures::StreamExt;
+use vector_lib::EstimatedJsonEncodedSizeOf;
+use vector_lib::codecs::BytesDeserializerConfig;
+use vector_lib::config::SourceOutput;
+use vector_lib::configurable::configurable_component;
+use vector_lib::{config::LogNamespace, schema::Definition};
+
+use crate::mezmo::event_trace::EventTraceSubscription;
+use crate::{
+    SourceSender,
+    config::{DataType, SourceConfig, SourceContext},
+    event::Event,
+    internal_events::{InternalLogsBytesReceived, InternalLogsEventsReceived, StreamClosedError},
+    shutdown::ShutdownSignal,
+};
+
+/// Configuration for the `mezmo_event_traces` source.
+///
+/// Emits the hops of the sampled traced events as OpenTelemetry spans, in the shape accepted by
+/// the `mezmo_opentelemetry` sink.
+#[configurable_component(source("mezmo_event_traces"))]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct MezmoEventTracesConfig {
+    /// The namespace to use for logs. This overrides the global setting.
+    #[configurable(metadata(docs::hidden))]
+    #[serde(default)]
+    log_namespace: Option<bool>,
+}
+
+impl_generate_config_from_default!(MezmoEventTracesConfig);
+
+impl MezmoEventTracesConfig {
+    fn schema_definition(&self, log_namespace: LogNamespace) -> Definition {
+        BytesDeserializerConfig
+            .schema_definition(log_namespace)
+            .with_standard_vector_source_metadata()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "mezmo_event_traces")]
+impl SourceConfig for MezmoEventTracesConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let subscription = EventTraceSubscription::subscribe();
+
+        let log_namespace = cx.log_namespace(self.log_namespace);
+
+        Ok(Box::pin(mezmo_event_traces(
+            subscription,
+            cx.out,
+            cx.shutdown,
+            log_namespace,
+        )))
+    }
+
+    fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
+        let schema_definition =
+            self.schema_definition(global_log_namespace.merge(self.log_namespace));
+
+        vec![SourceOutput::new_maybe_logs(
+            DataType::Log,
+            schema_definition,
+        )]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+async fn mezmo_event_traces(
+    subscription: EventTraceSubscription,
+    mut out: SourceSender,
+    shutdown: ShutdownSignal,
+    log_namespace: LogNamespace,
+) -> Result<(), ()> {
+    let mut span_stream = subscription.into_stream().take_until(shutdown);
+
+    while let Some(mut span) = span_stream.next().await {
+        let byte_size = span.estimated_json_encoded_size_of().get();
+        emit!(InternalLogsBytesReceived { byte_size });
+        emit!(InternalLogsEventsReceived {
+            count: 1,
+            byte_size: byte_size.into(),
+        });
+
+        log_namespace.insert_standard_vector_source_metadata(
+            &mut span,
+            MezmoEventTracesConfig::NAME,
+            Utc::now(),
+        );
+
+        if let Err(_) = out.send_event(Event::from(span)).await {
+            emit!(StreamClosedError { count: 1 });
+            return Err(());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_config() {
+        crate::test_util::test_generate_config::<MezmoEventTracesConfig>();
+    }
+}
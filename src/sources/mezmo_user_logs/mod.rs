@@ -10,7 +10,7 @@ use crate::mezmo::user_trace::UserLogSubscription;
 use crate::{
     SourceSender,
     config::{DataType, SourceConfig, SourceContext},
-    event::Event,
+    event::{Event, LogEvent},
     internal_events::{InternalLogsBytesReceived, InternalLogsEventsReceived, StreamClosedError},
     shutdown::ShutdownSignal,
 };
@@ -26,6 +26,82 @@ pub struct MezmoUserLogsConfig {
     #[configurable(metadata(docs::hidden))]
     #[serde(default)]
     log_namespace: Option<bool>,
+
+    /// Only emit the user logs with one of these levels. All levels are emitted when empty.
+    #[serde(default)]
+    levels: Vec<UserLogLevel>,
+
+    /// Only emit the user logs with one of these error codes. All user logs are emitted when empty.
+    #[configurable(metadata(docs::examples = "FIELD_NOT_FOUND"))]
+    #[serde(default)]
+    codes: Vec<String>,
+
+    /// Only emit the user logs of these pipelines. All pipelines are emitted when empty.
+    #[configurable(metadata(docs::examples = "pipeline_123"))]
+    #[serde(default)]
+    pipelines: Vec<String>,
+}
+
+/// The level of a user log.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum UserLogLevel {
+    /// Debug user logs.
+    Debug,
+
+    /// Info user logs.
+    Info,
+
+    /// Warn user logs.
+    Warn,
+
+    /// Error user logs.
+    Error,
+}
+
+impl UserLogLevel {
+    /// The level as set in the `meta.mezmo.level` of the user logs.
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Debug => "DEBUG",
+            Self::Info => "INFO",
+            Self::Warn => "WARN",
+            Self::Error => "ERROR",
+        }
+    }
+}
+
+/// Selects the user logs emitted by the source, by level, code and pipeline.
+#[derive(Debug, Default)]
+struct UserLogFilter {
+    levels: Vec<&'static str>,
+    codes: Vec<String>,
+    pipelines: Vec<String>,
+}
+
+impl UserLogFilter {
+    fn new(config: &MezmoUserLogsConfig) -> Self {
+        Self {
+            levels: config.levels.iter().map(|level| level.as_str()).collect(),
+            codes: config.codes.clone(),
+            pipelines: config.pipelines.clone(),
+        }
+    }
+
+    fn matches(&self, log: &LogEvent) -> bool {
+        fn field_in<T: AsRef<str>>(log: &LogEvent, path: &str, values: &[T]) -> bool {
+            values.is_empty()
+                || log
+                    .get(path)
+                    .and_then(|value| value.as_str())
+                    .is_some_and(|value| values.iter().any(|v| v.as_ref() == value))
+        }
+
+        field_in(log, "meta.mezmo.level", &self.levels)
+            && field_in(log, "meta.mezmo.code", &self.codes)
+            && field_in(log, "meta.mezmo.pipeline_id", &self.pipelines)
+    }
 }
 
 impl_generate_config_from_default!(MezmoUserLogsConfig);
@@ -48,6 +124,7 @@ impl SourceConfig for MezmoUserLogsConfig {
 
         Ok(Box::pin(mezmo_user_logs(
             subscription,
+            UserLogFilter::new(self),
             cx.out,
             cx.shutdown,
             log_namespace,
@@ -71,6 +148,7 @@ impl SourceConfig for MezmoUserLogsConfig {
 
 async fn mezmo_user_logs(
     subscription: UserLogSubscription,
+    filter: UserLogFilter,
     mut out: SourceSender,
     shutdown: ShutdownSignal,
     log_namespace: LogNamespace,
@@ -78,6 +156,10 @@ async fn mezmo_user_logs(
     let mut log_stream = subscription.into_stream().take_until(shutdown);
 
     while let Some(mut log) = log_stream.next().await {
+        if !filter.matches(&log) {
+            continue;
+        }
+
         let byte_size = log.estimated_json_encoded_size_of().get();
         emit!(InternalLogsBytesReceived { byte_size });
         emit!(InternalLogsEventsReceived {
@@ -131,6 +213,43 @@ mod tests {
         crate::test_util::test_generate_config::<MezmoUserLogsConfig>();
     }
 
+    #[test]
+    fn validates_levels() {
+        let config: MezmoUserLogsConfig = toml::from_str(r#"levels = ["error", "warn"]"#).unwrap();
+        assert_eq!(config.levels, vec![UserLogLevel::Error, UserLogLevel::Warn]);
+
+        assert!(toml::from_str::<MezmoUserLogsConfig>(r#"levels = ["eror"]"#).is_err());
+    }
+
+    #[test]
+    fn filters_logs() {
+        let log = |level: &str, code: Option<&str>, pipeline_id: &str| {
+            let mut log = LogEvent::from("msg");
+            log.insert("meta.mezmo.level", level);
+            log.insert("meta.mezmo.pipeline_id", pipeline_id);
+            if let Some(code) = code {
+                log.insert("meta.mezmo.code", code);
+            }
+            log
+        };
+
+        let filter = UserLogFilter::new(&MezmoUserLogsConfig::default());
+        assert!(filter.matches(&log("DEBUG", None, "pipeline_123")));
+
+        let filter = UserLogFilter::new(&MezmoUserLogsConfig {
+            levels: vec![UserLogLevel::Error, UserLogLevel::Warn],
+            codes: vec!["FIELD_NOT_FOUND".into()],
+            pipelines: vec!["pipeline_123".into()],
+            ..Default::default()
+        });
+        assert!(filter.matches(&log("ERROR", Some("FIELD_NOT_FOUND"), "pipeline_123")));
+        assert!(filter.matches(&log("WARN", Some("FIELD_NOT_FOUND"), "pipeline_123")));
+        assert!(!filter.matches(&log("INFO", Some("FIELD_NOT_FOUND"), "pipeline_123")));
+        assert!(!filter.matches(&log("ERROR", Some("FIELD_NULL"), "pipeline_123")));
+        assert!(!filter.matches(&log("ERROR", None, "pipeline_123")));
+        assert!(!filter.matches(&log("ERROR", Some("FIELD_NOT_FOUND"), "pipeline_456")));
+    }
+
     #[tokio::test]
     async fn receives_logs() {
         assert_source_compliance(&SOURCE_TAGS, async {
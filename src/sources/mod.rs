@@ -60,6 +60,8 @@ pub mod logstash;
 pub mod mezmo_analytics;
 #[cfg(feature = "sources-mezmo_demo_logs")]
 pub mod mezmo_demo_logs;
+#[cfg(feature = "sources-mezmo_event_traces")]
+pub mod mezmo_event_traces;
 #[cfg(feature = "sources-mezmo_pipeline_state_variable_change")]
 pub mod mezmo_pipeline_state_variable_change;
 #[cfg(feature = "sources-mezmo_user_logs")]
@@ -5,7 +5,10 @@ use vector_lib::{
     tap::{EventFormatter, OutputChannel, TapRunner},
 };
 
-use crate::signal::{SignalRx, SignalTo};
+use crate::{
+    conditions::{ConditionalConfig, VrlConfig},
+    signal::{SignalRx, SignalTo},
+};
 
 /// Delay (in milliseconds) before attempting to reconnect to the Vector API
 const RECONNECT_DELAY: u64 = 5000;
@@ -34,6 +37,21 @@ pub(crate) async fn cmd(opts: &super::Opts, signal_rx: SignalRx) -> exitcode::Ex
         return exitcode::UNAVAILABLE;
     }
 
+    // The API returns no events when the filter doesn't compile, report it upfront instead
+    if let Some(filter) = &opts.event_filter {
+        let condition = VrlConfig {
+            source: filter.clone(),
+            runtime: Default::default(),
+        };
+        if let Err(error) = condition.build(&Default::default(), &Default::default(), None) {
+            #[allow(clippy::print_stderr)]
+            {
+                eprintln!("Invalid event filter: {error}");
+            }
+            return exitcode::USAGE;
+        }
+    }
+
     tap(opts, signal_rx).await
 }
 
@@ -43,7 +61,7 @@ pub async fn tap(opts: &super::Opts, mut signal_rx: SignalRx) -> exitcode::ExitC
     let output_channel = OutputChannel::Stdout(EventFormatter::new(opts.meta, opts.format));
     let tap_runner = TapRunner::new(
         &subscription_url,
-        opts.inputs_of.clone(),
+        opts.inputs_patterns(),
         opts.outputs_patterns().clone(),
         &output_channel,
         opts.format,
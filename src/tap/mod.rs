@@ -45,6 +45,10 @@ pub struct Opts {
     #[arg(value_delimiter(','), long)]
     inputs_of: Vec<String>,
 
+    /// Components (transforms) IDs whose inputs and outputs to observe together, to compare events before and after them (comma-separated; accepts glob patterns)
+    #[arg(value_delimiter(','), long)]
+    inputs_and_outputs_of: Vec<String>,
+
     /// Quiet output includes only events
     #[arg(short, long)]
     quiet: bool,
@@ -70,6 +74,7 @@ impl Opts {
         if self.component_id_patterns.is_empty()
             && self.outputs_of.is_empty()
             && self.inputs_of.is_empty()
+            && self.inputs_and_outputs_of.is_empty()
         {
             vec!["*".to_string()]
         } else {
@@ -77,10 +82,20 @@ impl Opts {
                 .iter()
                 .cloned()
                 .chain(self.component_id_patterns.iter().cloned())
+                .chain(self.inputs_and_outputs_of.iter().cloned())
                 .collect()
         }
     }
 
+    /// Component ID patterns whose inputs to tap
+    pub fn inputs_patterns(&self) -> Vec<String> {
+        self.inputs_of
+            .iter()
+            .cloned()
+            .chain(self.inputs_and_outputs_of.iter().cloned())
+            .collect()
+    }
+
     /// Use the provided URL as the Vector GraphQL API server, or default to the local port
     /// provided by the API config.
     pub fn url(&self) -> Url {
@@ -40,7 +40,10 @@ use super::{
     schema,
     task::{Task, TaskOutput, TaskResult},
 };
-use crate::mezmo::event_trace::{MezmoSyncTransformTrace, MezmoTaskTransformTrace};
+use crate::mezmo::event_trace::{
+    MezmoBufferTrace, MezmoSinkTraceCollector, MezmoSourceTrace, MezmoSyncTransformTrace,
+    MezmoTaskTransformTrace,
+};
 use crate::{
     SourceSender,
     config::{
@@ -292,6 +295,7 @@ impl<'a> Builder<'a> {
             let mut controls = HashMap::new();
             let mut schema_definitions = HashMap::with_capacity(source_outputs.len());
             let source_name = key.id().parse().ok();
+            let source_trace = MezmoSourceTrace::maybe_new(key);
 
             for output in source_outputs.into_iter() {
                 let rx = builder.add_source_output(output.clone(), key.clone());
@@ -302,7 +306,14 @@ impl<'a> Builder<'a> {
                 let source_type = source.inner.get_component_name();
                 let source = Arc::new(key.clone());
 
-                let pump = run_source_output_pump(rx, fanout, source, source_type, usage_tracker);
+                let pump = run_source_output_pump(
+                    rx,
+                    fanout,
+                    source,
+                    source_type,
+                    usage_tracker,
+                    source_trace.clone(),
+                );
 
                 pumps.push(pump.instrument(span.clone()));
                 controls.insert(
@@ -532,13 +543,17 @@ impl<'a> Builder<'a> {
             };
 
             let metrics = ChannelMetricMetadata::new(TRANSFORM_CHANNEL_METRIC_PREFIX, None);
-            let (input_tx, input_rx) = TopologyBuilder::standalone_memory(
+            let (mut input_tx, mut input_rx) = TopologyBuilder::standalone_memory(
                 TOPOLOGY_BUFFER_SIZE,
                 WhenFull::Block,
                 &span,
                 Some(metrics),
                 self.config.global.buffer_utilization_ewma_half_life_seconds,
             );
+            if let Some(buffer_trace) = MezmoBufferTrace::maybe_new(key) {
+                input_tx.with_custom_instrumentation(buffer_trace.clone());
+                input_rx.with_custom_instrumentation(buffer_trace);
+            }
 
             self.inputs
                 .insert(key.clone(), (input_tx, node.inputs.clone()));
@@ -623,7 +638,13 @@ impl<'a> Builder<'a> {
                             self.errors.push(format!("Sink \"{key}\": {error}"));
                             continue;
                         }
-                        Ok((tx, rx)) => (tx, Arc::new(Mutex::new(Some(rx.into_stream())))),
+                        Ok((mut tx, mut rx)) => {
+                            if let Some(buffer_trace) = MezmoBufferTrace::maybe_new(key) {
+                                tx.with_custom_instrumentation(buffer_trace.clone());
+                                rx.with_custom_instrumentation(buffer_trace);
+                            }
+                            (tx, Arc::new(Mutex::new(Some(rx.into_stream()))))
+                        }
                     }
                 }
             };
@@ -658,6 +679,7 @@ impl<'a> Builder<'a> {
                 .utilization_registry
                 .add_component(key.clone(), gauge!("utilization"));
             let component_key = key.clone();
+            let trace_collector = MezmoSinkTraceCollector::maybe_new(key);
             let sink = async move {
                 debug!("Sink starting.");
 
@@ -681,6 +703,12 @@ impl<'a> Builder<'a> {
                 sink.run(
                     rx.by_ref()
                         .filter(|events: &EventArray| ready(filter_events_type(events, input_type)))
+                        .map(|mut events| {
+                            if let Some(collector) = &trace_collector {
+                                collector.collect(&mut events);
+                            }
+                            events
+                        })
                         .inspect(|events| {
                             usage_tracker.track(events);
 
@@ -925,6 +953,7 @@ async fn run_source_output_pump(
     source: Arc<ComponentKey>,
     source_type: &'static str,
     usage_tracker: Box<dyn ComponentUsageTracker>,
+    source_trace: Option<MezmoSourceTrace>,
 ) -> TaskResult {
     debug!("Source pump starting.");
 
@@ -946,6 +975,9 @@ async fn run_source_output_pump(
             metadata.set_source_type(source_type);
             metadata.set_last_transform_timestamp(now);
         });
+        if let Some(source_trace) = &source_trace {
+            source_trace.on_emit(&mut array, send_reference);
+        }
         fanout
             .send(array, Some(send_reference))
             .await
@@ -138,6 +138,10 @@ impl TopologyController {
                     api_server.update_config(self.topology.config());
                 }
 
+                #[cfg(feature = "api-client")]
+                // Keep the running config available to the remote tasks.
+                crate::mezmo::remote_task_execution::update_config_snapshot(self.topology.config());
+
                 emit!(VectorReloaded {
                     config_paths: &self.config_paths
                 });
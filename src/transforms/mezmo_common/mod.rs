@@ -66,3 +66,19 @@ pub mod state {
         Duration::from_millis(ms)
     }
 }
+
+pub mod headers {
+    use vector_lib::event::{ObjectMap, Value};
+
+    /// The `Content-Type` header of the request an event was received with, when the source
+    /// added the request headers to the event metadata.
+    pub fn content_type_header(metadata: &ObjectMap) -> Option<&str> {
+        metadata
+            .get("headers")
+            .and_then(Value::as_object)?
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+            .and_then(|(_, value)| value.as_bytes())
+            .and_then(|value| std::str::from_utf8(value).ok())
+    }
+}
@@ -1,7 +1,7 @@
 use chrono::{TimeZone, Utc};
 
 use crate::config::log_schema;
-use crate::event::{LogEvent, ObjectMap, Value};
+use crate::event::{LogEvent, MetricKind, ObjectMap, Value};
 
 #[derive(Copy, Clone, Debug)]
 pub enum TimestampUnit {
@@ -30,6 +30,22 @@ pub fn get_message_object_mut(log: &mut LogEvent) -> Result<&mut ObjectMap, Stri
         .ok_or_else(|| "Message is not an object".to_string())
 }
 
+/// Looks up a field ignoring case and underscores. The agent encodes the same payload
+/// structs with Go field names (msgpack) or snake_case names (JSON), so `agent_hostname`
+/// and `AgentHostname` both match the `agenthostname` name.
+pub fn get_field<'a>(obj: &'a ObjectMap, name: &str) -> Option<&'a Value> {
+    obj.get(name).or_else(|| {
+        obj.iter()
+            .find(|(key, _)| {
+                key.chars()
+                    .filter(|c| *c != '_')
+                    .map(|c| c.to_ascii_lowercase())
+                    .eq(name.chars())
+            })
+            .map(|(_, value)| value)
+    })
+}
+
 pub fn parse_timestamp(value: &Value, unit: TimestampUnit) -> Option<chrono::DateTime<Utc>> {
     match value {
         Value::Timestamp(timestamp) => Some(*timestamp),
@@ -66,6 +82,40 @@ pub fn parse_timestamp(value: &Value, unit: TimestampUnit) -> Option<chrono::Dat
     }
 }
 
+pub fn parse_tag_array(tags: &[Value]) -> ObjectMap {
+    tags.iter()
+        .filter_map(|tag| tag.as_str())
+        .map(|tag| {
+            let (key, val) = match tag.split_once(':') {
+                Some((prefix, suffix)) => (prefix, Value::from(suffix.to_string())),
+                None => (tag.as_ref(), Value::Null),
+            };
+            (key.to_string().into(), val)
+        })
+        .collect()
+}
+
+pub const fn metric_kind_as_str(kind: MetricKind) -> &'static str {
+    match kind {
+        MetricKind::Incremental => "incremental",
+        MetricKind::Absolute => "absolute",
+    }
+}
+
+pub fn namespace_name_from_dd_metric(
+    dd_metric_name: &str,
+    split_metric_namespace: bool,
+) -> (Option<&str>, &str) {
+    if !split_metric_namespace {
+        return (None, dd_metric_name);
+    }
+
+    match dd_metric_name.split_once('.') {
+        Some((namespace, name)) => (Some(namespace), name),
+        None => (None, dd_metric_name),
+    }
+}
+
 fn split_float_seconds(value: f64) -> Option<(i64, u32)> {
     if value < (i64::MIN as f64) || value > (i64::MAX as f64) {
         return None;
@@ -51,6 +51,15 @@ pub struct EventTypeValues {
 
     /// Value that identifies a sketch event.
     pub sketch: String,
+
+    /// Value that identifies an APM stats event.
+    pub stats: String,
+
+    /// Value that identifies a service check event.
+    pub service_check: String,
+
+    /// Value that identifies a Datadog event.
+    pub event: String,
 }
 
 impl Default for EventTypeValues {
@@ -60,6 +69,9 @@ impl Default for EventTypeValues {
             metric: "metric".into(),
             trace: "trace".into(),
             sketch: "sketch".into(),
+            stats: "stats".into(),
+            service_check: "service_check".into(),
+            event: "event".into(),
         }
     }
 }
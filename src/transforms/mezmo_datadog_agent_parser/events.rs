@@ -0,0 +1,228 @@
+use crate::config::log_schema;
+use crate::event::{Event, MaybeAsLogMut, ObjectMap, Value};
+
+use super::common::{TimestampUnit, get_message_object, parse_tag_array, parse_timestamp};
+use super::{MezmoDatadogAgentParser, TransformDatadogEvent, TransformDatadogEventError};
+
+pub(super) struct DatadogEventEvent;
+
+impl TransformDatadogEvent for DatadogEventEvent {
+    /// Transform Datadog events into normalized log events.
+    ///
+    /// The agent sends events to the intake endpoint grouped by source type
+    /// (`{"events": {"<source_type>": [...]}}`) using `msg_title`/`msg_text`, while the events
+    /// API (/api/v1/events) carries a single event using `title`/`text`. Both are accepted.
+    /// Event timestamps are in seconds.
+    /// See: https://docs.datadoghq.com/api/latest/events/#post-an-event
+    fn transform(
+        mut event: Event,
+        parser: &MezmoDatadogAgentParser,
+    ) -> Result<Vec<Event>, TransformDatadogEventError> {
+        let log_result = event
+            .maybe_as_log_mut()
+            .ok_or_else(|| "Event is not a log".to_string());
+
+        let log = match log_result {
+            Ok(log) => log,
+            Err(msg) => return Err(TransformDatadogEventError::from(event, &msg)),
+        };
+
+        let message_obj = match get_message_object(log) {
+            Ok(message_obj) => message_obj,
+            Err(msg) => return Err(TransformDatadogEventError::from(event, &msg)),
+        };
+
+        let dd_events = match transform_events_payload(message_obj) {
+            Ok(dd_events) => dd_events,
+            Err(msg) => return Err(TransformDatadogEventError::from(event, &msg)),
+        };
+
+        parser
+            .build_events_from_payloads(event, dd_events)
+            .map_err(|(msg, event)| TransformDatadogEventError::from(*event, &msg))
+    }
+}
+
+fn transform_events_payload(
+    message: &ObjectMap,
+) -> Result<Vec<(ObjectMap, Option<Value>)>, String> {
+    let Some(grouped) = message.get("events") else {
+        return transform_event(message, None).map(|event| vec![event]);
+    };
+
+    let grouped = grouped
+        .as_object()
+        .ok_or_else(|| "Invalid events payload".to_string())?;
+
+    let mut outputs = Vec::new();
+    for (source_type_name, events) in grouped {
+        let events = events
+            .as_array()
+            .ok_or_else(|| "Invalid events payload".to_string())?;
+        for event in events {
+            let event = event
+                .as_object()
+                .ok_or_else(|| "Invalid event".to_string())?;
+            outputs.push(transform_event(event, Some(source_type_name.as_str()))?);
+        }
+    }
+
+    Ok(outputs)
+}
+
+fn transform_event(
+    message: &ObjectMap,
+    source_type_name: Option<&str>,
+) -> Result<(ObjectMap, Option<Value>), String> {
+    let get_str = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| message.get(*name).and_then(Value::as_str))
+            .filter(|value| !value.is_empty())
+            .map(|value| Value::from(value.into_owned()))
+    };
+
+    let title =
+        get_str(&["title", "msg_title"]).ok_or_else(|| "Missing event title".to_string())?;
+
+    let mut output = ObjectMap::new();
+    output.insert("title".into(), title);
+    for (field, names) in [
+        ("text", &["text", "msg_text"][..]),
+        ("priority", &["priority"][..]),
+        ("alert_type", &["alert_type"][..]),
+        ("aggregation_key", &["aggregation_key"][..]),
+        ("event_type", &["event_type"][..]),
+        ("source_type_name", &["source_type_name"][..]),
+    ] {
+        if let Some(value) = get_str(names) {
+            output.insert(field.into(), value);
+        }
+    }
+    if !output.contains_key("source_type_name")
+        && let Some(source_type_name) = source_type_name
+    {
+        output.insert(
+            "source_type_name".into(),
+            Value::from(source_type_name.to_string()),
+        );
+    }
+
+    if let Some(host_key) = log_schema().host_key()
+        && let Some(host) = get_str(&["host"])
+    {
+        output.insert(host_key.to_string().into(), host);
+    }
+
+    let tags = message
+        .get("tags")
+        .and_then(Value::as_array)
+        .map(parse_tag_array)
+        .unwrap_or_default();
+    if !tags.is_empty() {
+        output.insert("tags".into(), Value::Object(tags));
+    }
+
+    let timestamp = ["date_happened", "timestamp"]
+        .iter()
+        .find_map(|name| message.get(*name))
+        .and_then(|value| parse_timestamp(value, TimestampUnit::Seconds))
+        .map(Value::Timestamp);
+
+    Ok((output, timestamp))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::super::TransformDatadogEvent;
+    use super::*;
+    use crate::event::LogEvent;
+    use crate::transforms::mezmo_datadog_agent_parser::MezmoDatadogAgentParserConfig;
+
+    const FIXTURE: &str = include_str!("../../../tests/data/mezmo/datadog_agent/events.json");
+
+    fn build_event(message: serde_json::Value) -> Event {
+        let mut log = LogEvent::default();
+        log.insert(log_schema().message_key_target_path().unwrap(), message);
+        Event::Log(log)
+    }
+
+    fn message(event: &Event) -> &ObjectMap {
+        event
+            .as_log()
+            .get(log_schema().message_key_target_path().unwrap())
+            .and_then(Value::as_object)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_transform_intake_events() {
+        let config = MezmoDatadogAgentParserConfig::default();
+        let parser = MezmoDatadogAgentParser::new(&config, None);
+        let payload: serde_json::Value = serde_json::from_str(FIXTURE).unwrap();
+
+        let results = DatadogEventEvent::transform(build_event(payload), &parser).unwrap();
+        assert_eq!(results.len(), 2);
+
+        let docker = message(&results[0]);
+        assert_eq!(
+            docker.get("title"),
+            Some(&Value::from("Container web restarted"))
+        );
+        assert_eq!(docker.get("alert_type"), Some(&Value::from("warning")));
+        assert_eq!(
+            docker.get("aggregation_key"),
+            Some(&Value::from("docker:web"))
+        );
+        assert_eq!(docker.get("host"), Some(&Value::from("web-1")));
+        let tags = docker.get("tags").and_then(Value::as_object).unwrap();
+        assert_eq!(tags.get("container_name"), Some(&Value::from("web")));
+        assert_eq!(
+            results[0]
+                .as_log()
+                .get(log_schema().timestamp_key_target_path().unwrap())
+                .and_then(Value::as_timestamp),
+            Some(&Utc.timestamp_opt(1_700_000_000, 0).single().unwrap())
+        );
+
+        let kubernetes = message(&results[1]);
+        assert_eq!(
+            kubernetes.get("source_type_name"),
+            Some(&Value::from("kubernetes"))
+        );
+        assert!(kubernetes.get("aggregation_key").is_none());
+    }
+
+    #[test]
+    fn test_transform_api_event() {
+        let config = MezmoDatadogAgentParserConfig::default();
+        let parser = MezmoDatadogAgentParser::new(&config, None);
+
+        let results = DatadogEventEvent::transform(
+            build_event(serde_json::json!({
+                "title": "Deploy finished",
+                "text": "Version 1.2.3 deployed",
+                "date_happened": 1_700_000_120,
+                "tags": ["service:web"]
+            })),
+            &parser,
+        )
+        .unwrap();
+        assert_eq!(results.len(), 1);
+        let event = message(&results[0]);
+        assert_eq!(event.get("title"), Some(&Value::from("Deploy finished")));
+        assert_eq!(
+            event.get("text"),
+            Some(&Value::from("Version 1.2.3 deployed"))
+        );
+
+        let err = DatadogEventEvent::transform(
+            build_event(serde_json::json!({ "text": "no title" })),
+            &parser,
+        )
+        .unwrap_err();
+        assert_eq!(err.message, "Missing event title");
+    }
+}
@@ -2,7 +2,10 @@ use crate::common::datadog::DatadogMetricType;
 use crate::config::log_schema;
 use crate::event::{Event, MaybeAsLogMut, MetricKind, ObjectMap, Value};
 
-use super::common::{TimestampUnit, get_message_object, parse_timestamp};
+use super::common::{
+    TimestampUnit, get_message_object, metric_kind_as_str, namespace_name_from_dd_metric,
+    parse_tag_array, parse_timestamp,
+};
 use super::{MezmoDatadogAgentParser, TransformDatadogEvent, TransformDatadogEventError};
 
 pub(super) struct DatadogMetricEvent;
@@ -367,19 +370,6 @@ fn transform_sketch_payload(
     Ok(outputs)
 }
 
-fn parse_tag_array(tags: &[Value]) -> ObjectMap {
-    tags.iter()
-        .filter_map(|tag| tag.as_str())
-        .map(|tag| {
-            let (key, val) = match tag.split_once(':') {
-                Some((prefix, suffix)) => (prefix, Value::from(suffix.to_string())),
-                None => (tag.as_ref(), Value::Null),
-            };
-            (key.to_string().into(), val)
-        })
-        .collect()
-}
-
 fn parse_metric_type(metric_type: &Value) -> Result<DatadogMetricType, String> {
     // Some legacy agents send the metric type as an integer
     if let Some(metric_type) = metric_type.as_integer() {
@@ -510,27 +500,6 @@ impl AsMetricKindStr for DatadogMetricType {
     }
 }
 
-const fn metric_kind_as_str(kind: MetricKind) -> &'static str {
-    match kind {
-        MetricKind::Incremental => "incremental",
-        MetricKind::Absolute => "absolute",
-    }
-}
-
-fn namespace_name_from_dd_metric(
-    dd_metric_name: &str,
-    split_metric_namespace: bool,
-) -> (Option<&str>, &str) {
-    if !split_metric_namespace {
-        return (None, dd_metric_name);
-    }
-
-    match dd_metric_name.split_once('.') {
-        Some((namespace, name)) => (Some(namespace), name),
-        None => (None, dd_metric_name),
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::super::TransformDatadogEvent;
@@ -11,14 +11,20 @@ use crate::{
     internal_events::MezmoDatadogAgentParserError,
 };
 
+use events::DatadogEventEvent;
 use logs::DatadogLogEvent;
 use metrics::{DatadogMetricEvent, DatadogSketchEvent};
+use service_checks::DatadogServiceCheckEvent;
+use stats::DatadogStatsEvent;
 use traces::DatadogTraceEvent;
 
 mod common;
 mod config;
+mod events;
 mod logs;
 mod metrics;
+mod service_checks;
+mod stats;
 mod traces;
 
 pub use config::{EventTypeValues, MezmoDatadogAgentParserConfig};
@@ -199,6 +205,33 @@ impl SyncTransform for MezmoDatadogAgentParser {
                     "trace",
                 );
             }
+            Some(t) if t == self.event_type_values.stats => {
+                self.handle_transform_event::<DatadogStatsEvent>(
+                    event,
+                    output,
+                    METRICS_OUTPUT,
+                    "stats",
+                    "APM stats",
+                );
+            }
+            Some(t) if t == self.event_type_values.service_check => {
+                self.handle_transform_event::<DatadogServiceCheckEvent>(
+                    event,
+                    output,
+                    LOGS_OUTPUT,
+                    "service_check",
+                    "service check",
+                );
+            }
+            Some(t) if t == self.event_type_values.event => {
+                self.handle_transform_event::<DatadogEventEvent>(
+                    event,
+                    output,
+                    LOGS_OUTPUT,
+                    "event",
+                    "event",
+                );
+            }
             _ => {
                 if self.reroute_unmatched {
                     output.push(Some(UNMATCHED_OUTPUT), event);
@@ -331,14 +364,51 @@ mod tests {
         );
         parser.transform(trace_event, &mut outputs);
 
+        let stats_event = build_event(
+            "stats",
+            serde_json::json!({
+                "agentHostname": "agent-host",
+                "stats": [{
+                    "stats": [{
+                        "start": 1234567890000000000_i64,
+                        "duration": 10000000000_i64,
+                        "stats": [{"name": "http.request", "hits": 1, "errors": 0, "duration": 10}]
+                    }]
+                }]
+            })
+            .into(),
+        );
+        parser.transform(stats_event, &mut outputs);
+
+        let service_check_event = build_event(
+            "service_check",
+            serde_json::json!({
+                "check": "datadog.agent.up",
+                "status": 0,
+                "timestamp": 1234567890
+            })
+            .into(),
+        );
+        parser.transform(service_check_event, &mut outputs);
+
+        let dd_event = build_event(
+            "event",
+            serde_json::json!({
+                "title": "Deploy finished",
+                "date_happened": 1234567890
+            })
+            .into(),
+        );
+        parser.transform(dd_event, &mut outputs);
+
         let unmatched_event = build_event("unknown", serde_json::json!({}).into());
         parser.transform(unmatched_event, &mut outputs);
 
         for output_name in output_names {
             let events: Vec<_> = outputs.drain_named(output_name).collect();
             match output_name {
-                LOGS_OUTPUT => assert_eq!(events.len(), 1),
-                METRICS_OUTPUT => assert_eq!(events.len(), 2),
+                LOGS_OUTPUT => assert_eq!(events.len(), 3),
+                METRICS_OUTPUT => assert_eq!(events.len(), 5),
                 TRACES_OUTPUT => assert_eq!(events.len(), 1),
                 UNMATCHED_OUTPUT => assert_eq!(events.len(), 1),
                 _ => unreachable!("unexpected output"),
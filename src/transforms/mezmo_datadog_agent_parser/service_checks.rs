@@ -0,0 +1,170 @@
+use crate::config::log_schema;
+use crate::event::{Event, MaybeAsLogMut, ObjectMap, Value};
+
+use super::common::{TimestampUnit, get_message_object, parse_tag_array, parse_timestamp};
+use super::{MezmoDatadogAgentParser, TransformDatadogEvent, TransformDatadogEventError};
+
+pub(super) struct DatadogServiceCheckEvent;
+
+impl TransformDatadogEvent for DatadogServiceCheckEvent {
+    /// Transform a service check (/api/v1/check_run) into a normalized log event.
+    ///
+    /// The agent sends service checks as an array which is unrolled so that each check
+    /// arrives as a separate event. The check timestamp is in seconds.
+    /// See: https://docs.datadoghq.com/api/latest/service-checks/
+    fn transform(
+        mut event: Event,
+        parser: &MezmoDatadogAgentParser,
+    ) -> Result<Vec<Event>, TransformDatadogEventError> {
+        let log_result = event
+            .maybe_as_log_mut()
+            .ok_or_else(|| "Event is not a log".to_string());
+
+        let log = match log_result {
+            Ok(log) => log,
+            Err(msg) => return Err(TransformDatadogEventError::from(event, &msg)),
+        };
+
+        let message_obj = match get_message_object(log) {
+            Ok(message_obj) => message_obj,
+            Err(msg) => return Err(TransformDatadogEventError::from(event, &msg)),
+        };
+
+        let service_check = match transform_service_check(message_obj) {
+            Ok(service_check) => service_check,
+            Err(msg) => return Err(TransformDatadogEventError::from(event, &msg)),
+        };
+
+        parser
+            .build_events_from_payloads(event, vec![service_check])
+            .map_err(|(msg, event)| TransformDatadogEventError::from(*event, &msg))
+    }
+}
+
+const fn status_name(status: i64) -> Option<&'static str> {
+    match status {
+        0 => Some("ok"),
+        1 => Some("warning"),
+        2 => Some("critical"),
+        3 => Some("unknown"),
+        _ => None,
+    }
+}
+
+fn transform_service_check(message: &ObjectMap) -> Result<(ObjectMap, Option<Value>), String> {
+    let check = message
+        .get("check")
+        .and_then(Value::as_str)
+        .ok_or_else(|| "Missing check name".to_string())?;
+
+    let status = message
+        .get("status")
+        .and_then(Value::as_integer)
+        .ok_or_else(|| "Missing check status".to_string())?;
+    let status_name = status_name(status).ok_or_else(|| "Unknown check status".to_string())?;
+
+    let mut output = ObjectMap::new();
+    output.insert("check".into(), Value::from(check.into_owned()));
+    output.insert("status".into(), Value::from(status));
+    output.insert("status_name".into(), Value::from(status_name));
+
+    if let Some(message) = message
+        .get("message")
+        .and_then(Value::as_str)
+        .filter(|message| !message.is_empty())
+    {
+        output.insert("message".into(), Value::from(message.into_owned()));
+    }
+
+    if let Some(host_key) = log_schema().host_key()
+        && let Some(host) = message
+            .get("host_name")
+            .and_then(Value::as_str)
+            .filter(|host| !host.is_empty())
+    {
+        output.insert(host_key.to_string().into(), Value::from(host.into_owned()));
+    }
+
+    let tags = message
+        .get("tags")
+        .and_then(Value::as_array)
+        .map(parse_tag_array)
+        .unwrap_or_default();
+    if !tags.is_empty() {
+        output.insert("tags".into(), Value::Object(tags));
+    }
+
+    let timestamp = message
+        .get("timestamp")
+        .and_then(|value| parse_timestamp(value, TimestampUnit::Seconds))
+        .map(Value::Timestamp);
+
+    Ok((output, timestamp))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::super::TransformDatadogEvent;
+    use super::*;
+    use crate::event::LogEvent;
+    use crate::transforms::mezmo_datadog_agent_parser::MezmoDatadogAgentParserConfig;
+
+    const FIXTURE: &str =
+        include_str!("../../../tests/data/mezmo/datadog_agent/service_checks.json");
+
+    fn fixture_events() -> Vec<Event> {
+        let checks: Vec<serde_json::Value> = serde_json::from_str(FIXTURE).unwrap();
+        checks
+            .into_iter()
+            .map(|check| {
+                let mut log = LogEvent::default();
+                log.insert(log_schema().message_key_target_path().unwrap(), check);
+                Event::Log(log)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_transform_service_checks() {
+        let config = MezmoDatadogAgentParserConfig::default();
+        let parser = MezmoDatadogAgentParser::new(&config, None);
+        let mut events = fixture_events().into_iter();
+
+        let results = DatadogServiceCheckEvent::transform(events.next().unwrap(), &parser).unwrap();
+        assert_eq!(results.len(), 1);
+        let log = results[0].as_log();
+        let message = log
+            .get(log_schema().message_key_target_path().unwrap())
+            .and_then(Value::as_object)
+            .unwrap();
+        assert_eq!(message.get("check"), Some(&Value::from("datadog.agent.up")));
+        assert_eq!(message.get("status"), Some(&Value::from(0)));
+        assert_eq!(message.get("status_name"), Some(&Value::from("ok")));
+        assert_eq!(message.get("host"), Some(&Value::from("web-1")));
+        assert!(message.get("message").is_none());
+        let tags = message.get("tags").and_then(Value::as_object).unwrap();
+        assert_eq!(tags.get("env"), Some(&Value::from("prod")));
+        assert_eq!(
+            log.get(log_schema().timestamp_key_target_path().unwrap())
+                .and_then(Value::as_timestamp),
+            Some(&Utc.timestamp_opt(1_700_000_000, 0).single().unwrap())
+        );
+
+        let results = DatadogServiceCheckEvent::transform(events.next().unwrap(), &parser).unwrap();
+        let message = results[0]
+            .as_log()
+            .get(log_schema().message_key_target_path().unwrap())
+            .and_then(Value::as_object)
+            .unwrap();
+        assert_eq!(message.get("status_name"), Some(&Value::from("critical")));
+        assert_eq!(
+            message.get("message"),
+            Some(&Value::from("Connection refused"))
+        );
+
+        let err = DatadogServiceCheckEvent::transform(events.next().unwrap(), &parser).unwrap_err();
+        assert_eq!(err.message, "Unknown check status");
+    }
+}
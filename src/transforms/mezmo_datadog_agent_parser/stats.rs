@@ -0,0 +1,323 @@
+use crate::config::log_schema;
+use crate::event::{Event, MaybeAsLogMut, MetricKind, ObjectMap, Value};
+
+use super::common::{
+    TimestampUnit, get_field, get_message_object, metric_kind_as_str,
+    namespace_name_from_dd_metric, parse_tag_array, parse_timestamp,
+};
+use super::{MezmoDatadogAgentParser, TransformDatadogEvent, TransformDatadogEventError};
+
+pub(super) struct DatadogStatsEvent;
+
+impl TransformDatadogEvent for DatadogStatsEvent {
+    /// Transform an APM stats payload into normalized MezmoMetric log events.
+    ///
+    /// The incoming event holds a `StatsPayload` (/api/v0.2/stats) in `.message`. Each grouped
+    /// stat of each bucket produces `trace.<operation>.hits`, `trace.<operation>.errors` and
+    /// `trace.<operation>.duration` counters, mirroring the Datadog trace metrics.
+    /// Schema: https://github.com/DataDog/datadog-agent/blob/main/pkg/proto/datadog/trace/stats.proto
+    fn transform(
+        mut event: Event,
+        parser: &MezmoDatadogAgentParser,
+    ) -> Result<Vec<Event>, TransformDatadogEventError> {
+        let log_result = event
+            .maybe_as_log_mut()
+            .ok_or_else(|| "Event is not a log".to_string());
+
+        let log = match log_result {
+            Ok(log) => log,
+            Err(msg) => return Err(TransformDatadogEventError::from(event, &msg)),
+        };
+
+        let message_obj = match get_message_object(log) {
+            Ok(message_obj) => message_obj,
+            Err(msg) => return Err(TransformDatadogEventError::from(event, &msg)),
+        };
+
+        let metrics = match transform_stats_payload(message_obj, parser.split_metric_namespace) {
+            Ok(metrics) => metrics,
+            Err(msg) => return Err(TransformDatadogEventError::from(event, &msg)),
+        };
+
+        parser
+            .build_events_from_payloads(event, metrics)
+            .map_err(|(msg, event)| TransformDatadogEventError::from(*event, &msg))
+    }
+}
+
+fn get_str<'a>(obj: &'a ObjectMap, name: &str) -> Option<std::borrow::Cow<'a, str>> {
+    get_field(obj, name)
+        .and_then(Value::as_str)
+        .filter(|value| !value.is_empty())
+}
+
+fn get_u64(obj: &ObjectMap, name: &str) -> Result<u64, String> {
+    match get_field(obj, name) {
+        None | Some(Value::Null) => Ok(0),
+        Some(Value::Integer(value)) if *value >= 0 => Ok(*value as u64),
+        Some(_) => Err(format!("Invalid stats field '{name}'")),
+    }
+}
+
+fn get_array<'a>(obj: &'a ObjectMap, name: &str) -> Result<&'a [Value], String> {
+    match get_field(obj, name) {
+        None | Some(Value::Null) => Ok(&[]),
+        Some(Value::Array(values)) => Ok(values),
+        Some(_) => Err(format!("Invalid stats field '{name}'")),
+    }
+}
+
+fn transform_stats_payload(
+    message: &ObjectMap,
+    split_metric_namespace: bool,
+) -> Result<Vec<(ObjectMap, Option<Value>)>, String> {
+    let agent_hostname = get_str(message, "agenthostname");
+    let agent_env = get_str(message, "agentenv");
+    if get_field(message, "stats").is_none() {
+        return Err("Missing stats".to_string());
+    }
+    let client_payloads = get_array(message, "stats")?;
+
+    let mut outputs = Vec::new();
+    for client_payload in client_payloads {
+        let client_payload = client_payload
+            .as_object()
+            .ok_or_else(|| "Invalid client stats payload".to_string())?;
+
+        let mut base_tags = get_field(client_payload, "tags")
+            .and_then(Value::as_array)
+            .map(parse_tag_array)
+            .unwrap_or_default();
+        for (tag, value) in [
+            (
+                log_schema().host_key().map(|key| key.to_string()),
+                get_str(client_payload, "hostname").or_else(|| agent_hostname.clone()),
+            ),
+            (
+                Some("env".to_string()),
+                get_str(client_payload, "env").or_else(|| agent_env.clone()),
+            ),
+            (
+                Some("version".to_string()),
+                get_str(client_payload, "version"),
+            ),
+        ] {
+            if let (Some(tag), Some(value)) = (tag, value) {
+                base_tags.insert(tag.into(), Value::from(value.into_owned()));
+            }
+        }
+
+        for bucket in get_array(client_payload, "stats")? {
+            let bucket = bucket
+                .as_object()
+                .ok_or_else(|| "Invalid stats bucket".to_string())?;
+
+            let start = get_field(bucket, "start")
+                .and_then(|start| parse_timestamp(start, TimestampUnit::Nanoseconds))
+                .map(Value::Timestamp);
+            let interval_ms = get_u64(bucket, "duration")? / 1_000_000;
+
+            for grouped in get_array(bucket, "stats")? {
+                let grouped = grouped
+                    .as_object()
+                    .ok_or_else(|| "Invalid grouped stats".to_string())?;
+                outputs.extend(
+                    grouped_stats_metrics(
+                        grouped,
+                        &base_tags,
+                        interval_ms,
+                        split_metric_namespace,
+                    )?
+                    .into_iter()
+                    .map(|metric| (metric, start.clone())),
+                );
+            }
+        }
+    }
+
+    Ok(outputs)
+}
+
+fn grouped_stats_metrics(
+    grouped: &ObjectMap,
+    base_tags: &ObjectMap,
+    interval_ms: u64,
+    split_metric_namespace: bool,
+) -> Result<Vec<ObjectMap>, String> {
+    let operation = get_str(grouped, "name").ok_or_else(|| "Missing operation name".to_string())?;
+
+    let mut tags = base_tags.clone();
+    for (tag, field) in [
+        ("service", "service"),
+        ("resource", "resource"),
+        ("span.type", "type"),
+        ("span.kind", "spankind"),
+        ("db.type", "dbtype"),
+    ] {
+        if let Some(value) = get_str(grouped, field) {
+            tags.insert(tag.into(), Value::from(value.into_owned()));
+        }
+    }
+    let status_code = get_u64(grouped, "httpstatuscode")?;
+    if status_code > 0 {
+        tags.insert(
+            "http.status_code".into(),
+            Value::from(status_code.to_string()),
+        );
+    }
+    if get_field(grouped, "synthetics").and_then(Value::as_boolean) == Some(true) {
+        tags.insert("synthetics".into(), Value::from("true"));
+    }
+
+    let duration_ns = get_u64(grouped, "duration")?;
+    let values = [
+        ("hits", get_u64(grouped, "hits")? as f64),
+        ("errors", get_u64(grouped, "errors")? as f64),
+        ("duration", duration_ns as f64 / 1_000_000_000.0),
+    ];
+
+    Ok(values
+        .into_iter()
+        .map(|(suffix, value)| {
+            let metric_name = format!("trace.{operation}.{suffix}");
+            let (namespace, name) =
+                namespace_name_from_dd_metric(&metric_name, split_metric_namespace);
+
+            let mut output = ObjectMap::new();
+            output.insert("name".into(), Value::from(name.to_string()));
+            if let Some(namespace) = namespace {
+                output.insert("namespace".into(), Value::from(namespace.to_string()));
+            }
+            output.insert(
+                "kind".into(),
+                Value::from(metric_kind_as_str(MetricKind::Incremental)),
+            );
+            if !tags.is_empty() {
+                output.insert("tags".into(), Value::Object(tags.clone()));
+            }
+
+            let mut value_obj = ObjectMap::new();
+            value_obj.insert("type".into(), Value::from("counter"));
+            value_obj.insert("value".into(), Value::from(value));
+            output.insert("value".into(), Value::Object(value_obj));
+
+            if interval_ms > 0 {
+                let mut time_obj = ObjectMap::new();
+                time_obj.insert("interval_ms".into(), Value::Integer(interval_ms as i64));
+                output.insert("time".into(), Value::Object(time_obj));
+            }
+
+            output
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use vrl::btreemap;
+
+    use super::super::TransformDatadogEvent;
+    use super::*;
+    use crate::event::LogEvent;
+    use crate::transforms::mezmo_datadog_agent_parser::MezmoDatadogAgentParserConfig;
+
+    const FIXTURE: &str = include_str!("../../../tests/data/mezmo/datadog_agent/apm_stats.json");
+
+    fn create_stats_event() -> Event {
+        let mut log = LogEvent::default();
+        let payload: serde_json::Value = serde_json::from_str(FIXTURE).unwrap();
+        log.insert(log_schema().message_key_target_path().unwrap(), payload);
+        Event::Log(log)
+    }
+
+    fn message(event: &Event) -> &ObjectMap {
+        event
+            .as_log()
+            .get(log_schema().message_key_target_path().unwrap())
+            .and_then(Value::as_object)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_transform_stats_payload() {
+        let config = MezmoDatadogAgentParserConfig::default();
+        let parser = MezmoDatadogAgentParser::new(&config, None);
+
+        let results = DatadogStatsEvent::transform(create_stats_event(), &parser).unwrap();
+        // Two grouped stats, three metrics each
+        assert_eq!(results.len(), 6);
+
+        let hits = message(&results[0]);
+        assert_eq!(hits.get("namespace"), Some(&Value::from("trace")));
+        assert_eq!(hits.get("name"), Some(&Value::from("http.request.hits")));
+        assert_eq!(hits.get("kind"), Some(&Value::from("incremental")));
+        assert_eq!(
+            hits.get("value"),
+            Some(&Value::from(btreemap! {
+                "type" => "counter",
+                "value" => 10.0,
+            }))
+        );
+        assert_eq!(
+            hits.get("time"),
+            Some(&Value::from(btreemap! { "interval_ms" => 10_000 }))
+        );
+
+        let tags = hits.get("tags").and_then(Value::as_object).unwrap();
+        assert_eq!(tags.get("service"), Some(&Value::from("web-store")));
+        assert_eq!(tags.get("resource"), Some(&Value::from("GET /cart")));
+        assert_eq!(tags.get("http.status_code"), Some(&Value::from("200")));
+        assert_eq!(tags.get("env"), Some(&Value::from("prod")));
+        assert_eq!(tags.get("version"), Some(&Value::from("1.2.3")));
+        assert_eq!(tags.get("host"), Some(&Value::from("web-1")));
+
+        let errors = message(&results[1]);
+        assert_eq!(
+            errors.get("name"),
+            Some(&Value::from("http.request.errors"))
+        );
+
+        let duration = message(&results[2]);
+        assert_eq!(
+            duration.get("value"),
+            Some(&Value::from(btreemap! {
+                "type" => "counter",
+                "value" => 1.5,
+            }))
+        );
+
+        let timestamp = results[0]
+            .as_log()
+            .get(log_schema().timestamp_key_target_path().unwrap())
+            .and_then(Value::as_timestamp)
+            .unwrap();
+        assert_eq!(
+            *timestamp,
+            Utc.timestamp_opt(1_700_000_000, 0).single().unwrap()
+        );
+
+        // Falls back to the agent env/hostname and skips a zero status code
+        let db_tags = message(&results[3])
+            .get("tags")
+            .and_then(Value::as_object)
+            .unwrap();
+        assert_eq!(db_tags.get("host"), Some(&Value::from("agent-host")));
+        assert_eq!(db_tags.get("db.type"), Some(&Value::from("postgres")));
+        assert!(db_tags.get("http.status_code").is_none());
+    }
+
+    #[test]
+    fn test_transform_stats_missing_stats() {
+        let config = MezmoDatadogAgentParserConfig::default();
+        let parser = MezmoDatadogAgentParser::new(&config, None);
+
+        let mut log = LogEvent::default();
+        log.insert(
+            log_schema().message_key_target_path().unwrap(),
+            serde_json::json!({ "agentHostname": "agent-host" }),
+        );
+        let err = DatadogStatsEvent::transform(Event::Log(log), &parser).unwrap_err();
+        assert_eq!(err.message, "Missing stats");
+    }
+}
@@ -1,13 +1,19 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
+use chrono::Utc;
 use vector_lib::config::TransformOutput;
 use vector_lib::configurable::configurable_component;
-use vector_lib::event::metric::mezmo::to_metric;
+use vector_lib::event::metric::mezmo::{
+    TransformError, from_f64_or_zero, parse_metric_value, to_metric,
+};
+use vector_lib::event::metric::{Metric, MetricKind, MetricTags};
+use vector_lib::lookup::lookup_v2::ConfigTargetPath;
 
 use crate::{
     config::{DataType, GenerateConfig, Input, OutputId, TransformConfig, TransformContext},
-    event::Event,
+    event::{Event, KeyString, LogEvent, Value},
     schema,
+    template::Template,
     transforms::{FunctionTransform, OutputBuffer, Transform},
 };
 use mezmo::{MezmoContext, user_trace::handle_transform_error};
@@ -16,11 +22,99 @@ use mezmo::{MezmoContext, user_trace::handle_transform_error};
 #[configurable_component(transform("mezmo_log_to_metric"))]
 #[derive(Clone, Debug, Default)]
 #[serde(deny_unknown_fields)]
-pub struct LogToMetricConfig {}
+pub struct LogToMetricConfig {
+    /// A list of metrics to generate from each log event.
+    ///
+    /// When not set, the `.message` field of the log is expected to already contain
+    /// a Mezmo metric (`name`, `namespace`, `kind`, `tags` and `value`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<Vec<MetricMappingConfig>>,
+}
+
+/// Specification of a metric derived from a log event.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct MetricMappingConfig {
+    /// The name of the metric.
+    #[configurable(metadata(docs::examples = "http_requests_total"))]
+    #[configurable(metadata(docs::examples = "{{ .app }}_requests"))]
+    pub name: Template,
+
+    /// The namespace of the metric.
+    pub namespace: Option<Template>,
+
+    /// The type of the metric.
+    #[serde(rename = "type")]
+    pub metric_type: MetricMappingType,
+
+    /// The kind of the metric.
+    #[serde(default = "default_kind")]
+    pub kind: MetricKind,
+
+    /// The path of the field containing the metric value.
+    ///
+    /// Counters and gauges expect a number, sets expect a string or an array of strings, and
+    /// the remaining types expect an object in the Mezmo metric value shape. When not set, a
+    /// counter is incremented by `1`.
+    #[configurable(metadata(docs::examples = ".duration_ms"))]
+    pub value_field: Option<ConfigTargetPath>,
+
+    /// Tags to apply to the metric.
+    ///
+    /// Tag values are templates, allowing dynamic tags to be taken from the log.
+    #[configurable(metadata(docs::additional_props_description = "A metric tag."))]
+    #[serde(default)]
+    pub tags: HashMap<String, Template>,
+}
+
+/// The type of a metric derived from a log event.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricMappingType {
+    /// A counter.
+    Counter,
+
+    /// A gauge.
+    Gauge,
+
+    /// A set of unique values.
+    Set,
+
+    /// An aggregated histogram.
+    Histogram,
+
+    /// An aggregated summary.
+    Summary,
+
+    /// A distribution of samples.
+    Distribution,
+
+    /// A DDSketch.
+    Sketch,
+}
+
+impl MetricMappingType {
+    const fn as_str(self) -> &'static str {
+        match self {
+            MetricMappingType::Counter => "counter",
+            MetricMappingType::Gauge => "gauge",
+            MetricMappingType::Set => "set",
+            MetricMappingType::Histogram => "histogram",
+            MetricMappingType::Summary => "summary",
+            MetricMappingType::Distribution => "distribution",
+            MetricMappingType::Sketch => "sketch",
+        }
+    }
+}
+
+const fn default_kind() -> MetricKind {
+    MetricKind::Incremental
+}
 
 #[derive(Debug, Clone)]
 pub struct LogToMetric {
-    #[allow(dead_code)]
     config: LogToMetricConfig,
 
     /// The mezmo context used to surface errors
@@ -29,7 +123,7 @@ pub struct LogToMetric {
 
 impl GenerateConfig for LogToMetricConfig {
     fn generate_config() -> toml::Value {
-        toml::Value::try_from(Self {}).unwrap()
+        toml::Value::try_from(Self::default()).unwrap()
     }
 }
 
@@ -37,6 +131,16 @@ impl GenerateConfig for LogToMetricConfig {
 #[typetag::serde(name = "mezmo_log_to_metric")]
 impl TransformConfig for LogToMetricConfig {
     async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        for metric in self.metrics.iter().flatten() {
+            if metric.value_field.is_none() && metric.metric_type != MetricMappingType::Counter {
+                return Err(format!(
+                    "`value_field` is required for `{}` metrics",
+                    metric.metric_type.as_str()
+                )
+                .into());
+            }
+        }
+
         Ok(Transform::function(LogToMetric::new(
             self.clone(),
             context.mezmo_ctx.clone(),
@@ -67,24 +171,115 @@ impl LogToMetric {
     }
 }
 
+fn render_template(
+    template: &Template,
+    field: &str,
+    log: &LogEvent,
+) -> Result<String, TransformError> {
+    template
+        .render_string(log)
+        .map_err(|err| TransformError::TemplateRenderingFailed {
+            field: field.into(),
+            error: err.to_string(),
+        })
+}
+
+fn mapped_value(config: &MetricMappingConfig, log: &LogEvent) -> Result<Value, TransformError> {
+    let Some(path) = &config.value_field else {
+        return match config.metric_type {
+            MetricMappingType::Counter => Ok(Value::from(1)),
+            _ => Err(TransformError::FieldNotFound {
+                field: "value_field".into(),
+            }),
+        };
+    };
+
+    let field = path.to_string();
+    let value = match log.get(path) {
+        None => return Err(TransformError::FieldNotFound { field }),
+        Some(Value::Null) => return Err(TransformError::FieldNull { field }),
+        Some(value) => value,
+    };
+
+    match (config.metric_type, value) {
+        // Arbitrary logs frequently carry numbers as strings
+        (MetricMappingType::Counter | MetricMappingType::Gauge, Value::Bytes(_)) => value
+            .to_string_lossy()
+            .trim()
+            .parse::<f64>()
+            .map(from_f64_or_zero)
+            .map_err(|_| TransformError::FieldInvalidType { field }),
+        (MetricMappingType::Set, Value::Bytes(_)) => Ok(Value::from(BTreeMap::from([(
+            KeyString::from("values"),
+            Value::Array(vec![value.clone()]),
+        )]))),
+        (MetricMappingType::Set, Value::Array(_)) => Ok(Value::from(BTreeMap::from([(
+            KeyString::from("values"),
+            value.clone(),
+        )]))),
+        (_, value) => Ok(value.clone()),
+    }
+}
+
+fn to_metric_with_config(
+    config: &MetricMappingConfig,
+    log: &LogEvent,
+) -> Result<Metric, TransformError> {
+    let timestamp = log
+        .get_timestamp()
+        .and_then(Value::as_timestamp)
+        .copied()
+        .or_else(|| Some(Utc::now()));
+
+    let name = render_template(&config.name, "name", log)?;
+    let namespace = config
+        .namespace
+        .as_ref()
+        .map(|namespace| render_template(namespace, "namespace", log))
+        .transpose()?;
+
+    let tags = config
+        .tags
+        .iter()
+        .map(|(key, template)| {
+            render_template(template, &format!("tags.{key}"), log).map(|value| (key.clone(), value))
+        })
+        .collect::<Result<MetricTags, _>>()?;
+
+    let value = parse_metric_value(config.metric_type.as_str(), mapped_value(config, log)?)?;
+
+    Ok(
+        Metric::new_with_metadata(name, config.kind, value, log.metadata().clone())
+            .with_namespace(namespace)
+            .with_tags(tags.as_option())
+            .with_timestamp(timestamp),
+    )
+}
+
 impl FunctionTransform for LogToMetric {
     fn transform(&mut self, output: &mut OutputBuffer, event: Event) {
-        // Metrics are "all or none" for a specific log. If a single fails, none are produced.
-        let mut buffer: Option<Event> = None;
+        let log = event.into_log();
 
-        match to_metric(&event.into_log()) {
-            Ok(metric) => {
-                buffer = Some(Event::Metric(metric));
+        // Metrics are "all or none" for a specific log. If a single fails, none are produced.
+        let result = match &self.config.metrics {
+            Some(metrics) => metrics
+                .iter()
+                .map(|config| to_metric_with_config(config, &log))
+                .collect::<Result<Vec<_>, _>>(),
+            None => to_metric(&log).map(|metric| vec![metric]),
+        };
+
+        match result {
+            // Metric generation was successful, publish it
+            Ok(metrics) => {
+                for metric in metrics {
+                    output.push(Event::Metric(metric));
+                }
             }
             Err(err) => {
                 handle_transform_error(&self.mezmo_ctx, err);
             }
         }
-
-        // Metric generation was successful, publish it
-        if let Some(event) = buffer {
-            output.push(event);
-        }
     }
 }
 
@@ -102,6 +297,7 @@ mod tests {
     use tokio_stream::wrappers::ReceiverStream;
     use vector_lib::event::metric::{Bucket, Quantile, Sample};
     use vector_lib::event::{KeyString, Value};
+    use vector_lib::metric_tags;
 
     use super::*;
     use crate::mezmo::user_trace::UserLogSubscription;
@@ -186,15 +382,29 @@ mod tests {
     }
 
     async fn do_transform(event: Event) -> Option<Event> {
+        do_transform_with_config(LogToMetricConfig::default(), event, 1)
+            .await
+            .pop()
+    }
+
+    async fn do_transform_with_config(
+        config: LogToMetricConfig,
+        event: Event,
+        expected: usize,
+    ) -> Vec<Event> {
         assert_transform_compliance(async move {
             let (tx, rx) = mpsc::channel(1);
             let name = "v1:mezmo_log_to_metric:transform:ef757476-43a5-4e0d-b998-3db35dbde001:1515707f-f668-4ca1-8493-969e5b13e781:800e5a08-3e67-431c-bbf0-14aa94beafcc";
             let (topology, mut out) =
-            create_topology_with_name(ReceiverStream::new(rx), LogToMetricConfig::default(), name).await;
+            create_topology_with_name(ReceiverStream::new(rx), config, name).await;
             tx.send(event).await.unwrap();
-            let result = tokio::time::timeout(Duration::from_secs(5), out.recv())
-                .await
-                .unwrap_or(None);
+            let mut result = Vec::new();
+            for _ in 0..expected {
+                match tokio::time::timeout(Duration::from_secs(5), out.recv()).await {
+                    Ok(Some(event)) => result.push(event),
+                    _ => break,
+                }
+            }
             drop(tx);
             topology.stop().await;
             assert_eq!(out.recv().await, None);
@@ -203,6 +413,10 @@ mod tests {
         .await
     }
 
+    fn mapping_config(config: &str) -> LogToMetricConfig {
+        toml::from_str(config).expect("valid config")
+    }
+
     // FIXME: Tests using this appear to be flakey when run locally all together. When run individually,
     // or within `make test environment=true`, they pass. This might have to do with the channels of
     // the UserSubscription being shared. Attempts to serialize it or share it did not work.
@@ -612,4 +826,116 @@ mod tests {
         assert_eq!(do_transform(event.into()).await, None);
         assert_error_message(log_stream, "Field 'message' type is not valid").await;
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn mapping_multiple_metrics_test() {
+        let config = mapping_config(
+            r#"
+            [[metrics]]
+            name = "{{ .service }}_requests_total"
+            namespace = "web"
+            type = "counter"
+            tags.status = "{{ .status }}"
+
+            [[metrics]]
+            name = "request_duration_ms"
+            type = "gauge"
+            kind = "absolute"
+            value_field = ".duration"
+            "#,
+        );
+        let mut event = create_event("service", "checkout");
+        event.as_mut_log().insert("status", 200);
+        event.as_mut_log().insert("duration", "12.5");
+        let metadata = event.metadata().clone();
+
+        let metrics = do_transform_with_config(config, event, 2).await;
+        assert_eq!(metrics.len(), 2);
+
+        assert_eq!(
+            metrics[0].clone().into_metric(),
+            Metric::new_with_metadata(
+                "checkout_requests_total",
+                MetricKind::Incremental,
+                MetricValue::Counter { value: 1.0 },
+                metadata.clone(),
+            )
+            .with_namespace(Some("web"))
+            .with_tags(Some(metric_tags!("status" => "200")))
+            .with_timestamp(Some(ts()))
+        );
+        assert_eq!(
+            metrics[1].clone().into_metric(),
+            Metric::new_with_metadata(
+                "request_duration_ms",
+                MetricKind::Absolute,
+                MetricValue::Gauge { value: 12.5 },
+                metadata,
+            )
+            .with_timestamp(Some(ts()))
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn mapping_set_test() {
+        let config = mapping_config(
+            r#"
+            [[metrics]]
+            name = "unique_users"
+            type = "set"
+            value_field = ".user"
+            "#,
+        );
+        let event = create_event("user", "bob");
+        let metadata = event.metadata().clone();
+
+        let metric = do_transform_with_config(config, event, 1)
+            .await
+            .pop()
+            .unwrap();
+        assert_eq!(
+            metric.into_metric(),
+            Metric::new_with_metadata(
+                "unique_users",
+                MetricKind::Incremental,
+                MetricValue::Set {
+                    values: BTreeSet::from(["bob".to_string()]),
+                },
+                metadata,
+            )
+            .with_timestamp(Some(ts()))
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn mapping_all_or_none_test() {
+        let log_stream = UserLogSubscription::subscribe().into_stream();
+        let config = mapping_config(
+            r#"
+            [[metrics]]
+            name = "hits"
+            type = "counter"
+
+            [[metrics]]
+            name = "bytes"
+            type = "gauge"
+            value_field = ".bytes"
+            "#,
+        );
+        let event = create_event("k", "v");
+
+        assert!(do_transform_with_config(config, event, 1).await.is_empty());
+
+        let user_log = tokio::select! {
+            e = log_stream.next() => e,
+            _ = sleep(Duration::from_secs(1)) => None,
+        }
+        .expect("The failure should be output to the user logs");
+        let msg = user_log.get(".message").unwrap().to_string_lossy();
+        assert!(msg.starts_with("Required field"));
+        assert!(msg.contains("bytes"));
+    }
 }
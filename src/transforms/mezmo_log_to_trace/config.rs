@@ -6,7 +6,10 @@ use vector_lib::configurable::configurable_component;
 use crate::config::{DataType, GenerateConfig, Input, OutputId, TransformConfig, TransformContext};
 use crate::transforms::Transform;
 
-use super::LogToTrace;
+use super::{
+    LogToTrace,
+    otlp::{ERRORS_OUTPUT, OtlpLogToTrace},
+};
 
 /// Configuration for the `mezmo_log_to_trace` transform.
 #[configurable_component(transform("mezmo_log_to_trace"))]
@@ -16,6 +19,26 @@ pub struct LogToTraceConfig {
     /// When true, drop all metadata from converted trace events.
     #[serde(default)]
     pub exclude_metadata: bool,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub mode: LogToTraceMode,
+}
+
+/// How the `.message` of a log is converted into a trace event.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogToTraceMode {
+    /// Copy the `.message` object into the trace event as-is.
+    #[default]
+    Passthrough,
+
+    /// Validate the `.message` as an OpenTelemetry span and normalize it into an OTLP
+    /// `resourceSpans` trace event that can be sent to the `opentelemetry` sink.
+    ///
+    /// Logs that fail validation are sent to the `errors` output.
+    Otlp,
 }
 
 impl GenerateConfig for LogToTraceConfig {
@@ -28,10 +51,14 @@ impl GenerateConfig for LogToTraceConfig {
 #[typetag::serde(name = "mezmo_log_to_trace")]
 impl TransformConfig for LogToTraceConfig {
     async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
-        Ok(Transform::function(LogToTrace::new(
-            self.clone(),
-            context.mezmo_ctx.clone(),
-        )))
+        Ok(match self.mode {
+            LogToTraceMode::Passthrough => {
+                Transform::function(LogToTrace::new(self.clone(), context.mezmo_ctx.clone()))
+            }
+            LogToTraceMode::Otlp => {
+                Transform::synchronous(OtlpLogToTrace::new(self.clone(), context.mezmo_ctx.clone()))
+            }
+        })
     }
 
     fn input(&self) -> Input {
@@ -43,7 +70,12 @@ impl TransformConfig for LogToTraceConfig {
         _: &TransformContext,
         _: &[(OutputId, crate::schema::Definition)],
     ) -> Vec<TransformOutput> {
-        vec![TransformOutput::new(DataType::Trace, HashMap::new())]
+        let mut outputs = vec![TransformOutput::new(DataType::Trace, HashMap::new())];
+        if self.mode == LogToTraceMode::Otlp {
+            outputs
+                .push(TransformOutput::new(DataType::Log, HashMap::new()).with_port(ERRORS_OUTPUT));
+        }
+        outputs
     }
 
     fn enable_concurrency(&self) -> bool {
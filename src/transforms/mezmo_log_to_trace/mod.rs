@@ -1,4 +1,5 @@
 pub mod config;
+pub mod otlp;
 
 use crate::{
     config::log_schema,
@@ -173,6 +174,7 @@ mod tests {
         let mut transform = LogToTrace::new(
             LogToTraceConfig {
                 exclude_metadata: true,
+                ..Default::default()
             },
             None,
         );
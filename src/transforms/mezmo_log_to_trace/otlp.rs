@@ -0,0 +1,588 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use mezmo::{MezmoContext, user_log_error, user_trace::MezmoUserLog};
+
+use vector_lib::lookup::PathPrefix;
+
+use crate::{
+    config::log_schema,
+    event::{Event, EventMetadata, KeyString, LogEvent, ObjectMap, TraceEvent, Value},
+    internal_events::MezmoLogToTraceEventDropped,
+    transforms::{SyncTransform, TransformOutputsBuf},
+};
+
+use super::config::LogToTraceConfig;
+
+/// The name of the output receiving logs that could not be mapped to an OTLP span.
+pub const ERRORS_OUTPUT: &str = "errors";
+
+const TRACE_ID_LEN: usize = 16;
+const SPAN_ID_LEN: usize = 8;
+
+/// Errors raised while validating a log as an OpenTelemetry span. The `Display`
+/// implementation is surfaced to the user.
+#[derive(Debug, PartialEq)]
+pub enum SpanError {
+    MissingMessage,
+    FieldNotFound { field: &'static str },
+    FieldInvalidType { field: &'static str },
+    InvalidId { field: &'static str, len: usize },
+    InvalidTimeRange,
+}
+
+impl Display for SpanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpanError::MissingMessage => {
+                write!(
+                    f,
+                    "The log event must have a 'message' field of type object"
+                )
+            }
+            SpanError::FieldNotFound { field } => {
+                write!(f, "Required span field '{field}' not found")
+            }
+            SpanError::FieldInvalidType { field } => {
+                write!(f, "Span field '{field}' type is not valid")
+            }
+            SpanError::InvalidId { field, len } => {
+                write!(
+                    f,
+                    "Span field '{field}' must be a non-zero hex string of {len} bytes"
+                )
+            }
+            SpanError::InvalidTimeRange => {
+                write!(f, "Span end time must not be before its start time")
+            }
+        }
+    }
+}
+
+/// Validates and normalizes log events into OTLP `resourceSpans` trace events, using the
+/// protobuf JSON field names expected by the OTLP encoder of the `opentelemetry` sink.
+#[derive(Debug, Clone)]
+pub struct OtlpLogToTrace {
+    config: LogToTraceConfig,
+    mezmo_ctx: Option<MezmoContext>,
+}
+
+impl OtlpLogToTrace {
+    pub const fn new(config: LogToTraceConfig, mezmo_ctx: Option<MezmoContext>) -> Self {
+        Self { config, mezmo_ctx }
+    }
+}
+
+impl SyncTransform for OtlpLogToTrace {
+    fn transform(&mut self, event: Event, output: &mut TransformOutputsBuf) {
+        match event {
+            Event::Trace(trace) => output.push(None, Event::Trace(trace)),
+            Event::Log(log) => match log_to_resource_spans(&log) {
+                Ok(resource_spans) => {
+                    let (_, metadata) = log.into_parts();
+                    let metadata = if self.config.exclude_metadata {
+                        EventMetadata::default()
+                    } else {
+                        metadata
+                    };
+                    let mut fields = ObjectMap::new();
+                    fields.insert("resourceSpans".into(), Value::Array(vec![resource_spans]));
+                    output.push(None, Event::Trace(TraceEvent::from_parts(fields, metadata)));
+                }
+                Err(err) => {
+                    user_log_error!(self.mezmo_ctx, Value::from(err.to_string()));
+                    output.push(Some(ERRORS_OUTPUT), Event::Log(log));
+                }
+            },
+            _ => {
+                emit!(MezmoLogToTraceEventDropped {
+                    reason: "Event is not a log or trace",
+                });
+            }
+        }
+    }
+}
+
+/// Builds a single OTLP `ResourceSpans` object from the span described in the `.message`
+/// of the log. Resource, scope and span attributes are read from the message first and
+/// fall back to the Mezmo user metadata.
+pub fn log_to_resource_spans(log: &LogEvent) -> Result<Value, SpanError> {
+    let message = log
+        .get_message()
+        .and_then(Value::as_object)
+        .ok_or(SpanError::MissingMessage)?;
+    let user_metadata = log
+        .get((PathPrefix::Event, log_schema().user_metadata_key()))
+        .and_then(Value::as_object);
+    let find = |key: &str| {
+        message
+            .get(key)
+            .or_else(|| user_metadata.and_then(|metadata| metadata.get(key)))
+    };
+
+    let mut span = ObjectMap::new();
+    span.insert(
+        "traceId".into(),
+        required_id(message, "trace_id", TRACE_ID_LEN)?,
+    );
+    span.insert(
+        "spanId".into(),
+        required_id(message, "span_id", SPAN_ID_LEN)?,
+    );
+    if let Some(parent_span_id) = optional_id(message, "parent_span_id", SPAN_ID_LEN)? {
+        span.insert("parentSpanId".into(), parent_span_id);
+    }
+    if let Some(trace_state) = message.get("trace_state") {
+        let trace_state = trace_state.as_str().ok_or(SpanError::FieldInvalidType {
+            field: "trace_state",
+        })?;
+        span.insert("traceState".into(), Value::from(trace_state.into_owned()));
+    }
+
+    let name = message
+        .get("name")
+        .ok_or(SpanError::FieldNotFound { field: "name" })?
+        .as_str()
+        .ok_or(SpanError::FieldInvalidType { field: "name" })?;
+    span.insert("name".into(), Value::from(name.into_owned()));
+    span.insert("kind".into(), Value::from(span_kind(message.get("kind"))?));
+
+    let start = timestamp_nanos(
+        message
+            .get("start_timestamp")
+            .or_else(|| message.get("start_time_unix_nano")),
+        "start_timestamp",
+    )?;
+    let end = timestamp_nanos(
+        message
+            .get("end_timestamp")
+            .or_else(|| message.get("end_time_unix_nano")),
+        "end_timestamp",
+    )?;
+    if end < start {
+        return Err(SpanError::InvalidTimeRange);
+    }
+    span.insert("startTimeUnixNano".into(), Value::from(start));
+    span.insert("endTimeUnixNano".into(), Value::from(end));
+
+    span.insert(
+        "attributes".into(),
+        key_values(find("attributes"), "attributes")?,
+    );
+    if let Some(status) = message.get("status") {
+        span.insert("status".into(), span_status(status)?);
+    }
+    for (from, to) in [
+        ("dropped_attributes_count", "droppedAttributesCount"),
+        ("dropped_events_count", "droppedEventsCount"),
+        ("dropped_links_count", "droppedLinksCount"),
+    ] {
+        if let Some(Value::Integer(count)) = message.get(from) {
+            span.insert(to.into(), Value::Integer((*count).max(0)));
+        }
+    }
+
+    if let Some(events) = message.get("events") {
+        span.insert("events".into(), span_events(events)?);
+    }
+    if let Some(links) = message.get("links") {
+        span.insert("links".into(), span_links(links)?);
+    }
+
+    // The Mezmo OTLP decoders nest resource attributes under `resource.attributes`
+    let resource_attributes = match find("resource") {
+        Some(Value::Object(resource)) if resource.get("attributes").is_some() => {
+            resource.get("attributes")
+        }
+        resource => resource,
+    };
+    let mut resource = ObjectMap::new();
+    resource.insert(
+        "attributes".into(),
+        key_values(resource_attributes, "resource")?,
+    );
+
+    let mut scope = ObjectMap::new();
+    if let Some(Value::Object(scope_obj)) = find("scope") {
+        for field in ["name", "version"] {
+            if let Some(Value::Bytes(value)) = scope_obj.get(field) {
+                scope.insert(field.into(), Value::Bytes(value.clone()));
+            }
+        }
+    }
+
+    let mut scope_spans = ObjectMap::new();
+    scope_spans.insert("scope".into(), Value::Object(scope));
+    scope_spans.insert("spans".into(), Value::Array(vec![Value::Object(span)]));
+
+    let mut resource_spans = ObjectMap::new();
+    resource_spans.insert("resource".into(), Value::Object(resource));
+    resource_spans.insert(
+        "scopeSpans".into(),
+        Value::Array(vec![Value::Object(scope_spans)]),
+    );
+    Ok(Value::Object(resource_spans))
+}
+
+fn decode_id(value: &Value, field: &'static str, len: usize) -> Result<Vec<u8>, SpanError> {
+    let invalid = || SpanError::InvalidId { field, len };
+    let hex_str = value.as_str().ok_or_else(invalid)?;
+    let bytes = hex::decode(hex_str.trim()).map_err(|_| invalid())?;
+    if bytes.len() != len || bytes.iter().all(|b| *b == 0) {
+        return Err(invalid());
+    }
+    Ok(bytes)
+}
+
+fn required_id(message: &ObjectMap, field: &'static str, len: usize) -> Result<Value, SpanError> {
+    let value = message
+        .get(field)
+        .ok_or(SpanError::FieldNotFound { field })?;
+    decode_id(value, field, len).map(|id| Value::Bytes(Bytes::from(id)))
+}
+
+fn optional_id(
+    message: &ObjectMap,
+    field: &'static str,
+    len: usize,
+) -> Result<Option<Value>, SpanError> {
+    match message.get(field) {
+        None | Some(Value::Null) => Ok(None),
+        // Root spans are represented with an empty parent id
+        Some(Value::Bytes(bytes)) if bytes.is_empty() => Ok(None),
+        Some(value) => decode_id(value, field, len).map(|id| Some(Value::Bytes(Bytes::from(id)))),
+    }
+}
+
+fn span_events(value: &Value) -> Result<Value, SpanError> {
+    let invalid = || SpanError::FieldInvalidType { field: "events" };
+    value
+        .as_array()
+        .ok_or_else(invalid)?
+        .iter()
+        .map(|event| {
+            let event = event.as_object().ok_or_else(invalid)?;
+            let mut normalized = ObjectMap::new();
+            normalized.insert(
+                "timeUnixNano".into(),
+                Value::from(timestamp_nanos(
+                    event
+                        .get("timestamp")
+                        .or_else(|| event.get("time_unix_nano")),
+                    "events.timestamp",
+                )?),
+            );
+            if let Some(name) = event.get("name").and_then(Value::as_str) {
+                normalized.insert("name".into(), Value::from(name.into_owned()));
+            }
+            normalized.insert(
+                "attributes".into(),
+                key_values(event.get("attributes"), "events.attributes")?,
+            );
+            Ok(Value::Object(normalized))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Value::Array)
+}
+
+fn span_links(value: &Value) -> Result<Value, SpanError> {
+    let invalid = || SpanError::FieldInvalidType { field: "links" };
+    value
+        .as_array()
+        .ok_or_else(invalid)?
+        .iter()
+        .map(|link| {
+            let link = link.as_object().ok_or_else(invalid)?;
+            let mut normalized = ObjectMap::new();
+            normalized.insert(
+                "traceId".into(),
+                required_id(link, "trace_id", TRACE_ID_LEN)?,
+            );
+            normalized.insert("spanId".into(), required_id(link, "span_id", SPAN_ID_LEN)?);
+            if let Some(trace_state) = link.get("trace_state").and_then(Value::as_str) {
+                normalized.insert("traceState".into(), Value::from(trace_state.into_owned()));
+            }
+            normalized.insert(
+                "attributes".into(),
+                key_values(link.get("attributes"), "links.attributes")?,
+            );
+            Ok(Value::Object(normalized))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Value::Array)
+}
+
+fn span_kind(value: Option<&Value>) -> Result<i64, SpanError> {
+    let invalid = SpanError::FieldInvalidType { field: "kind" };
+    match value {
+        None | Some(Value::Null) => Ok(0),
+        Some(Value::Integer(kind)) if (0..=5).contains(kind) => Ok(*kind),
+        Some(Value::Bytes(kind)) => {
+            let kind = String::from_utf8_lossy(kind).to_lowercase();
+            match kind.trim_start_matches("span_kind_") {
+                "unspecified" => Ok(0),
+                "internal" => Ok(1),
+                "server" => Ok(2),
+                "client" => Ok(3),
+                "producer" => Ok(4),
+                "consumer" => Ok(5),
+                _ => Err(invalid),
+            }
+        }
+        Some(_) => Err(invalid),
+    }
+}
+
+fn span_status(value: &Value) -> Result<Value, SpanError> {
+    let invalid = || SpanError::FieldInvalidType { field: "status" };
+    let status = value.as_object().ok_or_else(invalid)?;
+    let code = match status.get("code") {
+        None | Some(Value::Null) => 0,
+        Some(Value::Integer(code)) if (0..=2).contains(code) => *code,
+        Some(Value::Bytes(code)) => {
+            let code = String::from_utf8_lossy(code).to_lowercase();
+            match code.trim_start_matches("status_code_") {
+                "unset" => 0,
+                "ok" => 1,
+                "error" => 2,
+                _ => return Err(invalid()),
+            }
+        }
+        Some(_) => return Err(invalid()),
+    };
+
+    let mut normalized = ObjectMap::new();
+    normalized.insert("code".into(), Value::Integer(code));
+    if let Some(message) = status.get("message").and_then(Value::as_str) {
+        normalized.insert("message".into(), Value::from(message.into_owned()));
+    }
+    Ok(Value::Object(normalized))
+}
+
+fn timestamp_nanos(value: Option<&Value>, field: &'static str) -> Result<i64, SpanError> {
+    let invalid = SpanError::FieldInvalidType { field };
+    match value {
+        None | Some(Value::Null) => Err(SpanError::FieldNotFound { field }),
+        Some(Value::Timestamp(ts)) => ts.timestamp_nanos_opt().ok_or(invalid),
+        Some(Value::Integer(nanos)) if *nanos >= 0 => Ok(*nanos),
+        Some(Value::Bytes(ts)) => DateTime::parse_from_rfc3339(&String::from_utf8_lossy(ts))
+            .ok()
+            .and_then(|ts| ts.with_timezone(&Utc).timestamp_nanos_opt())
+            .ok_or(invalid),
+        Some(_) => Err(invalid),
+    }
+}
+
+/// Converts an object into the OTLP `KeyValue` list representation.
+fn key_values(value: Option<&Value>, field: &'static str) -> Result<Value, SpanError> {
+    match value {
+        None | Some(Value::Null) => Ok(Value::Array(vec![])),
+        Some(Value::Object(obj)) => Ok(Value::Array(
+            obj.iter()
+                .map(|(key, value)| key_value(key, value))
+                .collect(),
+        )),
+        Some(_) => Err(SpanError::FieldInvalidType { field }),
+    }
+}
+
+fn key_value(key: &KeyString, value: &Value) -> Value {
+    Value::from(BTreeMap::from([
+        (KeyString::from("key"), Value::from(key.as_str())),
+        (KeyString::from("value"), any_value(value)),
+    ]))
+}
+
+fn any_value(value: &Value) -> Value {
+    let (field, value) = match value {
+        Value::Bytes(bytes) => ("stringValue", Value::Bytes(bytes.clone())),
+        Value::Regex(regex) => ("stringValue", Value::from(regex.as_str())),
+        Value::Integer(int) => ("intValue", Value::Integer(*int)),
+        Value::Float(float) => ("doubleValue", Value::Float(*float)),
+        Value::Boolean(boolean) => ("boolValue", Value::Boolean(*boolean)),
+        Value::Timestamp(ts) => ("stringValue", Value::from(ts.to_rfc3339())),
+        Value::Array(values) => (
+            "arrayValue",
+            Value::from(BTreeMap::from([(
+                KeyString::from("values"),
+                Value::Array(values.iter().map(any_value).collect()),
+            )])),
+        ),
+        Value::Object(obj) => (
+            "kvlistValue",
+            Value::from(BTreeMap::from([(
+                KeyString::from("values"),
+                Value::Array(obj.iter().map(|(k, v)| key_value(k, v)).collect()),
+            )])),
+        ),
+        Value::Null => return Value::Object(ObjectMap::new()),
+    };
+    Value::from(BTreeMap::from([(KeyString::from(field), value)]))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+    use vrl::{btreemap, event_path};
+
+    use super::*;
+    use crate::config::{DataType, TransformOutput};
+
+    fn span_log(message: serde_json::Value) -> LogEvent {
+        let mut log = LogEvent::default();
+        log.insert(log_schema().message_key_target_path().unwrap(), message);
+        log
+    }
+
+    fn valid_span() -> serde_json::Value {
+        json!({
+            "trace_id": "5b8efff798038103d269b633813fc60c",
+            "span_id": "eee19b7ec3c1b174",
+            "parent_span_id": "",
+            "name": "GET /users",
+            "kind": "server",
+            "start_timestamp": "2024-01-01T00:00:00Z",
+            "end_timestamp": 1_704_067_201_000_000_000_i64,
+            "attributes": { "http.status_code": 200, "http.route": "/users" },
+            "resource": { "service.name": "api" },
+            "scope": { "name": "tracer", "version": "1.0" },
+            "status": { "code": "STATUS_CODE_ERROR", "message": "boom" }
+        })
+    }
+
+    fn transform_one(log: LogEvent) -> Result<Event, Event> {
+        let mut outputs = TransformOutputsBuf::new_with_capacity(
+            vec![
+                TransformOutput::new(DataType::Trace, HashMap::new()),
+                TransformOutput::new(DataType::Log, HashMap::new()).with_port(ERRORS_OUTPUT),
+            ],
+            1,
+        );
+        let mut transform = OtlpLogToTrace::new(LogToTraceConfig::default(), None);
+        transform.transform(Event::Log(log), &mut outputs);
+
+        let mut primary = outputs.drain().collect::<Vec<_>>();
+        let mut errors = outputs.drain_named(ERRORS_OUTPUT).collect::<Vec<_>>();
+        match (primary.pop(), errors.pop()) {
+            (Some(good), None) => Ok(good),
+            (None, Some(bad)) => Err(bad),
+            (a, b) => panic!("expected output xor error output, got {a:?} and {b:?}"),
+        }
+    }
+
+    #[test]
+    fn normalizes_span() {
+        let event = transform_one(span_log(valid_span())).expect("valid span");
+        let trace = event.as_trace();
+
+        let resource_spans = trace
+            .get(event_path!("resourceSpans"))
+            .and_then(Value::as_array)
+            .expect("resourceSpans");
+        let resource_spans = resource_spans[0].as_object().unwrap();
+        let scope_spans = resource_spans["scopeSpans"].as_array().unwrap()[0]
+            .as_object()
+            .unwrap();
+        let span = scope_spans["spans"].as_array().unwrap()[0]
+            .as_object()
+            .unwrap();
+
+        assert_eq!(
+            span["traceId"],
+            Value::Bytes(
+                hex::decode("5b8efff798038103d269b633813fc60c")
+                    .unwrap()
+                    .into()
+            )
+        );
+        assert_eq!(
+            span["spanId"],
+            Value::Bytes(hex::decode("eee19b7ec3c1b174").unwrap().into())
+        );
+        assert!(!span.contains_key("parentSpanId"));
+        assert_eq!(span["kind"], Value::Integer(2));
+        assert_eq!(
+            span["startTimeUnixNano"],
+            Value::Integer(1_704_067_200_000_000_000)
+        );
+        assert_eq!(
+            span["endTimeUnixNano"],
+            Value::Integer(1_704_067_201_000_000_000)
+        );
+        assert_eq!(
+            span["status"].as_object().unwrap()["code"],
+            Value::Integer(2)
+        );
+        assert_eq!(span["attributes"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            resource_spans["resource"].as_object().unwrap()["attributes"],
+            Value::from(vec![Value::from(btreemap! {
+                "key" => "service.name",
+                "value" => btreemap! { "stringValue" => "api" },
+            })])
+        );
+        assert_eq!(
+            scope_spans["scope"],
+            Value::from(btreemap! { "name" => "tracer", "version" => "1.0" })
+        );
+    }
+
+    #[test]
+    fn falls_back_to_user_metadata() {
+        let mut span = valid_span();
+        let resource = span.as_object_mut().unwrap().remove("resource").unwrap();
+        let mut log = span_log(span);
+        log.insert(
+            (PathPrefix::Event, log_schema().user_metadata_key()),
+            json!({ "resource": { "attributes": resource, "dropped_attributes_count": 0 } }),
+        );
+
+        let event = transform_one(log).expect("valid span");
+        let resource_attributes = event
+            .as_trace()
+            .get(event_path!("resourceSpans"))
+            .and_then(Value::as_array)
+            .and_then(|spans| spans[0].as_object())
+            .and_then(|spans| spans["resource"].as_object())
+            .and_then(|resource| resource.get("attributes").cloned())
+            .expect("resource attributes");
+        assert_eq!(resource_attributes.as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn routes_invalid_spans_to_errors_output() {
+        let cases = [
+            ("trace_id", json!("not-hex")),
+            ("span_id", json!("0000000000000000")),
+            ("parent_span_id", json!("abcd")),
+            ("kind", json!("sideways")),
+            ("end_timestamp", json!("2023-01-01T00:00:00Z")),
+            ("status", json!({ "code": 7 })),
+        ];
+        for (field, value) in cases {
+            let mut span = valid_span();
+            span.as_object_mut().unwrap().insert(field.into(), value);
+            let log = span_log(span);
+            let rejected = transform_one(log.clone()).expect_err(field);
+            assert_eq!(rejected.as_log(), &log);
+        }
+    }
+
+    #[test]
+    fn reports_validation_errors() {
+        let mut span = valid_span();
+        span.as_object_mut().unwrap().remove("name");
+        assert_eq!(
+            log_to_resource_spans(&span_log(span)),
+            Err(SpanError::FieldNotFound { field: "name" })
+        );
+        assert_eq!(
+            log_to_resource_spans(&span_log(json!("text"))),
+            Err(SpanError::MissingMessage)
+        );
+    }
+}
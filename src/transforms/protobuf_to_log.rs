@@ -13,7 +13,9 @@ use crate::{
     config::{DataType, GenerateConfig, Input, OutputId, TransformConfig, TransformContext},
     event::{Event, LogEvent},
     schema,
-    transforms::{FunctionTransform, OutputBuffer, Transform},
+    transforms::{
+        FunctionTransform, OutputBuffer, Transform, mezmo_common::headers::content_type_header,
+    },
 };
 
 /// The Enum to choose a protobuf vendor.
@@ -118,12 +120,13 @@ impl FunctionTransform for ProtobufToLog {
         }
         let root_user_metadata = root_user_metadata;
 
+        let content_type = content_type_header(root_internal_metadata);
         let deserializer = match self.config.vendor {
             ProtobufVendors::OpenTelemetryLogs => {
-                MezmoDeserializer::build(&MezmoDeserializer::OpenTelemetryLogs)
+                MezmoDeserializer::OpenTelemetryLogs.build_for_content_type(content_type)
             }
             ProtobufVendors::OpenTelemetryTraces => {
-                MezmoDeserializer::build(&MezmoDeserializer::OpenTelemetryTraces)
+                MezmoDeserializer::OpenTelemetryTraces.build_for_content_type(content_type)
             }
         };
 
@@ -10,7 +10,9 @@ use crate::{
     config::{DataType, GenerateConfig, Input, OutputId, TransformConfig, TransformContext},
     event::{Event, LogEvent},
     schema,
-    transforms::{FunctionTransform, OutputBuffer, Transform},
+    transforms::{
+        FunctionTransform, OutputBuffer, Transform, mezmo_common::headers::content_type_header,
+    },
 };
 
 use vector_lib::config::{TransformOutput, log_schema};
@@ -113,9 +115,10 @@ impl FunctionTransform for ProtobufToMetric {
         }
         let root_user_metadata = root_user_metadata;
 
+        let content_type = content_type_header(root_internal_metadata);
         let deserializer = match self.config.vendor {
             ProtobufVendors::OpenTelemetryMetrics => {
-                MezmoDeserializer::build(&MezmoDeserializer::OpenTelemetryMetrics)
+                MezmoDeserializer::OpenTelemetryMetrics.build_for_content_type(content_type)
             }
         };
 
@@ -356,4 +359,52 @@ mod tests {
             }
         }
     }
+
+    #[tokio::test]
+    async fn gauge_json_test() {
+        let metrics = br#"{
+            "resourceMetrics": [{
+                "scopeMetrics": [{
+                    "metrics": [{
+                        "name": "queue",
+                        "gauge": {
+                            "dataPoints": [
+                                {"timeUnixNano": "1579134612000000011", "asDouble": 3.5}
+                            ]
+                        }
+                    }]
+                }]
+            }]
+        }"#;
+        let metadata = Value::Object(BTreeMap::from([(
+            "headers".into(),
+            Value::Object(BTreeMap::from([(
+                "Content-Type".into(),
+                "application/json".into(),
+            )])),
+        )]));
+
+        let event = log_event_from_bytes(metrics, &metadata);
+        let result = do_transform(
+            event.into(),
+            ProtobufToMetricConfig {
+                vendor: ProtobufVendors::OpenTelemetryMetrics,
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(1, result.len());
+
+        let log = result[0].clone().into_log();
+        assert_eq!(
+            *log.get("message.value.type").unwrap(),
+            Value::from("gauge")
+        );
+        assert_eq!(*log.get("message.value.value").unwrap(), Value::from(3.5));
+        assert_eq!(
+            *log.get("metadata.headers").unwrap(),
+            *metadata.get("headers").unwrap()
+        );
+    }
 }
@@ -22,9 +22,31 @@ const DEFAULT_RATE: u64 = 10;
 const MINIMUM_TTL_SECS: u64 = 60;
 const DEFAULT_TTL_SECS: u64 = 15 * 60; // 15 min
 
+/// Number of random bits in a trace ID used for consistent probability sampling, see
+/// https://opentelemetry.io/docs/specs/otel/trace/tracestate-probability-sampling/
+const RANDOMNESS_BITS: usize = 56;
+const MAX_THRESHOLD: u64 = 1 << RANDOMNESS_BITS;
+const HEX_DIGITS: usize = RANDOMNESS_BITS / 4;
+
+/// How traces are selected for sampling
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HeadSampleMode {
+    /// Keep every n-th distinct trace ID seen by this instance, tracking decisions on disk.
+    #[default]
+    Counter,
+
+    /// Derive the decision from the randomness of the trace ID, following the OpenTelemetry
+    /// consistent probability sampling specification. Every instance makes the same decision
+    /// for the same trace without any shared state, and the sampling threshold is recorded in
+    /// the W3C tracestate of kept spans.
+    Consistent,
+}
+
 /// Configuration for the `trace_head_sample` transform.
 #[configurable_component(transform("trace_head_sample"))]
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct TraceHeadSampleConfig {
     /// the key of the event to evaluate
@@ -42,6 +64,15 @@ pub struct TraceHeadSampleConfig {
 
     /// the base path on disk to maintain keys and data while tracking traces
     state_persistence_base_path: Option<String>,
+
+    /// the sampling mode
+    #[serde(default)]
+    mode: HeadSampleMode,
+
+    /// the W3C tracestate field of the event, used by the consistent mode to read explicit
+    /// randomness and record the sampling threshold
+    #[serde(default = "default_trace_state_field")]
+    trace_state_field: String,
 }
 
 fn default_trace_id_field() -> String {
@@ -56,6 +87,23 @@ const fn default_ttl_secs() -> u64 {
     DEFAULT_TTL_SECS
 }
 
+fn default_trace_state_field() -> String {
+    ".trace_state".to_owned()
+}
+
+impl Default for TraceHeadSampleConfig {
+    fn default() -> Self {
+        Self {
+            trace_id_field: default_trace_id_field(),
+            rate: default_rate(),
+            ttl_secs: default_ttl_secs(),
+            state_persistence_base_path: None,
+            mode: HeadSampleMode::default(),
+            trace_state_field: default_trace_state_field(),
+        }
+    }
+}
+
 impl TraceHeadSampleConfig {
     pub fn new(config: &TraceHeadSampleConfig) -> Self {
         TraceHeadSampleConfig {
@@ -63,6 +111,8 @@ impl TraceHeadSampleConfig {
             rate: config.rate,
             ttl_secs: config.ttl_secs,
             state_persistence_base_path: config.state_persistence_base_path.clone(),
+            mode: config.mode,
+            trace_state_field: config.trace_state_field.clone(),
         }
     }
 }
@@ -175,6 +225,138 @@ impl TraceHeadSample {
     }
 }
 
+/// The rejection threshold for a `1 in rate` sampling probability. Traces whose randomness
+/// is greater than or equal to the threshold are kept.
+const fn rejection_threshold(rate: u64) -> u64 {
+    MAX_THRESHOLD - MAX_THRESHOLD / rate
+}
+
+/// Encodes a threshold as the `th` value of the OpenTelemetry tracestate entry: the 14 hex
+/// digits of the threshold with trailing zeros removed.
+fn encode_threshold(threshold: u64) -> String {
+    if threshold == 0 {
+        return "0".to_owned();
+    }
+    format!("{threshold:014x}").trim_end_matches('0').to_owned()
+}
+
+/// Parses a hex value of at most 14 digits, padded with trailing zeros when shorter.
+fn parse_hex_56(value: &str) -> Option<u64> {
+    if value.is_empty() || value.len() > HEX_DIGITS {
+        return None;
+    }
+    let parsed = u64::from_str_radix(value, 16).ok()?;
+    Some(parsed << (4 * (HEX_DIGITS - value.len())))
+}
+
+/// Returns the value of a sub-key of the `ot` entry of a W3C tracestate.
+fn ot_value<'a>(trace_state: &'a str, key: &str) -> Option<&'a str> {
+    trace_state
+        .split(',')
+        .find_map(|entry| entry.trim().strip_prefix("ot="))?
+        .split(';')
+        .find_map(|sub_key| sub_key.strip_prefix(key)?.strip_prefix(':'))
+}
+
+/// Sets the `th` sub-key of the `ot` entry, moving the entry to the front as required for
+/// modified tracestate entries.
+fn with_threshold(trace_state: &str, threshold: &str) -> String {
+    let mut ot = vec![format!("th:{threshold}")];
+    let mut others = Vec::new();
+    for entry in trace_state
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+    {
+        if let Some(value) = entry.strip_prefix("ot=") {
+            ot.extend(
+                value
+                    .split(';')
+                    .filter(|sub_key| !sub_key.is_empty() && !sub_key.starts_with("th:"))
+                    .map(str::to_owned),
+            );
+        } else {
+            others.push(entry);
+        }
+    }
+    std::iter::once(format!("ot={}", ot.join(";")))
+        .chain(others.into_iter().map(str::to_owned))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// The 56 bits of randomness of a trace. An explicit `rv` in the tracestate takes precedence,
+/// then the least significant 56 bits of a hex (or raw 16 byte) trace ID. Other trace ID
+/// formats are hashed.
+fn trace_randomness(trace_id: &[u8], trace_state: Option<&str>) -> u64 {
+    if let Some(rv) = trace_state
+        .and_then(|trace_state| ot_value(trace_state, "rv"))
+        .filter(|rv| rv.len() == HEX_DIGITS)
+        .and_then(parse_hex_56)
+    {
+        return rv;
+    }
+
+    let hex: String = String::from_utf8_lossy(trace_id)
+        .chars()
+        .filter(|c| *c != '-')
+        .collect();
+    if hex.len() >= HEX_DIGITS
+        && hex.chars().all(|c| c.is_ascii_hexdigit())
+        && let Ok(randomness) = u64::from_str_radix(&hex[hex.len() - HEX_DIGITS..], 16)
+    {
+        return randomness;
+    }
+
+    if trace_id.len() == 16 {
+        return trace_id[9..]
+            .iter()
+            .fold(0, |acc, byte| (acc << 8) | u64::from(*byte));
+    }
+
+    seahash::hash(trace_id) & (MAX_THRESHOLD - 1)
+}
+
+impl TraceHeadSample {
+    /// Samples a trace based on its randomness, without any local state. Kept events have
+    /// the sampling threshold recorded in their tracestate.
+    fn transform_consistent(
+        &mut self,
+        output: &mut OutputBuffer,
+        mut event: Event,
+        trace_id: &[u8],
+    ) {
+        let message_path = log_schema().message_key_target_path().unwrap();
+        let trace_state = event
+            .as_log()
+            .get(message_path)
+            .and_then(|message| message.get(self.config.trace_state_field.as_str()))
+            .and_then(Value::as_str)
+            .map(|trace_state| trace_state.into_owned());
+
+        // An upstream sampler may already have applied a stricter threshold
+        let upstream_threshold = trace_state
+            .as_deref()
+            .and_then(|trace_state| ot_value(trace_state, "th"))
+            .and_then(parse_hex_56)
+            .unwrap_or_default();
+        let threshold = rejection_threshold(self.config.rate).max(upstream_threshold);
+
+        if trace_randomness(trace_id, trace_state.as_deref()) < threshold {
+            return;
+        }
+
+        let trace_state = with_threshold(
+            trace_state.as_deref().unwrap_or_default(),
+            &encode_threshold(threshold),
+        );
+        if let Some(message) = event.as_mut_log().get_mut(message_path) {
+            message.insert(self.config.trace_state_field.as_str(), trace_state);
+        }
+        output.push(event);
+    }
+}
+
 impl FunctionTransform for TraceHeadSample {
     fn transform(&mut self, output: &mut OutputBuffer, event: Event) {
         // TODO: update when we're ready to handle TraceEvent types with datadog traces
@@ -192,6 +374,12 @@ impl FunctionTransform for TraceHeadSample {
             }
 
             if let Some(Value::Bytes(b)) = message.get(self.config.trace_id_field.as_str()) {
+                if self.config.mode == HeadSampleMode::Consistent {
+                    let trace_id = b.clone();
+                    self.transform_consistent(output, event, &trace_id);
+                    return;
+                }
+
                 let trace_id = String::from_utf8_lossy(b);
                 if let Some(value) = self.get_value(&trace_id) {
                     if value {
@@ -301,6 +489,7 @@ mod test {
             rate: 0, // a rate of 0 would never sample and cause division errors
             ttl_secs: MINIMUM_TTL_SECS - 1,
             state_persistence_base_path: Some("/some-path".to_owned()),
+            ..Default::default()
         };
 
         let test_ctx = test_ctx();
@@ -377,6 +566,7 @@ mod test {
             rate: 2,
             ttl_secs: 300,
             state_persistence_base_path: None,
+            ..Default::default()
         };
         let test_ctx = test_ctx();
         let connection = test_connection(test_ctx.clone(), config.ttl_secs, None);
@@ -426,6 +616,7 @@ mod test {
             rate: 2,
             ttl_secs: 300,
             state_persistence_base_path: Some("/data/component-state".to_owned()),
+            ..Default::default()
         };
         let test_ctx = test_ctx();
 
@@ -507,6 +698,7 @@ mod test {
             rate: 2,
             ttl_secs: 300,
             state_persistence_base_path: Some("/data/component-state".to_owned()),
+            ..Default::default()
         };
         let test_ctx = test_ctx();
         let connection = test_connection(test_ctx.clone(), config.ttl_secs, None);
@@ -593,6 +785,7 @@ mod test {
             rate: 2,
             ttl_secs: 300,
             state_persistence_base_path: Some("/data/component-state".to_owned()),
+            ..Default::default()
         };
         let test_ctx = test_ctx();
         #[allow(deprecated)]
@@ -626,4 +819,105 @@ mod test {
 
         assert_eq!(sampler.count, 0, "reset back to 0");
     }
+
+    #[test]
+    fn consistent_threshold_encoding() {
+        assert_eq!(
+            encode_threshold(rejection_threshold(1)),
+            "0",
+            "always sample"
+        );
+        assert_eq!(encode_threshold(rejection_threshold(2)), "8");
+        assert_eq!(encode_threshold(rejection_threshold(4)), "c");
+
+        let threshold = rejection_threshold(10);
+        assert_eq!(
+            parse_hex_56(&encode_threshold(threshold)),
+            Some(threshold),
+            "encoding is lossless"
+        );
+        assert_eq!(parse_hex_56("8"), Some(MAX_THRESHOLD / 2));
+        assert_eq!(parse_hex_56("123456789abcdef"), None, "too many digits");
+    }
+
+    #[test]
+    fn consistent_trace_state() {
+        assert_eq!(with_threshold("", "8"), "ot=th:8");
+        assert_eq!(
+            with_threshold("vendor=x, ot=th:4;rv:ffffffffffffff", "c"),
+            "ot=th:c;rv:ffffffffffffff,vendor=x"
+        );
+        assert_eq!(
+            ot_value("vendor=x,ot=th:4;rv:01020304050607", "rv"),
+            Some("01020304050607")
+        );
+
+        assert_eq!(
+            trace_randomness(b"0af7651916cd43dd8448eb211c80319c", None),
+            0x48eb211c80319c
+        );
+        assert_eq!(
+            trace_randomness(b"1ebddd15-314e-4f5c-bacc-782dd746883d", None),
+            0xcc782dd746883d
+        );
+        assert_eq!(
+            trace_randomness(
+                b"0af7651916cd43dd8448eb211c80319c",
+                Some("ot=rv:00000000000001")
+            ),
+            1,
+            "explicit randomness takes precedence"
+        );
+        assert_eq!(
+            trace_randomness(b"not a hex id", None),
+            trace_randomness(b"not a hex id", None),
+            "hashed ids are deterministic"
+        );
+    }
+
+    #[assay(env = [("POD_NAME", "vector-test0-0")])]
+    #[test]
+    fn consistent_sampling_across_instances() {
+        let span = |trace_id: &str, trace_state: Option<&str>| {
+            let mut message = btreemap! {
+                "name" => "hello",
+                "trace_id" => trace_id,
+            };
+            if let Some(trace_state) = trace_state {
+                message.insert("trace_state".into(), trace_state.into());
+            }
+            Event::Log(LogEvent::from(btreemap! { "message" => message }))
+        };
+        let kept = span("0af7651916cd43ddffffffffffffffff", Some("vendor=x"));
+        let dropped = span("0af7651916cd43dd0000000000000001", None);
+        let upstream = span("0af7651916cd43dd0090000000000000", Some("ot=th:c"));
+
+        let config = TraceHeadSampleConfig {
+            rate: 2,
+            mode: HeadSampleMode::Consistent,
+            ..Default::default()
+        };
+
+        // independent instances, as in separate replicas, make the same decisions
+        for _ in 0..2 {
+            let test_ctx = test_ctx();
+            let connection = test_connection(test_ctx.clone(), config.ttl_secs, None);
+            let mut sampler = TraceHeadSample::new(config.clone(), test_ctx, connection);
+
+            let mut output = OutputBuffer::default();
+            sampler.transform(&mut output, kept.clone());
+            sampler.transform(&mut output, kept.clone());
+            sampler.transform(&mut output, dropped.clone());
+            sampler.transform(&mut output, upstream.clone());
+
+            assert_eq!(output.len(), 2, "only the spans of the kept trace");
+            for event in output.into_events() {
+                assert_eq!(
+                    event.as_log().get(".message.trace_state"),
+                    Some(&Value::from("ot=th:8,vendor=x"))
+                );
+            }
+            assert_eq!(sampler.count, 0, "no local state");
+        }
+    }
 }
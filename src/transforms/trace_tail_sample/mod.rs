@@ -17,10 +17,19 @@ use serde::Serialize;
 use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 use vrl::value::Value;
 
+mod policy;
+
+use policy::PolicyTailSample;
+
+pub use policy::{PolicyFields, SpanStatusCode, TailSamplePolicy};
+
 const MINIMUM_TTL_SECS: u64 = 15;
 const DEFAULT_TTL_SECS: u64 = 5 * 60; // 5 min
+const DEFAULT_DECISION_WAIT_SECS: u64 = 30;
+const DEFAULT_NUM_TRACES: usize = 50_000;
 
 /// A conditional as defined by the customer
 #[configurable_component]
@@ -36,7 +45,7 @@ pub struct TailSampleConditional {
 
 /// Configuration for the `trace_head_sample` transform.
 #[configurable_component(transform("trace_tail_sample"))]
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct TraceTailSampleConfig {
     /// the key of the event to evaluate
@@ -57,6 +66,23 @@ pub struct TraceTailSampleConfig {
 
     /// the base path on disk to maintain keys and data while tracking traces
     state_persistence_base_path: Option<String>,
+
+    /// trace level sampling policies, a trace is kept when any policy matches.
+    /// Cannot be combined with conditionals.
+    #[serde(default)]
+    policies: Vec<TailSamplePolicy>,
+
+    /// the time to wait after the first span of a trace before evaluating policies
+    #[serde(default = "default_decision_wait_secs")]
+    decision_wait_secs: u64,
+
+    /// the maximum number of traces held in memory while waiting for a decision
+    #[serde(default = "default_num_traces")]
+    num_traces: usize,
+
+    /// the span fields used when evaluating policies
+    #[serde(default)]
+    policy_fields: PolicyFields,
 }
 
 fn default_trace_id_field() -> String {
@@ -75,6 +101,30 @@ const fn default_ttl_secs() -> u64 {
     DEFAULT_TTL_SECS
 }
 
+const fn default_decision_wait_secs() -> u64 {
+    DEFAULT_DECISION_WAIT_SECS
+}
+
+const fn default_num_traces() -> usize {
+    DEFAULT_NUM_TRACES
+}
+
+impl Default for TraceTailSampleConfig {
+    fn default() -> Self {
+        Self {
+            trace_id_field: default_trace_id_field(),
+            parent_span_id_field: default_parent_span_id_field(),
+            conditionals: default_conditionals(),
+            ttl_secs: default_ttl_secs(),
+            state_persistence_base_path: None,
+            policies: Vec::new(),
+            decision_wait_secs: default_decision_wait_secs(),
+            num_traces: default_num_traces(),
+            policy_fields: PolicyFields::default(),
+        }
+    }
+}
+
 impl TraceTailSampleConfig {
     pub fn new(config: &TraceTailSampleConfig) -> Self {
         TraceTailSampleConfig {
@@ -83,6 +133,10 @@ impl TraceTailSampleConfig {
             conditionals: config.conditionals.clone(),
             ttl_secs: config.ttl_secs,
             state_persistence_base_path: config.state_persistence_base_path.clone(),
+            policies: config.policies.clone(),
+            decision_wait_secs: config.decision_wait_secs,
+            num_traces: config.num_traces,
+            policy_fields: config.policy_fields.clone(),
         }
     }
 }
@@ -93,6 +147,10 @@ impl_generate_config_from_default!(TraceTailSampleConfig);
 #[typetag::serde(name = "trace_tail_sample")]
 impl TransformConfig for TraceTailSampleConfig {
     async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        if !self.policies.is_empty() && !self.conditionals.is_empty() {
+            return Err("`conditionals` and `policies` cannot be used together".into());
+        }
+
         // generate a unique path for this component to store its data
         let mezmo_ctx = context.mezmo_ctx.clone().unwrap();
         let sample_path = "trace_tail_sample".to_owned();
@@ -101,8 +159,27 @@ impl TransformConfig for TraceTailSampleConfig {
         } else {
             sample_path
         };
+        let ttl_secs = if self.ttl_secs < MINIMUM_TTL_SECS {
+            DEFAULT_TTL_SECS
+        } else {
+            self.ttl_secs
+        };
         let persistence =
-            RocksDBPersistenceConnection::new_with_ttl(&base_path, &mezmo_ctx, self.ttl_secs)?;
+            RocksDBPersistenceConnection::new_with_ttl(&base_path, &mezmo_ctx, ttl_secs)?;
+
+        if !self.policies.is_empty() {
+            return Ok(Transform::event_task(PolicyTailSample::new(
+                self.trace_id_field.clone(),
+                self.parent_span_id_field.clone(),
+                self.policy_fields.clone(),
+                self.policies.clone(),
+                Duration::from_secs(self.decision_wait_secs),
+                Duration::from_secs(ttl_secs),
+                self.num_traces,
+                mezmo_ctx,
+                Arc::new(persistence),
+            )));
+        }
 
         //build all the conditions from their configs to be used in evaluations
         let conditions = self
@@ -476,6 +553,7 @@ mod test {
             conditionals: vec![],
             ttl_secs: MINIMUM_TTL_SECS - 1,
             state_persistence_base_path: Some("/some-path".to_owned()),
+            ..Default::default()
         };
 
         let test_ctx = get_test_ctx();
@@ -512,6 +590,52 @@ mod test {
         }
     }
 
+    #[assay(env = [("POD_NAME", "vector-test0-0")])]
+    #[test]
+    async fn build_policy_transform_from_config() {
+        let mezmo_ctx = get_test_ctx();
+        let ctx = TransformContext {
+            mezmo_ctx: Some(mezmo_ctx),
+            ..Default::default()
+        };
+
+        let config: TraceTailSampleConfig = serde_json::from_str(
+            r#"{
+                "decision_wait_secs": 10,
+                "policies": [
+                    { "type": "status_code" },
+                    { "type": "latency", "threshold_ms": 500 },
+                    { "type": "rate_limiting", "spans_per_second": 100 }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(config.policies.len(), 3);
+        assert!(matches!(
+            &config.policies[0],
+            TailSamplePolicy::StatusCode { status_codes } if status_codes == &[SpanStatusCode::Error]
+        ));
+
+        match config.build(&ctx).await {
+            Ok(Transform::Task(_)) => {}
+            Ok(_) => panic!("Expected a Task transform"),
+            Err(e) => panic!("Failed to generate config: {e}"),
+        }
+
+        let config = TraceTailSampleConfig {
+            conditionals: vec![TailSampleConditional {
+                rate: 1,
+                condition: AnyCondition::String("true".to_owned()),
+                output_name: "all".to_owned(),
+            }],
+            ..config
+        };
+        assert!(
+            config.build(&ctx).await.is_err(),
+            "conditionals and policies are exclusive"
+        );
+    }
+
     #[assay(env = [("POD_NAME", "vector-test0-0")])]
     #[test]
     fn log_not_object() {
@@ -555,6 +679,7 @@ mod test {
             conditionals: vec![],
             ttl_secs: 300,
             state_persistence_base_path: None,
+            ..Default::default()
         };
 
         let test_ctx = get_test_ctx();
@@ -615,6 +740,7 @@ mod test {
             conditionals: vec![condition_config.clone()],
             ttl_secs: 300,
             state_persistence_base_path: Some("/data/component-state".to_owned()),
+            ..Default::default()
         };
         let test_ctx = get_test_ctx();
         let connection = test_connection(test_ctx.clone(), config.ttl_secs, None);
@@ -680,6 +806,7 @@ mod test {
             conditionals: vec![condition_config.clone()],
             ttl_secs: 300,
             state_persistence_base_path: Some("/data/component-state".to_owned()),
+            ..Default::default()
         };
         let test_ctx = get_test_ctx();
         let connection = test_connection(test_ctx.clone(), config.ttl_secs, None);
@@ -813,6 +940,7 @@ mod test {
             conditionals: vec![condition_config.clone()],
             ttl_secs: 300,
             state_persistence_base_path: Some("/data/component-state".to_owned()),
+            ..Default::default()
         };
         let test_ctx = get_test_ctx();
         let connection = test_connection(test_ctx.clone(), config.ttl_secs, None);
@@ -960,6 +1088,7 @@ mod test {
             conditionals: vec![condition_config.clone()],
             ttl_secs: 300,
             state_persistence_base_path: Some("/data/component-state".to_owned()),
+            ..Default::default()
         };
         let test_ctx = get_test_ctx();
         let connection = test_connection(test_ctx.clone(), config.ttl_secs, Some(base_dir.clone()));
@@ -996,6 +1125,7 @@ mod test {
             conditionals: vec![condition_config.clone()],
             ttl_secs: 300,
             state_persistence_base_path: Some("/data/component-state".to_owned()),
+            ..Default::default()
         };
 
         let conditions = vec![(
@@ -1084,6 +1214,7 @@ mod test {
             conditionals: vec![condition_config.clone()],
             ttl_secs: 300,
             state_persistence_base_path: Some("/data/component-state".to_owned()),
+            ..Default::default()
         };
         let test_ctx = get_test_ctx();
         let connection = test_connection(test_ctx.clone(), config.ttl_secs, Some(base_dir.clone()));
@@ -0,0 +1,632 @@
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use futures::Stream;
+use indexmap::IndexMap;
+use vector_lib::config::log_schema;
+use vector_lib::configurable::configurable_component;
+use vector_lib::stream::expiration_map::{Emitter, map_with_expiration};
+use vrl::value::Value;
+
+use crate::{event::Event, mezmo::persistence::PersistenceConnection, transforms::TaskTransform};
+use mezmo::MezmoContext;
+
+/// How often pending traces are checked for an expired decision wait.
+const EXPIRATION_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A span status code, as defined by OpenTelemetry.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SpanStatusCode {
+    /// The default status.
+    Unset,
+
+    /// The operation completed successfully.
+    Ok,
+
+    /// The operation contains an error.
+    Error,
+}
+
+impl SpanStatusCode {
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Integer(0) => Some(Self::Unset),
+            Value::Integer(1) => Some(Self::Ok),
+            Value::Integer(2) => Some(Self::Error),
+            Value::Bytes(code) => {
+                let code = String::from_utf8_lossy(code).to_lowercase();
+                match code.trim_start_matches("status_code_") {
+                    "unset" => Some(Self::Unset),
+                    "ok" => Some(Self::Ok),
+                    "error" => Some(Self::Error),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+fn default_status_codes() -> Vec<SpanStatusCode> {
+    vec![SpanStatusCode::Error]
+}
+
+/// A sampling policy evaluated against a whole trace, modeled after the OpenTelemetry
+/// collector `tail_sampling` processor.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[configurable(metadata(docs::enum_tag_description = "The type of the policy."))]
+pub enum TailSamplePolicy {
+    /// Keep traces containing at least one span with one of the given status codes.
+    StatusCode {
+        /// the span status codes that cause a trace to be kept
+        #[serde(default = "default_status_codes")]
+        status_codes: Vec<SpanStatusCode>,
+    },
+
+    /// Keep traces whose duration exceeds a threshold. The duration of the root span is used
+    /// when it was received, otherwise the time between the earliest span start and the latest
+    /// span end.
+    Latency {
+        /// the minimum duration, in milliseconds, of a kept trace
+        threshold_ms: u64,
+    },
+
+    /// Keep traces as long as the spans kept per second for the service of the trace stay
+    /// below a limit.
+    RateLimiting {
+        /// the maximum number of spans kept per second and service
+        spans_per_second: u64,
+    },
+}
+
+/// The fields, relative to the `.message` of a span, used to evaluate policies.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(default)]
+pub struct PolicyFields {
+    /// the span status code field
+    pub status_code_field: String,
+
+    /// the span start timestamp field
+    pub start_timestamp_field: String,
+
+    /// the span end timestamp field
+    pub end_timestamp_field: String,
+
+    /// the path of the service name of a span, relative to the root of the event
+    pub service_field: String,
+}
+
+impl Default for PolicyFields {
+    fn default() -> Self {
+        Self {
+            status_code_field: ".status.code".to_owned(),
+            start_timestamp_field: ".start_timestamp".to_owned(),
+            end_timestamp_field: ".end_timestamp".to_owned(),
+            service_field: ".metadata.resource.attributes.\"service.name\"".to_owned(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PendingTrace {
+    first_seen: Instant,
+    spans: Vec<Event>,
+}
+
+#[derive(Debug)]
+struct RateWindow {
+    start: Instant,
+    spans: u64,
+}
+
+/// Buffers the spans of each trace for the decision wait, then keeps or drops the whole
+/// trace according to the configured policies. Decisions are persisted for `decision_ttl` so
+/// that spans arriving after the decision are handled consistently.
+#[derive(Debug)]
+pub struct PolicyTailSample {
+    trace_id_field: String,
+    parent_span_id_field: String,
+    fields: PolicyFields,
+    policies: Vec<TailSamplePolicy>,
+    decision_wait: Duration,
+    decision_ttl: Duration,
+    num_traces: usize,
+    pending: IndexMap<String, PendingTrace>,
+    rate_windows: HashMap<String, RateWindow>,
+    mezmo_ctx: MezmoContext,
+    persistence: Arc<dyn PersistenceConnection>,
+}
+
+impl PolicyTailSample {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        trace_id_field: String,
+        parent_span_id_field: String,
+        fields: PolicyFields,
+        policies: Vec<TailSamplePolicy>,
+        decision_wait: Duration,
+        decision_ttl: Duration,
+        num_traces: usize,
+        mezmo_ctx: MezmoContext,
+        persistence: Arc<dyn PersistenceConnection>,
+    ) -> Self {
+        Self {
+            trace_id_field,
+            parent_span_id_field,
+            fields,
+            policies,
+            decision_wait,
+            decision_ttl,
+            num_traces: num_traces.max(1),
+            pending: IndexMap::new(),
+            rate_windows: HashMap::new(),
+            mezmo_ctx,
+            persistence,
+        }
+    }
+
+    fn result_key(trace_id: &str) -> String {
+        format!("{trace_id}:result")
+    }
+
+    /// Decisions are stored along with when they expire, in milliseconds since the epoch.
+    /// Expired decisions are deleted when they're read, and otherwise left to the TTL of the
+    /// persistence connection.
+    fn get_decision(&self, trace_id: &str) -> Option<bool> {
+        let key = Self::result_key(trace_id);
+        match self.persistence.get(&key) {
+            Ok(value) => {
+                let (decision, expires_at) =
+                    value?.split_once(':').and_then(|(decision, expires_at)| {
+                        Some((decision.parse().ok()?, expires_at.parse::<i64>().ok()?))
+                    })?;
+                if expires_at <= Utc::now().timestamp_millis() {
+                    self.delete_decision(&key);
+                    return None;
+                }
+                Some(decision)
+            }
+            Err(e) => {
+                error!(
+                    message = "Failed to get key/value",
+                    key,
+                    component_id = self.mezmo_ctx.component_id(),
+                    error = e
+                );
+                None
+            }
+        }
+    }
+
+    fn set_decision(&self, trace_id: &str, decision: bool) {
+        let key = Self::result_key(trace_id);
+        let expires_at = Utc::now().timestamp_millis()
+            + i64::try_from(self.decision_ttl.as_millis()).unwrap_or(i64::MAX / 2);
+        if let Err(e) = self
+            .persistence
+            .set(&key, &format!("{decision}:{expires_at}"))
+        {
+            error!(
+                message = "Failed to set key/value",
+                key,
+                component_id = self.mezmo_ctx.component_id(),
+                error = e
+            );
+        }
+    }
+
+    fn delete_decision(&self, key: &str) {
+        if let Err(e) = self.persistence.delete(key) {
+            error!(
+                message = "Failed to delete key/value",
+                key,
+                component_id = self.mezmo_ctx.component_id(),
+                error = e
+            );
+        }
+    }
+
+    fn message_field<'a>(event: &'a Event, field: &str) -> Option<&'a Value> {
+        event
+            .as_log()
+            .get(log_schema().message_key_target_path()?)
+            .and_then(|message| message.get(field))
+    }
+
+    fn message_string(event: &Event, field: &str) -> Option<String> {
+        match Self::message_field(event, field) {
+            Some(Value::Bytes(b)) if !b.is_empty() => Some(String::from_utf8_lossy(b).into_owned()),
+            _ => None,
+        }
+    }
+
+    fn timestamp(event: &Event, field: &str) -> Option<DateTime<Utc>> {
+        match Self::message_field(event, field)? {
+            Value::Timestamp(ts) => Some(*ts),
+            Value::Bytes(ts) => String::from_utf8_lossy(ts).parse().ok(),
+            Value::Integer(nanos) => Some(DateTime::from_timestamp_nanos(*nanos)),
+            _ => None,
+        }
+    }
+
+    /// Handles a single span. Spans of undecided traces are buffered, spans of decided traces
+    /// are forwarded or dropped according to the earlier decision.
+    pub fn process(&mut self, event: Event, now: Instant) -> Vec<Event> {
+        let Some(log) = event.maybe_as_log() else {
+            return Vec::new();
+        };
+        if !log
+            .get(log_schema().message_key_target_path().unwrap())
+            .is_some_and(Value::is_object)
+        {
+            return Vec::new();
+        }
+        let Some(trace_id) = Self::message_string(&event, &self.trace_id_field) else {
+            return Vec::new();
+        };
+
+        if let Some(pending) = self.pending.get_mut(&trace_id) {
+            pending.spans.push(event);
+            return Vec::new();
+        }
+
+        // Late spans of a trace for which a decision was already made
+        if let Some(decision) = self.get_decision(&trace_id) {
+            return if decision { vec![event] } else { Vec::new() };
+        }
+
+        self.pending.insert(
+            trace_id,
+            PendingTrace {
+                first_seen: now,
+                spans: vec![event],
+            },
+        );
+
+        // When too many traces are pending, decide the oldest ones early
+        let mut output = Vec::new();
+        while self.pending.len() > self.num_traces {
+            if let Some((trace_id, pending)) = self.pending.shift_remove_index(0) {
+                output.extend(self.decide(&trace_id, pending.spans, now));
+            }
+        }
+        output
+    }
+
+    /// Decides every trace whose decision wait has elapsed.
+    pub fn expire(&mut self, now: Instant) -> Vec<Event> {
+        let mut output = Vec::new();
+        while let Some((_, pending)) = self.pending.first() {
+            if now.saturating_duration_since(pending.first_seen) < self.decision_wait {
+                break;
+            }
+            if let Some((trace_id, pending)) = self.pending.shift_remove_index(0) {
+                output.extend(self.decide(&trace_id, pending.spans, now));
+            }
+        }
+        output
+    }
+
+    /// Decides every pending trace, regardless of the decision wait.
+    pub fn flush_all(&mut self, now: Instant) -> Vec<Event> {
+        let pending = std::mem::take(&mut self.pending);
+        pending
+            .into_iter()
+            .flat_map(|(trace_id, pending)| self.decide(&trace_id, pending.spans, now))
+            .collect()
+    }
+
+    fn decide(&mut self, trace_id: &str, spans: Vec<Event>, now: Instant) -> Vec<Event> {
+        // Rate limiting policies update their windows, so the policies are moved out while
+        // they're evaluated
+        let policies = std::mem::take(&mut self.policies);
+        let decision = policies
+            .iter()
+            .any(|policy| self.evaluate(policy, &spans, now));
+        self.policies = policies;
+        self.set_decision(trace_id, decision);
+        if decision { spans } else { Vec::new() }
+    }
+
+    fn is_root(&self, span: &Event) -> bool {
+        Self::message_string(span, &self.parent_span_id_field).is_none()
+    }
+
+    fn evaluate(&mut self, policy: &TailSamplePolicy, spans: &[Event], now: Instant) -> bool {
+        match policy {
+            TailSamplePolicy::StatusCode { status_codes } => spans.iter().any(|span| {
+                Self::message_field(span, &self.fields.status_code_field)
+                    .and_then(SpanStatusCode::from_value)
+                    .is_some_and(|code| status_codes.contains(&code))
+            }),
+            TailSamplePolicy::Latency { threshold_ms } => self
+                .trace_duration(spans)
+                .is_some_and(|duration| duration.num_milliseconds() >= *threshold_ms as i64),
+            TailSamplePolicy::RateLimiting { spans_per_second } => {
+                let service = spans
+                    .iter()
+                    .find(|span| self.is_root(span))
+                    .or_else(|| spans.first())
+                    .and_then(|span| {
+                        span.as_log()
+                            .parse_path_and_get_value(self.fields.service_field.as_str())
+                            .ok()
+                            .flatten()
+                            .map(Value::to_string_lossy)
+                    })
+                    .map(|service| service.into_owned())
+                    .unwrap_or_default();
+
+                let window = self.rate_windows.entry(service).or_insert(RateWindow {
+                    start: now,
+                    spans: 0,
+                });
+                if now.saturating_duration_since(window.start) >= Duration::from_secs(1) {
+                    *window = RateWindow {
+                        start: now,
+                        spans: 0,
+                    };
+                }
+                let spans = spans.len() as u64;
+                if window.spans + spans <= *spans_per_second {
+                    window.spans += spans;
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    fn trace_duration(&self, spans: &[Event]) -> Option<chrono::TimeDelta> {
+        let span_bounds = |span: &Event| {
+            Some((
+                Self::timestamp(span, &self.fields.start_timestamp_field)?,
+                Self::timestamp(span, &self.fields.end_timestamp_field)?,
+            ))
+        };
+
+        if let Some((start, end)) = spans
+            .iter()
+            .find(|span| self.is_root(span))
+            .and_then(span_bounds)
+        {
+            return Some(end - start);
+        }
+
+        let (start, end) = spans.iter().filter_map(span_bounds).fold(
+            (None, None),
+            |(min_start, max_end): (Option<DateTime<Utc>>, Option<DateTime<Utc>>), (s, e)| {
+                (
+                    Some(min_start.map_or(s, |m| m.min(s))),
+                    Some(max_end.map_or(e, |m| m.max(e))),
+                )
+            },
+        );
+        Some(end? - start?)
+    }
+}
+
+impl TaskTransform<Event> for PolicyTailSample {
+    fn transform(
+        self: Box<Self>,
+        input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let emit_all = |events: Vec<Event>, emitter: &mut Emitter<Event>| {
+            for event in events {
+                emitter.emit(event);
+            }
+        };
+
+        Box::pin(map_with_expiration(
+            self,
+            input_rx,
+            EXPIRATION_INTERVAL,
+            move |me: &mut Box<PolicyTailSample>, event, emitter: &mut Emitter<Event>| {
+                emit_all(me.process(event, Instant::now()), emitter);
+            },
+            move |me: &mut Box<PolicyTailSample>, emitter: &mut Emitter<Event>| {
+                emit_all(me.expire(Instant::now()), emitter);
+            },
+            move |me: &mut Box<PolicyTailSample>, emitter: &mut Emitter<Event>| {
+                emit_all(me.flush_all(Instant::now()), emitter);
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assay::assay;
+    use tempfile::tempdir;
+    use uuid::Uuid;
+    use vector_lib::btreemap;
+    use vector_lib::event::LogEvent;
+
+    use super::*;
+    use crate::mezmo::persistence::RocksDBPersistenceConnection;
+
+    fn sampler(policies: Vec<TailSamplePolicy>, num_traces: usize) -> PolicyTailSample {
+        sampler_with_ttl(policies, num_traces, Duration::from_secs(300))
+    }
+
+    fn sampler_with_ttl(
+        policies: Vec<TailSamplePolicy>,
+        num_traces: usize,
+        decision_ttl: Duration,
+    ) -> PolicyTailSample {
+        let mezmo_ctx = MezmoContext::try_from(format!(
+            "v1:trace_tail_sample:transform:{}:pipeline_id:{}",
+            Uuid::new_v4(),
+            Uuid::new_v4()
+        ))
+        .unwrap();
+        let tmp_path = tempdir().expect("Could not create temp dir").keep();
+        let persistence =
+            RocksDBPersistenceConnection::new_with_ttl(tmp_path.to_str().unwrap(), &mezmo_ctx, 300)
+                .unwrap();
+
+        PolicyTailSample::new(
+            ".trace_id".to_owned(),
+            ".parent_span_id".to_owned(),
+            PolicyFields::default(),
+            policies,
+            Duration::from_secs(10),
+            decision_ttl,
+            num_traces,
+            mezmo_ctx,
+            Arc::new(persistence),
+        )
+    }
+
+    fn span(
+        trace_id: &str,
+        parent_span_id: Option<&str>,
+        status_code: i64,
+        duration_ms: i64,
+    ) -> Event {
+        let start = Utc::now();
+        let mut message = btreemap! {
+            "trace_id" => trace_id,
+            "status" => btreemap! { "code" => status_code },
+            "start_timestamp" => start,
+            "end_timestamp" => start + chrono::Duration::milliseconds(duration_ms),
+        };
+        if let Some(parent_span_id) = parent_span_id {
+            message.insert("parent_span_id".into(), parent_span_id.into());
+        }
+        Event::Log(LogEvent::from(btreemap! {
+            "message" => message,
+            "metadata" => btreemap! {
+                "resource" => btreemap! {
+                    "attributes" => btreemap! { "service.name" => "checkout" }
+                }
+            }
+        }))
+    }
+
+    #[assay(env = [("POD_NAME", "vector-test0-0")])]
+    #[test]
+    fn keeps_traces_with_error_spans() {
+        let mut sampler = sampler(
+            vec![TailSamplePolicy::StatusCode {
+                status_codes: default_status_codes(),
+            }],
+            100,
+        );
+        let now = Instant::now();
+
+        assert!(sampler.process(span("t1", None, 0, 5), now).is_empty());
+        assert!(sampler.process(span("t1", Some("a"), 2, 5), now).is_empty());
+        assert!(sampler.process(span("t2", None, 1, 5), now).is_empty());
+
+        // Nothing is decided before the decision wait elapses
+        assert!(sampler.expire(now + Duration::from_secs(5)).is_empty());
+
+        let output = sampler.expire(now + Duration::from_secs(10));
+        assert_eq!(output.len(), 2, "the whole error trace is kept");
+
+        // Late spans follow the earlier decision
+        assert_eq!(sampler.process(span("t1", Some("b"), 0, 1), now).len(), 1);
+        assert!(sampler.process(span("t2", Some("b"), 0, 1), now).is_empty());
+    }
+
+    #[assay(env = [("POD_NAME", "vector-test0-0")])]
+    #[test]
+    fn keeps_slow_traces() {
+        let mut sampler = sampler(vec![TailSamplePolicy::Latency { threshold_ms: 500 }], 100);
+        let now = Instant::now();
+
+        sampler.process(span("slow", None, 0, 800), now);
+        sampler.process(span("slow", Some("a"), 0, 10), now);
+        sampler.process(span("fast", None, 0, 100), now);
+        // Without a root span the whole trace duration is used
+        sampler.process(span("no-root", Some("a"), 0, 900), now);
+
+        let output = sampler.flush_all(now);
+        assert_eq!(output.len(), 3);
+    }
+
+    #[assay(env = [("POD_NAME", "vector-test0-0")])]
+    #[test]
+    fn rate_limits_per_service() {
+        let mut sampler = sampler(
+            vec![TailSamplePolicy::RateLimiting {
+                spans_per_second: 2,
+            }],
+            100,
+        );
+        let now = Instant::now();
+
+        sampler.process(span("t1", None, 0, 1), now);
+        sampler.process(span("t1", Some("a"), 0, 1), now);
+        sampler.process(span("t2", None, 0, 1), now);
+
+        let output = sampler.flush_all(now);
+        assert_eq!(
+            output.len(),
+            2,
+            "the second trace exceeds the limit of the service"
+        );
+
+        // The limit applies per second
+        sampler.process(span("t3", None, 0, 1), now);
+        let output = sampler.flush_all(now + Duration::from_millis(999));
+        assert!(output.is_empty());
+        sampler.process(span("t4", None, 0, 1), now);
+        let output = sampler.flush_all(now + Duration::from_secs(1));
+        assert_eq!(output.len(), 1);
+    }
+
+    #[assay(env = [("POD_NAME", "vector-test0-0")])]
+    #[test]
+    fn decides_oldest_trace_when_full() {
+        let mut sampler = sampler(
+            vec![TailSamplePolicy::StatusCode {
+                status_codes: default_status_codes(),
+            }],
+            1,
+        );
+        let now = Instant::now();
+
+        assert!(sampler.process(span("t1", None, 2, 1), now).is_empty());
+        let output = sampler.process(span("t2", None, 0, 1), now);
+        assert_eq!(output.len(), 1, "t1 is decided early to make room for t2");
+    }
+
+    #[assay(env = [("POD_NAME", "vector-test0-0")])]
+    #[test]
+    fn expires_decisions() {
+        let mut sampler = sampler_with_ttl(
+            vec![TailSamplePolicy::StatusCode {
+                status_codes: default_status_codes(),
+            }],
+            10,
+            Duration::ZERO,
+        );
+        let now = Instant::now();
+
+        sampler.process(span("t1", None, 2, 1), now);
+        assert_eq!(sampler.flush_all(now).len(), 1);
+
+        // Without a decision, a late span starts a new pending trace
+        assert!(
+            sampler
+                .process(span("t1", Some("s1"), 0, 1), now)
+                .is_empty()
+        );
+        assert_eq!(sampler.pending.len(), 1);
+        assert_eq!(sampler.persistence.get("t1:result").unwrap(), None);
+    }
+}
@@ -358,6 +358,10 @@ cli: {
 					description: "Components (transforms, sinks) to observe for their inputs (comma-separated; accepts glob patterns)"
 					type:        "list"
 				}
+				"inputs-and-outputs-of": {
+					description: "Components (transforms) to observe for both their inputs and outputs, to compare events before and after them (comma-separated; accepts glob patterns)"
+					type:        "list"
+				}
 				"outputs-of": {
 					description: "Components (sources, transforms) to observe for their inputs (comma-separated; accepts glob patterns)"
 					type:        "list"
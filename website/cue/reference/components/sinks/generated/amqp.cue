@@ -54,7 +54,7 @@ generated: components: sinks: amqp: configuration: {
 			avro: {
 				description:   "Apache Avro-specific encoder options."
 				relevant_when: "codec = \"avro\""
-				required:      true
+				required:      false
 				type: object: options: schema: {
 					description: "The Avro schema."
 					required:    true
@@ -383,7 +383,7 @@ generated: components: sinks: amqp: configuration: {
 			protobuf: {
 				description:   "Options for the Protobuf serializer."
 				relevant_when: "codec = \"protobuf\""
-				required:      true
+				required:      false
 				type: object: options: {
 					desc_file: {
 						description: """
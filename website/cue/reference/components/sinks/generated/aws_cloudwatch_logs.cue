@@ -250,7 +250,7 @@ generated: components: sinks: aws_cloudwatch_logs: configuration: {
 			avro: {
 				description:   "Apache Avro-specific encoder options."
 				relevant_when: "codec = \"avro\""
-				required:      true
+				required:      false
 				type: object: options: schema: {
 					description: "The Avro schema."
 					required:    true
@@ -579,7 +579,7 @@ generated: components: sinks: aws_cloudwatch_logs: configuration: {
 			protobuf: {
 				description:   "Options for the Protobuf serializer."
 				relevant_when: "codec = \"protobuf\""
-				required:      true
+				required:      false
 				type: object: options: {
 					desc_file: {
 						description: """
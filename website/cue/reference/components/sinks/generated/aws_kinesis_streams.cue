@@ -229,7 +229,7 @@ generated: components: sinks: aws_kinesis_streams: configuration: {
 			avro: {
 				description:   "Apache Avro-specific encoder options."
 				relevant_when: "codec = \"avro\""
-				required:      true
+				required:      false
 				type: object: options: schema: {
 					description: "The Avro schema."
 					required:    true
@@ -558,7 +558,7 @@ generated: components: sinks: aws_kinesis_streams: configuration: {
 			protobuf: {
 				description:   "Options for the Protobuf serializer."
 				relevant_when: "codec = \"protobuf\""
-				required:      true
+				required:      false
 				type: object: options: {
 					desc_file: {
 						description: """
@@ -338,7 +338,7 @@ generated: components: sinks: aws_s3: configuration: {
 			avro: {
 				description:   "Apache Avro-specific encoder options."
 				relevant_when: "codec = \"avro\""
-				required:      true
+				required:      false
 				type: object: options: schema: {
 					description: "The Avro schema."
 					required:    true
@@ -667,7 +667,7 @@ generated: components: sinks: aws_s3: configuration: {
 			protobuf: {
 				description:   "Options for the Protobuf serializer."
 				relevant_when: "codec = \"protobuf\""
-				required:      true
+				required:      false
 				type: object: options: {
 					desc_file: {
 						description: """
@@ -93,7 +93,7 @@ generated: components: sinks: kafka: configuration: {
 			avro: {
 				description:   "Apache Avro-specific encoder options."
 				relevant_when: "codec = \"avro\""
-				required:      true
+				required:      false
 				type: object: options: schema: {
 					description: "The Avro schema."
 					required:    true
@@ -422,7 +422,7 @@ generated: components: sinks: kafka: configuration: {
 			protobuf: {
 				description:   "Options for the Protobuf serializer."
 				relevant_when: "codec = \"protobuf\""
-				required:      true
+				required:      false
 				type: object: options: {
 					desc_file: {
 						description: """
@@ -634,6 +634,72 @@ generated: components: sinks: kafka: configuration: {
 			}
 		}
 	}
+	schema_registry: {
+		description: """
+			The schema registry holding the schema used to encode the events.
+
+			When set, the events are encoded in the Confluent wire format, prefixed by the id of their
+			schema, with the schema fetched from the registry when the sink starts. Only the `avro` and
+			`protobuf` encoding codecs are supported. The registered schema replaces the one of their
+			`avro` or `protobuf` options, which can then be left out.
+			"""
+		required: false
+		type: object: options: {
+			auth: {
+				description: "The basic authentication credentials of the schema registry."
+				required:    false
+				type: object: options: {
+					password: {
+						description: "The basic authentication password."
+						required:    true
+						type: string: examples: ["${SCHEMA_REGISTRY_PASSWORD}"]
+					}
+					user: {
+						description: "The basic authentication username."
+						required:    true
+						type: string: examples: ["${SCHEMA_REGISTRY_USERNAME}"]
+					}
+				}
+			}
+			cache_dir: {
+				description: """
+					The directory where the fetched schemas are stored.
+
+					The schemas stored in this directory are used when the schema registry can't be reached.
+					"""
+				required: false
+				type: string: examples: ["/var/lib/vector/schemas"]
+			}
+			message_type: {
+				description: """
+					The name of the message type of the registered schema used to encode the events.
+
+					Only used by the `protobuf` encoding codec. Defaults to the first message type of the
+					schema.
+					"""
+				required: false
+				type: string: examples: ["package.Message"]
+			}
+			schema_id: {
+				description: "The id of the registered schema used to encode the events."
+				required:    true
+				type: uint: examples: [1]
+			}
+			timeout_secs: {
+				description: "The timeout for the schema registry requests."
+				required:    false
+				type: uint: {
+					default: 10
+					unit:    "seconds"
+				}
+			}
+			url: {
+				description: "The base URL of the schema registry."
+				required:    true
+				type: string: examples: ["http://localhost:8081"]
+			}
+		}
+	}
 	socket_timeout_ms: {
 		description: "Default timeout, in milliseconds, for network requests."
 		required:    false
@@ -64,6 +64,30 @@ generated: components: sinks: mezmo: configuration: {
 			}
 		}
 	}
+	compression: {
+		description: """
+			Compression configuration.
+
+			All compression algorithms use the default compression level unless otherwise specified.
+			"""
+		required: false
+		type: string: {
+			default: "none"
+			enum: {
+				gzip: """
+					[Gzip][gzip] compression.
+
+					[gzip]: https://www.gzip.org/
+					"""
+				none: "No compression."
+				zstd: """
+					[Zstandard][zstd] compression.
+
+					[zstd]: https://facebook.github.io/zstd/
+					"""
+			}
+		}
+	}
 	default_app: {
 		description: "The default app that is set for events that do not contain a `file` or `app` field."
 		required:    false
@@ -140,6 +164,36 @@ generated: components: sinks: mezmo: configuration: {
 		required:    false
 		type: string: examples: ["my-mac-address"]
 	}
+	oversized_lines: {
+		description: "Client-side handling of oversized lines."
+		required:    false
+		type: object: options: {
+			action: {
+				description: "What to do with lines over the ingestion API's size limit."
+				required:    false
+				type: string: {
+					default: "reject"
+					enum: {
+						reject:   "Send the line as is, leaving it to the ingestion API to reject it."
+						split:    "Split the line into several lines of up to `max_bytes`, each with the same metadata."
+						truncate: "Truncate the line to `max_bytes`."
+					}
+				}
+			}
+			max_bytes: {
+				description: """
+					The maximum size of the `line` of an event, in bytes.
+
+					Only string lines are truncated or split, at UTF-8 character boundaries.
+					"""
+				required: false
+				type: uint: {
+					default: 32768
+					unit:    "bytes"
+				}
+			}
+		}
+	}
 	request: {
 		description: """
 			Middleware settings for outbound requests.
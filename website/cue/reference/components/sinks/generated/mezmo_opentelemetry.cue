@@ -25,12 +25,45 @@ generated: components: sinks: mezmo_opentelemetry: configuration: {
 		description: "Authentication strategies."
 		required:    false
 		type: object: options: {
+			client_id: {
+				description:   "The client ID."
+				relevant_when: "strategy = \"oauth2\""
+				required:      true
+				type: string: {}
+			}
+			client_secret: {
+				description:   "The client secret."
+				relevant_when: "strategy = \"oauth2\""
+				required:      true
+				type: string: {}
+			}
+			name: {
+				description:   "The name of the header to send."
+				relevant_when: "strategy = \"header\""
+				required:      true
+				type: string: examples: ["x-honeycomb-team"]
+			}
 			password: {
 				description:   "Basic authentication password."
 				relevant_when: "strategy = \"basic\""
 				required:      true
 				type: string: {}
 			}
+			refresh_margin_secs: {
+				description:   "How long before they expire access tokens are refreshed, in seconds."
+				relevant_when: "strategy = \"oauth2\""
+				required:      false
+				type: uint: default: 60
+			}
+			scopes: {
+				description:   "The scopes to request."
+				relevant_when: "strategy = \"oauth2\""
+				required:      false
+				type: array: {
+					default: []
+					items: type: string: {}
+				}
+			}
 			strategy: {
 				description: "The authentication strategy to use."
 				required:    true
@@ -41,6 +74,18 @@ generated: components: sinks: mezmo_opentelemetry: configuration: {
 
 						A bearer token (OAuth2, JWT, etc) is passed as-is.
 						"""
+					header: """
+						Custom header authentication.
+
+						The value is sent as-is in the named header, such as the API key header of a SaaS
+						collector.
+						"""
+					oauth2: """
+						OAuth2 client credentials authentication.
+
+						Access tokens are requested from the token endpoint and sent as bearer tokens. They're
+						cached and refreshed before they expire.
+						"""
 				}
 			}
 			token: {
@@ -49,12 +94,24 @@ generated: components: sinks: mezmo_opentelemetry: configuration: {
 				required:      true
 				type: string: {}
 			}
+			token_endpoint: {
+				description:   "The URL of the token endpoint."
+				relevant_when: "strategy = \"oauth2\""
+				required:      true
+				type: string: examples: ["https://auth.example.com/oauth2/token"]
+			}
 			user: {
 				description:   "Basic authentication username."
 				relevant_when: "strategy = \"basic\""
 				required:      true
 				type: string: {}
 			}
+			value: {
+				description:   "The value of the header."
+				relevant_when: "strategy = \"header\""
+				required:      true
+				type: string: {}
+			}
 		}
 	}
 	batch: {
@@ -138,6 +195,22 @@ generated: components: sinks: mezmo_opentelemetry: configuration: {
 			}
 		}
 	}
+	encoding: {
+		description: "The encoding of the OTLP/HTTP requests."
+		required:    false
+		type: string: {
+			default: "protobuf"
+			enum: {
+				json: """
+					OTLP/JSON, sent with the `application/json` content type.
+
+					Field names are lowerCamelCase and trace and span ids are hex encoded, as the OTLP spec
+					requires. Only supported by the `http` protocol.
+					"""
+				protobuf: "OTLP/protobuf, sent with the `application/x-protobuf` content type."
+			}
+		}
+	}
 	endpoint: {
 		description: """
 			The endpoint to send data to.
@@ -147,6 +220,32 @@ generated: components: sinks: mezmo_opentelemetry: configuration: {
 		required: true
 		type: string: examples: ["https://localhost:8087"]
 	}
+	protocol: {
+		description: "The protocol used to send data to the collector."
+		required:    false
+		type: string: {
+			default: "http"
+			enum: {
+				grpc: "OTLP/gRPC. Only the scheme and authority of the endpoint are used."
+				http: """
+					OTLP/HTTP, with requests sent to the `/v1/logs`, `/v1/metrics` and `/v1/traces` paths of
+					the endpoint.
+					"""
+			}
+		}
+	}
+	quantiles: {
+		description: """
+			Quantiles to use for aggregating [distribution][dist_metric_docs] metrics into summaries.
+
+			[dist_metric_docs]: https://vector.dev/docs/about/under-the-hood/architecture/data-model/metric/#distribution
+			"""
+		required: false
+		type: array: {
+			default: [0.5, 0.75, 0.9, 0.95, 0.99]
+			items: type: float: {}
+		}
+	}
 	request: {
 		description: "Outbound HTTP request settings."
 		required:    false
@@ -291,7 +291,7 @@ generated: components: sinks: opentelemetry: configuration: protocol: {
 				avro: {
 					description:   "Apache Avro-specific encoder options."
 					relevant_when: "codec = \"avro\""
-					required:      true
+					required:      false
 					type: object: options: schema: {
 						description: "The Avro schema."
 						required:    true
@@ -620,7 +620,7 @@ generated: components: sinks: opentelemetry: configuration: protocol: {
 				protobuf: {
 					description:   "Options for the Protobuf serializer."
 					relevant_when: "codec = \"protobuf\""
-					required:      true
+					required:      false
 					type: object: options: {
 						desc_file: {
 							description: """
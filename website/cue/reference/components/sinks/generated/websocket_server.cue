@@ -94,7 +94,7 @@ generated: components: sinks: websocket_server: configuration: {
 			avro: {
 				description:   "Apache Avro-specific encoder options."
 				relevant_when: "codec = \"avro\""
-				required:      true
+				required:      false
 				type: object: options: schema: {
 					description: "The Avro schema."
 					required:    true
@@ -423,7 +423,7 @@ generated: components: sinks: websocket_server: configuration: {
 			protobuf: {
 				description:   "Options for the Protobuf serializer."
 				relevant_when: "codec = \"protobuf\""
-				required:      true
+				required:      false
 				type: object: options: {
 					desc_file: {
 						description: """
@@ -588,7 +588,7 @@ generated: components: sinks: websocket_server: configuration: {
 							avro: {
 								description:   "Apache Avro-specific encoder options."
 								relevant_when: "codec = \"avro\""
-								required:      true
+								required:      false
 								type: object: options: {
 									schema: {
 										description: """
@@ -599,6 +599,9 @@ generated: components: sinks: websocket_server: configuration: {
 																								* `Duration`
 																								* `Fixed`
 																								* `TimeMillis`
+
+																								When a schema registry is configured, this is the reader schema the registered schemas are
+																								resolved to.
 																								"""
 										required: true
 										type: string: examples: ["{ \"type\": \"record\", \"name\": \"log\", \"fields\": [{ \"name\": \"message\", \"type\": \"string\" }] }"]
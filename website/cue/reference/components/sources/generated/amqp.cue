@@ -56,7 +56,7 @@ generated: components: sources: amqp: configuration: {
 			avro: {
 				description:   "Apache Avro-specific encoder options."
 				relevant_when: "codec = \"avro\""
-				required:      true
+				required:      false
 				type: object: options: {
 					schema: {
 						description: """
@@ -67,6 +67,9 @@ generated: components: sources: amqp: configuration: {
 																* `Duration`
 																* `Fixed`
 																* `TimeMillis`
+
+																When a schema registry is configured, this is the reader schema the registered schemas are
+																resolved to.
 																"""
 						required: true
 						type: string: examples: ["{ \"type\": \"record\", \"name\": \"log\", \"fields\": [{ \"name\": \"message\", \"type\": \"string\" }] }"]
@@ -174,7 +174,7 @@ generated: components: sources: aws_s3: configuration: {
 			avro: {
 				description:   "Apache Avro-specific encoder options."
 				relevant_when: "codec = \"avro\""
-				required:      true
+				required:      false
 				type: object: options: {
 					schema: {
 						description: """
@@ -185,6 +185,9 @@ generated: components: sources: aws_s3: configuration: {
 																* `Duration`
 																* `Fixed`
 																* `TimeMillis`
+
+																When a schema registry is configured, this is the reader schema the registered schemas are
+																resolved to.
 																"""
 						required: true
 						type: string: examples: ["{ \"type\": \"record\", \"name\": \"log\", \"fields\": [{ \"name\": \"message\", \"type\": \"string\" }] }"]
@@ -65,7 +65,7 @@ generated: components: sources: kafka: configuration: {
 			avro: {
 				description:   "Apache Avro-specific encoder options."
 				relevant_when: "codec = \"avro\""
-				required:      true
+				required:      false
 				type: object: options: {
 					schema: {
 						description: """
@@ -76,6 +76,9 @@ generated: components: sources: kafka: configuration: {
 																* `Duration`
 																* `Fixed`
 																* `TimeMillis`
+
+																When a schema registry is configured, this is the reader schema the registered schemas are
+																resolved to.
 																"""
 						required: true
 						type: string: examples: ["{ \"type\": \"record\", \"name\": \"log\", \"fields\": [{ \"name\": \"message\", \"type\": \"string\" }] }"]
@@ -712,6 +715,57 @@ generated: components: sources: kafka: configuration: {
 			}
 		}
 	}
+	schema_registry: {
+		description: """
+			The schema registry holding the schemas of the messages.
+
+			When set, the messages are expected to be in the Confluent wire format, prefixed by the id
+			of their schema, and are decoded with the schema fetched from the registry the first time
+			it's used. Only the `avro` and `protobuf` decoding codecs are supported. Their `avro` or
+			`protobuf` options can then be left out, the schemas coming from the registry.
+			"""
+		required: false
+		type: object: options: {
+			auth: {
+				description: "The basic authentication credentials of the schema registry."
+				required:    false
+				type: object: options: {
+					password: {
+						description: "The basic authentication password."
+						required:    true
+						type: string: examples: ["${SCHEMA_REGISTRY_PASSWORD}"]
+					}
+					user: {
+						description: "The basic authentication username."
+						required:    true
+						type: string: examples: ["${SCHEMA_REGISTRY_USERNAME}"]
+					}
+				}
+			}
+			cache_dir: {
+				description: """
+					The directory where the fetched schemas are stored.
+
+					The schemas stored in this directory are used when the schema registry can't be reached.
+					"""
+				required: false
+				type: string: examples: ["/var/lib/vector/schemas"]
+			}
+			timeout_secs: {
+				description: "The timeout for the schema registry requests."
+				required:    false
+				type: uint: {
+					default: 10
+					unit:    "seconds"
+				}
+			}
+			url: {
+				description: "The base URL of the schema registry."
+				required:    true
+				type: string: examples: ["http://localhost:8081"]
+			}
+		}
+	}
 	session_timeout_ms: {
 		description: "The Kafka session timeout."
 		required:    false
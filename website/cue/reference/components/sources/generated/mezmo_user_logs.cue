@@ -1,3 +1,33 @@
 package metadata
 
-generated: components: sources: mezmo_user_logs: configuration: {}
+generated: components: sources: mezmo_user_logs: configuration: {
+	codes: {
+		description: "Only emit the user logs with one of these error codes. All user logs are emitted when empty."
+		required:    false
+		type: array: {
+			default: []
+			items: type: string: examples: ["FIELD_NOT_FOUND"]
+		}
+	}
+	levels: {
+		description: "Only emit the user logs with one of these levels. All levels are emitted when empty."
+		required:    false
+		type: array: {
+			default: []
+			items: type: string: enum: {
+				debug: "Debug user logs."
+				error: "Error user logs."
+				info:  "Info user logs."
+				warn:  "Warn user logs."
+			}
+		}
+	}
+	pipelines: {
+		description: "Only emit the user logs of these pipelines. All pipelines are emitted when empty."
+		required:    false
+		type: array: {
+			default: []
+			items: type: string: examples: ["pipeline_123"]
+		}
+	}
+}